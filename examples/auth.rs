@@ -41,7 +41,7 @@ extern crate virt;
 
 use std::{env, io};
 
-use virt::connect::{Connect, ConnectCredential, ConnectAuth};
+use virt::connect::{Connect, ConnectAuth, ConnectCredential, ConnectCredentialType, ConnectFlags};
 
 fn main() {
     let uri = match env::args().nth(1) {
@@ -55,11 +55,11 @@ fn main() {
 
             println!("{}:", cred.prompt);
             match cred.typed {
-                ::virt::connect::VIR_CRED_AUTHNAME => {
+                ConnectCredentialType::Authname => {
                     io::stdin().read_line(&mut input).expect("");
                     cred.result = Some(String::from(input.trim()));
                 }
-                ::virt::connect::VIR_CRED_PASSPHRASE => {
+                ConnectCredentialType::Passphrase => {
                     io::stdin().read_line(&mut input).expect("");
                     cred.result = Some(String::from(input.trim()));
                 }
@@ -69,12 +69,12 @@ fn main() {
             }
         }
     };
-    let mut auth = ConnectAuth::new(vec![::virt::connect::VIR_CRED_AUTHNAME,
-                                         ::virt::connect::VIR_CRED_PASSPHRASE],
+    let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname,
+                                         ConnectCredentialType::Passphrase],
                                     callback);
 
     println!("Attempting to connect to hypervisor: '{}'...", uri);
-    let mut conn = match Connect::open_auth(&uri, &mut auth, 0) {
+    let mut conn = match Connect::open_auth(&uri, &mut auth, ConnectFlags::empty()) {
         Ok(c) => {
             println!("Connected");
             c
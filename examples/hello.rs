@@ -26,7 +26,8 @@ extern crate virt;
 
 use std::env;
 
-use virt::connect::Connect;
+use virt::connect::{Connect, ConnectListAllDomainsFlags};
+use virt::domain::DomainModImpactFlags;
 use virt::error::Error;
 
 
@@ -49,8 +50,7 @@ fn show_hypervisor_info(conn: &Connect) -> Result<(), Error> {
 }
 
 fn show_domains(conn: &Connect) -> Result<(), Error> {
-    let flags = virt::connect::VIR_CONNECT_LIST_DOMAINS_ACTIVE |
-                virt::connect::VIR_CONNECT_LIST_DOMAINS_INACTIVE;
+    let flags = ConnectListAllDomainsFlags::ACTIVE | ConnectListAllDomainsFlags::INACTIVE;
 
     if let Ok(num_active_domains) = conn.num_of_domains() {
         if let Ok(num_inactive_domains) = conn.num_of_defined_domains() {
@@ -69,13 +69,13 @@ fn show_domains(conn: &Connect) -> Result<(), Error> {
                     println!("ID: {}, Name: {}, Active: {}", id, name, active);
                     if let Ok(dinfo) = dom.get_info() {
                         println!("Domain info:");
-                        println!("    State: {}", dinfo.state);
+                        println!("    State: {:?}", dinfo.state);
                         println!("    Max Memory: {}", dinfo.max_mem);
                         println!("    Memory: {}", dinfo.memory);
                         println!("    CPUs: {}", dinfo.nr_virt_cpu);
                         println!("    CPU Time: {}", dinfo.cpu_time);
                     }
-                    if let Ok(memtune) = dom.get_memory_parameters(0) {
+                    if let Ok(memtune) = dom.get_memory_parameters(DomainModImpactFlags::empty()) {
                         println!("Memory tune:");
                         println!("    Hard Limit: {}", memtune.hard_limit.unwrap_or(0));
                         println!("    Soft Limit: {}", memtune.soft_limit.unwrap_or(0));
@@ -83,11 +83,13 @@ fn show_domains(conn: &Connect) -> Result<(), Error> {
                         println!("    Swap Hard Limit: {}",
                                  memtune.swap_hard_limit.unwrap_or(0));
                     }
-                    if let Ok(numa) = dom.get_numa_parameters(0) {
+                    if let Ok(numa) = dom.get_numa_parameters(DomainModImpactFlags::empty()) {
                         println!("NUMA:");
                         println!("    Node Set: {}",
                                  numa.node_set.unwrap_or(String::from("")));
-                        println!("    Mode: {}", numa.mode.unwrap_or(0));
+                        if let Some(mode) = numa.mode {
+                            println!("    Mode: {:?}", mode);
+                        }
                     }
                 }
             }
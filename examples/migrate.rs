@@ -23,7 +23,7 @@ extern crate virt;
 use std::env;
 
 use virt::connect::Connect;
-use virt::domain::Domain;
+use virt::domain::{Domain, DomainMigrateFlags};
 
 fn main() {
     if env::args().len() < 4 {
@@ -50,8 +50,8 @@ fn main() {
     };
 
     if let Ok(dom) = Domain::lookup_by_name(&conn, &dname) {
-        let flags = ::virt::domain::VIR_MIGRATE_LIVE | ::virt::domain::VIR_MIGRATE_PEER2PEER |
-                    ::virt::domain::VIR_MIGRATE_TUNNELLED;
+        let flags = DomainMigrateFlags::LIVE | DomainMigrateFlags::PEER2PEER |
+                    DomainMigrateFlags::TUNNELLED;
         if let Ok(_) = dom.migrate(&conn, flags, &dst_uri, 0) {
             println!("Domain migrated");
         }
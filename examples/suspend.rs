@@ -24,7 +24,7 @@ extern crate virt;
 
 use std::{env, thread, time};
 
-use virt::connect::Connect;
+use virt::connect::{Connect, ConnectListAllDomainsFlags};
 use virt::domain::Domain;
 use virt::error::Error;
 
@@ -45,7 +45,7 @@ fn suspend_and_resume(conn: &Connect, name: &str, sec: u64) -> Result<(), Error>
 }
 
 fn fetch_domains(conn: &Connect) -> Result<(), Error> {
-    let flags = virt::connect::VIR_CONNECT_LIST_DOMAINS_ACTIVE;
+    let flags = ConnectListAllDomainsFlags::ACTIVE;
     if let Ok(doms) = conn.list_all_domains(flags) {
         println!("Running domains:");
         println!("----------------");
@@ -19,20 +19,25 @@
 extern crate libc;
 
 use std::{str, ptr, mem};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use network::sys::virNetworkPtr;
 use interface::sys::virInterfacePtr;
 use secret::sys::virSecretPtr;
 use nwfilter::sys::virNWFilterPtr;
+use nwfilter_binding::sys::virNWFilterBindingPtr;
 use nodedev::sys::virNodeDevicePtr;
 use storage_pool::sys::virStoragePoolPtr;
 use domain::sys::{virDomainPtr, virDomainStatsRecordPtr};
 
-use domain::{Domain, DomainStatsRecord};
-use error::Error;
+use domain::{Domain, DomainStatsRecord, DomainStatsTypes};
+use error::{Error, ErrorCallback};
+use error::sys::{virErrorFunc, virErrorPtr};
 use network::Network;
 use nodedev::NodeDevice;
 use nwfilter::NWFilter;
+use nwfilter_binding::NWFilterBinding;
 use interface::Interface;
 use storage_pool::StoragePool;
 use secret::Secret;
@@ -73,6 +78,12 @@ pub mod sys {
 
     pub type virConnectAuthPtr = *mut virConnectAuth;
 
+    pub type virConnectCloseFunc = extern "C" fn(virConnectPtr,
+                                                 libc::c_int,
+                                                 *mut libc::c_void);
+
+    pub type virFreeCallback = extern "C" fn(*mut libc::c_void);
+
     #[repr(C)]
     #[derive(Default)]
     pub struct virNodeInfo {
@@ -101,7 +112,20 @@ extern "C" {
                           auth: sys::virConnectAuthPtr,
                           flags: libc::c_uint)
                           -> sys::virConnectPtr;
+    static virConnectAuthPtrDefault: sys::virConnectAuthPtr;
     fn virConnectClose(ptr: sys::virConnectPtr) -> libc::c_int;
+    fn virConnectRegisterCloseCallback(ptr: sys::virConnectPtr,
+                                       cb: sys::virConnectCloseFunc,
+                                       opaque: *mut libc::c_void,
+                                       freecb: Option<sys::virFreeCallback>)
+                                       -> libc::c_int;
+    fn virConnectUnregisterCloseCallback(ptr: sys::virConnectPtr,
+                                         cb: sys::virConnectCloseFunc)
+                                         -> libc::c_int;
+    fn virConnSetErrorFunc(ptr: sys::virConnectPtr,
+                           userData: *mut libc::c_void,
+                           handler: Option<virErrorFunc>);
+    fn virConnectRef(ptr: sys::virConnectPtr) -> libc::c_int;
     fn virConnectGetVersion(ptr: sys::virConnectPtr, hyver: *mut libc::c_ulong) -> libc::c_int;
     fn virConnectGetHostname(ptr: sys::virConnectPtr) -> *mut libc::c_char;
     fn virConnectGetCapabilities(ptr: sys::virConnectPtr) -> *mut libc::c_char;
@@ -176,6 +200,10 @@ extern "C" {
                                   nwfilters: *mut *mut virNWFilterPtr,
                                   flags: libc::c_uint)
                                   -> libc::c_int;
+    fn virConnectListAllNWFilterBindings(ptr: sys::virConnectPtr,
+                                         bindings: *mut *mut virNWFilterBindingPtr,
+                                         flags: libc::c_uint)
+                                         -> libc::c_int;
     fn virConnectListAllStoragePools(ptr: sys::virConnectPtr,
                                      storages: *mut *mut virStoragePoolPtr,
                                      flags: libc::c_uint)
@@ -244,11 +272,12 @@ extern "C" fn connectCallback(ccreds: sys::virConnectCredentialPtr,
                               ncred: libc::c_uint,
                               cbdata: *mut libc::c_void)
                               -> libc::c_int {
-    let callback: ConnectAuthCallback = unsafe {
+    let provider = unsafe {
         // Safe because connectCallback is private and only used by
-        // Connect::open_auth(). In open_auth() we transmute the
-        // callback allocate in *void.
-        mem::transmute(cbdata)
+        // Connect::open_auth() which passes the provider of the
+        // ConnectAuth it borrows mutably for the duration of the
+        // call.
+        &mut *(cbdata as *mut Box<dyn CredentialProvider + Send>)
     };
     let mut rcreds: Vec<ConnectCredential> = Vec::new();
     for i in 0..ncred as isize {
@@ -258,7 +287,7 @@ extern "C" fn connectCallback(ccreds: sys::virConnectCredentialPtr,
             rcreds.push(c);
         }
     }
-    callback(&mut rcreds);
+    provider.provide(&mut rcreds);
     for i in 0..ncred as isize {
         if rcreds[i as usize].result.is_some() {
             if let Some(ref result) = rcreds[i as usize].result {
@@ -274,104 +303,548 @@ extern "C" fn connectCallback(ccreds: sys::virConnectCredentialPtr,
     0
 }
 
+/// Identifiers of the `Connect` handles, used to release what a
+/// handle registered when it gets closed.
+static CONN_HANDLE_IDS: AtomicUsize = AtomicUsize::new(1);
+
+/// Close callbacks registered on connections, indexed by the address
+/// of the connection and the identifier of the registering handle.
+///
+/// The callbacks are owned here instead of being handed to libvirt,
+/// drivers which do not support close callbacks accept the
+/// registration without keeping the opaque data and would never
+/// release it.
+static CONN_CLOSE_CALLBACKS: Mutex<Vec<(usize, usize, SharedCloseCallback)>> =
+    Mutex::new(Vec::new());
+
+type SharedCloseCallback = Arc<Mutex<ConnectCloseCallback>>;
+
+extern "C" fn connectCloseCallback(c: sys::virConnectPtr,
+                                   reason: libc::c_int,
+                                   opaque: *mut libc::c_void) {
+    let callback = match CONN_CLOSE_CALLBACKS.lock() {
+        Ok(callbacks) => callbacks.iter().find(|f| f.0 == opaque as usize).map(|f| f.2.clone()),
+        Err(_) => return,
+    };
+    if let Some(callback) = callback {
+        // The connection is borrowed from libvirt, it must not be
+        // closed when going out of scope.
+        let conn = mem::ManuallyDrop::new(Connect::new(c));
+        if let Ok(mut callback) = callback.lock() {
+            (*callback)(&conn, CloseReason::from_raw(reason))
+        }
+    }
+}
+
+/// Removes the close callback of the connection, only if registered
+/// by handle `id` when given. Returns whether one was removed.
+fn remove_conn_close_callback(ptr: sys::virConnectPtr, id: Option<usize>) -> bool {
+    match CONN_CLOSE_CALLBACKS.lock() {
+        Ok(mut callbacks) => {
+            let len = callbacks.len();
+            callbacks.retain(|f| f.0 != ptr as usize || id.unwrap_or(f.1) != f.1);
+            callbacks.len() != len
+        }
+        Err(_) => false,
+    }
+}
+
+/// Error handlers installed on connections, indexed by the address
+/// of the connection and the identifier of the installing handle.
+static CONN_ERROR_FUNCS: Mutex<Vec<(usize, usize, ErrorCallback)>> = Mutex::new(Vec::new());
+
+extern "C" fn connErrorCallback(data: *mut libc::c_void, err: virErrorPtr) {
+    let handler = match CONN_ERROR_FUNCS.lock() {
+        Ok(funcs) => funcs.iter().find(|f| f.0 == data as usize).map(|f| f.2.clone()),
+        Err(_) => return,
+    };
+    if let Some(handler) = handler {
+        if !err.is_null() {
+            handler(&unsafe { Error::from_ptr(err) });
+        }
+    }
+}
+
+/// Removes the error handler of the connection, only if installed by
+/// handle `id` when given. Returns whether one was removed.
+fn remove_conn_error_func(ptr: sys::virConnectPtr, id: Option<usize>) -> bool {
+    match CONN_ERROR_FUNCS.lock() {
+        Ok(mut funcs) => {
+            let len = funcs.len();
+            funcs.retain(|f| f.0 != ptr as usize || id.unwrap_or(f.1) != f.1);
+            funcs.len() != len
+        }
+        Err(_) => false,
+    }
+}
+
+
+bitflags! {
+    /// Flags for opening a connection.
+    pub struct ConnectFlags: libc::c_uint {
+        const RO = 1 << 0;
+        const NO_ALIASES = 1 << 1;
+    }
+}
+
+#[deprecated(note = "use `ConnectFlags::RO`")]
+pub const VIR_CONNECT_RO: ConnectFlags = ConnectFlags::RO;
+#[deprecated(note = "use `ConnectFlags::NO_ALIASES`")]
+pub const VIR_CONNECT_NO_ALIASES: ConnectFlags = ConnectFlags::NO_ALIASES;
+
+bitflags! {
+    /// Filters for `Connect::list_all_node_devices`.
+    pub struct ConnectListAllNodeDeviceFlags: libc::c_uint {
+        const CAP_SYSTEM = 1 << 0;
+        const CAP_PCI_DEV = 1 << 1;
+        const CAP_USB_DEV = 1 << 2;
+        const CAP_USB_INTERFACE = 1 << 3;
+        const CAP_NET = 1 << 4;
+        const CAP_SCSI_HOST = 1 << 5;
+        const CAP_SCSI_TARGET = 1 << 6;
+        const CAP_SCSI = 1 << 7;
+        const CAP_STORAGE = 1 << 8;
+        const CAP_FC_HOST = 1 << 9;
+        const CAP_VPORTS = 1 << 10;
+        const CAP_SCSI_GENERIC = 1 << 11;
+        const CAP_DRM = 1 << 12;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_SYSTEM`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SYSTEM: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_SYSTEM;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_PCI_DEV`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_PCI_DEV: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_PCI_DEV;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_USB_DEV`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_USB_DEV: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_USB_DEV;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_USB_INTERFACE`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_USB_INTERFACE: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_USB_INTERFACE;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_NET`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_NET: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_NET;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_SCSI_HOST`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_HOST: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_SCSI_HOST;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_SCSI_TARGET`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_TARGET: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_SCSI_TARGET;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_SCSI`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_SCSI;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_STORAGE`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_STORAGE: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_STORAGE;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_FC_HOST`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_FC_HOST: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_FC_HOST;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_VPORTS`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_VPORTS: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_VPORTS;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_SCSI_GENERIC`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_GENERIC: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_SCSI_GENERIC;
+#[deprecated(note = "use `ConnectListAllNodeDeviceFlags::CAP_DRM`")]
+pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_DRM: ConnectListAllNodeDeviceFlags =
+    ConnectListAllNodeDeviceFlags::CAP_DRM;
+
+bitflags! {
+    /// Filters for `Connect::list_all_secrets`.
+    pub struct ConnectListAllSecretsFlags: libc::c_uint {
+        const EPHEMERAL = 1 << 0;
+        const NO_EPHEMERAL = 1 << 1;
+        const PRIVATE = 1 << 2;
+        const NO_PRIVATE = 1 << 3;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllSecretsFlags::EPHEMERAL`")]
+pub const VIR_CONNECT_LIST_SECRETS_EPHEMERAL: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::EPHEMERAL;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::NO_EPHEMERAL`")]
+pub const VIR_CONNECT_LIST_SECRETS_NO_EPHEMERAL: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::NO_EPHEMERAL;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::PRIVATE`")]
+pub const VIR_CONNECT_LIST_SECRETS_PRIVATE: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::PRIVATE;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::NO_PRIVATE`")]
+pub const VIR_CONNECT_LIST_SECRETS_NO_PRIVATE: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::NO_PRIVATE;
+
+bitflags! {
+    /// Filters for `Connect::list_all_domains`.
+    pub struct ConnectListAllDomainsFlags: libc::c_uint {
+        const ACTIVE = 1 << 0;
+        const INACTIVE = 1 << 1;
+        const PERSISTENT = 1 << 2;
+        const TRANSIENT = 1 << 3;
+        const RUNNING = 1 << 4;
+        const PAUSED = 1 << 5;
+        const SHUTOFF = 1 << 6;
+        const OTHER = 1 << 7;
+        const MANAGEDSAVE = 1 << 8;
+        const NO_MANAGEDSAVE = 1 << 9;
+        const AUTOSTART = 1 << 10;
+        const NO_AUTOSTART = 1 << 11;
+        const HAS_SNAPSHOT = 1 << 12;
+        const NO_SNAPSHOT = 1 << 13;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllDomainsFlags::ACTIVE`")]
+pub const VIR_CONNECT_LIST_DOMAINS_ACTIVE: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::ACTIVE;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::INACTIVE`")]
+pub const VIR_CONNECT_LIST_DOMAINS_INACTIVE: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::INACTIVE;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::PERSISTENT`")]
+pub const VIR_CONNECT_LIST_DOMAINS_PERSISTENT: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::PERSISTENT;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::TRANSIENT`")]
+pub const VIR_CONNECT_LIST_DOMAINS_TRANSIENT: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::TRANSIENT;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::RUNNING`")]
+pub const VIR_CONNECT_LIST_DOMAINS_RUNNING: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::RUNNING;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::PAUSED`")]
+pub const VIR_CONNECT_LIST_DOMAINS_PAUSED: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::PAUSED;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::SHUTOFF`")]
+pub const VIR_CONNECT_LIST_DOMAINS_SHUTOFF: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::SHUTOFF;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::OTHER`")]
+pub const VIR_CONNECT_LIST_DOMAINS_OTHER: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::OTHER;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::MANAGEDSAVE`")]
+pub const VIR_CONNECT_LIST_DOMAINS_MANAGEDSAVE: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::MANAGEDSAVE;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::NO_MANAGEDSAVE`")]
+pub const VIR_CONNECT_LIST_DOMAINS_NO_MANAGEDSAVE: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::NO_MANAGEDSAVE;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::AUTOSTART`")]
+pub const VIR_CONNECT_LIST_DOMAINS_AUTOSTART: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::AUTOSTART;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::NO_AUTOSTART`")]
+pub const VIR_CONNECT_LIST_DOMAINS_NO_AUTOSTART: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::NO_AUTOSTART;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::HAS_SNAPSHOT`")]
+pub const VIR_CONNECT_LIST_DOMAINS_HAS_SNAPSHOT: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::HAS_SNAPSHOT;
+#[deprecated(note = "use `ConnectListAllDomainsFlags::NO_SNAPSHOT`")]
+pub const VIR_CONNECT_LIST_DOMAINS_NO_SNAPSHOT: ConnectListAllDomainsFlags =
+    ConnectListAllDomainsFlags::NO_SNAPSHOT;
+
+bitflags! {
+    /// Filters and behavior of `Connect::get_all_domain_stats`.
+    pub struct ConnectGetAllDomainStatsFlags: libc::c_uint {
+        const ACTIVE = 1 << 0;
+        const INACTIVE = 1 << 1;
+        const PERSISTENT = 1 << 2;
+        const TRANSIENT = 1 << 3;
+        const RUNNING = 1 << 4;
+        const PAUSED = 1 << 5;
+        const SHUTOFF = 1 << 6;
+        const OTHER = 1 << 7;
+        /// Report statistics without waiting for a busy domain.
+        const NOWAIT = 1 << 29;
+        /// Include backing chain information in block statistics.
+        const BACKING = 1 << 30;
+        /// Fail if any requested statistics group is unsupported.
+        const ENFORCE_STATS = 1 << 31;
+    }
+}
+
+bitflags! {
+    /// Filters for `Connect::list_all_networks`.
+    pub struct ConnectListAllNetworksFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+        const ACTIVE = 1 << 1;
+        const PERSISTENT = 1 << 2;
+        const TRANSIENT = 1 << 3;
+        const AUTOSTART = 1 << 4;
+        const NO_AUTOSTART = 1 << 5;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllNetworksFlags::INACTIVE`")]
+pub const VIR_CONNECT_LIST_NETWORKS_INACTIVE: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::INACTIVE;
+#[deprecated(note = "use `ConnectListAllNetworksFlags::ACTIVE`")]
+pub const VIR_CONNECT_LIST_NETWORKS_ACTIVE: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::ACTIVE;
+#[deprecated(note = "use `ConnectListAllNetworksFlags::PERSISTENT`")]
+pub const VIR_CONNECT_LIST_NETWORKS_PERSISTENT: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::PERSISTENT;
+#[deprecated(note = "use `ConnectListAllNetworksFlags::TRANSIENT`")]
+pub const VIR_CONNECT_LIST_NETWORKS_TRANSIENT: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::TRANSIENT;
+#[deprecated(note = "use `ConnectListAllNetworksFlags::AUTOSTART`")]
+pub const VIR_CONNECT_LIST_NETWORKS_AUTOSTART: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::AUTOSTART;
+#[deprecated(note = "use `ConnectListAllNetworksFlags::NO_AUTOSTART`")]
+pub const VIR_CONNECT_LIST_NETWORKS_NO_AUTOSTART: ConnectListAllNetworksFlags =
+    ConnectListAllNetworksFlags::NO_AUTOSTART;
+
+bitflags! {
+    /// Filters for `Connect::list_all_interfaces`.
+    pub struct ConnectListAllInterfacesFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+        const ACTIVE = 1 << 1;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllInterfacesFlags::INACTIVE`")]
+pub const VIR_CONNECT_LIST_INTERFACES_INACTIVE: ConnectListAllInterfacesFlags =
+    ConnectListAllInterfacesFlags::INACTIVE;
+#[deprecated(note = "use `ConnectListAllInterfacesFlags::ACTIVE`")]
+pub const VIR_CONNECT_LIST_INTERFACES_ACTIVE: ConnectListAllInterfacesFlags =
+    ConnectListAllInterfacesFlags::ACTIVE;
+
+bitflags! {
+    /// Filters for `Connect::list_all_storage_pools`.
+    pub struct ConnectListAllStoragePoolsFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+        const ACTIVE = 1 << 1;
+        const PERSISTENT = 1 << 2;
+        const TRANSIENT = 1 << 3;
+        const AUTOSTART = 1 << 4;
+        const NO_AUTOSTART = 1 << 5;
+        const DIR = 1 << 6;
+        const FS = 1 << 7;
+        const NETFS = 1 << 8;
+        const LOGICAL = 1 << 9;
+        const DISK = 1 << 10;
+        const ISCSI = 1 << 11;
+        const SCSI = 1 << 12;
+        const MPATH = 1 << 13;
+        const RBD = 1 << 14;
+        const SHEEPDOG = 1 << 15;
+        const GLUSTER = 1 << 16;
+        const ZFS = 1 << 17;
+        const VSTORAGE = 1 << 18;
+    }
+}
+
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::INACTIVE`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_INACTIVE: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::INACTIVE;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::ACTIVE`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_ACTIVE: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::ACTIVE;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::PERSISTENT`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_PERSISTENT: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::PERSISTENT;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::TRANSIENT`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_TRANSIENT: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::TRANSIENT;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::AUTOSTART`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_AUTOSTART: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::AUTOSTART;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::NO_AUTOSTART`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_NO_AUTOSTART: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::NO_AUTOSTART;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::DIR`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_DIR: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::DIR;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::FS`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_FS: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::FS;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::NETFS`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_NETFS: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::NETFS;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::LOGICAL`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_LOGICAL: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::LOGICAL;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::DISK`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_DISK: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::DISK;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::ISCSI`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_ISCSI: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::ISCSI;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::SCSI`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_SCSI: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::SCSI;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::MPATH`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_MPATH: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::MPATH;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::RBD`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_RBD: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::RBD;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::SHEEPDOG`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_SHEEPDOG: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::SHEEPDOG;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::GLUSTER`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_GLUSTER: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::GLUSTER;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::ZFS`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_ZFS: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::ZFS;
+#[deprecated(note = "use `ConnectListAllStoragePoolsFlags::VSTORAGE`")]
+pub const VIR_CONNECT_LIST_STORAGE_POOLS_VSTORAGE: ConnectListAllStoragePoolsFlags =
+    ConnectListAllStoragePoolsFlags::VSTORAGE;
+
+bitflags! {
+    /// Flags for `Connect::compare_cpu`.
+    pub struct ConnectCompareCPUFlags: libc::c_uint {
+        const FAIL_INCOMPATIBLE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `ConnectCompareCPUFlags::FAIL_INCOMPATIBLE`")]
+pub const VIR_CONNECT_COMPARE_CPU_FAIL_INCOMPATIBLE: ConnectCompareCPUFlags =
+    ConnectCompareCPUFlags::FAIL_INCOMPATIBLE;
+
+/// Result of comparing a CPU description with the host CPU.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CPUCompareResult {
+    /// The comparison failed.
+    Error = -1,
+    /// The host CPU is incompatible.
+    Incompatible = 0,
+    /// The host CPU is identical.
+    Identical = 1,
+    /// The host CPU is a superset.
+    Superset = 2,
+    /// Value not known by the binding.
+    Unknown = -2,
+}
+
+impl CPUCompareResult {
+    pub fn from_raw(value: i32) -> CPUCompareResult {
+        match value {
+            -1 => CPUCompareResult::Error,
+            0 => CPUCompareResult::Incompatible,
+            1 => CPUCompareResult::Identical,
+            2 => CPUCompareResult::Superset,
+            _ => CPUCompareResult::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `CPUCompareResult::Error`")]
+pub const VIR_CPU_COMPARE_ERROR: CPUCompareResult = CPUCompareResult::Error;
+#[deprecated(note = "use `CPUCompareResult::Incompatible`")]
+pub const VIR_CPU_COMPARE_INCOMPATIBLE: CPUCompareResult = CPUCompareResult::Incompatible;
+#[deprecated(note = "use `CPUCompareResult::Identical`")]
+pub const VIR_CPU_COMPARE_IDENTICAL: CPUCompareResult = CPUCompareResult::Identical;
+#[deprecated(note = "use `CPUCompareResult::Superset`")]
+pub const VIR_CPU_COMPARE_SUPERSET: CPUCompareResult = CPUCompareResult::Superset;
+
+bitflags! {
+    /// Flags for `Connect::baseline_cpu`.
+    pub struct BaselineCPUFlags: libc::c_uint {
+        const EXPAND_FEATURES = 1 << 0;
+        const MIGRATABLE = 1 << 1;
+    }
+}
 
-pub type ConnectFlags = self::libc::c_uint;
-pub const VIR_CONNECT_RO: ConnectFlags = 1 << 0;
-pub const VIR_CONNECT_NO_ALIASES: ConnectFlags = 1 << 1;
-
-pub type ConnectListAllNodeDeviceFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SYSTEM: ConnectListAllNodeDeviceFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_PCI_DEV: ConnectListAllNodeDeviceFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_USB_DEV: ConnectListAllNodeDeviceFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_USB_INTERFACE: ConnectListAllNodeDeviceFlags = 1 << 3;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_NET: ConnectListAllNodeDeviceFlags = 1 << 4;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_HOST: ConnectListAllNodeDeviceFlags = 1 << 5;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_TARGET: ConnectListAllNodeDeviceFlags = 1 << 6;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI: ConnectListAllNodeDeviceFlags = 1 << 7;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_STORAGE: ConnectListAllNodeDeviceFlags = 1 << 8;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_FC_HOST: ConnectListAllNodeDeviceFlags = 1 << 9;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_VPORTS: ConnectListAllNodeDeviceFlags = 1 << 10;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_SCSI_GENERIC: ConnectListAllNodeDeviceFlags = 1 << 11;
-pub const VIR_CONNECT_LIST_NODE_DEVICES_CAP_DRM: ConnectListAllNodeDeviceFlags = 1 << 12;
-
-pub type ConnectListAllSecretsFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_SECRETS_EPHEMERAL: ConnectListAllSecretsFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_SECRETS_NO_EPHEMERAL: ConnectListAllSecretsFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_SECRETS_PRIVATE: ConnectListAllSecretsFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_SECRETS_NO_PRIVATE: ConnectListAllSecretsFlags = 1 << 3;
-
-pub type ConnectListAllDomainsFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_DOMAINS_ACTIVE: ConnectListAllDomainsFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_DOMAINS_INACTIVE: ConnectListAllDomainsFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_DOMAINS_PERSISTENT: ConnectListAllDomainsFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_DOMAINS_TRANSIENT: ConnectListAllDomainsFlags = 1 << 3;
-pub const VIR_CONNECT_LIST_DOMAINS_RUNNING: ConnectListAllDomainsFlags = 1 << 4;
-pub const VIR_CONNECT_LIST_DOMAINS_PAUSED: ConnectListAllDomainsFlags = 1 << 5;
-pub const VIR_CONNECT_LIST_DOMAINS_SHUTOFF: ConnectListAllDomainsFlags = 1 << 6;
-pub const VIR_CONNECT_LIST_DOMAINS_OTHER: ConnectListAllDomainsFlags = 1 << 7;
-pub const VIR_CONNECT_LIST_DOMAINS_MANAGEDSAVE: ConnectListAllDomainsFlags = 1 << 8;
-pub const VIR_CONNECT_LIST_DOMAINS_NO_MANAGEDSAVE: ConnectListAllDomainsFlags = 1 << 9;
-pub const VIR_CONNECT_LIST_DOMAINS_AUTOSTART: ConnectListAllDomainsFlags = 1 << 10;
-pub const VIR_CONNECT_LIST_DOMAINS_NO_AUTOSTART: ConnectListAllDomainsFlags = 1 << 11;
-pub const VIR_CONNECT_LIST_DOMAINS_HAS_SNAPSHOT: ConnectListAllDomainsFlags = 1 << 12;
-pub const VIR_CONNECT_LIST_DOMAINS_NO_SNAPSHOT: ConnectListAllDomainsFlags = 1 << 13;
-
-pub type ConnectListAllNetworksFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_NETWORKS_INACTIVE: ConnectListAllNetworksFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_NETWORKS_ACTIVE: ConnectListAllNetworksFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_NETWORKS_PERSISTENT: ConnectListAllNetworksFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_NETWORKS_TRANSIENT: ConnectListAllNetworksFlags = 1 << 3;
-pub const VIR_CONNECT_LIST_NETWORKS_AUTOSTART: ConnectListAllNetworksFlags = 1 << 4;
-pub const VIR_CONNECT_LIST_NETWORKS_NO_AUTOSTART: ConnectListAllNetworksFlags = 1 << 5;
-
-pub type ConnectListAllInterfacesFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_INTERFACES_INACTIVE: ConnectListAllInterfacesFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_INTERFACES_ACTIVE: ConnectListAllInterfacesFlags = 1 << 1;
-
-pub type ConnectListAllStoragePoolsFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_INACTIVE: ConnectListAllStoragePoolsFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_ACTIVE: ConnectListAllStoragePoolsFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_PERSISTENT: ConnectListAllStoragePoolsFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_TRANSIENT: ConnectListAllStoragePoolsFlags = 1 << 3;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_AUTOSTART: ConnectListAllStoragePoolsFlags = 1 << 4;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_NO_AUTOSTART: ConnectListAllStoragePoolsFlags = 1 << 5;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_DIR: ConnectListAllStoragePoolsFlags = 1 << 6;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_FS: ConnectListAllStoragePoolsFlags = 1 << 7;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_NETFS: ConnectListAllStoragePoolsFlags = 1 << 8;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_LOGICAL: ConnectListAllStoragePoolsFlags = 1 << 9;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_DISK: ConnectListAllStoragePoolsFlags = 1 << 10;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_ISCSI: ConnectListAllStoragePoolsFlags = 1 << 11;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_SCSI: ConnectListAllStoragePoolsFlags = 1 << 12;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_MPATH: ConnectListAllStoragePoolsFlags = 1 << 13;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_RBD: ConnectListAllStoragePoolsFlags = 1 << 14;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_SHEEPDOG: ConnectListAllStoragePoolsFlags = 1 << 15;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_GLUSTER: ConnectListAllStoragePoolsFlags = 1 << 16;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_ZFS: ConnectListAllStoragePoolsFlags = 1 << 17;
-pub const VIR_CONNECT_LIST_STORAGE_POOLS_VSTORAGE: ConnectListAllStoragePoolsFlags = 1 << 18;
-
-pub type ConnectCompareCPUFlags = self::libc::c_uint;
-pub const VIR_CONNECT_COMPARE_CPU_FAIL_INCOMPATIBLE: ConnectCompareCPUFlags = 1 << 0;
-
-pub type CPUCompareResult = self::libc::c_int;
-pub const VIR_CPU_COMPARE_ERROR: CPUCompareResult = -1;
-pub const VIR_CPU_COMPARE_INCOMPATIBLE: CPUCompareResult = 0;
-pub const VIR_CPU_COMPARE_IDENTICAL: CPUCompareResult = 1;
-pub const VIR_CPU_COMPARE_SUPERSET: CPUCompareResult = 2;
-
-pub type BaselineCPUFlags = self::libc::c_int;
-pub const VIR_CONNECT_BASELINE_CPU_EXPAND_FEATURES: BaselineCPUFlags = (1 << 0);
-pub const VIR_CONNECT_BASELINE_CPU_MIGRATABLE: BaselineCPUFlags = (1 << 1);
-
-pub type ConnectCredentialType = self::libc::c_int;
-pub const VIR_CRED_USERNAME: ConnectCredentialType = 1;
-pub const VIR_CRED_AUTHNAME: ConnectCredentialType = 2;
-pub const VIR_CRED_LANGUAGE: ConnectCredentialType = 3;
-pub const VIR_CRED_CNONCE: ConnectCredentialType = 4;
-pub const VIR_CRED_PASSPHRASE: ConnectCredentialType = 5;
-pub const VIR_CRED_ECHOPROMPT: ConnectCredentialType = 6;
-pub const VIR_CRED_NOECHOPROMPT: ConnectCredentialType = 7;
-pub const VIR_CRED_REALM: ConnectCredentialType = 8;
-pub const VIR_CRED_EXTERNAL: ConnectCredentialType = 9;
+#[deprecated(note = "use `BaselineCPUFlags::EXPAND_FEATURES`")]
+pub const VIR_CONNECT_BASELINE_CPU_EXPAND_FEATURES: BaselineCPUFlags =
+    BaselineCPUFlags::EXPAND_FEATURES;
+#[deprecated(note = "use `BaselineCPUFlags::MIGRATABLE`")]
+pub const VIR_CONNECT_BASELINE_CPU_MIGRATABLE: BaselineCPUFlags = BaselineCPUFlags::MIGRATABLE;
+
+/// Kind of credential requested during authentication.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectCredentialType {
+    /// Identity to act as.
+    Username = 1,
+    /// Identify to authorize as.
+    Authname = 2,
+    /// RFC 1766 languages, comma separated.
+    Language = 3,
+    /// Client supplies a nonce.
+    Cnonce = 4,
+    /// Passphrase secret.
+    Passphrase = 5,
+    /// Challenge response, echoed.
+    EchoPrompt = 6,
+    /// Challenge response, not echoed.
+    NoEchoPrompt = 7,
+    /// Authentication realm.
+    Realm = 8,
+    /// Externally managed credential.
+    External = 9,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl ConnectCredentialType {
+    pub fn from_raw(value: i32) -> ConnectCredentialType {
+        match value {
+            1 => ConnectCredentialType::Username,
+            2 => ConnectCredentialType::Authname,
+            3 => ConnectCredentialType::Language,
+            4 => ConnectCredentialType::Cnonce,
+            5 => ConnectCredentialType::Passphrase,
+            6 => ConnectCredentialType::EchoPrompt,
+            7 => ConnectCredentialType::NoEchoPrompt,
+            8 => ConnectCredentialType::Realm,
+            9 => ConnectCredentialType::External,
+            _ => ConnectCredentialType::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `ConnectCredentialType::Username`")]
+pub const VIR_CRED_USERNAME: ConnectCredentialType = ConnectCredentialType::Username;
+#[deprecated(note = "use `ConnectCredentialType::Authname`")]
+pub const VIR_CRED_AUTHNAME: ConnectCredentialType = ConnectCredentialType::Authname;
+#[deprecated(note = "use `ConnectCredentialType::Language`")]
+pub const VIR_CRED_LANGUAGE: ConnectCredentialType = ConnectCredentialType::Language;
+#[deprecated(note = "use `ConnectCredentialType::Cnonce`")]
+pub const VIR_CRED_CNONCE: ConnectCredentialType = ConnectCredentialType::Cnonce;
+#[deprecated(note = "use `ConnectCredentialType::Passphrase`")]
+pub const VIR_CRED_PASSPHRASE: ConnectCredentialType = ConnectCredentialType::Passphrase;
+#[deprecated(note = "use `ConnectCredentialType::EchoPrompt`")]
+pub const VIR_CRED_ECHOPROMPT: ConnectCredentialType = ConnectCredentialType::EchoPrompt;
+#[deprecated(note = "use `ConnectCredentialType::NoEchoPrompt`")]
+pub const VIR_CRED_NOECHOPROMPT: ConnectCredentialType = ConnectCredentialType::NoEchoPrompt;
+#[deprecated(note = "use `ConnectCredentialType::Realm`")]
+pub const VIR_CRED_REALM: ConnectCredentialType = ConnectCredentialType::Realm;
+#[deprecated(note = "use `ConnectCredentialType::External`")]
+pub const VIR_CRED_EXTERNAL: ConnectCredentialType = ConnectCredentialType::External;
+
+/// Why a connection has been closed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Misc I/O error.
+    Error = 0,
+    /// End-of-file from server.
+    Eof = 1,
+    /// Keepalive timer triggered.
+    KeepAlive = 2,
+    /// Client requested it.
+    Client = 3,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl CloseReason {
+    pub fn from_raw(value: i32) -> CloseReason {
+        match value {
+            0 => CloseReason::Error,
+            1 => CloseReason::Eof,
+            2 => CloseReason::KeepAlive,
+            3 => CloseReason::Client,
+            _ => CloseReason::Unknown,
+        }
+    }
+}
+
+/// Callback invoked when a connection is closed.
+pub type ConnectCloseCallback = Box<dyn FnMut(&Connect, CloseReason) + Send>;
 
 #[derive(Clone, Debug)]
 pub struct NodeInfo {
@@ -398,13 +871,31 @@ pub struct NodeInfo {
     pub threads: u32,
 }
 
-// TODO(sahid): should support closure
 pub type ConnectAuthCallback = fn(creds: &mut Vec<ConnectCredential>);
 
+/// Provides the credentials requested by libvirt when opening a
+/// connection with `Connect::open_auth`.
+///
+/// Implemented for closures, stock providers are available in the
+/// `credentials` module.
+pub trait CredentialProvider {
+    /// Fills `result` of the credentials it knows about, credentials
+    /// left without result make the authentication fail.
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>);
+}
+
+impl<F> CredentialProvider for F
+    where F: FnMut(&mut Vec<ConnectCredential>)
+{
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>) {
+        self(creds)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ConnectCredential {
-    /// One of `ConnectCredentialType` constants
-    pub typed: i32,
+    /// Kind of credential requested.
+    pub typed: ConnectCredentialType,
     /// Prompt to show to user.
     pub prompt: String,
     /// Additional challenge to show.
@@ -423,7 +914,7 @@ impl ConnectCredential {
                 default = c_chars_to_string!((*cred).defresult, nofree);
             }
             ConnectCredential {
-                typed: (*cred).typed,
+                typed: ConnectCredentialType::from_raw((*cred).typed),
                 prompt: c_chars_to_string!((*cred).prompt, nofree),
                 challenge: c_chars_to_string!((*cred).challenge, nofree),
                 def_result: default,
@@ -434,17 +925,19 @@ impl ConnectCredential {
 }
 
 pub struct ConnectAuth {
-    /// List of supported `ConnectCredentialType` values.
-    creds: Vec<ConnectCredentialType>,
-    /// Callback used to collect credentials.
-    callback: ConnectAuthCallback,
+    /// List of supported `ConnectCredentialType` raw values.
+    creds: Vec<libc::c_int>,
+    /// Provider used to collect credentials.
+    provider: Box<dyn CredentialProvider + Send>,
 }
 
 impl ConnectAuth {
-    pub fn new(creds: Vec<ConnectCredentialType>, callback: ConnectAuthCallback) -> ConnectAuth {
+    pub fn new<P>(creds: Vec<ConnectCredentialType>, provider: P) -> ConnectAuth
+        where P: CredentialProvider + Send + 'static
+    {
         ConnectAuth {
-            creds: creds,
-            callback: callback,
+            creds: creds.iter().map(|c| c.to_raw()).collect(),
+            provider: Box::new(provider),
         }
     }
 }
@@ -455,15 +948,59 @@ impl ConnectAuth {
 #[derive(Debug)]
 pub struct Connect {
     ptr: Option<sys::virConnectPtr>,
+    id: usize,
 }
 
+impl Drop for Connect {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.close() {
+                panic!("Unable to close Connect, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+impl Clone for Connect {
+    fn clone(&self) -> Connect {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for Connect, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for Connect {}
+unsafe impl Sync for Connect {}
+
 impl Connect {
     pub fn as_ptr(&self) -> sys::virConnectPtr {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the connection and returns a new
+    /// handle on it, the connection is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<Connect, Error> {
+        unsafe {
+            if virConnectRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(Connect::new(self.as_ptr()))
+    }
+
     pub fn new(ptr: sys::virConnectPtr) -> Connect {
-        return Connect { ptr: Some(ptr) };
+        return Connect {
+                   ptr: Some(ptr),
+                   id: CONN_HANDLE_IDS.fetch_add(1, Ordering::Relaxed),
+               };
     }
 
     pub fn get_version() -> Result<u32, Error> {
@@ -556,18 +1093,20 @@ impl Connect {
                      auth: &mut ConnectAuth,
                      flags: ConnectFlags)
                      -> Result<Connect, Error> {
-        let mut cauth = unsafe {
-            // Safe because Rust forces to allocate all attributes of
-            // the struct ConnectAuth.
-            sys::virConnectAuth {
-                credtype: &mut auth.creds[0],
-                ncredtype: auth.creds.len() as libc::c_uint,
-                cb: connectCallback,
-                cbdata: mem::transmute(auth.callback),
-            }
+        let credtype = if auth.creds.is_empty() {
+            ptr::null_mut()
+        } else {
+            auth.creds.as_mut_ptr()
+        };
+        let provider: *mut Box<dyn CredentialProvider + Send> = &mut auth.provider;
+        let mut cauth = sys::virConnectAuth {
+            credtype,
+            ncredtype: auth.creds.len() as libc::c_uint,
+            cb: connectCallback,
+            cbdata: provider as *mut libc::c_void,
         };
         let c = unsafe {
-            virConnectOpenAuth(string_to_c_chars!(uri), &mut cauth, flags as libc::c_uint)
+            virConnectOpenAuth(string_to_c_chars!(uri), &mut cauth, flags.bits())
         };
         if c.is_null() {
             return Err(Error::new());
@@ -575,20 +1114,45 @@ impl Connect {
         return Ok(Connect::new(c));
     }
 
+    /// Opens a connection using the default authentication callback
+    /// of libvirt, which prompts for credentials on the terminal or
+    /// takes them from the client `auth.conf` file.
+    pub fn open_auth_default(uri: &str, flags: ConnectFlags) -> Result<Connect, Error> {
+        let c = unsafe {
+            virConnectOpenAuth(string_to_c_chars!(uri),
+                               virConnectAuthPtrDefault,
+                               flags.bits())
+        };
+        if c.is_null() {
+            return Err(Error::new());
+        }
+        Ok(Connect::new(c))
+    }
 
     /// This function closes the connection to the hypervisor. This
     /// should not be called if further interaction with the
     /// hypervisor are needed especially if there is running domain
     /// which need further monitoring by the application.
+    ///
+    /// The reference held by this handle is released whatever the
+    /// returned value, which is the number of references remaining
+    /// on the connection. The error handler and close callback
+    /// registered through this handle are released as well, even when
+    /// other objects like domains still hold the connection.
     pub fn close(&mut self) -> Result<i32, Error> {
         unsafe {
-            let ret = virConnectClose(self.as_ptr());
+            let ptr = self.as_ptr();
+            if remove_conn_error_func(ptr, Some(self.id)) {
+                virConnSetErrorFunc(ptr, ptr::null_mut(), None);
+            }
+            if remove_conn_close_callback(ptr, Some(self.id)) {
+                virConnectUnregisterCloseCallback(ptr, connectCloseCallback);
+            }
+            let ret = virConnectClose(ptr);
             if ret == -1 {
                 return Err(Error::new());
             }
-            if ret == 0 {
-                self.ptr = None;
-            }
+            self.ptr = None;
             Ok(ret)
         }
     }
@@ -900,7 +1464,7 @@ impl Connect {
                             -> Result<Vec<Domain>, Error> {
         unsafe {
             let mut domains: *mut virDomainPtr = ptr::null_mut();
-            let size = virConnectListAllDomains(self.as_ptr(), &mut domains, flags as libc::c_uint);
+            let size = virConnectListAllDomains(self.as_ptr(), &mut domains, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -923,7 +1487,7 @@ impl Connect {
         unsafe {
             let mut networks: *mut virNetworkPtr = ptr::null_mut();
             let size =
-                virConnectListAllNetworks(self.as_ptr(), &mut networks, flags as libc::c_uint);
+                virConnectListAllNetworks(self.as_ptr(), &mut networks, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -944,7 +1508,7 @@ impl Connect {
         unsafe {
             let mut interfaces: *mut virInterfacePtr = ptr::null_mut();
             let size =
-                virConnectListAllInterfaces(self.as_ptr(), &mut interfaces, flags as libc::c_uint);
+                virConnectListAllInterfaces(self.as_ptr(), &mut interfaces, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -965,7 +1529,7 @@ impl Connect {
         unsafe {
             let mut nodedevs: *mut virNodeDevicePtr = ptr::null_mut();
             let size =
-                virConnectListAllNodeDevices(self.as_ptr(), &mut nodedevs, flags as libc::c_uint);
+                virConnectListAllNodeDevices(self.as_ptr(), &mut nodedevs, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -985,7 +1549,7 @@ impl Connect {
                             -> Result<Vec<Secret>, Error> {
         unsafe {
             let mut secrets: *mut virSecretPtr = ptr::null_mut();
-            let size = virConnectListAllSecrets(self.as_ptr(), &mut secrets, flags as libc::c_uint);
+            let size = virConnectListAllSecrets(self.as_ptr(), &mut secrets, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -1006,7 +1570,7 @@ impl Connect {
         unsafe {
             let mut storages: *mut virStoragePoolPtr = ptr::null_mut();
             let size =
-                virConnectListAllStoragePools(self.as_ptr(), &mut storages, flags as libc::c_uint);
+                virConnectListAllStoragePools(self.as_ptr(), &mut storages, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -1040,6 +1604,28 @@ impl Connect {
         }
     }
 
+    /// Get the bindings of network filters to port devices, one for
+    /// each filtered guest interface.
+    pub fn list_all_nw_filter_bindings(&self, flags: u32) -> Result<Vec<NWFilterBinding>, Error> {
+        unsafe {
+            let mut bindings: *mut virNWFilterBindingPtr = ptr::null_mut();
+            let size = virConnectListAllNWFilterBindings(self.as_ptr(),
+                                                         &mut bindings,
+                                                         flags as libc::c_uint);
+            if size == -1 {
+                return Err(Error::new());
+            }
+
+            let mut array: Vec<NWFilterBinding> = Vec::new();
+            for x in 0..size as isize {
+                array.push(NWFilterBinding::new(*bindings.offset(x)));
+            }
+            libc::free(bindings as *mut libc::c_void);
+
+            Ok(array)
+        }
+    }
+
     ///
     ///
     /// # Examples
@@ -1467,11 +2053,11 @@ impl Connect {
         unsafe {
             let res = virConnectCompareCPU(self.as_ptr(),
                                            string_to_c_chars!(xml),
-                                           flags as libc::c_uint);
-            if res == VIR_CPU_COMPARE_ERROR {
+                                           flags.bits());
+            if res == -1 {
                 return Err(Error::new());
             }
-            return Ok(res as CPUCompareResult);
+            return Ok(CPUCompareResult::from_raw(res));
         }
     }
 
@@ -1505,6 +2091,80 @@ impl Connect {
         }
     }
 
+    /// Installs `handler` to be called with the errors raised by
+    /// this connection, it takes precedence over the handler set
+    /// with `error::set_error_func`.
+    ///
+    /// The handler is shared by all the handles on the connection
+    /// and released once this handle is closed.
+    pub fn set_error_func<F>(&self, handler: F)
+        where F: Fn(&Error) + Send + Sync + 'static
+    {
+        let ptr = self.as_ptr();
+        if let Ok(mut funcs) = CONN_ERROR_FUNCS.lock() {
+            funcs.retain(|f| f.0 != ptr as usize);
+            funcs.push((ptr as usize, self.id, Arc::new(handler)));
+        }
+        unsafe {
+            virConnSetErrorFunc(ptr, ptr as *mut libc::c_void, Some(connErrorCallback));
+        }
+    }
+
+    /// Removes the handler installed with `set_error_func`, errors
+    /// are then reported to the global handler.
+    pub fn reset_error_func(&self) {
+        unsafe {
+            virConnSetErrorFunc(self.as_ptr(), ptr::null_mut(), None);
+        }
+        remove_conn_error_func(self.as_ptr(), None);
+    }
+
+    /// Registers `callback` to be invoked when the connection gets
+    /// closed, because the remote end went away, the keepalive timer
+    /// triggered or `close` was called.
+    ///
+    /// Only one callback can be registered at a time on a
+    /// connection. The callback is invoked from the event loop, see
+    /// `event::register_default_impl`, and is dropped once
+    /// unregistered or when this handle is closed, even if other
+    /// objects like domains still hold the connection.
+    ///
+    /// Close callbacks are only implemented by the remote driver.
+    /// Drivers running in the client process, like `test:///`, accept
+    /// the registration but never invoke the callback nor complain
+    /// about a second registration.
+    pub fn register_close_callback<F>(&self, callback: F) -> Result<(), Error>
+        where F: FnMut(&Connect, CloseReason) + Send + 'static
+    {
+        let ptr = self.as_ptr();
+        unsafe {
+            if virConnectRegisterCloseCallback(ptr,
+                                               connectCloseCallback,
+                                               ptr as *mut libc::c_void,
+                                               None) == -1 {
+                return Err(Error::new());
+            }
+        }
+        let callback: ConnectCloseCallback = Box::new(callback);
+        if let Ok(mut callbacks) = CONN_CLOSE_CALLBACKS.lock() {
+            callbacks.retain(|f| f.0 != ptr as usize);
+            callbacks.push((ptr as usize, self.id, Arc::new(Mutex::new(callback))));
+        }
+        Ok(())
+    }
+
+    /// Unregisters the callback set with `register_close_callback`
+    /// and drops it.
+    pub fn unregister_close_callback(&self) -> Result<(), Error> {
+        unsafe {
+            if virConnectUnregisterCloseCallback(self.as_ptr(), connectCloseCallback) == -1 {
+                return Err(Error::new());
+            }
+        }
+        remove_conn_close_callback(self.as_ptr(), None);
+        Ok(())
+    }
+
     pub fn set_keep_alive(&self, interval: i32, count: u32) -> Result<i32, Error> {
         unsafe {
             let ret = virConnectSetKeepAlive(self.as_ptr(),
@@ -1573,15 +2233,15 @@ impl Connect {
     }
 
     pub fn get_all_domain_stats(&self,
-                                stats: u32,
-                                flags: u32)
+                                stats: DomainStatsTypes,
+                                flags: ConnectGetAllDomainStatsFlags)
                                 -> Result<Vec<DomainStatsRecord>, Error> {
         unsafe {
             let mut record: *mut virDomainStatsRecordPtr = ptr::null_mut();
             let size = virConnectGetAllDomainStats(self.as_ptr(),
-                                                   stats as libc::c_uint,
+                                                   stats.bits(),
                                                    &mut record,
-                                                   flags as libc::c_uint);
+                                                   flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -1605,7 +2265,7 @@ impl Connect {
             let ret = virConnectBaselineCPU(self.as_ptr(),
                                             xcpus.as_ptr(),
                                             xmlcpus.len() as libc::c_uint,
-                                            flags as libc::c_uint);
+                                            flags.bits());
             if ret.is_null() {
                 return Err(Error::new());
             }
@@ -0,0 +1,306 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Long-lived connection reopened on demand.
+//!
+//! `ConnectionManager` keeps the settings needed to open a
+//! connection and hands out `Connect` handles, reopening the
+//! connection with an exponential backoff when libvirt reports it as
+//! closed, for instance after a keepalive failure or a restart of
+//! the daemon.
+//!
+//! Closing notifications and keepalive messages are delivered by the
+//! event loop, see the `event` module.
+//!
+//! ```
+//! use virt::connection_manager::ConnectionManager;
+//!
+//! let manager = ConnectionManager::new("test:///default");
+//! manager.subscribe(|conn| {
+//!     println!("connected to {}", conn.get_uri()?);
+//!     Ok(())
+//! }).unwrap();
+//! if let Ok(conn) = manager.get() {
+//!     assert_eq!(Ok(true), conn.is_alive());
+//! }
+//! ```
+
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use connect::{Connect, ConnectAuth, ConnectFlags};
+use error::Error;
+
+/// Hook invoked with every newly opened connection.
+pub type Subscription = Arc<dyn Fn(&Connect) -> Result<(), Error> + Send + Sync>;
+
+/// Delays between attempts to reopen a connection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backoff {
+    /// Delay before the second attempt, doubled after each failure.
+    pub initial: Duration,
+    /// Upper bound of the delay between two attempts.
+    pub max: Duration,
+    /// Number of attempts before giving up, `None` to retry forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            max_attempts: Some(8),
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay to wait after the failed attempt number `attempt`,
+    /// starting at 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        match self.initial.checked_mul(factor) {
+            Some(delay) if delay < self.max => delay,
+            _ => self.max,
+        }
+    }
+}
+
+struct State {
+    conn: Option<Connect>,
+    /// Set by the close callback of `conn`.
+    closed: Arc<AtomicBool>,
+    auth: Option<ConnectAuth>,
+    generation: u64,
+    /// Set while a thread opens a new connection without holding the
+    /// lock.
+    reconnecting: bool,
+}
+
+/// Opens a connection on demand and reopens it once closed.
+pub struct ConnectionManager {
+    uri: String,
+    flags: ConnectFlags,
+    keep_alive: Option<(i32, u32)>,
+    backoff: Backoff,
+    state: Mutex<State>,
+    /// Notified when `State::reconnecting` is reset.
+    reconnected: Condvar,
+    subscriptions: Mutex<Vec<Subscription>>,
+}
+
+impl ConnectionManager {
+    pub fn new(uri: &str) -> ConnectionManager {
+        ConnectionManager {
+            uri: uri.to_string(),
+            flags: ConnectFlags::empty(),
+            keep_alive: None,
+            backoff: Backoff::default(),
+            state: Mutex::new(State {
+                conn: None,
+                closed: Arc::new(AtomicBool::new(false)),
+                auth: None,
+                generation: 0,
+                reconnecting: false,
+            }),
+            reconnected: Condvar::new(),
+            subscriptions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Opens the connections with `Connect::open_auth`.
+    pub fn auth(self, auth: ConnectAuth) -> ConnectionManager {
+        self.state.lock().unwrap().auth = Some(auth);
+        self
+    }
+
+    /// Opens read-only connections.
+    pub fn read_only(mut self) -> ConnectionManager {
+        self.flags |= ConnectFlags::RO;
+        self
+    }
+
+    /// Enables keepalive messages on the connections, see
+    /// `Connect::set_keep_alive`.
+    pub fn keep_alive(mut self, interval: i32, count: u32) -> ConnectionManager {
+        self.keep_alive = Some((interval, count));
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> ConnectionManager {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn get_uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Number of connections opened so far.
+    pub fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Registers `hook` to be called with every connection opened by
+    /// the manager, typically to register event callbacks again. If
+    /// a connection is already opened the hook is called right away.
+    ///
+    /// A hook returning an error makes the opening fail. Hooks must
+    /// not call back into the manager.
+    pub fn subscribe<F>(&self, hook: F) -> Result<(), Error>
+        where F: Fn(&Connect) -> Result<(), Error> + Send + Sync + 'static
+    {
+        let hook: Subscription = Arc::new(hook);
+        // A connection being reopened may have been given the hooks
+        // already, wait for it so that the hook is called with it.
+        let state = self.wait_reconnected();
+        self.subscriptions.lock().unwrap().push(hook.clone());
+        match state.conn {
+            Some(ref conn) => hook(conn),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a handle on the managed connection, opening it first
+    /// if needed.
+    ///
+    /// The connection is reopened when it has been closed by
+    /// libvirt, retrying according to the backoff settings. The
+    /// error of the last attempt is returned if none succeeded.
+    ///
+    /// When another thread is already reopening the connection, waits
+    /// for it to finish instead of opening a second one.
+    pub fn get(&self) -> Result<Connect, Error> {
+        let state = self.wait_reconnected();
+        if let Some(ref conn) = state.conn {
+            if !state.closed.load(Ordering::SeqCst) && conn.is_alive() == Ok(true) {
+                return Ok(conn.clone());
+            }
+        }
+        self.reopen(state)
+    }
+
+    /// Closes the managed connection and opens a new one.
+    pub fn reconnect(&self) -> Result<Connect, Error> {
+        let state = self.wait_reconnected();
+        self.reopen(state)
+    }
+
+    fn wait_reconnected(&self) -> MutexGuard<'_, State> {
+        let mut state = self.state.lock().unwrap();
+        while state.reconnecting {
+            state = self.reconnected.wait(state).unwrap();
+        }
+        state
+    }
+
+    /// Closes the managed connection, the next call to `get` opens a
+    /// new one.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        ConnectionManager::release(&mut state);
+    }
+
+    fn release(state: &mut State) {
+        if let Some(mut conn) = state.conn.take() {
+            let _ = conn.unregister_close_callback();
+            if conn.close().is_err() {
+                // Not much to do with a connection which can't be
+                // closed, avoid panicking on drop.
+                mem::forget(conn);
+            }
+        }
+    }
+
+    fn reopen(&self, mut state: MutexGuard<State>) -> Result<Connect, Error> {
+        ConnectionManager::release(&mut state);
+        state.reconnecting = true;
+        let mut auth = state.auth.take();
+        // Opening and waiting between attempts can take long, the lock
+        // is released meanwhile so that `generation` and `close` do not
+        // block, concurrent `get` calls wait on `reconnected`.
+        drop(state);
+
+        let res = self.connect(&mut auth);
+
+        let mut state = self.state.lock().unwrap();
+        state.auth = auth;
+        state.reconnecting = false;
+        self.reconnected.notify_all();
+        let (conn, closed) = res?;
+        state.closed = closed;
+        state.generation += 1;
+        // The close callback lives as long as the handle which
+        // registered it, that one is kept and callers get a clone.
+        let handle = conn.clone();
+        state.conn = Some(conn);
+        Ok(handle)
+    }
+
+    fn connect(&self,
+               auth: &mut Option<ConnectAuth>)
+               -> Result<(Connect, Arc<AtomicBool>), Error> {
+        let mut attempt = 0;
+        let conn = loop {
+            match self.open(auth) {
+                Ok(conn) => break conn,
+                Err(e) => {
+                    if let Some(max) = self.backoff.max_attempts {
+                        if attempt + 1 >= max {
+                            return Err(e);
+                        }
+                    }
+                    thread::sleep(self.backoff.delay(attempt));
+                    attempt += 1;
+                }
+            }
+        };
+
+        let closed = Arc::new(AtomicBool::new(false));
+        let flag = closed.clone();
+        conn.register_close_callback(move |_, _| flag.store(true, Ordering::SeqCst))?;
+        for hook in self.subscriptions.lock().unwrap().iter() {
+            hook(&conn)?;
+        }
+        Ok((conn, closed))
+    }
+
+    fn open(&self, auth: &mut Option<ConnectAuth>) -> Result<Connect, Error> {
+        let conn = match *auth {
+            Some(ref mut auth) => Connect::open_auth(&self.uri, auth, self.flags)?,
+            None if self.flags.contains(ConnectFlags::RO) => Connect::open_read_only(&self.uri)?,
+            None => Connect::open(&self.uri)?,
+        };
+        if let Some((interval, count)) = self.keep_alive {
+            conn.set_keep_alive(interval, count)?;
+        }
+        Ok(conn)
+    }
+}
+
+impl Drop for ConnectionManager {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            ConnectionManager::release(&mut state);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Stock implementations of `CredentialProvider`.
+//!
+//! ```no_run
+//! use virt::connect::{Connect, ConnectAuth, ConnectCredentialType, ConnectFlags};
+//! use virt::credentials::EnvCredentials;
+//!
+//! let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname,
+//!                                      ConnectCredentialType::Passphrase],
+//!                                 EnvCredentials::new());
+//! let conn = Connect::open_auth("qemu+tcp://host/system", &mut auth, ConnectFlags::empty());
+//! ```
+
+extern crate libc;
+
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use connect::{ConnectCredential, ConnectCredentialType, CredentialProvider};
+
+fn is_username(typed: ConnectCredentialType) -> bool {
+    typed == ConnectCredentialType::Username || typed == ConnectCredentialType::Authname
+}
+
+fn is_secret(typed: ConnectCredentialType) -> bool {
+    typed == ConnectCredentialType::Passphrase || typed == ConnectCredentialType::NoEchoPrompt
+}
+
+/// Sets the result of each credential to the username or password
+/// given, falling back on the default result proposed by libvirt.
+fn fill(creds: &mut [ConnectCredential], username: Option<String>, password: Option<String>) {
+    for cred in creds.iter_mut() {
+        let value = if is_username(cred.typed) {
+            username.clone()
+        } else if is_secret(cred.typed) {
+            password.clone()
+        } else {
+            None
+        };
+        cred.result = value.or_else(|| if cred.def_result.is_empty() {
+            None
+        } else {
+            Some(cred.def_result.clone())
+        });
+    }
+}
+
+/// Username and password known in advance.
+#[derive(Clone)]
+pub struct StaticCredentials {
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl StaticCredentials {
+    pub fn new(username: &str, password: &str) -> StaticCredentials {
+        StaticCredentials {
+            username: Some(username.to_string()),
+            password: Some(password.to_string()),
+        }
+    }
+
+    /// Only provides a password, the username is left to its default.
+    pub fn password(password: &str) -> StaticCredentials {
+        StaticCredentials {
+            username: None,
+            password: Some(password.to_string()),
+        }
+    }
+}
+
+impl CredentialProvider for StaticCredentials {
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>) {
+        fill(creds, self.username.clone(), self.password.clone())
+    }
+}
+
+/// Username and password read from environment variables, by default
+/// `LIBVIRT_AUTH_USERNAME` and `LIBVIRT_AUTH_PASSWORD`.
+#[derive(Clone, Debug)]
+pub struct EnvCredentials {
+    username_var: String,
+    password_var: String,
+}
+
+impl EnvCredentials {
+    pub fn new() -> EnvCredentials {
+        EnvCredentials::with_vars("LIBVIRT_AUTH_USERNAME", "LIBVIRT_AUTH_PASSWORD")
+    }
+
+    pub fn with_vars(username_var: &str, password_var: &str) -> EnvCredentials {
+        EnvCredentials {
+            username_var: username_var.to_string(),
+            password_var: password_var.to_string(),
+        }
+    }
+}
+
+impl Default for EnvCredentials {
+    fn default() -> EnvCredentials {
+        EnvCredentials::new()
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>) {
+        fill(creds,
+             env::var(&self.username_var).ok(),
+             env::var(&self.password_var).ok())
+    }
+}
+
+/// Username and password read from a file of `key=value` lines with
+/// the keys `username` and `password`. Empty lines and lines starting
+/// with `#` are ignored.
+///
+/// The file is read each time credentials are requested so that it
+/// can be updated between two connections.
+#[derive(Clone, Debug)]
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new<P: AsRef<Path>>(path: P) -> FileCredentials {
+        FileCredentials { path: path.as_ref().to_path_buf() }
+    }
+
+    fn read(&self) -> (Option<String>, Option<String>) {
+        let mut username = None;
+        let mut password = None;
+        if let Ok(file) = File::open(&self.path) {
+            for line in BufReader::new(file).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                if let Some(pos) = line.find('=') {
+                    let value = Some(line[pos + 1..].trim().to_string());
+                    match line[..pos].trim() {
+                        "username" => username = value,
+                        "password" => password = value,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        (username, password)
+    }
+}
+
+impl CredentialProvider for FileCredentials {
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>) {
+        let (username, password) = self.read();
+        fill(creds, username, password)
+    }
+}
+
+/// Prompts for credentials on the controlling terminal, the input is
+/// not echoed for `ConnectCredentialType::Passphrase` and
+/// `ConnectCredentialType::NoEchoPrompt`.
+#[derive(Clone, Debug, Default)]
+pub struct TtyCredentials;
+
+impl TtyCredentials {
+    pub fn new() -> TtyCredentials {
+        TtyCredentials
+    }
+
+    fn prompt(tty: &mut File, cred: &ConnectCredential) -> Option<String> {
+        let mut prompt = cred.prompt.clone();
+        if !cred.def_result.is_empty() {
+            prompt = format!("{} [{}]", prompt, cred.def_result);
+        }
+        write!(tty, "{}: ", prompt).ok()?;
+        tty.flush().ok()?;
+
+        let echo = !is_secret(cred.typed);
+        let fd = tty.as_raw_fd();
+        let mut term: libc::termios = unsafe { ::std::mem::zeroed() };
+        let restore = !echo && unsafe { libc::tcgetattr(fd, &mut term) } == 0;
+        if restore {
+            let mut noecho = term;
+            noecho.c_lflag &= !libc::ECHO;
+            unsafe { libc::tcsetattr(fd, libc::TCSAFLUSH, &noecho) };
+        }
+        let line = TtyCredentials::read_line(tty);
+        if restore {
+            unsafe { libc::tcsetattr(fd, libc::TCSAFLUSH, &term) };
+            let _ = writeln!(tty);
+        }
+
+        match line {
+            Some(ref input) if input.is_empty() && !cred.def_result.is_empty() => {
+                Some(cred.def_result.clone())
+            }
+            line => line,
+        }
+    }
+
+    fn read_line(tty: &mut File) -> Option<String> {
+        let mut line = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            match tty.read(&mut byte) {
+                Ok(1) if byte[0] != b'\n' => line.push(byte[0]),
+                Ok(1) => break,
+                Ok(_) if !line.is_empty() => break,
+                _ => return None,
+            }
+        }
+        String::from_utf8(line).ok().map(|l| l.trim_end_matches('\r').to_string())
+    }
+}
+
+impl CredentialProvider for TtyCredentials {
+    fn provide(&mut self, creds: &mut Vec<ConnectCredential>) {
+        let mut tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+            Ok(tty) => tty,
+            Err(_) => return,
+        };
+        for cred in creds.iter_mut() {
+            cred.result = TtyCredentials::prompt(&mut tty, cred);
+        }
+    }
+}
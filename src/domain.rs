@@ -19,17 +19,21 @@
 extern crate libc;
 
 use std::ffi::CStr;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::{str, ptr, mem};
 
 use connect::sys::virConnectPtr;
+use domain_checkpoint::sys::virDomainCheckpointPtr;
 use domain_snapshot::sys::virDomainSnapshotPtr;
 use stream::sys::virStreamPtr;
 use typedparam::sys::{virTypedParameterPtr, virTypedParameter};
 
 use connect::Connect;
-use domain_snapshot::DomainSnapshot;
+use domain_checkpoint::{DomainCheckpoint, DomainCheckpointListFlags};
+use domain_snapshot::{DomainSnapshot, DomainSnapshotListFlags};
 use error::Error;
 use stream::Stream;
+use typedparam::TypedParameterType;
 
 pub mod sys {
     extern crate libc;
@@ -53,6 +57,16 @@ pub mod sys {
 
     pub type virDomainInfoPtr = *mut virDomainInfo;
 
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct virDomainControlInfo {
+        pub state: libc::c_uint,
+        pub details: libc::c_uint,
+        pub stateTime: libc::c_ulonglong,
+    }
+
+    pub type virDomainControlInfoPtr = *mut virDomainControlInfo;
+
     #[repr(C)]
     pub struct virDomainStatsRecord {
         pub dom: virDomainPtr,
@@ -119,6 +133,7 @@ extern "C" {
     fn virDomainDestroyFlags(ptr: sys::virDomainPtr, flags: libc::c_uint) -> libc::c_int;
     fn virDomainUndefine(ptr: sys::virDomainPtr) -> libc::c_int;
     fn virDomainFree(ptr: sys::virDomainPtr) -> libc::c_int;
+    fn virDomainRef(ptr: sys::virDomainPtr) -> libc::c_int;
     fn virDomainShutdown(ptr: sys::virDomainPtr) -> libc::c_int;
     fn virDomainReboot(ptr: sys::virDomainPtr) -> libc::c_int;
     fn virDomainSuspend(ptr: sys::virDomainPtr) -> libc::c_int;
@@ -163,7 +178,12 @@ extern "C" {
                              flags: libc::c_uint)
                              -> libc::c_int;
     fn virDomainGetConnect(ptr: sys::virDomainPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
     fn virDomainGetInfo(ptr: sys::virDomainPtr, ninfo: sys::virDomainInfoPtr) -> libc::c_int;
+    fn virDomainGetControlInfo(ptr: sys::virDomainPtr,
+                               info: sys::virDomainControlInfoPtr,
+                               flags: libc::c_uint)
+                               -> libc::c_int;
     fn virDomainMigrateSetMaxSpeed(ptr: sys::virDomainPtr,
                                    bandwidth: libc::c_ulong,
                                    flags: libc::c_uint)
@@ -363,90 +383,936 @@ extern "C" {
                                  snaps: *mut *mut virDomainSnapshotPtr,
                                  flags: libc::c_uint)
                                  -> libc::c_int;
+    fn virDomainListAllCheckpoints(ptr: sys::virDomainPtr,
+                                   checkpoints: *mut *mut virDomainCheckpointPtr,
+                                   flags: libc::c_uint)
+                                   -> libc::c_int;
+    fn virDomainGetJobStats(ptr: sys::virDomainPtr,
+                            kind: *mut libc::c_int,
+                            params: *mut virTypedParameterPtr,
+                            nparams: *mut libc::c_int,
+                            flags: libc::c_uint)
+                            -> libc::c_int;
+    fn virDomainAbortJob(ptr: sys::virDomainPtr) -> libc::c_int;
+    fn virDomainBackupBegin(ptr: sys::virDomainPtr,
+                            backup_xml: *const libc::c_char,
+                            checkpoint_xml: *const libc::c_char,
+                            flags: libc::c_uint)
+                            -> libc::c_int;
+    fn virDomainBackupGetXMLDesc(ptr: sys::virDomainPtr, flags: libc::c_uint) -> *mut libc::c_char;
+    fn virTypedParamsFree(params: virTypedParameterPtr, nparams: libc::c_int);
+}
+
+bitflags! {
+    /// Flags for `Domain::get_xml_desc`.
+    pub struct DomainXMLFlags: libc::c_uint {
+        const SECURE = 1 << 0;
+        const INACTIVE = 1 << 1;
+        const UPDATE_CPU = 1 << 2;
+        const MIGRATABLE = 1 << 3;
+    }
+}
+
+#[deprecated(note = "use `DomainXMLFlags::SECURE`")]
+pub const VIR_DOMAIN_XML_SECURE: DomainXMLFlags = DomainXMLFlags::SECURE;
+#[deprecated(note = "use `DomainXMLFlags::INACTIVE`")]
+pub const VIR_DOMAIN_XML_INACTIVE: DomainXMLFlags = DomainXMLFlags::INACTIVE;
+#[deprecated(note = "use `DomainXMLFlags::UPDATE_CPU`")]
+pub const VIR_DOMAIN_XML_UPDATE_CPU: DomainXMLFlags = DomainXMLFlags::UPDATE_CPU;
+#[deprecated(note = "use `DomainXMLFlags::MIGRATABLE`")]
+pub const VIR_DOMAIN_XML_MIGRATABLE: DomainXMLFlags = DomainXMLFlags::MIGRATABLE;
+
+bitflags! {
+    /// Flags for starting a domain.
+    pub struct DomainCreateFlags: libc::c_uint {
+        const NONE = 0;
+        const PAUSED = 1 << 0;
+        const AUTODESTROY = 1 << 1;
+        const BYPASS_CACHE = 1 << 2;
+        const FORCE_BOOT = 1 << 3;
+        const VALIDATE = 1 << 4;
+    }
+}
+
+#[deprecated(note = "use `DomainCreateFlags::NONE`")]
+pub const VIR_DOMAIN_NONE: DomainCreateFlags = DomainCreateFlags::NONE;
+#[deprecated(note = "use `DomainCreateFlags::PAUSED`")]
+pub const VIR_DOMAIN_START_PAUSED: DomainCreateFlags = DomainCreateFlags::PAUSED;
+#[deprecated(note = "use `DomainCreateFlags::AUTODESTROY`")]
+pub const VIR_DOMAIN_START_AUTODESTROY: DomainCreateFlags = DomainCreateFlags::AUTODESTROY;
+#[deprecated(note = "use `DomainCreateFlags::BYPASS_CACHE`")]
+pub const VIR_DOMAIN_START_BYPASS_CACHE: DomainCreateFlags = DomainCreateFlags::BYPASS_CACHE;
+#[deprecated(note = "use `DomainCreateFlags::FORCE_BOOT`")]
+pub const VIR_DOMAIN_START_FORCE_BOOT: DomainCreateFlags = DomainCreateFlags::FORCE_BOOT;
+#[deprecated(note = "use `DomainCreateFlags::VALIDATE`")]
+pub const VIR_DOMAIN_START_VALIDATE: DomainCreateFlags = DomainCreateFlags::VALIDATE;
+
+bitflags! {
+    /// Flags for `Domain::destroy_flags`.
+    pub struct DomainDestroyFlags: libc::c_uint {
+        const DEFAULT = 0;
+        const GRACEFUL = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `DomainDestroyFlags::DEFAULT`")]
+pub const VIR_DOMAIN_DESTROY_DEFAULT: DomainDestroyFlags = DomainDestroyFlags::DEFAULT;
+#[deprecated(note = "use `DomainDestroyFlags::GRACEFUL`")]
+pub const VIR_DOMAIN_DESTROY_GRACEFUL: DomainDestroyFlags = DomainDestroyFlags::GRACEFUL;
+
+bitflags! {
+    /// Whether a change applies to the live domain, its persistent
+    /// config or both.
+    pub struct DomainModImpactFlags: libc::c_uint {
+        const CURRENT = 0;
+        const LIVE = 1 << 0;
+        const CONFIG = 1 << 1;
+    }
 }
 
-pub type DomainXMLFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_XML_SECURE: DomainXMLFlags = 1 << 0;
-pub const VIR_DOMAIN_XML_INACTIVE: DomainXMLFlags = 1 << 1;
-pub const VIR_DOMAIN_XML_UPDATE_CPU: DomainXMLFlags = 1 << 2;
-pub const VIR_DOMAIN_XML_MIGRATABLE: DomainXMLFlags = 1 << 3;
-
-pub type DomainCreateFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_NONE: DomainCreateFlags = 0;
-pub const VIR_DOMAIN_START_PAUSED: DomainCreateFlags = 1 << 0;
-pub const VIR_DOMAIN_START_AUTODESTROY: DomainCreateFlags = 1 << 1;
-pub const VIR_DOMAIN_START_BYPASS_CACHE: DomainCreateFlags = 1 << 2;
-pub const VIR_DOMAIN_START_FORCE_BOOT: DomainCreateFlags = 1 << 3;
-pub const VIR_DOMAIN_START_VALIDATE: DomainCreateFlags = 1 << 4;
-
-pub type DomainDestroyFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_DESTROY_DEFAULT: DomainDestroyFlags = 0;
-pub const VIR_DOMAIN_DESTROY_GRACEFUL: DomainDestroyFlags = 1 << 0;
-
-pub type DomainModImpactFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_AFFECT_CURRENT: DomainModImpactFlags = 0;
-pub const VIR_DOMAIN_AFFECT_LIVE: DomainModImpactFlags = 1 << 0;
-pub const VIR_DOMAIN_AFFECT_CONFIG: DomainModImpactFlags = 1 << 1;
-
-pub type DomainMemoryModFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_MEM_CURRENT: DomainMemoryModFlags = VIR_DOMAIN_AFFECT_CURRENT;
-pub const VIR_DOMAIN_MEM_LIVE: DomainMemoryModFlags = VIR_DOMAIN_AFFECT_LIVE;
-pub const VIR_DOMAIN_MEM_CONFIG: DomainMemoryModFlags = VIR_DOMAIN_AFFECT_CONFIG;
-pub const VIR_DOMAIN_MEM_MAXIMUM: DomainMemoryModFlags = 1 << 2;
-
-pub type DomainVcpuFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_VCPU_CURRENT: DomainVcpuFlags = VIR_DOMAIN_AFFECT_CURRENT;
-pub const VIR_DOMAIN_VCPU_LIVE: DomainVcpuFlags = VIR_DOMAIN_AFFECT_LIVE;
-pub const VIR_DOMAIN_VCPU_CONFIG: DomainVcpuFlags = VIR_DOMAIN_AFFECT_CONFIG;
-pub const VIR_DOMAIN_VCPU_MAXIMUM: DomainVcpuFlags = 1 << 2;
-pub const VIR_DOMAIN_VCPU_GUEST: DomainVcpuFlags = 1 << 3;
-pub const VIR_DOMAIN_VCPU_HOTPLUGGABLE: DomainVcpuFlags = 1 << 4;
-
-pub type DomainMigrateFlags = self::libc::c_uint;
-pub const VIR_MIGRATE_LIVE: DomainMigrateFlags = 1 << 0;
-pub const VIR_MIGRATE_PEER2PEER: DomainMigrateFlags = 1 << 1;
-pub const VIR_MIGRATE_TUNNELLED: DomainMigrateFlags = 1 << 2;
-pub const VIR_MIGRATE_PERSIST_DEST: DomainMigrateFlags = 1 << 3;
-pub const VIR_MIGRATE_UNDEFINE_SOURCE: DomainMigrateFlags = 1 << 4;
-pub const VIR_MIGRATE_PAUSED: DomainMigrateFlags = 1 << 5;
-pub const VIR_MIGRATE_NON_SHARED_DISK: DomainMigrateFlags = 1 << 6;
-pub const VIR_MIGRATE_NON_SHARED_INC: DomainMigrateFlags = 1 << 7;
-pub const VIR_MIGRATE_CHANGE_PROTECTION: DomainMigrateFlags = 1 << 8;
-pub const VIR_MIGRATE_UNSAFE: DomainMigrateFlags = 1 << 9;
-pub const VIR_MIGRATE_OFFLINE: DomainMigrateFlags = 1 << 10;
-pub const VIR_MIGRATE_COMPRESSED: DomainMigrateFlags = 1 << 11;
-pub const VIR_MIGRATE_ABORT_ON_ERROR: DomainMigrateFlags = 1 << 12;
-pub const VIR_MIGRATE_AUTO_CONVERGE: DomainMigrateFlags = 1 << 13;
-pub const VIR_MIGRATE_RDMA_PIN_ALL: DomainMigrateFlags = 1 << 14;
-pub const VIR_MIGRATE_POSTCOPY: DomainMigrateFlags = 1 << 15;
-pub const VIR_MIGRATE_TLS: DomainMigrateFlags = 1 << 16;
-
-pub type DomainDefineFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_DEFINE_VALIDATE: DomainDefineFlags = 1 << 0;
-
-pub type DomainSaveRestoreFlags = self::libc::c_uint;
-pub const VIR_DOMAIN_SAVE_BYPASS_CACHE: DomainSaveRestoreFlags = 1 << 0;
-pub const VIR_DOMAIN_SAVE_RUNNING: DomainSaveRestoreFlags = 1 << 1;
-pub const VIR_DOMAIN_SAVE_PAUSED: DomainSaveRestoreFlags = 1 << 2;
-
-pub type DomainNumatuneMemMode = self::libc::c_int;
-pub const VIR_DOMAIN_NUMATUNE_MEM_STRICT: DomainNumatuneMemMode = 0;
-pub const VIR_DOMAIN_NUMATUNE_MEM_PREFERRED: DomainNumatuneMemMode = 1;
-pub const VIR_DOMAIN_NUMATUNE_MEM_INTERLEAVE: DomainNumatuneMemMode = 2;
-
-pub type DomainState = self::libc::c_uint;
-pub const VIR_DOMAIN_NOSTATE: DomainState = 0;
-pub const VIR_DOMAIN_RUNNING: DomainState = 1;
-pub const VIR_DOMAIN_BLOCKED: DomainState = 2;
-pub const VIR_DOMAIN_PAUSED: DomainState = 3;
-pub const VIR_DOMAIN_SHUTDOWN: DomainState = 4;
-pub const VIR_DOMAIN_SHUTOFF: DomainState = 5;
-pub const VIR_DOMAIN_CRASHED: DomainState = 6;
-pub const VIR_DOMAIN_PMSUSPENDED: DomainState = 7;
+#[deprecated(note = "use `DomainModImpactFlags::CURRENT`")]
+pub const VIR_DOMAIN_AFFECT_CURRENT: DomainModImpactFlags = DomainModImpactFlags::CURRENT;
+#[deprecated(note = "use `DomainModImpactFlags::LIVE`")]
+pub const VIR_DOMAIN_AFFECT_LIVE: DomainModImpactFlags = DomainModImpactFlags::LIVE;
+#[deprecated(note = "use `DomainModImpactFlags::CONFIG`")]
+pub const VIR_DOMAIN_AFFECT_CONFIG: DomainModImpactFlags = DomainModImpactFlags::CONFIG;
+
+bitflags! {
+    /// Flags for `Domain::set_memory_flags`.
+    pub struct DomainMemoryModFlags: libc::c_uint {
+        const CURRENT = 0;
+        const LIVE = 1 << 0;
+        const CONFIG = 1 << 1;
+        const MAXIMUM = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `DomainMemoryModFlags::CURRENT`")]
+pub const VIR_DOMAIN_MEM_CURRENT: DomainMemoryModFlags = DomainMemoryModFlags::CURRENT;
+#[deprecated(note = "use `DomainMemoryModFlags::LIVE`")]
+pub const VIR_DOMAIN_MEM_LIVE: DomainMemoryModFlags = DomainMemoryModFlags::LIVE;
+#[deprecated(note = "use `DomainMemoryModFlags::CONFIG`")]
+pub const VIR_DOMAIN_MEM_CONFIG: DomainMemoryModFlags = DomainMemoryModFlags::CONFIG;
+#[deprecated(note = "use `DomainMemoryModFlags::MAXIMUM`")]
+pub const VIR_DOMAIN_MEM_MAXIMUM: DomainMemoryModFlags = DomainMemoryModFlags::MAXIMUM;
+
+bitflags! {
+    /// Flags for `Domain::set_vcpus_flags` and `Domain::get_vcpus_flags`.
+    pub struct DomainVcpuFlags: libc::c_uint {
+        const CURRENT = 0;
+        const LIVE = 1 << 0;
+        const CONFIG = 1 << 1;
+        const MAXIMUM = 1 << 2;
+        const GUEST = 1 << 3;
+        const HOTPLUGGABLE = 1 << 4;
+    }
+}
+
+#[deprecated(note = "use `DomainVcpuFlags::CURRENT`")]
+pub const VIR_DOMAIN_VCPU_CURRENT: DomainVcpuFlags = DomainVcpuFlags::CURRENT;
+#[deprecated(note = "use `DomainVcpuFlags::LIVE`")]
+pub const VIR_DOMAIN_VCPU_LIVE: DomainVcpuFlags = DomainVcpuFlags::LIVE;
+#[deprecated(note = "use `DomainVcpuFlags::CONFIG`")]
+pub const VIR_DOMAIN_VCPU_CONFIG: DomainVcpuFlags = DomainVcpuFlags::CONFIG;
+#[deprecated(note = "use `DomainVcpuFlags::MAXIMUM`")]
+pub const VIR_DOMAIN_VCPU_MAXIMUM: DomainVcpuFlags = DomainVcpuFlags::MAXIMUM;
+#[deprecated(note = "use `DomainVcpuFlags::GUEST`")]
+pub const VIR_DOMAIN_VCPU_GUEST: DomainVcpuFlags = DomainVcpuFlags::GUEST;
+#[deprecated(note = "use `DomainVcpuFlags::HOTPLUGGABLE`")]
+pub const VIR_DOMAIN_VCPU_HOTPLUGGABLE: DomainVcpuFlags = DomainVcpuFlags::HOTPLUGGABLE;
+
+bitflags! {
+    /// Flags for the migration methods of `Domain`.
+    pub struct DomainMigrateFlags: libc::c_uint {
+        const LIVE = 1 << 0;
+        const PEER2PEER = 1 << 1;
+        const TUNNELLED = 1 << 2;
+        const PERSIST_DEST = 1 << 3;
+        const UNDEFINE_SOURCE = 1 << 4;
+        const PAUSED = 1 << 5;
+        const NON_SHARED_DISK = 1 << 6;
+        const NON_SHARED_INC = 1 << 7;
+        const CHANGE_PROTECTION = 1 << 8;
+        const UNSAFE = 1 << 9;
+        const OFFLINE = 1 << 10;
+        const COMPRESSED = 1 << 11;
+        const ABORT_ON_ERROR = 1 << 12;
+        const AUTO_CONVERGE = 1 << 13;
+        const RDMA_PIN_ALL = 1 << 14;
+        const POSTCOPY = 1 << 15;
+        const TLS = 1 << 16;
+    }
+}
+
+#[deprecated(note = "use `DomainMigrateFlags::LIVE`")]
+pub const VIR_MIGRATE_LIVE: DomainMigrateFlags = DomainMigrateFlags::LIVE;
+#[deprecated(note = "use `DomainMigrateFlags::PEER2PEER`")]
+pub const VIR_MIGRATE_PEER2PEER: DomainMigrateFlags = DomainMigrateFlags::PEER2PEER;
+#[deprecated(note = "use `DomainMigrateFlags::TUNNELLED`")]
+pub const VIR_MIGRATE_TUNNELLED: DomainMigrateFlags = DomainMigrateFlags::TUNNELLED;
+#[deprecated(note = "use `DomainMigrateFlags::PERSIST_DEST`")]
+pub const VIR_MIGRATE_PERSIST_DEST: DomainMigrateFlags = DomainMigrateFlags::PERSIST_DEST;
+#[deprecated(note = "use `DomainMigrateFlags::UNDEFINE_SOURCE`")]
+pub const VIR_MIGRATE_UNDEFINE_SOURCE: DomainMigrateFlags = DomainMigrateFlags::UNDEFINE_SOURCE;
+#[deprecated(note = "use `DomainMigrateFlags::PAUSED`")]
+pub const VIR_MIGRATE_PAUSED: DomainMigrateFlags = DomainMigrateFlags::PAUSED;
+#[deprecated(note = "use `DomainMigrateFlags::NON_SHARED_DISK`")]
+pub const VIR_MIGRATE_NON_SHARED_DISK: DomainMigrateFlags = DomainMigrateFlags::NON_SHARED_DISK;
+#[deprecated(note = "use `DomainMigrateFlags::NON_SHARED_INC`")]
+pub const VIR_MIGRATE_NON_SHARED_INC: DomainMigrateFlags = DomainMigrateFlags::NON_SHARED_INC;
+#[deprecated(note = "use `DomainMigrateFlags::CHANGE_PROTECTION`")]
+pub const VIR_MIGRATE_CHANGE_PROTECTION: DomainMigrateFlags = DomainMigrateFlags::CHANGE_PROTECTION;
+#[deprecated(note = "use `DomainMigrateFlags::UNSAFE`")]
+pub const VIR_MIGRATE_UNSAFE: DomainMigrateFlags = DomainMigrateFlags::UNSAFE;
+#[deprecated(note = "use `DomainMigrateFlags::OFFLINE`")]
+pub const VIR_MIGRATE_OFFLINE: DomainMigrateFlags = DomainMigrateFlags::OFFLINE;
+#[deprecated(note = "use `DomainMigrateFlags::COMPRESSED`")]
+pub const VIR_MIGRATE_COMPRESSED: DomainMigrateFlags = DomainMigrateFlags::COMPRESSED;
+#[deprecated(note = "use `DomainMigrateFlags::ABORT_ON_ERROR`")]
+pub const VIR_MIGRATE_ABORT_ON_ERROR: DomainMigrateFlags = DomainMigrateFlags::ABORT_ON_ERROR;
+#[deprecated(note = "use `DomainMigrateFlags::AUTO_CONVERGE`")]
+pub const VIR_MIGRATE_AUTO_CONVERGE: DomainMigrateFlags = DomainMigrateFlags::AUTO_CONVERGE;
+#[deprecated(note = "use `DomainMigrateFlags::RDMA_PIN_ALL`")]
+pub const VIR_MIGRATE_RDMA_PIN_ALL: DomainMigrateFlags = DomainMigrateFlags::RDMA_PIN_ALL;
+#[deprecated(note = "use `DomainMigrateFlags::POSTCOPY`")]
+pub const VIR_MIGRATE_POSTCOPY: DomainMigrateFlags = DomainMigrateFlags::POSTCOPY;
+#[deprecated(note = "use `DomainMigrateFlags::TLS`")]
+pub const VIR_MIGRATE_TLS: DomainMigrateFlags = DomainMigrateFlags::TLS;
+
+bitflags! {
+    /// Flags for `Domain::define_xml_flags`.
+    pub struct DomainDefineFlags: libc::c_uint {
+        const VALIDATE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `DomainDefineFlags::VALIDATE`")]
+pub const VIR_DOMAIN_DEFINE_VALIDATE: DomainDefineFlags = DomainDefineFlags::VALIDATE;
+
+bitflags! {
+    /// Flags for saving and restoring a domain.
+    pub struct DomainSaveRestoreFlags: libc::c_uint {
+        const BYPASS_CACHE = 1 << 0;
+        const RUNNING = 1 << 1;
+        const PAUSED = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `DomainSaveRestoreFlags::BYPASS_CACHE`")]
+pub const VIR_DOMAIN_SAVE_BYPASS_CACHE: DomainSaveRestoreFlags =
+    DomainSaveRestoreFlags::BYPASS_CACHE;
+#[deprecated(note = "use `DomainSaveRestoreFlags::RUNNING`")]
+pub const VIR_DOMAIN_SAVE_RUNNING: DomainSaveRestoreFlags = DomainSaveRestoreFlags::RUNNING;
+#[deprecated(note = "use `DomainSaveRestoreFlags::PAUSED`")]
+pub const VIR_DOMAIN_SAVE_PAUSED: DomainSaveRestoreFlags = DomainSaveRestoreFlags::PAUSED;
+
+bitflags! {
+    /// Flags for `Domain::core_dump` and `Domain::core_dump_with_format`.
+    pub struct DomainCoreDumpFlags: libc::c_uint {
+        /// Crash the domain after the dump.
+        const CRASH = 1 << 0;
+        /// Dump without pausing the domain.
+        const LIVE = 1 << 1;
+        /// Avoid the file system cache.
+        const BYPASS_CACHE = 1 << 2;
+        /// Reset the domain after the dump.
+        const RESET = 1 << 3;
+        /// Use the dump-guest-memory monitor command.
+        const MEMORY_DUMP = 1 << 4;
+    }
+}
+
+/// Format of the file written by `Domain::core_dump_with_format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainCoreDumpFormat {
+    /// Raw memory of the guest, like the output of `core_dump`.
+    Raw = 0,
+    /// kdump-compressed format, with zlib compression.
+    KdumpZlib = 1,
+    /// kdump-compressed format, with lzo compression.
+    KdumpLzo = 2,
+    /// kdump-compressed format, with snappy compression.
+    KdumpSnappy = 3,
+    /// Windows full crash dump format.
+    WinDmp = 4,
+}
+
+bitflags! {
+    /// Groups of statistics returned by `Connect::get_all_domain_stats`.
+    pub struct DomainStatsTypes: libc::c_uint {
+        const STATE = 1 << 0;
+        const CPU_TOTAL = 1 << 1;
+        const BALLOON = 1 << 2;
+        const VCPU = 1 << 3;
+        const INTERFACE = 1 << 4;
+        const BLOCK = 1 << 5;
+        const PERF = 1 << 6;
+        const IOTHREAD = 1 << 7;
+        const MEMORY = 1 << 8;
+        const DIRTYRATE = 1 << 9;
+        const VM = 1 << 10;
+    }
+}
+
+bitflags! {
+    /// Flags for `Domain::get_job_stats`.
+    pub struct DomainGetJobStatsFlags: libc::c_uint {
+        /// Report the statistics of the most recently completed job
+        /// rather than of the running one.
+        const COMPLETED = 1 << 0;
+        /// Keep the statistics of the completed job, to be fetched
+        /// again later.
+        const KEEP_COMPLETED = 1 << 1;
+    }
+}
+
+bitflags! {
+    /// Flags for `Domain::backup_begin`.
+    pub struct DomainBackupBeginFlags: libc::c_uint {
+        /// Use the existing target files of a push mode backup
+        /// instead of creating them.
+        const REUSE_EXTERNAL = 1 << 0;
+    }
+}
+
+/// Memory allocation policy of the NUMA tuning of a domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainNumatuneMemMode {
+    Strict = 0,
+    Preferred = 1,
+    Interleave = 2,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl DomainNumatuneMemMode {
+    pub fn from_raw(value: i32) -> DomainNumatuneMemMode {
+        match value {
+            0 => DomainNumatuneMemMode::Strict,
+            1 => DomainNumatuneMemMode::Preferred,
+            2 => DomainNumatuneMemMode::Interleave,
+            _ => DomainNumatuneMemMode::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `DomainNumatuneMemMode::Strict`")]
+pub const VIR_DOMAIN_NUMATUNE_MEM_STRICT: DomainNumatuneMemMode = DomainNumatuneMemMode::Strict;
+#[deprecated(note = "use `DomainNumatuneMemMode::Preferred`")]
+pub const VIR_DOMAIN_NUMATUNE_MEM_PREFERRED: DomainNumatuneMemMode =
+    DomainNumatuneMemMode::Preferred;
+#[deprecated(note = "use `DomainNumatuneMemMode::Interleave`")]
+pub const VIR_DOMAIN_NUMATUNE_MEM_INTERLEAVE: DomainNumatuneMemMode =
+    DomainNumatuneMemMode::Interleave;
+
+/// Running state of a domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainState {
+    /// No state.
+    NoState = 0,
+    /// The domain is running.
+    Running = 1,
+    /// The domain is blocked on resource.
+    Blocked = 2,
+    /// The domain is paused by user.
+    Paused = 3,
+    /// The domain is being shut down.
+    Shutdown = 4,
+    /// The domain is shut off.
+    Shutoff = 5,
+    /// The domain is crashed.
+    Crashed = 6,
+    /// The domain is suspended by guest power management.
+    PmSuspended = 7,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl DomainState {
+    pub fn from_raw(value: i32) -> DomainState {
+        match value {
+            0 => DomainState::NoState,
+            1 => DomainState::Running,
+            2 => DomainState::Blocked,
+            3 => DomainState::Paused,
+            4 => DomainState::Shutdown,
+            5 => DomainState::Shutoff,
+            6 => DomainState::Crashed,
+            7 => DomainState::PmSuspended,
+            _ => DomainState::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `DomainState::NoState`")]
+pub const VIR_DOMAIN_NOSTATE: DomainState = DomainState::NoState;
+#[deprecated(note = "use `DomainState::Running`")]
+pub const VIR_DOMAIN_RUNNING: DomainState = DomainState::Running;
+#[deprecated(note = "use `DomainState::Blocked`")]
+pub const VIR_DOMAIN_BLOCKED: DomainState = DomainState::Blocked;
+#[deprecated(note = "use `DomainState::Paused`")]
+pub const VIR_DOMAIN_PAUSED: DomainState = DomainState::Paused;
+#[deprecated(note = "use `DomainState::Shutdown`")]
+pub const VIR_DOMAIN_SHUTDOWN: DomainState = DomainState::Shutdown;
+#[deprecated(note = "use `DomainState::Shutoff`")]
+pub const VIR_DOMAIN_SHUTOFF: DomainState = DomainState::Shutoff;
+#[deprecated(note = "use `DomainState::Crashed`")]
+pub const VIR_DOMAIN_CRASHED: DomainState = DomainState::Crashed;
+#[deprecated(note = "use `DomainState::PmSuspended`")]
+pub const VIR_DOMAIN_PMSUSPENDED: DomainState = DomainState::PmSuspended;
+
+impl Display for DomainState {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainState::NoState => "no state",
+            DomainState::Running => "running",
+            DomainState::Blocked => "idle",
+            DomainState::Paused => "paused",
+            DomainState::Shutdown => "in shutdown",
+            DomainState::Shutoff => "shut off",
+            DomainState::Crashed => "crashed",
+            DomainState::PmSuspended => "pmsuspended",
+            DomainState::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason of the `Running` state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainRunningReason {
+    /// Reason not known, also used for values not known by the
+    /// binding.
+    Unknown(i32),
+    Booted,
+    Migrated,
+    Restored,
+    FromSnapshot,
+    Unpaused,
+    MigrationCanceled,
+    SaveCanceled,
+    Wakeup,
+    Crashed,
+    Postcopy,
+    PostcopyFailed,
+}
+
+impl DomainRunningReason {
+    pub fn from_raw(value: i32) -> DomainRunningReason {
+        match value {
+            1 => DomainRunningReason::Booted,
+            2 => DomainRunningReason::Migrated,
+            3 => DomainRunningReason::Restored,
+            4 => DomainRunningReason::FromSnapshot,
+            5 => DomainRunningReason::Unpaused,
+            6 => DomainRunningReason::MigrationCanceled,
+            7 => DomainRunningReason::SaveCanceled,
+            8 => DomainRunningReason::Wakeup,
+            9 => DomainRunningReason::Crashed,
+            10 => DomainRunningReason::Postcopy,
+            11 => DomainRunningReason::PostcopyFailed,
+            _ => DomainRunningReason::Unknown(value),
+        }
+    }
+}
+
+impl Display for DomainRunningReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainRunningReason::Unknown(0) => "unknown",
+            DomainRunningReason::Unknown(reason) => return write!(f, "unknown ({})", reason),
+            DomainRunningReason::Booted => "booted",
+            DomainRunningReason::Migrated => "migrated",
+            DomainRunningReason::Restored => "restored",
+            DomainRunningReason::FromSnapshot => "from snapshot",
+            DomainRunningReason::Unpaused => "unpaused",
+            DomainRunningReason::MigrationCanceled => "migration canceled",
+            DomainRunningReason::SaveCanceled => "save canceled",
+            DomainRunningReason::Wakeup => "event wakeup",
+            DomainRunningReason::Crashed => "crashed",
+            DomainRunningReason::Postcopy => "post-copy",
+            DomainRunningReason::PostcopyFailed => "post-copy failed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason of the `Paused` state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainPausedReason {
+    /// Reason not known, also used for values not known by the
+    /// binding.
+    Unknown(i32),
+    User,
+    Migration,
+    Save,
+    Dump,
+    IoError,
+    Watchdog,
+    FromSnapshot,
+    ShuttingDown,
+    Snapshot,
+    Crashed,
+    StartingUp,
+    Postcopy,
+    PostcopyFailed,
+    ApiError,
+}
+
+impl DomainPausedReason {
+    pub fn from_raw(value: i32) -> DomainPausedReason {
+        match value {
+            1 => DomainPausedReason::User,
+            2 => DomainPausedReason::Migration,
+            3 => DomainPausedReason::Save,
+            4 => DomainPausedReason::Dump,
+            5 => DomainPausedReason::IoError,
+            6 => DomainPausedReason::Watchdog,
+            7 => DomainPausedReason::FromSnapshot,
+            8 => DomainPausedReason::ShuttingDown,
+            9 => DomainPausedReason::Snapshot,
+            10 => DomainPausedReason::Crashed,
+            11 => DomainPausedReason::StartingUp,
+            12 => DomainPausedReason::Postcopy,
+            13 => DomainPausedReason::PostcopyFailed,
+            14 => DomainPausedReason::ApiError,
+            _ => DomainPausedReason::Unknown(value),
+        }
+    }
+}
+
+impl Display for DomainPausedReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainPausedReason::Unknown(0) => "unknown",
+            DomainPausedReason::Unknown(reason) => return write!(f, "unknown ({})", reason),
+            DomainPausedReason::User => "user",
+            DomainPausedReason::Migration => "migrating",
+            DomainPausedReason::Save => "saving",
+            DomainPausedReason::Dump => "dumping",
+            DomainPausedReason::IoError => "I/O error",
+            DomainPausedReason::Watchdog => "watchdog",
+            DomainPausedReason::FromSnapshot => "from snapshot",
+            DomainPausedReason::ShuttingDown => "shutting down",
+            DomainPausedReason::Snapshot => "creating snapshot",
+            DomainPausedReason::Crashed => "crashed",
+            DomainPausedReason::StartingUp => "starting up",
+            DomainPausedReason::Postcopy => "post-copy",
+            DomainPausedReason::PostcopyFailed => "post-copy failed",
+            DomainPausedReason::ApiError => "api error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason of the `Shutdown` state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainShutdownReason {
+    /// Reason not known, also used for values not known by the
+    /// binding.
+    Unknown(i32),
+    User,
+}
+
+impl DomainShutdownReason {
+    pub fn from_raw(value: i32) -> DomainShutdownReason {
+        match value {
+            1 => DomainShutdownReason::User,
+            _ => DomainShutdownReason::Unknown(value),
+        }
+    }
+}
+
+impl Display for DomainShutdownReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainShutdownReason::Unknown(0) => "unknown",
+            DomainShutdownReason::Unknown(reason) => return write!(f, "unknown ({})", reason),
+            DomainShutdownReason::User => "user",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason of the `Shutoff` state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainShutoffReason {
+    /// Reason not known, also used for values not known by the
+    /// binding.
+    Unknown(i32),
+    Shutdown,
+    Destroyed,
+    Crashed,
+    Migrated,
+    Saved,
+    Failed,
+    FromSnapshot,
+    Daemon,
+}
+
+impl DomainShutoffReason {
+    pub fn from_raw(value: i32) -> DomainShutoffReason {
+        match value {
+            1 => DomainShutoffReason::Shutdown,
+            2 => DomainShutoffReason::Destroyed,
+            3 => DomainShutoffReason::Crashed,
+            4 => DomainShutoffReason::Migrated,
+            5 => DomainShutoffReason::Saved,
+            6 => DomainShutoffReason::Failed,
+            7 => DomainShutoffReason::FromSnapshot,
+            8 => DomainShutoffReason::Daemon,
+            _ => DomainShutoffReason::Unknown(value),
+        }
+    }
+}
+
+impl Display for DomainShutoffReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainShutoffReason::Unknown(0) => "unknown",
+            DomainShutoffReason::Unknown(reason) => return write!(f, "unknown ({})", reason),
+            DomainShutoffReason::Shutdown => "shutdown",
+            DomainShutoffReason::Destroyed => "destroyed",
+            DomainShutoffReason::Crashed => "crashed",
+            DomainShutoffReason::Migrated => "migrated",
+            DomainShutoffReason::Saved => "saved",
+            DomainShutoffReason::Failed => "failed",
+            DomainShutoffReason::FromSnapshot => "from snapshot",
+            DomainShutoffReason::Daemon => "daemon",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason of the `Crashed` state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainCrashedReason {
+    /// Reason not known, also used for values not known by the
+    /// binding.
+    Unknown(i32),
+    Panicked,
+}
+
+impl DomainCrashedReason {
+    pub fn from_raw(value: i32) -> DomainCrashedReason {
+        match value {
+            1 => DomainCrashedReason::Panicked,
+            _ => DomainCrashedReason::Unknown(value),
+        }
+    }
+}
+
+impl Display for DomainCrashedReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            DomainCrashedReason::Unknown(0) => "unknown",
+            DomainCrashedReason::Unknown(reason) => return write!(f, "unknown ({})", reason),
+            DomainCrashedReason::Panicked => "panicked",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reason which led a domain to its current state, each variant
+/// matches a `DomainState`. libvirt does not give any reason for
+/// `NoState`, `Blocked` and `PmSuspended`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainStateReason {
+    NoState,
+    Running(DomainRunningReason),
+    Blocked,
+    Paused(DomainPausedReason),
+    Shutdown(DomainShutdownReason),
+    Shutoff(DomainShutoffReason),
+    Crashed(DomainCrashedReason),
+    PmSuspended,
+    /// Reason of a state not known by the binding.
+    Unknown(i32),
+}
+
+impl DomainStateReason {
+    /// Decodes the raw `reason` reported by libvirt for `state`.
+    pub fn from_raw(state: DomainState, reason: i32) -> DomainStateReason {
+        match state {
+            DomainState::NoState => DomainStateReason::NoState,
+            DomainState::Running => {
+                DomainStateReason::Running(DomainRunningReason::from_raw(reason))
+            }
+            DomainState::Blocked => DomainStateReason::Blocked,
+            DomainState::Paused => DomainStateReason::Paused(DomainPausedReason::from_raw(reason)),
+            DomainState::Shutdown => {
+                DomainStateReason::Shutdown(DomainShutdownReason::from_raw(reason))
+            }
+            DomainState::Shutoff => {
+                DomainStateReason::Shutoff(DomainShutoffReason::from_raw(reason))
+            }
+            DomainState::Crashed => {
+                DomainStateReason::Crashed(DomainCrashedReason::from_raw(reason))
+            }
+            DomainState::PmSuspended => DomainStateReason::PmSuspended,
+            DomainState::Unknown => DomainStateReason::Unknown(reason),
+        }
+    }
+}
+
+impl Display for DomainStateReason {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            DomainStateReason::Running(reason) => reason.fmt(f),
+            DomainStateReason::Paused(reason) => reason.fmt(f),
+            DomainStateReason::Shutdown(reason) => reason.fmt(f),
+            DomainStateReason::Shutoff(reason) => reason.fmt(f),
+            DomainStateReason::Crashed(reason) => reason.fmt(f),
+            DomainStateReason::NoState |
+            DomainStateReason::Blocked |
+            DomainStateReason::PmSuspended => write!(f, "unknown"),
+            DomainStateReason::Unknown(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// State of the control interface of a domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainControlState {
+    /// Operational, ready to accept commands.
+    Ok,
+    /// Background job is running, only limited set of commands
+    /// may be allowed.
+    Job,
+    /// Occupied by a running command.
+    Occupied,
+    /// Unusable, the domain cannot be fully operated.
+    Error,
+    /// Value not known by the binding.
+    Unknown(i32),
+}
+
+impl DomainControlState {
+    pub fn from_raw(value: i32) -> DomainControlState {
+        match value {
+            0 => DomainControlState::Ok,
+            1 => DomainControlState::Job,
+            2 => DomainControlState::Occupied,
+            3 => DomainControlState::Error,
+            _ => DomainControlState::Unknown(value),
+        }
+    }
+}
+
+/// Why the control interface of a domain is unusable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainControlErrorReason {
+    /// Control interface is not in the error state.
+    None,
+    /// Unknown or unexpected reason, also used for values not known
+    /// by the binding.
+    Unknown(i32),
+    /// Monitor connection is broken.
+    Monitor,
+    /// Internal error in the driver.
+    Internal,
+}
+
+impl DomainControlErrorReason {
+    pub fn from_raw(value: i32) -> DomainControlErrorReason {
+        match value {
+            0 => DomainControlErrorReason::None,
+            2 => DomainControlErrorReason::Monitor,
+            3 => DomainControlErrorReason::Internal,
+            _ => DomainControlErrorReason::Unknown(value),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DomainControlInfo {
+    /// The state of the control interface.
+    pub state: DomainControlState,
+    /// Details about the state, only set in the `Error` state.
+    pub details: DomainControlErrorReason,
+    /// For how long, in milliseconds, the control interface has been
+    /// in its current state, 0 for the `Ok` and `Error` states.
+    pub state_time: u64,
+}
+
+impl DomainControlInfo {
+    fn from_ptr(ptr: sys::virDomainControlInfoPtr) -> DomainControlInfo {
+        unsafe {
+            DomainControlInfo {
+                state: DomainControlState::from_raw((*ptr).state as i32),
+                details: DomainControlErrorReason::from_raw((*ptr).details as i32),
+                state_time: (*ptr).stateTime,
+            }
+        }
+    }
+}
+
+/// Progress of the job of a domain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DomainJobType {
+    /// No job is running.
+    #[default]
+    None = 0,
+    /// Job with a finite completion time.
+    Bounded = 1,
+    /// Job without a finite completion time.
+    Unbounded = 2,
+    /// Job has finished successfully.
+    Completed = 3,
+    /// Job has hit an error.
+    Failed = 4,
+    /// Job was aborted by the user.
+    Cancelled = 5,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl DomainJobType {
+    pub fn from_raw(value: i32) -> DomainJobType {
+        match value {
+            0 => DomainJobType::None,
+            1 => DomainJobType::Bounded,
+            2 => DomainJobType::Unbounded,
+            3 => DomainJobType::Completed,
+            4 => DomainJobType::Failed,
+            5 => DomainJobType::Cancelled,
+            _ => DomainJobType::Unknown,
+        }
+    }
+
+    /// Whether the job is over, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        matches!(*self,
+                 DomainJobType::Completed | DomainJobType::Failed | DomainJobType::Cancelled)
+    }
+}
+
+/// Operation a job of a domain is performing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DomainJobOperation {
+    /// Value not known by the binding or not reported.
+    #[default]
+    Unknown = 0,
+    Start = 1,
+    Save = 2,
+    Restore = 3,
+    MigrationIn = 4,
+    MigrationOut = 5,
+    Snapshot = 6,
+    SnapshotRevert = 7,
+    Dump = 8,
+    Backup = 9,
+    SnapshotDelete = 10,
+}
+
+impl DomainJobOperation {
+    pub fn from_raw(value: i32) -> DomainJobOperation {
+        match value {
+            1 => DomainJobOperation::Start,
+            2 => DomainJobOperation::Save,
+            3 => DomainJobOperation::Restore,
+            4 => DomainJobOperation::MigrationIn,
+            5 => DomainJobOperation::MigrationOut,
+            6 => DomainJobOperation::Snapshot,
+            7 => DomainJobOperation::SnapshotRevert,
+            8 => DomainJobOperation::Dump,
+            9 => DomainJobOperation::Backup,
+            10 => DomainJobOperation::SnapshotDelete,
+            _ => DomainJobOperation::Unknown,
+        }
+    }
+}
+
+/// Statistics of the job of a domain, like a migration or a backup.
+///
+/// Which statistics are set depends on the operation and on the
+/// hypervisor, the ones not known by the binding are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct DomainJobStats {
+    pub kind: DomainJobType,
+    pub operation: DomainJobOperation,
+    /// Time since the start of the job, in milliseconds.
+    pub time_elapsed: Option<u64>,
+    /// Estimated time until the end of the job, in milliseconds.
+    pub time_remaining: Option<u64>,
+    /// Amounts of data to be transferred overall, in bytes.
+    pub data_total: Option<u64>,
+    pub data_processed: Option<u64>,
+    pub data_remaining: Option<u64>,
+    /// Amounts of guest memory to be transferred, in bytes.
+    pub memory_total: Option<u64>,
+    pub memory_processed: Option<u64>,
+    pub memory_remaining: Option<u64>,
+    /// Memory transfer rate, in bytes per second.
+    pub memory_bps: Option<u64>,
+    /// Amounts of disk data to be transferred, in bytes.
+    pub disk_total: Option<u64>,
+    pub disk_processed: Option<u64>,
+    pub disk_remaining: Option<u64>,
+    /// Disk transfer rate, in bytes per second.
+    pub disk_bps: Option<u64>,
+    /// Space used and allocated by the scratch files of a pull mode
+    /// backup, in bytes.
+    pub disk_temp_used: Option<u64>,
+    pub disk_temp_total: Option<u64>,
+    /// Time the domain was paused by a migration, in milliseconds.
+    pub downtime: Option<u64>,
+    /// Whether a completed job succeeded.
+    pub success: Option<bool>,
+    /// Error which made the job fail.
+    pub error_message: Option<String>,
+}
+
+impl DomainJobStats {
+    pub fn from_vec(vec: Vec<virTypedParameter>) -> DomainJobStats {
+        unsafe {
+            let mut ret = DomainJobStats::default();
+            for param in vec {
+                let value = Some(param.value);
+                match str::from_utf8(CStr::from_ptr(param.field.as_ptr()).to_bytes()).unwrap() {
+                    "operation" => {
+                        ret.operation = DomainJobOperation::from_raw(param.value as i32)
+                    }
+                    "time_elapsed" => ret.time_elapsed = value,
+                    "time_remaining" => ret.time_remaining = value,
+                    "data_total" => ret.data_total = value,
+                    "data_processed" => ret.data_processed = value,
+                    "data_remaining" => ret.data_remaining = value,
+                    "memory_total" => ret.memory_total = value,
+                    "memory_processed" => ret.memory_processed = value,
+                    "memory_remaining" => ret.memory_remaining = value,
+                    "memory_bps" => ret.memory_bps = value,
+                    "disk_total" => ret.disk_total = value,
+                    "disk_processed" => ret.disk_processed = value,
+                    "disk_remaining" => ret.disk_remaining = value,
+                    "disk_bps" => ret.disk_bps = value,
+                    "disk_temp_used" => ret.disk_temp_used = value,
+                    "disk_temp_total" => ret.disk_temp_total = value,
+                    "downtime" => ret.downtime = value,
+                    "success" => ret.success = Some(param.value as i32 != 0),
+                    "errmsg" => {
+                        ret.error_message =
+                            Some(c_chars_to_string!(param.value as *mut libc::c_char, nofree))
+                    }
+                    _ => (),
+                }
+            }
+            ret
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct DomainInfo {
-    /// The running state, one of virDomainState.
+    /// The running state.
     pub state: DomainState,
     /// The maximum memory in KBytes allowed.
     pub max_mem: u64,
@@ -462,7 +1328,7 @@ impl DomainInfo {
     pub fn from_ptr(ptr: sys::virDomainInfoPtr) -> DomainInfo {
         unsafe {
             DomainInfo {
-                state: (*ptr).state as DomainState,
+                state: DomainState::from_raw((*ptr).state as i32),
                 max_mem: (*ptr).maxMem as u64,
                 memory: (*ptr).memory as u64,
                 nr_virt_cpu: (*ptr).nrVirtCpu as u32,
@@ -538,8 +1404,7 @@ impl MemoryParameters {
 pub struct NUMAParameters {
     /// Lists the numa nodeset of a domain.
     pub node_set: Option<String>,
-    /// Numa mode of a domain, as an int containing a
-    /// DomainNumatuneMemMode value.
+    /// Numa mode of a domain.
     pub mode: Option<DomainNumatuneMemMode>,
 }
 
@@ -552,7 +1417,9 @@ impl NUMAParameters {
                     "numa_nodeset" => {
                         ret.node_set = Some(c_chars_to_string!(param.value as *mut libc::c_char))
                     }
-                    "numa_mode" => ret.mode = Some(param.value as libc::c_int),
+                    "numa_mode" => {
+                        ret.mode = Some(DomainNumatuneMemMode::from_raw(param.value as i32))
+                    }
                     unknow => panic!("Field not implemented for NUMAParameters, {:?}", unknow),
                 }
             }
@@ -627,6 +1494,22 @@ impl Drop for Domain {
     }
 }
 
+impl Clone for Domain {
+    fn clone(&self) -> Domain {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for Domain, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for Domain {}
+unsafe impl Sync for Domain {}
+
 impl Domain {
     pub fn new(ptr: sys::virDomainPtr) -> Domain {
         return Domain { ptr: Some(ptr) };
@@ -636,12 +1519,27 @@ impl Domain {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the domain and returns a new
+    /// handle on it, the domain is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<Domain, Error> {
+        unsafe {
+            if virDomainRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(Domain::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virDomainGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -678,9 +1576,9 @@ impl Domain {
 
     /// Extracts domain state.
     ///
-    /// Each state can be accompanied with a reason (if known) which
-    /// led to the state.
-    pub fn get_state(&self) -> Result<(DomainState, i32), Error> {
+    /// Each state is accompanied with the reason which led to the
+    /// state, `DomainStateReason` matches the returned state.
+    pub fn get_state(&self) -> Result<(DomainState, DomainStateReason), Error> {
         unsafe {
             let mut state: libc::c_int = -1;
             let mut reason: libc::c_int = -1;
@@ -688,7 +1586,8 @@ impl Domain {
             if ret == -1 {
                 return Err(Error::new());
             }
-            return Ok((state as DomainState, reason as i32));
+            let state = DomainState::from_raw(state);
+            return Ok((state, DomainStateReason::from_raw(state, reason)));
         }
     }
 
@@ -751,9 +1650,9 @@ impl Domain {
 
     /// Provide an XML description of the domain. The description may
     /// be reused later to relaunch the domain with `create_xml()`.
-    pub fn get_xml_desc(&self, flags: DomainCreateFlags) -> Result<String, Error> {
+    pub fn get_xml_desc(&self, flags: DomainXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virDomainGetXMLDesc(self.as_ptr(), flags);
+            let xml = virDomainGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -779,7 +1678,7 @@ impl Domain {
     /// from the defined to the running domains pools.
     pub fn create_with_flags(&self, flags: DomainCreateFlags) -> Result<u32, Error> {
         unsafe {
-            let res = virDomainCreateWithFlags(self.as_ptr(), flags as libc::c_uint);
+            let res = virDomainCreateWithFlags(self.as_ptr(), flags.bits());
             if res == -1 {
                 return Err(Error::new());
             }
@@ -801,6 +1700,24 @@ impl Domain {
         }
     }
 
+    /// Extract details about the state of the control interface
+    /// used by libvirt to talk to the hypervisor, typically the
+    /// monitor of a qemu domain.
+    ///
+    /// Unlike most of the other APIs this one can be used while a
+    /// job is running on the domain, the control interface being in
+    /// the `Job` or `Occupied` state.
+    pub fn get_control_info(&self, flags: u32) -> Result<DomainControlInfo, Error> {
+        unsafe {
+            let pinfo = &mut sys::virDomainControlInfo::default();
+            let res = virDomainGetControlInfo(self.as_ptr(), pinfo, flags as libc::c_uint);
+            if res == -1 {
+                return Err(Error::new());
+            }
+            Ok(DomainControlInfo::from_ptr(pinfo))
+        }
+    }
+
     /// Launch a new guest domain, based on an XML description similar
     /// to the one returned by `get_xml_desc()`.
     ///
@@ -816,7 +1733,7 @@ impl Domain {
         unsafe {
             let ptr = virDomainCreateXML(conn.as_ptr(),
                                          string_to_c_chars!(xml),
-                                         flags as libc::c_uint);
+                                         flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -863,7 +1780,7 @@ impl Domain {
         unsafe {
             let ptr = virDomainDefineXMLFlags(conn.as_ptr(),
                                               string_to_c_chars!(xml),
-                                              flags as libc::c_uint);
+                                              flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -908,7 +1825,7 @@ impl Domain {
     /// object. This function may require privileged access.
     pub fn destroy_flags(&self, flags: DomainDestroyFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainDestroyFlags(self.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainDestroyFlags(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1102,7 +2019,7 @@ impl Domain {
         unsafe {
             let ret = virDomainSetMemoryFlags(self.as_ptr(),
                                               memory as libc::c_ulong,
-                                              flags as libc::c_uint);
+                                              flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1117,7 +2034,7 @@ impl Domain {
         unsafe {
             let ret = virDomainSetMemoryStatsPeriod(self.as_ptr(),
                                                     period as libc::c_int,
-                                                    flags as libc::c_uint);
+                                                    flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1138,7 +2055,7 @@ impl Domain {
     pub fn set_vcpus_flags(&self, vcpus: u32, flags: DomainVcpuFlags) -> Result<bool, Error> {
         unsafe {
             let ret =
-                virDomainSetVcpusFlags(self.as_ptr(), vcpus as libc::c_uint, flags as libc::c_uint);
+                virDomainSetVcpusFlags(self.as_ptr(), vcpus as libc::c_uint, flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1162,7 +2079,7 @@ impl Domain {
         unsafe {
             if virDomainRestoreFlags(conn.as_ptr(),
                                      string_to_c_chars!(path),
-                                     flags as libc::c_uint) == -1 {
+                                     flags.bits()) == -1 {
                 return Err(Error::new());
             }
             return Ok(());
@@ -1171,7 +2088,7 @@ impl Domain {
 
     pub fn get_vcpus_flags(&self, flags: DomainVcpuFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainGetVcpusFlags(self.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainGetVcpusFlags(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1295,13 +2212,17 @@ impl Domain {
         }
     }
 
-    pub fn pin_vcpu_flags(&self, vcpu: u32, cpumap: &[u8], flags: u32) -> Result<u32, Error> {
+    pub fn pin_vcpu_flags(&self,
+                          vcpu: u32,
+                          cpumap: &[u8],
+                          flags: DomainModImpactFlags)
+                          -> Result<u32, Error> {
         unsafe {
             let ret = virDomainPinVcpuFlags(self.as_ptr(),
                                             vcpu as libc::c_uint,
                                             cpumap.as_ptr(),
                                             cpumap.len() as libc::c_uint,
-                                            flags as libc::c_uint);
+                                            flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1309,12 +2230,12 @@ impl Domain {
         }
     }
 
-    pub fn pin_emulator(&self, cpumap: &[u8], flags: u32) -> Result<u32, Error> {
+    pub fn pin_emulator(&self, cpumap: &[u8], flags: DomainModImpactFlags) -> Result<u32, Error> {
         unsafe {
             let ret = virDomainPinEmulator(self.as_ptr(),
                                            cpumap.as_ptr(),
                                            cpumap.len() as libc::c_uint,
-                                           flags as libc::c_uint);
+                                           flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1441,12 +2362,12 @@ impl Domain {
 
     pub fn save_image_get_xml_desc(conn: &Connect,
                                    file: &str,
-                                   flags: u32)
+                                   flags: DomainXMLFlags)
                                    -> Result<String, Error> {
         unsafe {
             let ptr = virDomainSaveImageGetXMLDesc(conn.as_ptr(),
                                                    string_to_c_chars!(file),
-                                                   flags as libc::c_uint);
+                                                   flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -1457,13 +2378,13 @@ impl Domain {
     pub fn save_image_define_xml(conn: &Connect,
                                  file: &str,
                                  dxml: &str,
-                                 flags: u32)
+                                 flags: DomainSaveRestoreFlags)
                                  -> Result<u32, Error> {
         unsafe {
             let ret = virDomainSaveImageDefineXML(conn.as_ptr(),
                                                   string_to_c_chars!(file),
                                                   string_to_c_chars!(dxml),
-                                                  flags as libc::c_uint);
+                                                  flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1481,11 +2402,14 @@ impl Domain {
         }
     }
 
-    pub fn attach_device_flags(&self, xml: &str, flags: u32) -> Result<u32, Error> {
+    pub fn attach_device_flags(&self,
+                               xml: &str,
+                               flags: DomainModImpactFlags)
+                               -> Result<u32, Error> {
         unsafe {
             let ret = virDomainAttachDeviceFlags(self.as_ptr(),
                                                  string_to_c_chars!(xml),
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1503,11 +2427,14 @@ impl Domain {
         }
     }
 
-    pub fn detach_device_flags(&self, xml: &str, flags: u32) -> Result<u32, Error> {
+    pub fn detach_device_flags(&self,
+                               xml: &str,
+                               flags: DomainModImpactFlags)
+                               -> Result<u32, Error> {
         unsafe {
             let ret = virDomainDetachDeviceFlags(self.as_ptr(),
                                                  string_to_c_chars!(xml),
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1515,11 +2442,14 @@ impl Domain {
         }
     }
 
-    pub fn update_device_flags(&self, xml: &str, flags: u32) -> Result<u32, Error> {
+    pub fn update_device_flags(&self,
+                               xml: &str,
+                               flags: DomainModImpactFlags)
+                               -> Result<u32, Error> {
         unsafe {
             let ret = virDomainUpdateDeviceFlags(self.as_ptr(),
                                                  string_to_c_chars!(xml),
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1527,9 +2457,9 @@ impl Domain {
         }
     }
 
-    pub fn managed_save(&self, flags: u32) -> Result<u32, Error> {
+    pub fn managed_save(&self, flags: DomainSaveRestoreFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainManagedSave(self.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainManagedSave(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1557,10 +2487,9 @@ impl Domain {
         }
     }
 
-    pub fn core_dump(&self, to: &str, flags: u32) -> Result<u32, Error> {
+    pub fn core_dump(&self, to: &str, flags: DomainCoreDumpFlags) -> Result<u32, Error> {
         unsafe {
-            let ret =
-                virDomainCoreDump(self.as_ptr(), string_to_c_chars!(to), flags as libc::c_uint);
+            let ret = virDomainCoreDump(self.as_ptr(), string_to_c_chars!(to), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1568,12 +2497,16 @@ impl Domain {
         }
     }
 
-    pub fn core_dump_with_format(&self, to: &str, format: u32, flags: u32) -> Result<u32, Error> {
+    pub fn core_dump_with_format(&self,
+                                 to: &str,
+                                 format: DomainCoreDumpFormat,
+                                 flags: DomainCoreDumpFlags)
+                                 -> Result<u32, Error> {
         unsafe {
             let ret = virDomainCoreDumpWithFormat(self.as_ptr(),
                                                   string_to_c_chars!(to),
                                                   format as libc::c_uint,
-                                                  flags as libc::c_uint);
+                                                  flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1586,7 +2519,7 @@ impl Domain {
                         metadata: &str,
                         key: &str,
                         uri: &str,
-                        flags: u32)
+                        flags: DomainModImpactFlags)
                         -> Result<u32, Error> {
         unsafe {
             let ret = virDomainSetMetadata(self.as_ptr(),
@@ -1594,7 +2527,7 @@ impl Domain {
                                            string_to_c_chars!(metadata),
                                            string_to_c_chars!(key),
                                            string_to_c_chars!(uri),
-                                           flags as libc::c_uint);
+                                           flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1602,12 +2535,16 @@ impl Domain {
         }
     }
 
-    pub fn get_metadata(&self, kind: i32, uri: &str, flags: u32) -> Result<String, Error> {
+    pub fn get_metadata(&self,
+                        kind: i32,
+                        uri: &str,
+                        flags: DomainModImpactFlags)
+                        -> Result<String, Error> {
         unsafe {
             let n = virDomainGetMetadata(self.as_ptr(),
                                          kind as libc::c_int,
                                          string_to_c_chars!(uri),
-                                         flags as libc::c_uint);
+                                         flags.bits());
             if n.is_null() {
                 return Err(Error::new());
             }
@@ -1628,13 +2565,15 @@ impl Domain {
         }
     }
 
-    pub fn get_memory_parameters(&self, flags: u32) -> Result<MemoryParameters, Error> {
+    pub fn get_memory_parameters(&self,
+                                 flags: DomainModImpactFlags)
+                                 -> Result<MemoryParameters, Error> {
         unsafe {
             let mut nparams: libc::c_int = 0;
             let ret = virDomainGetMemoryParameters(self.as_ptr(),
                                                    ptr::null_mut(),
                                                    &mut nparams,
-                                                   flags as libc::c_uint);
+                                                   flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1643,7 +2582,7 @@ impl Domain {
             let ret = virDomainGetMemoryParameters(self.as_ptr(),
                                                    &mut params[0],
                                                    &mut nparams,
-                                                   flags as libc::c_uint);
+                                                   flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1653,7 +2592,7 @@ impl Domain {
 
     pub fn set_memory_parameters(&self,
                                  params: MemoryParameters,
-                                 flags: u32)
+                                 flags: DomainModImpactFlags)
                                  -> Result<u32, Error> {
         unsafe {
             fn to_arr(name: &str) -> [libc::c_char; 80] {
@@ -1668,28 +2607,28 @@ impl Domain {
             if params.hard_limit.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("hard_limit\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_ULLONG,
+                                 typed: TypedParameterType::ULLong.to_raw(),
                                  value: params.hard_limit.unwrap(),
                              })
             }
             if params.soft_limit.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("soft_limit\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_ULLONG,
+                                 typed: TypedParameterType::ULLong.to_raw(),
                                  value: params.soft_limit.unwrap(),
                              })
             }
             if params.min_guarantee.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("min_guarantee\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_ULLONG,
+                                 typed: TypedParameterType::ULLong.to_raw(),
                                  value: params.min_guarantee.unwrap(),
                              })
             }
             if params.swap_hard_limit.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("swap_hard_limit\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_ULLONG,
+                                 typed: TypedParameterType::ULLong.to_raw(),
                                  value: params.swap_hard_limit.unwrap(),
                              })
             }
@@ -1697,7 +2636,7 @@ impl Domain {
             let ret = virDomainSetMemoryParameters(self.as_ptr(),
                                                    &mut cparams[0],
                                                    cparams.len() as libc::c_int,
-                                                   flags as libc::c_uint);
+                                                   flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1707,14 +2646,14 @@ impl Domain {
 
     pub fn migrate(&self,
                    dconn: &Connect,
-                   flags: u32,
+                   flags: DomainMigrateFlags,
                    uri: &str,
                    bandwidth: u64)
                    -> Result<Domain, Error> {
         unsafe {
             let ptr = virDomainMigrate(self.as_ptr(),
                                        dconn.as_ptr(),
-                                       flags as libc::c_uint,
+                                       flags.bits(),
                                        string_to_c_chars!(""),
                                        string_to_c_chars!(uri),
                                        bandwidth as libc::c_ulong);
@@ -1728,7 +2667,7 @@ impl Domain {
     pub fn migrate2(&self,
                     dconn: &Connect,
                     dxml: &str,
-                    flags: u32,
+                    flags: DomainMigrateFlags,
                     uri: &str,
                     bandwidth: u64)
                     -> Result<Domain, Error> {
@@ -1736,7 +2675,7 @@ impl Domain {
             let ptr = virDomainMigrate2(self.as_ptr(),
                                         dconn.as_ptr(),
                                         string_to_c_chars!(dxml),
-                                        flags as libc::c_uint,
+                                        flags.bits(),
                                         string_to_c_chars!(""),
                                         string_to_c_chars!(uri),
                                         bandwidth as libc::c_ulong);
@@ -1747,11 +2686,15 @@ impl Domain {
         }
     }
 
-    pub fn migrate_to_uri(&self, duri: &str, flags: u32, bandwidth: u64) -> Result<Domain, Error> {
+    pub fn migrate_to_uri(&self,
+                          duri: &str,
+                          flags: DomainMigrateFlags,
+                          bandwidth: u64)
+                          -> Result<Domain, Error> {
         unsafe {
             let ptr = virDomainMigrateToURI(self.as_ptr(),
                                             string_to_c_chars!(duri),
-                                            flags as libc::c_uint,
+                                            flags.bits(),
                                             string_to_c_chars!(""),
                                             bandwidth as libc::c_ulong);
             if ptr.is_null() {
@@ -1765,7 +2708,7 @@ impl Domain {
                            dconn_uri: &str,
                            mig_uri: &str,
                            dxml: &str,
-                           flags: u32,
+                           flags: DomainMigrateFlags,
                            bandwidth: u64)
                            -> Result<Domain, Error> {
         unsafe {
@@ -1773,7 +2716,7 @@ impl Domain {
                                              string_to_c_chars!(dconn_uri),
                                              string_to_c_chars!(mig_uri),
                                              string_to_c_chars!(dxml),
-                                             flags as libc::c_uint,
+                                             flags.bits(),
                                              string_to_c_chars!(""),
                                              bandwidth as libc::c_ulong);
             if ptr.is_null() {
@@ -1783,13 +2726,15 @@ impl Domain {
         }
     }
 
-    pub fn get_numa_parameters(&self, flags: u32) -> Result<NUMAParameters, Error> {
+    pub fn get_numa_parameters(&self,
+                               flags: DomainModImpactFlags)
+                               -> Result<NUMAParameters, Error> {
         unsafe {
             let mut nparams: libc::c_int = 0;
             let ret = virDomainGetNumaParameters(self.as_ptr(),
                                                  ptr::null_mut(),
                                                  &mut nparams,
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1799,7 +2744,7 @@ impl Domain {
             let ret = virDomainGetNumaParameters(self.as_ptr(),
                                                  &mut params[0],
                                                  &mut nparams,
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1807,7 +2752,10 @@ impl Domain {
         }
     }
 
-    pub fn set_numa_parameters(&self, params: NUMAParameters, flags: u32) -> Result<u32, Error> {
+    pub fn set_numa_parameters(&self,
+                               params: NUMAParameters,
+                               flags: DomainModImpactFlags)
+                               -> Result<u32, Error> {
         unsafe {
             fn to_arr(name: &str) -> [libc::c_char; 80] {
                 let mut field: [libc::c_char; 80] = [0; 80];
@@ -1821,22 +2769,22 @@ impl Domain {
             if params.node_set.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("numa_nodeset\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_STRING,
+                                 typed: TypedParameterType::String.to_raw(),
                                  value: string_to_mut_c_chars!(params.node_set.unwrap()) as u64,
                              })
             }
             if params.mode.is_some() {
                 cparams.push(virTypedParameter {
                                  field: to_arr("numa_mode\0"),
-                                 typed: ::typedparam::VIR_TYPED_PARAM_INT,
-                                 value: params.mode.unwrap() as u64,
+                                 typed: TypedParameterType::Int.to_raw(),
+                                 value: params.mode.unwrap().to_raw() as u64,
                              })
             }
 
             let ret = virDomainSetNumaParameters(self.as_ptr(),
                                                  &mut cparams[0],
                                                  cparams.len() as libc::c_int,
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -1844,10 +2792,12 @@ impl Domain {
         }
     }
 
-    pub fn list_all_snapshots(&self, flags: u32) -> Result<Vec<DomainSnapshot>, Error> {
+    pub fn list_all_snapshots(&self,
+                              flags: DomainSnapshotListFlags)
+                              -> Result<Vec<DomainSnapshot>, Error> {
         unsafe {
             let mut snaps: *mut virDomainSnapshotPtr = ptr::null_mut();
-            let size = virDomainListAllSnapshots(self.as_ptr(), &mut snaps, flags as libc::c_uint);
+            let size = virDomainListAllSnapshots(self.as_ptr(), &mut snaps, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -1863,4 +2813,106 @@ impl Domain {
             return Ok(array);
         }
     }
+
+    /// Get statistics about the progress of the job running on the
+    /// domain, like a migration or a backup.
+    ///
+    /// Once the job is over its final statistics can be fetched
+    /// with `DomainGetJobStatsFlags::COMPLETED`.
+    pub fn get_job_stats(&self, flags: DomainGetJobStatsFlags) -> Result<DomainJobStats, Error> {
+        unsafe {
+            let mut kind: libc::c_int = 0;
+            let mut params: virTypedParameterPtr = ptr::null_mut();
+            let mut nparams: libc::c_int = 0;
+            let ret = virDomainGetJobStats(self.as_ptr(),
+                                           &mut kind,
+                                           &mut params,
+                                           &mut nparams,
+                                           flags.bits());
+            if ret == -1 {
+                return Err(Error::new());
+            }
+            let mut vec: Vec<virTypedParameter> = Vec::new();
+            for x in 0..nparams as isize {
+                vec.push(*params.offset(x));
+            }
+            let stats = DomainJobStats {
+                kind: DomainJobType::from_raw(kind),
+                ..DomainJobStats::from_vec(vec)
+            };
+            virTypedParamsFree(params, nparams);
+            Ok(stats)
+        }
+    }
+
+    /// Abort the job running on the domain, like a migration or a
+    /// backup.
+    pub fn abort_job(&self) -> Result<(), Error> {
+        unsafe {
+            if virDomainAbortJob(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    /// Start a backup job of the disks of the domain described by
+    /// `backup_xml`.
+    ///
+    /// When `checkpoint_xml` is provided a checkpoint is created
+    /// atomically with the start of the backup, to be used as the
+    /// base of the next incremental backup. The progress of the job
+    /// is reported by `get_job_stats`, a pull mode backup runs until
+    /// `abort_job` is called.
+    pub fn backup_begin(&self,
+                        backup_xml: &str,
+                        checkpoint_xml: Option<&str>,
+                        flags: DomainBackupBeginFlags)
+                        -> Result<(), Error> {
+        unsafe {
+            let ccheckpoint = checkpoint_xml.map(|x| ::std::ffi::CString::new(x).unwrap());
+            let pcheckpoint = match ccheckpoint {
+                Some(ref x) => x.as_ptr(),
+                None => ptr::null(),
+            };
+            if virDomainBackupBegin(self.as_ptr(),
+                                    string_to_c_chars!(backup_xml),
+                                    pcheckpoint,
+                                    flags.bits()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    /// Dump the XML of the backup job running on the domain.
+    pub fn backup_get_xml_desc(&self, flags: u32) -> Result<String, Error> {
+        unsafe {
+            let xml = virDomainBackupGetXMLDesc(self.as_ptr(), flags as libc::c_uint);
+            if xml.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(xml))
+        }
+    }
+
+    pub fn list_all_checkpoints(&self,
+                                flags: DomainCheckpointListFlags)
+                                -> Result<Vec<DomainCheckpoint>, Error> {
+        unsafe {
+            let mut checkpoints: *mut virDomainCheckpointPtr = ptr::null_mut();
+            let size = virDomainListAllCheckpoints(self.as_ptr(), &mut checkpoints, flags.bits());
+            if size == -1 {
+                return Err(Error::new());
+            }
+
+            let mut array: Vec<DomainCheckpoint> = Vec::new();
+            for x in 0..size as isize {
+                array.push(DomainCheckpoint::new(*checkpoints.offset(x)));
+            }
+            libc::free(checkpoints as *mut libc::c_void);
+
+            Ok(array)
+        }
+    }
 }
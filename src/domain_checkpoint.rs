@@ -0,0 +1,300 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate libc;
+
+use std::{str, ptr};
+
+use connect::sys::virConnectPtr;
+use domain::sys::virDomainPtr;
+
+use connect::Connect;
+use domain::Domain;
+use error::Error;
+
+pub mod sys {
+    #[repr(C)]
+    pub struct virDomainCheckpoint {}
+
+    pub type virDomainCheckpointPtr = *mut virDomainCheckpoint;
+}
+
+#[link(name = "virt")]
+extern "C" {
+    fn virDomainCheckpointGetName(ptr: sys::virDomainCheckpointPtr) -> *const libc::c_char;
+    fn virDomainCheckpointGetDomain(ptr: sys::virDomainCheckpointPtr) -> virDomainPtr;
+    fn virDomainRef(ptr: virDomainPtr) -> libc::c_int;
+    fn virDomainCheckpointGetConnect(ptr: sys::virDomainCheckpointPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
+    fn virDomainCheckpointGetXMLDesc(ptr: sys::virDomainCheckpointPtr,
+                                     flags: libc::c_uint)
+                                     -> *mut libc::c_char;
+    fn virDomainCheckpointDelete(ptr: sys::virDomainCheckpointPtr,
+                                 flags: libc::c_uint)
+                                 -> libc::c_int;
+    fn virDomainCheckpointCreateXML(d: virDomainPtr,
+                                    xml: *const libc::c_char,
+                                    flags: libc::c_uint)
+                                    -> sys::virDomainCheckpointPtr;
+    fn virDomainCheckpointFree(ptr: sys::virDomainCheckpointPtr) -> libc::c_int;
+    fn virDomainCheckpointRef(ptr: sys::virDomainCheckpointPtr) -> libc::c_int;
+    fn virDomainCheckpointGetParent(ptr: sys::virDomainCheckpointPtr,
+                                    flags: libc::c_uint)
+                                    -> sys::virDomainCheckpointPtr;
+    fn virDomainCheckpointLookupByName(d: virDomainPtr,
+                                       name: *const libc::c_char,
+                                       flags: libc::c_uint)
+                                       -> sys::virDomainCheckpointPtr;
+    fn virDomainCheckpointListAllChildren(ptr: sys::virDomainCheckpointPtr,
+                                          children: *mut *mut sys::virDomainCheckpointPtr,
+                                          flags: libc::c_uint)
+                                          -> libc::c_int;
+}
+
+bitflags! {
+    /// Flags for `DomainCheckpoint::create_xml`.
+    pub struct DomainCheckpointCreateFlags: libc::c_uint {
+        const REDEFINE = 1 << 0;
+        const QUIESCE = 1 << 1;
+        const REDEFINE_VALIDATE = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// Filters for listing checkpoints.
+    pub struct DomainCheckpointListFlags: libc::c_uint {
+        const ROOTS = 1 << 0;
+        const DESCENDANTS = 1 << 0;
+        const TOPOLOGICAL = 1 << 1;
+        const LEAVES = 1 << 2;
+        const NO_LEAVES = 1 << 3;
+    }
+}
+
+bitflags! {
+    /// Flags for `DomainCheckpoint::delete`.
+    pub struct DomainCheckpointDeleteFlags: libc::c_uint {
+        const CHILDREN = 1 << 0;
+        const METADATA_ONLY = 1 << 1;
+        const CHILDREN_ONLY = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// Flags for `DomainCheckpoint::get_xml_desc`.
+    pub struct DomainCheckpointXMLFlags: libc::c_uint {
+        const SECURE = 1 << 0;
+        /// Leave out the definition of the domain.
+        const NO_DOMAIN = 1 << 1;
+        /// Report the size of the dirty bitmap of each disk.
+        const SIZE = 1 << 2;
+    }
+}
+
+
+/// Provides APIs for the management of domain checkpoints.
+///
+/// A checkpoint tracks the blocks of the disks changed since it was
+/// created, to be used as the base of an incremental backup.
+///
+/// See https://libvirt.org/formatcheckpoint.html
+#[derive(Debug)]
+pub struct DomainCheckpoint {
+    ptr: Option<sys::virDomainCheckpointPtr>,
+}
+
+impl Drop for DomainCheckpoint {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.free() {
+                panic!("Unable to drop memory for DomainCheckpoint, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+impl Clone for DomainCheckpoint {
+    fn clone(&self) -> DomainCheckpoint {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for DomainCheckpoint, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for DomainCheckpoint {}
+unsafe impl Sync for DomainCheckpoint {}
+
+impl DomainCheckpoint {
+    pub fn new(ptr: sys::virDomainCheckpointPtr) -> DomainCheckpoint {
+        DomainCheckpoint { ptr: Some(ptr) }
+    }
+
+    pub fn as_ptr(&self) -> sys::virDomainCheckpointPtr {
+        self.ptr.unwrap()
+    }
+
+    /// Increments the reference count of the checkpoint and returns
+    /// a new handle on it, the checkpoint is released once every
+    /// handle has been freed.
+    pub fn add_ref(&self) -> Result<DomainCheckpoint, Error> {
+        unsafe {
+            if virDomainCheckpointRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(DomainCheckpoint::new(self.as_ptr()))
+    }
+
+    pub fn get_connect(&self) -> Result<Connect, Error> {
+        unsafe {
+            let ptr = virDomainCheckpointGetConnect(self.as_ptr());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
+            Ok(Connect::new(ptr))
+        }
+    }
+
+    pub fn get_domain(&self) -> Result<Domain, Error> {
+        unsafe {
+            let ptr = virDomainCheckpointGetDomain(self.as_ptr());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            if virDomainRef(ptr) == -1 {
+                return Err(Error::new());
+            }
+            Ok(Domain::new(ptr))
+        }
+    }
+
+    pub fn get_name(&self) -> Result<String, Error> {
+        unsafe {
+            let n = virDomainCheckpointGetName(self.as_ptr());
+            if n.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(n, nofree))
+        }
+    }
+
+    /// Get a handle to a named checkpoint.
+    pub fn lookup_by_name(dom: &Domain, name: &str, flags: u32) -> Result<DomainCheckpoint, Error> {
+        unsafe {
+            let ptr = virDomainCheckpointLookupByName(dom.as_ptr(),
+                                                      string_to_c_chars!(name),
+                                                      flags as libc::c_uint);
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(DomainCheckpoint::new(ptr))
+        }
+    }
+
+    /// Dump the XML of a checkpoint.
+    pub fn get_xml_desc(&self, flags: DomainCheckpointXMLFlags) -> Result<String, Error> {
+        unsafe {
+            let xml = virDomainCheckpointGetXMLDesc(self.as_ptr(), flags.bits());
+            if xml.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(xml))
+        }
+    }
+
+    /// Create a checkpoint of the disks of `dom` described by `xml`,
+    /// starting the tracking of their changes.
+    pub fn create_xml(dom: &Domain,
+                      xml: &str,
+                      flags: DomainCheckpointCreateFlags)
+                      -> Result<DomainCheckpoint, Error> {
+        unsafe {
+            let ptr = virDomainCheckpointCreateXML(dom.as_ptr(),
+                                                   string_to_c_chars!(xml),
+                                                   flags.bits());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(DomainCheckpoint::new(ptr))
+        }
+    }
+
+    /// Get a handle to the parent checkpoint, if one exists.
+    pub fn get_parent(&self, flags: u32) -> Result<DomainCheckpoint, Error> {
+        unsafe {
+            let ptr = virDomainCheckpointGetParent(self.as_ptr(), flags as libc::c_uint);
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(DomainCheckpoint::new(ptr))
+        }
+    }
+
+    /// Delete a checkpoint, merging its dirty bitmaps into the ones
+    /// of its parent.
+    pub fn delete(&self, flags: DomainCheckpointDeleteFlags) -> Result<(), Error> {
+        unsafe {
+            if virDomainCheckpointDelete(self.as_ptr(), flags.bits()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    /// Get all checkpoint object children for this checkpoint.
+    pub fn list_all_children(&self,
+                             flags: DomainCheckpointListFlags)
+                             -> Result<Vec<DomainCheckpoint>, Error> {
+        unsafe {
+            let mut children: *mut sys::virDomainCheckpointPtr = ptr::null_mut();
+            let size =
+                virDomainCheckpointListAllChildren(self.as_ptr(), &mut children, flags.bits());
+            if size == -1 {
+                return Err(Error::new());
+            }
+
+            let mut array: Vec<DomainCheckpoint> = Vec::new();
+            for x in 0..size as isize {
+                array.push(DomainCheckpoint::new(*children.offset(x)));
+            }
+            libc::free(children as *mut libc::c_void);
+
+            Ok(array)
+        }
+    }
+
+    pub fn free(&mut self) -> Result<(), Error> {
+        unsafe {
+            if virDomainCheckpointFree(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            self.ptr = None;
+            Ok(())
+        }
+    }
+}
@@ -38,7 +38,9 @@ pub mod sys {
 extern "C" {
     fn virDomainSnapshotGetName(ptr: sys::virDomainSnapshotPtr) -> *const libc::c_char;
     fn virDomainSnapshotGetDomain(ptr: sys::virDomainSnapshotPtr) -> virDomainPtr;
+    fn virDomainRef(ptr: virDomainPtr) -> libc::c_int;
     fn virDomainSnapshotGetConnect(ptr: sys::virDomainSnapshotPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
     fn virDomainSnapshotGetXMLDesc(ptr: sys::virDomainSnapshotPtr,
                                    flags: libc::c_uint)
                                    -> *mut libc::c_char;
@@ -54,8 +56,9 @@ extern "C" {
                                   flags: libc::c_uint)
                                   -> sys::virDomainSnapshotPtr;
     fn virDomainSnapshotFree(ptr: sys::virDomainSnapshotPtr) -> libc::c_int;
+    fn virDomainSnapshotRef(ptr: sys::virDomainSnapshotPtr) -> libc::c_int;
     fn virDomainSnapshotCurrent(d: virDomainPtr, flags: libc::c_uint) -> sys::virDomainSnapshotPtr;
-    fn virDomainSnapshotGetParent(d: virDomainPtr,
+    fn virDomainSnapshotGetParent(ptr: sys::virDomainSnapshotPtr,
                                   flags: libc::c_uint)
                                   -> sys::virDomainSnapshotPtr;
     fn virDomainSnapshotLookupByName(d: virDomainPtr,
@@ -70,8 +73,160 @@ extern "C" {
                                         snaps: *mut *mut sys::virDomainSnapshotPtr,
                                         flags: libc::c_uint)
                                         -> libc::c_int;
+    fn virDomainRevertToSnapshot(ptr: sys::virDomainSnapshotPtr,
+                                 flags: libc::c_uint)
+                                 -> libc::c_int;
 }
 
+bitflags! {
+    /// Flags for `DomainSnapshot::create_xml`.
+    pub struct DomainSnapshotCreateFlags: libc::c_uint {
+        const REDEFINE = 1 << 0;
+        const CURRENT = 1 << 1;
+        const NO_METADATA = 1 << 2;
+        const HALT = 1 << 3;
+        const DISK_ONLY = 1 << 4;
+        const REUSE_EXT = 1 << 5;
+        const QUIESCE = 1 << 6;
+        const ATOMIC = 1 << 7;
+        const LIVE = 1 << 8;
+        const VALIDATE = 1 << 9;
+    }
+}
+
+#[deprecated(note = "use `DomainSnapshotCreateFlags::REDEFINE`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_REDEFINE: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::REDEFINE;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::CURRENT`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_CURRENT: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::CURRENT;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::NO_METADATA`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_NO_METADATA: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::NO_METADATA;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::HALT`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_HALT: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::HALT;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::DISK_ONLY`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_DISK_ONLY: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::DISK_ONLY;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::REUSE_EXT`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_REUSE_EXT: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::REUSE_EXT;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::QUIESCE`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_QUIESCE: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::QUIESCE;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::ATOMIC`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_ATOMIC: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::ATOMIC;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::LIVE`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_LIVE: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::LIVE;
+#[deprecated(note = "use `DomainSnapshotCreateFlags::VALIDATE`")]
+pub const VIR_DOMAIN_SNAPSHOT_CREATE_VALIDATE: DomainSnapshotCreateFlags =
+    DomainSnapshotCreateFlags::VALIDATE;
+
+bitflags! {
+    /// Filters for listing and counting snapshots.
+    pub struct DomainSnapshotListFlags: libc::c_uint {
+        const ROOTS = 1 << 0;
+        const DESCENDANTS = 1 << 0;
+        const METADATA = 1 << 1;
+        const LEAVES = 1 << 2;
+        const NO_LEAVES = 1 << 3;
+        const NO_METADATA = 1 << 4;
+        const INACTIVE = 1 << 5;
+        const ACTIVE = 1 << 6;
+        const DISK_ONLY = 1 << 7;
+        const INTERNAL = 1 << 8;
+        const EXTERNAL = 1 << 9;
+        const TOPOLOGICAL = 1 << 10;
+    }
+}
+
+#[deprecated(note = "use `DomainSnapshotListFlags::ROOTS`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_ROOTS: DomainSnapshotListFlags = DomainSnapshotListFlags::ROOTS;
+#[deprecated(note = "use `DomainSnapshotListFlags::DESCENDANTS`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_DESCENDANTS: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::DESCENDANTS;
+#[deprecated(note = "use `DomainSnapshotListFlags::METADATA`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_METADATA: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::METADATA;
+#[deprecated(note = "use `DomainSnapshotListFlags::LEAVES`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_LEAVES: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::LEAVES;
+#[deprecated(note = "use `DomainSnapshotListFlags::NO_LEAVES`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_NO_LEAVES: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::NO_LEAVES;
+#[deprecated(note = "use `DomainSnapshotListFlags::NO_METADATA`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_NO_METADATA: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::NO_METADATA;
+#[deprecated(note = "use `DomainSnapshotListFlags::INACTIVE`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_INACTIVE: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::INACTIVE;
+#[deprecated(note = "use `DomainSnapshotListFlags::ACTIVE`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_ACTIVE: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::ACTIVE;
+#[deprecated(note = "use `DomainSnapshotListFlags::DISK_ONLY`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_DISK_ONLY: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::DISK_ONLY;
+#[deprecated(note = "use `DomainSnapshotListFlags::INTERNAL`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_INTERNAL: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::INTERNAL;
+#[deprecated(note = "use `DomainSnapshotListFlags::EXTERNAL`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_EXTERNAL: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::EXTERNAL;
+#[deprecated(note = "use `DomainSnapshotListFlags::TOPOLOGICAL`")]
+pub const VIR_DOMAIN_SNAPSHOT_LIST_TOPOLOGICAL: DomainSnapshotListFlags =
+    DomainSnapshotListFlags::TOPOLOGICAL;
+
+bitflags! {
+    /// Flags for `DomainSnapshot::revert`.
+    pub struct DomainSnapshotRevertFlags: libc::c_uint {
+        const RUNNING = 1 << 0;
+        const PAUSED = 1 << 1;
+        const FORCE = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `DomainSnapshotRevertFlags::RUNNING`")]
+pub const VIR_DOMAIN_SNAPSHOT_REVERT_RUNNING: DomainSnapshotRevertFlags =
+    DomainSnapshotRevertFlags::RUNNING;
+#[deprecated(note = "use `DomainSnapshotRevertFlags::PAUSED`")]
+pub const VIR_DOMAIN_SNAPSHOT_REVERT_PAUSED: DomainSnapshotRevertFlags =
+    DomainSnapshotRevertFlags::PAUSED;
+#[deprecated(note = "use `DomainSnapshotRevertFlags::FORCE`")]
+pub const VIR_DOMAIN_SNAPSHOT_REVERT_FORCE: DomainSnapshotRevertFlags =
+    DomainSnapshotRevertFlags::FORCE;
+
+bitflags! {
+    /// Flags for `DomainSnapshot::delete`.
+    pub struct DomainSnapshotDeleteFlags: libc::c_uint {
+        const CHILDREN = 1 << 0;
+        const METADATA_ONLY = 1 << 1;
+        const CHILDREN_ONLY = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `DomainSnapshotDeleteFlags::CHILDREN`")]
+pub const VIR_DOMAIN_SNAPSHOT_DELETE_CHILDREN: DomainSnapshotDeleteFlags =
+    DomainSnapshotDeleteFlags::CHILDREN;
+#[deprecated(note = "use `DomainSnapshotDeleteFlags::METADATA_ONLY`")]
+pub const VIR_DOMAIN_SNAPSHOT_DELETE_METADATA_ONLY: DomainSnapshotDeleteFlags =
+    DomainSnapshotDeleteFlags::METADATA_ONLY;
+#[deprecated(note = "use `DomainSnapshotDeleteFlags::CHILDREN_ONLY`")]
+pub const VIR_DOMAIN_SNAPSHOT_DELETE_CHILDREN_ONLY: DomainSnapshotDeleteFlags =
+    DomainSnapshotDeleteFlags::CHILDREN_ONLY;
+
+bitflags! {
+    /// Flags for `DomainSnapshot::get_xml_desc`.
+    pub struct DomainSnapshotXMLFlags: libc::c_uint {
+        const SECURE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `DomainSnapshotXMLFlags::SECURE`")]
+pub const VIR_DOMAIN_SNAPSHOT_XML_SECURE: DomainSnapshotXMLFlags = DomainSnapshotXMLFlags::SECURE;
+
 
 /// Provides APIs for the management of domain snapshots.
 ///
@@ -93,6 +248,22 @@ impl Drop for DomainSnapshot {
     }
 }
 
+impl Clone for DomainSnapshot {
+    fn clone(&self) -> DomainSnapshot {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for DomainSnapshot, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for DomainSnapshot {}
+unsafe impl Sync for DomainSnapshot {}
+
 impl DomainSnapshot {
     pub fn new(ptr: sys::virDomainSnapshotPtr) -> DomainSnapshot {
         return DomainSnapshot { ptr: Some(ptr) };
@@ -102,12 +273,27 @@ impl DomainSnapshot {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the snapshot and returns a new
+    /// handle on it, the snapshot is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<DomainSnapshot, Error> {
+        unsafe {
+            if virDomainSnapshotRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(DomainSnapshot::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virDomainSnapshotGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -118,6 +304,9 @@ impl DomainSnapshot {
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virDomainRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Domain::new(ptr));
         }
     }
@@ -146,9 +335,9 @@ impl DomainSnapshot {
     }
 
     /// Dump the XML of a snapshot.
-    pub fn get_xml_desc(&self, flags: u32) -> Result<String, Error> {
+    pub fn get_xml_desc(&self, flags: DomainSnapshotXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virDomainSnapshotGetXMLDesc(self.as_ptr(), flags as libc::c_uint);
+            let xml = virDomainSnapshotGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -156,11 +345,14 @@ impl DomainSnapshot {
         }
     }
 
-    pub fn create_xml(dom: &Domain, xml: &str, flags: u32) -> Result<DomainSnapshot, Error> {
+    pub fn create_xml(dom: &Domain,
+                      xml: &str,
+                      flags: DomainSnapshotCreateFlags)
+                      -> Result<DomainSnapshot, Error> {
         unsafe {
             let ptr = virDomainSnapshotCreateXML(dom.as_ptr(),
                                                  string_to_c_chars!(xml),
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -180,9 +372,9 @@ impl DomainSnapshot {
     }
 
     /// Get a handle to the parent snapshot, if one exists.
-    pub fn get_parent(dom: &Domain, flags: u32) -> Result<DomainSnapshot, Error> {
+    pub fn get_parent(&self, flags: u32) -> Result<DomainSnapshot, Error> {
         unsafe {
-            let ptr = virDomainSnapshotGetParent(dom.as_ptr(), flags as libc::c_uint);
+            let ptr = virDomainSnapshotGetParent(self.as_ptr(), flags as libc::c_uint);
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -191,9 +383,9 @@ impl DomainSnapshot {
     }
 
     /// Delete a snapshot.
-    pub fn delete(&self, flags: u32) -> Result<u32, Error> {
+    pub fn delete(&self, flags: DomainSnapshotDeleteFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainSnapshotDelete(self.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainSnapshotDelete(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -202,9 +394,9 @@ impl DomainSnapshot {
     }
 
     /// Return the number of snapshots for this domain.
-    pub fn num(dom: &Domain, flags: u32) -> Result<u32, Error> {
+    pub fn num(dom: &Domain, flags: DomainSnapshotListFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainSnapshotNum(dom.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainSnapshotNum(dom.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -213,9 +405,9 @@ impl DomainSnapshot {
     }
 
     /// Return the number of child snapshots for this snapshot.
-    pub fn num_children(&self, flags: u32) -> Result<u32, Error> {
+    pub fn num_children(&self, flags: DomainSnapshotListFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virDomainSnapshotNumChildren(self.as_ptr(), flags as libc::c_uint);
+            let ret = virDomainSnapshotNumChildren(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -247,11 +439,13 @@ impl DomainSnapshot {
     }
 
     /// Get all snapshot object children for this snapshot.
-    pub fn list_all_children(&self, flags: u32) -> Result<Vec<DomainSnapshot>, Error> {
+    pub fn list_all_children(&self,
+                             flags: DomainSnapshotListFlags)
+                             -> Result<Vec<DomainSnapshot>, Error> {
         unsafe {
             let mut snaps: *mut sys::virDomainSnapshotPtr = ptr::null_mut();
             let size =
-                virDomainSnapshotListAllChildren(self.as_ptr(), &mut snaps, flags as libc::c_uint);
+                virDomainSnapshotListAllChildren(self.as_ptr(), &mut snaps, flags.bits());
             if size == -1 {
                 return Err(Error::new());
             }
@@ -268,6 +462,22 @@ impl DomainSnapshot {
         }
     }
 
+    /// Revert the domain to the state captured by this snapshot.
+    ///
+    /// By default the domain is left in the state recorded in the
+    /// snapshot, `DomainSnapshotRevertFlags::RUNNING` or
+    /// `DomainSnapshotRevertFlags::PAUSED` can be used to override
+    /// it. Some reverts are considered risky by libvirt and are
+    /// refused unless `DomainSnapshotRevertFlags::FORCE` is passed.
+    pub fn revert(&self, flags: DomainSnapshotRevertFlags) -> Result<(), Error> {
+        unsafe {
+            if virDomainRevertToSnapshot(self.as_ptr(), flags.bits()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
     pub fn free(&mut self) -> Result<(), Error> {
         unsafe {
             if virDomainSnapshotFree(self.as_ptr()) == -1 {
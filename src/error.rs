@@ -20,24 +20,43 @@ extern crate libc;
 
 use std::error::Error as StdError;
 use std::fmt::{Display, Result as FmtResult, Formatter};
+use std::mem;
+use std::sync::{Arc, Mutex};
 
 pub mod sys {
     extern crate libc;
 
+    use connect::sys::virConnectPtr;
+    use domain::sys::virDomainPtr;
+    use network::sys::virNetworkPtr;
+
     #[repr(C)]
     pub struct virError {
         pub code: libc::c_int,
         pub domain: libc::c_int,
         pub message: *mut libc::c_char,
         pub level: libc::c_uint,
+        pub conn: virConnectPtr,
+        pub dom: virDomainPtr,
+        pub str1: *mut libc::c_char,
+        pub str2: *mut libc::c_char,
+        pub str3: *mut libc::c_char,
+        pub int1: libc::c_int,
+        pub int2: libc::c_int,
+        pub net: virNetworkPtr,
     }
 
     pub type virErrorPtr = *mut virError;
+
+    pub type virErrorFunc = extern "C" fn(userData: *mut libc::c_void, error: virErrorPtr);
 }
 
 #[link(name = "virt")]
 extern "C" {
-    fn virGetLastError() -> sys::virErrorPtr;
+    fn virCopyLastError(to: sys::virErrorPtr) -> libc::c_int;
+    fn virResetLastError();
+    fn virResetError(err: sys::virErrorPtr);
+    fn virSetErrorFunc(userData: *mut libc::c_void, handler: Option<sys::virErrorFunc>);
 }
 
 #[derive(Debug, PartialEq)]
@@ -51,27 +70,513 @@ pub enum ErrorLevel {
 }
 impl_from! { u32, ErrorLevel }
 
+/// The kind of an error, see `virErrorNumber`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorNumber {
+    Ok = 0,
+    InternalError = 1,
+    NoMemory = 2,
+    NoSupport = 3,
+    UnknownHost = 4,
+    NoConnect = 5,
+    InvalidConn = 6,
+    InvalidDomain = 7,
+    InvalidArg = 8,
+    OperationFailed = 9,
+    GetFailed = 10,
+    PostFailed = 11,
+    HttpError = 12,
+    SExprSerial = 13,
+    NoXen = 14,
+    XenCall = 15,
+    OsType = 16,
+    NoKernel = 17,
+    NoRoot = 18,
+    NoSource = 19,
+    NoTarget = 20,
+    NoName = 21,
+    NoOs = 22,
+    NoDevice = 23,
+    NoXenStore = 24,
+    DriverFull = 25,
+    CallFailed = 26,
+    XmlError = 27,
+    DomExist = 28,
+    OperationDenied = 29,
+    OpenFailed = 30,
+    ReadFailed = 31,
+    ParseFailed = 32,
+    ConfSyntax = 33,
+    WriteFailed = 34,
+    XmlDetail = 35,
+    InvalidNetwork = 36,
+    NetworkExist = 37,
+    SystemError = 38,
+    Rpc = 39,
+    GnuTLSError = 40,
+    WarNoNetwork = 41,
+    NoDomain = 42,
+    NoNetwork = 43,
+    InvalidMac = 44,
+    AuthFailed = 45,
+    InvalidStoragePool = 46,
+    InvalidStorageVol = 47,
+    WarNoStorage = 48,
+    NoStoragePool = 49,
+    NoStorageVol = 50,
+    WarNoNode = 51,
+    InvalidNodeDevice = 52,
+    NoNodeDevice = 53,
+    NoSecurityModel = 54,
+    OperationInvalid = 55,
+    WarNoInterface = 56,
+    NoInterface = 57,
+    InvalidInterface = 58,
+    MultipleInterfaces = 59,
+    WarNoNWFilter = 60,
+    InvalidNWFilter = 61,
+    NoNWFilter = 62,
+    BuildFirewall = 63,
+    WarNoSecret = 64,
+    InvalidSecret = 65,
+    NoSecret = 66,
+    ConfigUnsupported = 67,
+    OperationTimeout = 68,
+    MigratePersistFailed = 69,
+    HookScriptFailed = 70,
+    InvalidDomainSnapshot = 71,
+    NoDomainSnapshot = 72,
+    InvalidStream = 73,
+    ArgumentUnsupported = 74,
+    StorageProbeFailed = 75,
+    StoragePoolBuilt = 76,
+    SnapshotRevertRisky = 77,
+    OperationAborted = 78,
+    AuthCancelled = 79,
+    NoDomainMetadata = 80,
+    MigrateUnsafe = 81,
+    Overflow = 82,
+    BlockCopyActive = 83,
+    OperationUnsupported = 84,
+    Ssh = 85,
+    AgentUnresponsive = 86,
+    ResourceBusy = 87,
+    AccessDenied = 88,
+    DBusService = 89,
+    StorageVolExist = 90,
+    CpuIncompatible = 91,
+    XmlInvalidSchema = 92,
+    MigrateFinishOk = 93,
+    AuthUnavailable = 94,
+    NoServer = 95,
+    NoClient = 96,
+    AgentUnsynced = 97,
+    LibSSH = 98,
+    DeviceMissing = 99,
+    InvalidNWFilterBinding = 100,
+    NoNWFilterBinding = 101,
+    InvalidDomainCheckpoint = 102,
+    NoDomainCheckpoint = 103,
+    NoDomainBackup = 104,
+    InvalidNetworkPort = 105,
+    NetworkPortExist = 106,
+    NoNetworkPort = 107,
+    NoHostname = 108,
+    CheckpointInconsistent = 109,
+    MultipleDomains = 110,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl ErrorNumber {
+    pub fn from_raw(value: i32) -> ErrorNumber {
+        match value {
+            0 => ErrorNumber::Ok,
+            1 => ErrorNumber::InternalError,
+            2 => ErrorNumber::NoMemory,
+            3 => ErrorNumber::NoSupport,
+            4 => ErrorNumber::UnknownHost,
+            5 => ErrorNumber::NoConnect,
+            6 => ErrorNumber::InvalidConn,
+            7 => ErrorNumber::InvalidDomain,
+            8 => ErrorNumber::InvalidArg,
+            9 => ErrorNumber::OperationFailed,
+            10 => ErrorNumber::GetFailed,
+            11 => ErrorNumber::PostFailed,
+            12 => ErrorNumber::HttpError,
+            13 => ErrorNumber::SExprSerial,
+            14 => ErrorNumber::NoXen,
+            15 => ErrorNumber::XenCall,
+            16 => ErrorNumber::OsType,
+            17 => ErrorNumber::NoKernel,
+            18 => ErrorNumber::NoRoot,
+            19 => ErrorNumber::NoSource,
+            20 => ErrorNumber::NoTarget,
+            21 => ErrorNumber::NoName,
+            22 => ErrorNumber::NoOs,
+            23 => ErrorNumber::NoDevice,
+            24 => ErrorNumber::NoXenStore,
+            25 => ErrorNumber::DriverFull,
+            26 => ErrorNumber::CallFailed,
+            27 => ErrorNumber::XmlError,
+            28 => ErrorNumber::DomExist,
+            29 => ErrorNumber::OperationDenied,
+            30 => ErrorNumber::OpenFailed,
+            31 => ErrorNumber::ReadFailed,
+            32 => ErrorNumber::ParseFailed,
+            33 => ErrorNumber::ConfSyntax,
+            34 => ErrorNumber::WriteFailed,
+            35 => ErrorNumber::XmlDetail,
+            36 => ErrorNumber::InvalidNetwork,
+            37 => ErrorNumber::NetworkExist,
+            38 => ErrorNumber::SystemError,
+            39 => ErrorNumber::Rpc,
+            40 => ErrorNumber::GnuTLSError,
+            41 => ErrorNumber::WarNoNetwork,
+            42 => ErrorNumber::NoDomain,
+            43 => ErrorNumber::NoNetwork,
+            44 => ErrorNumber::InvalidMac,
+            45 => ErrorNumber::AuthFailed,
+            46 => ErrorNumber::InvalidStoragePool,
+            47 => ErrorNumber::InvalidStorageVol,
+            48 => ErrorNumber::WarNoStorage,
+            49 => ErrorNumber::NoStoragePool,
+            50 => ErrorNumber::NoStorageVol,
+            51 => ErrorNumber::WarNoNode,
+            52 => ErrorNumber::InvalidNodeDevice,
+            53 => ErrorNumber::NoNodeDevice,
+            54 => ErrorNumber::NoSecurityModel,
+            55 => ErrorNumber::OperationInvalid,
+            56 => ErrorNumber::WarNoInterface,
+            57 => ErrorNumber::NoInterface,
+            58 => ErrorNumber::InvalidInterface,
+            59 => ErrorNumber::MultipleInterfaces,
+            60 => ErrorNumber::WarNoNWFilter,
+            61 => ErrorNumber::InvalidNWFilter,
+            62 => ErrorNumber::NoNWFilter,
+            63 => ErrorNumber::BuildFirewall,
+            64 => ErrorNumber::WarNoSecret,
+            65 => ErrorNumber::InvalidSecret,
+            66 => ErrorNumber::NoSecret,
+            67 => ErrorNumber::ConfigUnsupported,
+            68 => ErrorNumber::OperationTimeout,
+            69 => ErrorNumber::MigratePersistFailed,
+            70 => ErrorNumber::HookScriptFailed,
+            71 => ErrorNumber::InvalidDomainSnapshot,
+            72 => ErrorNumber::NoDomainSnapshot,
+            73 => ErrorNumber::InvalidStream,
+            74 => ErrorNumber::ArgumentUnsupported,
+            75 => ErrorNumber::StorageProbeFailed,
+            76 => ErrorNumber::StoragePoolBuilt,
+            77 => ErrorNumber::SnapshotRevertRisky,
+            78 => ErrorNumber::OperationAborted,
+            79 => ErrorNumber::AuthCancelled,
+            80 => ErrorNumber::NoDomainMetadata,
+            81 => ErrorNumber::MigrateUnsafe,
+            82 => ErrorNumber::Overflow,
+            83 => ErrorNumber::BlockCopyActive,
+            84 => ErrorNumber::OperationUnsupported,
+            85 => ErrorNumber::Ssh,
+            86 => ErrorNumber::AgentUnresponsive,
+            87 => ErrorNumber::ResourceBusy,
+            88 => ErrorNumber::AccessDenied,
+            89 => ErrorNumber::DBusService,
+            90 => ErrorNumber::StorageVolExist,
+            91 => ErrorNumber::CpuIncompatible,
+            92 => ErrorNumber::XmlInvalidSchema,
+            93 => ErrorNumber::MigrateFinishOk,
+            94 => ErrorNumber::AuthUnavailable,
+            95 => ErrorNumber::NoServer,
+            96 => ErrorNumber::NoClient,
+            97 => ErrorNumber::AgentUnsynced,
+            98 => ErrorNumber::LibSSH,
+            99 => ErrorNumber::DeviceMissing,
+            100 => ErrorNumber::InvalidNWFilterBinding,
+            101 => ErrorNumber::NoNWFilterBinding,
+            102 => ErrorNumber::InvalidDomainCheckpoint,
+            103 => ErrorNumber::NoDomainCheckpoint,
+            104 => ErrorNumber::NoDomainBackup,
+            105 => ErrorNumber::InvalidNetworkPort,
+            106 => ErrorNumber::NetworkPortExist,
+            107 => ErrorNumber::NoNetworkPort,
+            108 => ErrorNumber::NoHostname,
+            109 => ErrorNumber::CheckpointInconsistent,
+            110 => ErrorNumber::MultipleDomains,
+            _ => ErrorNumber::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+/// The part of libvirt which raised an error, see `virErrorDomain`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorDomain {
+    None = 0,
+    Xen = 1,
+    Xend = 2,
+    XenStore = 3,
+    SExpr = 4,
+    Xml = 5,
+    Dom = 6,
+    Rpc = 7,
+    Proxy = 8,
+    Conf = 9,
+    Qemu = 10,
+    Net = 11,
+    Test = 12,
+    Remote = 13,
+    OpenVZ = 14,
+    XenXM = 15,
+    StatsLinux = 16,
+    Lxc = 17,
+    Storage = 18,
+    Network = 19,
+    Domain = 20,
+    Uml = 21,
+    NodeDev = 22,
+    XenInotify = 23,
+    Security = 24,
+    VBox = 25,
+    Interface = 26,
+    One = 27,
+    Esx = 28,
+    Phyp = 29,
+    Secret = 30,
+    Cpu = 31,
+    XenAPI = 32,
+    NWFilter = 33,
+    Hook = 34,
+    DomainSnapshot = 35,
+    Audit = 36,
+    SysInfo = 37,
+    Streams = 38,
+    VMware = 39,
+    Event = 40,
+    LibXL = 41,
+    Locking = 42,
+    HyperV = 43,
+    Capabilities = 44,
+    Uri = 45,
+    Auth = 46,
+    DBus = 47,
+    Parallels = 48,
+    Device = 49,
+    Ssh = 50,
+    LockSpace = 51,
+    InitCtl = 52,
+    Identity = 53,
+    Cgroup = 54,
+    Access = 55,
+    Systemd = 56,
+    Bhyve = 57,
+    Crypto = 58,
+    Firewall = 59,
+    Polkit = 60,
+    Thread = 61,
+    Admin = 62,
+    Logging = 63,
+    XenXL = 64,
+    Perf = 65,
+    LibSSH = 66,
+    ResCtrl = 67,
+    FirewallD = 68,
+    DomainCheckpoint = 69,
+    Tpm = 70,
+    Bpf = 71,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl ErrorDomain {
+    pub fn from_raw(value: i32) -> ErrorDomain {
+        match value {
+            0 => ErrorDomain::None,
+            1 => ErrorDomain::Xen,
+            2 => ErrorDomain::Xend,
+            3 => ErrorDomain::XenStore,
+            4 => ErrorDomain::SExpr,
+            5 => ErrorDomain::Xml,
+            6 => ErrorDomain::Dom,
+            7 => ErrorDomain::Rpc,
+            8 => ErrorDomain::Proxy,
+            9 => ErrorDomain::Conf,
+            10 => ErrorDomain::Qemu,
+            11 => ErrorDomain::Net,
+            12 => ErrorDomain::Test,
+            13 => ErrorDomain::Remote,
+            14 => ErrorDomain::OpenVZ,
+            15 => ErrorDomain::XenXM,
+            16 => ErrorDomain::StatsLinux,
+            17 => ErrorDomain::Lxc,
+            18 => ErrorDomain::Storage,
+            19 => ErrorDomain::Network,
+            20 => ErrorDomain::Domain,
+            21 => ErrorDomain::Uml,
+            22 => ErrorDomain::NodeDev,
+            23 => ErrorDomain::XenInotify,
+            24 => ErrorDomain::Security,
+            25 => ErrorDomain::VBox,
+            26 => ErrorDomain::Interface,
+            27 => ErrorDomain::One,
+            28 => ErrorDomain::Esx,
+            29 => ErrorDomain::Phyp,
+            30 => ErrorDomain::Secret,
+            31 => ErrorDomain::Cpu,
+            32 => ErrorDomain::XenAPI,
+            33 => ErrorDomain::NWFilter,
+            34 => ErrorDomain::Hook,
+            35 => ErrorDomain::DomainSnapshot,
+            36 => ErrorDomain::Audit,
+            37 => ErrorDomain::SysInfo,
+            38 => ErrorDomain::Streams,
+            39 => ErrorDomain::VMware,
+            40 => ErrorDomain::Event,
+            41 => ErrorDomain::LibXL,
+            42 => ErrorDomain::Locking,
+            43 => ErrorDomain::HyperV,
+            44 => ErrorDomain::Capabilities,
+            45 => ErrorDomain::Uri,
+            46 => ErrorDomain::Auth,
+            47 => ErrorDomain::DBus,
+            48 => ErrorDomain::Parallels,
+            49 => ErrorDomain::Device,
+            50 => ErrorDomain::Ssh,
+            51 => ErrorDomain::LockSpace,
+            52 => ErrorDomain::InitCtl,
+            53 => ErrorDomain::Identity,
+            54 => ErrorDomain::Cgroup,
+            55 => ErrorDomain::Access,
+            56 => ErrorDomain::Systemd,
+            57 => ErrorDomain::Bhyve,
+            58 => ErrorDomain::Crypto,
+            59 => ErrorDomain::Firewall,
+            60 => ErrorDomain::Polkit,
+            61 => ErrorDomain::Thread,
+            62 => ErrorDomain::Admin,
+            63 => ErrorDomain::Logging,
+            64 => ErrorDomain::XenXL,
+            65 => ErrorDomain::Perf,
+            66 => ErrorDomain::LibSSH,
+            67 => ErrorDomain::ResCtrl,
+            68 => ErrorDomain::FirewallD,
+            69 => ErrorDomain::DomainCheckpoint,
+            70 => ErrorDomain::Tpm,
+            71 => ErrorDomain::Bpf,
+            _ => ErrorDomain::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+impl Display for ErrorNumber {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Display for ErrorDomain {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// Error handling
 ///
+/// The `conn`, `dom` and `net` fields of `virError` are not exposed,
+/// libvirt deprecates them since no reference is held on the objects
+/// they point to.
+///
 /// See: http://libvirt.org/html/libvirt-virterror.html
 #[derive(Debug, PartialEq)]
 pub struct Error {
-    pub code: i32,
-    pub domain: i32,
+    pub code: ErrorNumber,
+    pub domain: ErrorDomain,
     pub message: String,
     pub level: ErrorLevel,
+    /// Extra string information.
+    pub str1: Option<String>,
+    /// Extra string information.
+    pub str2: Option<String>,
+    /// Extra string information.
+    pub str3: Option<String>,
+    /// Extra number information.
+    pub int1: i32,
+    /// Extra number information.
+    pub int2: i32,
 }
 
 impl Error {
+    /// Takes the last error raised by libvirt and resets it.
+    ///
+    /// libvirt keeps the last error in thread-local storage, so this
+    /// has to be called from the thread which issued the failing
+    /// call, before any other libvirt call is made on that thread.
+    /// If no error has been raised an error with code
+    /// `ErrorNumber::Ok` is returned.
     pub fn new() -> Error {
-        unsafe {
-            let ptr: sys::virErrorPtr = virGetLastError();
+        let err = Error::last_error().unwrap_or_else(|| {
             Error {
-                code: (*ptr).code,
-                domain: (*ptr).domain,
-                message: c_chars_to_string!((*ptr).message, nofree),
-                level: ErrorLevel::from((*ptr).level),
+                code: ErrorNumber::Ok,
+                domain: ErrorDomain::None,
+                message: String::from("an unknown error occurred"),
+                level: ErrorLevel::ERROR,
+                str1: None,
+                str2: None,
+                str3: None,
+                int1: 0,
+                int2: 0,
+            }
+        });
+        Error::clear();
+        err
+    }
+
+    /// Returns a copy of the last error raised by libvirt in the
+    /// current thread, if any, without resetting it.
+    pub fn last_error() -> Option<Error> {
+        unsafe {
+            let mut cerr: sys::virError = mem::zeroed();
+            if virCopyLastError(&mut cerr) <= 0 {
+                virResetError(&mut cerr);
+                return None;
             }
+            let err = Error::from_ptr(&mut cerr);
+            virResetError(&mut cerr);
+            Some(err)
+        }
+    }
+
+    /// Resets the last error raised by libvirt in the current thread.
+    pub fn clear() {
+        unsafe { virResetLastError() }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` has to point to a valid `virError`.
+    pub unsafe fn from_ptr(ptr: sys::virErrorPtr) -> Error {
+        fn to_opt(s: *mut libc::c_char) -> Option<String> {
+            if s.is_null() {
+                return None;
+            }
+            Some(unsafe { c_chars_to_string!(s, nofree) })
+        }
+        Error {
+            code: ErrorNumber::from_raw((*ptr).code),
+            domain: ErrorDomain::from_raw((*ptr).domain),
+            message: to_opt((*ptr).message).unwrap_or_default(),
+            level: ErrorLevel::from((*ptr).level),
+            str1: to_opt((*ptr).str1),
+            str2: to_opt((*ptr).str2),
+            str3: to_opt((*ptr).str3),
+            int1: (*ptr).int1,
+            int2: (*ptr).int2,
         }
     }
 }
@@ -92,3 +597,65 @@ impl Display for Error {
                self.message)
     }
 }
+
+/// Handler called with every error raised by libvirt.
+pub type ErrorCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+static ERROR_FUNC: Mutex<Option<ErrorCallback>> = Mutex::new(None);
+
+extern "C" fn errorCallback(_data: *mut libc::c_void, err: sys::virErrorPtr) {
+    // The handler is cloned out of the lock so it can itself call
+    // libvirt, or replace the handler, without deadlocking.
+    let handler = match ERROR_FUNC.lock() {
+        Ok(guard) => guard.clone(),
+        Err(_) => return,
+    };
+    if let Some(handler) = handler {
+        if !err.is_null() {
+            handler(&unsafe { Error::from_ptr(err) });
+        }
+    }
+}
+
+/// Installs `handler` to be called with every error raised by
+/// libvirt, instead of printing them on stderr.
+///
+/// The handler is global to the process and may be called from any
+/// thread. A handler set on a connection with
+/// `Connect::set_error_func` takes precedence for the errors raised
+/// by that connection.
+///
+/// # Examples
+///
+/// ```
+/// use virt::error;
+///
+/// // Route libvirt errors to the log crate.
+/// error::set_error_func(error::log_error);
+/// ```
+pub fn set_error_func<F>(handler: F)
+    where F: Fn(&Error) + Send + Sync + 'static
+{
+    if let Ok(mut guard) = ERROR_FUNC.lock() {
+        *guard = Some(Arc::new(handler));
+    }
+    unsafe { virSetErrorFunc(::std::ptr::null_mut(), Some(errorCallback)) }
+}
+
+/// Restores the default libvirt handler printing errors on stderr.
+pub fn reset_error_func() {
+    unsafe { virSetErrorFunc(::std::ptr::null_mut(), None) }
+    if let Ok(mut guard) = ERROR_FUNC.lock() {
+        *guard = None;
+    }
+}
+
+/// Error handler forwarding libvirt errors to the `log` crate, with a
+/// level depending of the `ErrorLevel` of the error.
+pub fn log_error(err: &Error) {
+    match err.level {
+        ErrorLevel::ERROR => error!("{}", err),
+        ErrorLevel::WARNING => warn!("{}", err),
+        ErrorLevel::NONE => debug!("{}", err),
+    }
+}
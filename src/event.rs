@@ -0,0 +1,66 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Event loop used by libvirt to dispatch asynchronous
+//! notifications, like keepalive messages or connection close
+//! callbacks.
+//!
+//! The default implementation has to be registered before opening
+//! connections and then run in a loop, typically from a dedicated
+//! thread.
+//!
+//! ```no_run
+//! use std::thread;
+//! use virt::event;
+//!
+//! event::register_default_impl().unwrap();
+//! thread::spawn(|| loop {
+//!     event::run_default_impl().unwrap();
+//! });
+//! ```
+
+extern crate libc;
+
+use error::Error;
+
+#[link(name = "virt")]
+extern "C" {
+    fn virEventRegisterDefaultImpl() -> libc::c_int;
+    fn virEventRunDefaultImpl() -> libc::c_int;
+}
+
+/// Registers the default event loop implementation of libvirt.
+pub fn register_default_impl() -> Result<(), Error> {
+    unsafe {
+        if virEventRegisterDefaultImpl() == -1 {
+            return Err(Error::new());
+        }
+    }
+    Ok(())
+}
+
+/// Runs one iteration of the default event loop, blocking until at
+/// least one event has been dispatched.
+pub fn run_default_impl() -> Result<(), Error> {
+    unsafe {
+        if virEventRunDefaultImpl() == -1 {
+            return Err(Error::new());
+        }
+    }
+    Ok(())
+}
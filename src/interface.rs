@@ -50,15 +50,24 @@ extern "C" {
     fn virInterfaceDestroy(ptr: sys::virInterfacePtr) -> libc::c_int;
     fn virInterfaceUndefine(ptr: sys::virInterfacePtr) -> libc::c_int;
     fn virInterfaceFree(ptr: sys::virInterfacePtr) -> libc::c_int;
+    fn virInterfaceRef(ptr: sys::virInterfacePtr) -> libc::c_int;
     fn virInterfaceIsActive(ptr: sys::virInterfacePtr) -> libc::c_int;
     fn virInterfaceGetName(ptr: sys::virInterfacePtr) -> *const libc::c_char;
     fn virInterfaceGetMACString(ptr: sys::virInterfacePtr) -> *const libc::c_char;
     fn virInterfaceGetXMLDesc(ptr: sys::virInterfacePtr, flags: libc::c_uint) -> *mut libc::c_char;
     fn virInterfaceGetConnect(ptr: sys::virInterfacePtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
 }
 
-pub type InterfaceXMLFlags = self::libc::c_uint;
-pub const VIR_INTERFACE_XML_INACTIVE: InterfaceXMLFlags = 1 << 0;
+bitflags! {
+    /// Flags for `Interface::get_xml_desc`.
+    pub struct InterfaceXMLFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `InterfaceXMLFlags::INACTIVE`")]
+pub const VIR_INTERFACE_XML_INACTIVE: InterfaceXMLFlags = InterfaceXMLFlags::INACTIVE;
 
 /// Provides APIs for the management of interfaces.
 ///
@@ -80,6 +89,22 @@ impl Drop for Interface {
     }
 }
 
+impl Clone for Interface {
+    fn clone(&self) -> Interface {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for Interface, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for Interface {}
+unsafe impl Sync for Interface {}
+
 impl Interface {
     pub fn new(ptr: sys::virInterfacePtr) -> Interface {
         return Interface { ptr: Some(ptr) };
@@ -89,12 +114,27 @@ impl Interface {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the interface and returns a new
+    /// handle on it, the interface is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<Interface, Error> {
+        unsafe {
+            if virInterfaceRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(Interface::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virInterfaceGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -173,7 +213,7 @@ impl Interface {
 
     pub fn get_xml_desc(&self, flags: InterfaceXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virInterfaceGetXMLDesc(self.as_ptr(), flags);
+            let xml = virInterfaceGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -181,9 +221,9 @@ impl Interface {
         }
     }
 
-    pub fn create(&self, flags: InterfaceXMLFlags) -> Result<u32, Error> {
+    pub fn create(&self, flags: u32) -> Result<u32, Error> {
         unsafe {
-            let ret = virInterfaceCreate(self.as_ptr(), flags);
+            let ret = virInterfaceCreate(self.as_ptr(), flags as libc::c_uint);
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -58,17 +58,60 @@
 //! }
 //! ```
 //!
+//! Since release 0.6.0 the libvirt public API is thread-safe, the
+//! handles (`Connect`, `Domain`, `Network`...) are therefore `Send`
+//! and `Sync` and can be shared between threads, for instance behind
+//! an `Arc`. Methods releasing a handle like `free` or `close` take
+//! `&mut self` so they cannot race with calls made through a shared
+//! reference. Errors are reported per thread, a failing call always
+//! returns the error raised by that call even if other threads are
+//! failing at the same time. `Stream` is only `Send`.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::thread;
+//! use virt::connect::Connect;
+//!
+//! if let Ok(conn) = Connect::open("test:///default") {
+//!   let conn = Arc::new(conn);
+//!   let workers: Vec<_> = (0..4).map(|_| {
+//!     let conn = conn.clone();
+//!     thread::spawn(move || conn.get_hostname())
+//!   }).collect();
+//!   for worker in workers {
+//!     assert!(worker.join().unwrap().is_ok());
+//!   }
+//! }
+//! ```
+//!
+//! Flags are typed, each family of `VIR_*` constants is a bitflags
+//! struct (`ConnectListAllDomainsFlags`, `DomainMigrateFlags`...)
+//! and each enumeration is a Rust enum (`DomainState`,
+//! `StorageVolWipeAlgorithm`...), so passing flags of a family to a
+//! method expecting another one does not compile. The `VIR_*` names
+//! are kept as deprecated aliases.
+//!
+//! ```
+//! use virt::connect::{Connect, ConnectListAllDomainsFlags};
+//!
+//! if let Ok(mut conn) = Connect::open("test:///default") {
+//!   let flags = ConnectListAllDomainsFlags::ACTIVE | ConnectListAllDomainsFlags::PERSISTENT;
+//!   assert_eq!(1, conn.list_all_domains(flags).unwrap().len());
+//!   assert_eq!(Ok(0), conn.close());
+//! }
+//! ```
+//!
 //! For each methods accepting or returning a virTypedParameter array
 //! a new Rust struct has been defined where each attribute is
 //! handling a type Option.
 //!
 //! ```
 //! use virt::connect::Connect;
-//! use virt::domain::Domain;
+//! use virt::domain::{Domain, DomainModImpactFlags};
 //!
 //! if let Ok(mut conn) = Connect::open("test://default") {
 //!   if let Ok(dom) = Domain::lookup_by_name(&conn, "myguest") {
-//!     if let Ok(memp) = dom.get_memory_parameters(0) {
+//!     if let Ok(memp) = dom.get_memory_parameters(DomainModImpactFlags::CURRENT) {
 //!       if memp.hard_limit.is_some() {
 //!         println!("hard limit: {}", memp.hard_limit.unwrap())
 //!       }
@@ -86,6 +129,12 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+#[macro_use]
+extern crate bitflags;
+#[macro_use]
+extern crate log;
+extern crate xml as xml_rs;
+
 macro_rules! c_chars_to_string {
     ($x:expr) => {{
         let ret = ::std::ffi::CStr::from_ptr($x).to_string_lossy().into_owned();
@@ -144,14 +193,23 @@ macro_rules! impl_from {
 
 pub mod typedparam;
 pub mod connect;
+pub mod connection_manager;
+pub mod credentials;
 pub mod domain;
+pub mod domain_checkpoint;
 pub mod domain_snapshot;
+pub mod snapshot_tree;
 pub mod error;
+pub mod event;
 pub mod network;
+pub mod network_port;
 pub mod nodedev;
 pub mod nwfilter;
+pub mod nwfilter_binding;
 pub mod interface;
 pub mod secret;
 pub mod storage_pool;
 pub mod storage_vol;
 pub mod stream;
+pub mod uri;
+pub mod xml;
@@ -18,18 +18,39 @@
 
 extern crate libc;
 
-use std::str;
+use std::{str, ptr};
 
 use connect::sys::virConnectPtr;
+use network_port::sys::virNetworkPortPtr;
 
 use connect::Connect;
 use error::Error;
+use network_port::NetworkPort;
+use xml::network::{DhcpHost, DhcpRange, DnsHost, DnsSrv, DnsTxt, Portgroup};
+use xml::XmlElement;
 
 pub mod sys {
+    extern crate libc;
+
     #[repr(C)]
     pub struct virNetwork {}
 
     pub type virNetworkPtr = *mut virNetwork;
+
+    #[repr(C)]
+    pub struct virNetworkDHCPLease {
+        pub iface: *mut libc::c_char,
+        pub expirytime: libc::c_longlong,
+        pub typed: libc::c_int,
+        pub mac: *mut libc::c_char,
+        pub iaid: *mut libc::c_char,
+        pub ipaddr: *mut libc::c_char,
+        pub prefix: libc::c_uint,
+        pub hostname: *mut libc::c_char,
+        pub clientid: *mut libc::c_char,
+    }
+
+    pub type virNetworkDHCPLeasePtr = *mut virNetworkDHCPLease;
 }
 
 #[link(name = "virt")]
@@ -48,6 +69,7 @@ extern "C" {
     fn virNetworkDestroy(ptr: sys::virNetworkPtr) -> libc::c_int;
     fn virNetworkUndefine(ptr: sys::virNetworkPtr) -> libc::c_int;
     fn virNetworkFree(ptr: sys::virNetworkPtr) -> libc::c_int;
+    fn virNetworkRef(ptr: sys::virNetworkPtr) -> libc::c_int;
     fn virNetworkIsActive(ptr: sys::virNetworkPtr) -> libc::c_int;
     fn virNetworkIsPersistent(ptr: sys::virNetworkPtr) -> libc::c_int;
     fn virNetworkGetName(ptr: sys::virNetworkPtr) -> *const libc::c_char;
@@ -64,37 +86,208 @@ extern "C" {
                         flags: libc::c_uint)
                         -> libc::c_int;
     fn virNetworkGetConnect(ptr: sys::virNetworkPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
+    fn virNetworkGetDHCPLeases(ptr: sys::virNetworkPtr,
+                               mac: *const libc::c_char,
+                               leases: *mut *mut sys::virNetworkDHCPLeasePtr,
+                               flags: libc::c_uint)
+                               -> libc::c_int;
+    fn virNetworkListAllPorts(ptr: sys::virNetworkPtr,
+                              ports: *mut *mut virNetworkPortPtr,
+                              flags: libc::c_uint)
+                              -> libc::c_int;
+    fn virNetworkDHCPLeaseFree(lease: sys::virNetworkDHCPLeasePtr);
 }
 
-pub type NetworkXMLFlags = self::libc::c_uint;
-pub const VIR_NETWORK_XML_INACTIVE: NetworkXMLFlags = 1 << 0;
-
-pub type NetworkUpdateCommand = self::libc::c_uint;
-pub const VIR_NETWORK_UPDATE_COMMAND_NONE: NetworkUpdateCommand = 0;
-pub const VIR_NETWORK_UPDATE_COMMAND_MODIFY: NetworkUpdateCommand = 1;
-pub const VIR_NETWORK_UPDATE_COMMAND_DELETE: NetworkUpdateCommand = 2;
-pub const VIR_NETWORK_UPDATE_COMMAND_ADD_LAST: NetworkUpdateCommand = 3;
-pub const VIR_NETWORK_UPDATE_COMMAND_ADD_FIRST: NetworkUpdateCommand = 4;
-
-pub type NetworkUpdateSection = self::libc::c_uint;
-pub const VIR_NETWORK_SECTION_NONE: NetworkUpdateSection = 0;
-pub const VIR_NETWORK_SECTION_BRIDGE: NetworkUpdateSection = 1;
-pub const VIR_NETWORK_SECTION_DOMAIN: NetworkUpdateSection = 2;
-pub const VIR_NETWORK_SECTION_IP: NetworkUpdateSection = 3;
-pub const VIR_NETWORK_SECTION_IP_DHCP_HOST: NetworkUpdateSection = 4;
-pub const VIR_NETWORK_SECTION_IP_DHCP_RANGE: NetworkUpdateSection = 5;
-pub const VIR_NETWORK_SECTION_FORWARD: NetworkUpdateSection = 6;
-pub const VIR_NETWORK_SECTION_FORWARD_INTERFACE: NetworkUpdateSection = 7;
-pub const VIR_NETWORK_SECTION_FORWARD_PF: NetworkUpdateSection = 8;
-pub const VIR_NETWORK_SECTION_PORTGROUP: NetworkUpdateSection = 9;
-pub const VIR_NETWORK_SECTION_DNS_HOST: NetworkUpdateSection = 10;
-pub const VIR_NETWORK_SECTION_DNS_TXT: NetworkUpdateSection = 11;
-pub const VIR_NETWORK_SECTION_DNS_SRV: NetworkUpdateSection = 12;
-
-pub type NetworkUpdateFlags = self::libc::c_uint;
-pub const VIR_NETWORK_UPDATE_AFFECT_CURRENT: NetworkUpdateFlags = 0;
-pub const VIR_NETWORK_UPDATE_AFFECT_LIVE: NetworkUpdateFlags = 1 << 0;
-pub const VIR_NETWORK_UPDATE_AFFECT_CONFIG: NetworkUpdateFlags = 1 << 1;
+bitflags! {
+    /// Flags for `Network::get_xml_desc`.
+    pub struct NetworkXMLFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `NetworkXMLFlags::INACTIVE`")]
+pub const VIR_NETWORK_XML_INACTIVE: NetworkXMLFlags = NetworkXMLFlags::INACTIVE;
+
+/// Operation performed by `Network::update`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkUpdateCommand {
+    /// Invalid.
+    None = 0,
+    /// Modify an existing element.
+    Modify = 1,
+    /// Delete an existing element.
+    Delete = 2,
+    /// Add an element at the end of the list.
+    AddLast = 3,
+    /// Add an element at the start of the list.
+    AddFirst = 4,
+}
+
+#[deprecated(note = "use `NetworkUpdateCommand::None`")]
+pub const VIR_NETWORK_UPDATE_COMMAND_NONE: NetworkUpdateCommand = NetworkUpdateCommand::None;
+#[deprecated(note = "use `NetworkUpdateCommand::Modify`")]
+pub const VIR_NETWORK_UPDATE_COMMAND_MODIFY: NetworkUpdateCommand = NetworkUpdateCommand::Modify;
+#[deprecated(note = "use `NetworkUpdateCommand::Delete`")]
+pub const VIR_NETWORK_UPDATE_COMMAND_DELETE: NetworkUpdateCommand = NetworkUpdateCommand::Delete;
+#[deprecated(note = "use `NetworkUpdateCommand::AddLast`")]
+pub const VIR_NETWORK_UPDATE_COMMAND_ADD_LAST: NetworkUpdateCommand = NetworkUpdateCommand::AddLast;
+#[deprecated(note = "use `NetworkUpdateCommand::AddFirst`")]
+pub const VIR_NETWORK_UPDATE_COMMAND_ADD_FIRST: NetworkUpdateCommand =
+    NetworkUpdateCommand::AddFirst;
+
+/// Section of the network XML changed by `Network::update`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkUpdateSection {
+    /// Invalid.
+    None = 0,
+    /// `<bridge>`
+    Bridge = 1,
+    /// `<domain>`
+    Domain = 2,
+    /// `<ip>`
+    Ip = 3,
+    /// `<ip>/<dhcp>/<host>`
+    IpDhcpHost = 4,
+    /// `<ip>/<dhcp>/<range>`
+    IpDhcpRange = 5,
+    /// `<forward>`
+    Forward = 6,
+    /// `<forward>/<interface>`
+    ForwardInterface = 7,
+    /// `<forward>/<pf>`
+    ForwardPf = 8,
+    /// `<portgroup>`
+    Portgroup = 9,
+    /// `<dns>/<host>`
+    DnsHost = 10,
+    /// `<dns>/<txt>`
+    DnsTxt = 11,
+    /// `<dns>/<srv>`
+    DnsSrv = 12,
+}
+
+#[deprecated(note = "use `NetworkUpdateSection::None`")]
+pub const VIR_NETWORK_SECTION_NONE: NetworkUpdateSection = NetworkUpdateSection::None;
+#[deprecated(note = "use `NetworkUpdateSection::Bridge`")]
+pub const VIR_NETWORK_SECTION_BRIDGE: NetworkUpdateSection = NetworkUpdateSection::Bridge;
+#[deprecated(note = "use `NetworkUpdateSection::Domain`")]
+pub const VIR_NETWORK_SECTION_DOMAIN: NetworkUpdateSection = NetworkUpdateSection::Domain;
+#[deprecated(note = "use `NetworkUpdateSection::Ip`")]
+pub const VIR_NETWORK_SECTION_IP: NetworkUpdateSection = NetworkUpdateSection::Ip;
+#[deprecated(note = "use `NetworkUpdateSection::IpDhcpHost`")]
+pub const VIR_NETWORK_SECTION_IP_DHCP_HOST: NetworkUpdateSection = NetworkUpdateSection::IpDhcpHost;
+#[deprecated(note = "use `NetworkUpdateSection::IpDhcpRange`")]
+pub const VIR_NETWORK_SECTION_IP_DHCP_RANGE: NetworkUpdateSection =
+    NetworkUpdateSection::IpDhcpRange;
+#[deprecated(note = "use `NetworkUpdateSection::Forward`")]
+pub const VIR_NETWORK_SECTION_FORWARD: NetworkUpdateSection = NetworkUpdateSection::Forward;
+#[deprecated(note = "use `NetworkUpdateSection::ForwardInterface`")]
+pub const VIR_NETWORK_SECTION_FORWARD_INTERFACE: NetworkUpdateSection =
+    NetworkUpdateSection::ForwardInterface;
+#[deprecated(note = "use `NetworkUpdateSection::ForwardPf`")]
+pub const VIR_NETWORK_SECTION_FORWARD_PF: NetworkUpdateSection = NetworkUpdateSection::ForwardPf;
+#[deprecated(note = "use `NetworkUpdateSection::Portgroup`")]
+pub const VIR_NETWORK_SECTION_PORTGROUP: NetworkUpdateSection = NetworkUpdateSection::Portgroup;
+#[deprecated(note = "use `NetworkUpdateSection::DnsHost`")]
+pub const VIR_NETWORK_SECTION_DNS_HOST: NetworkUpdateSection = NetworkUpdateSection::DnsHost;
+#[deprecated(note = "use `NetworkUpdateSection::DnsTxt`")]
+pub const VIR_NETWORK_SECTION_DNS_TXT: NetworkUpdateSection = NetworkUpdateSection::DnsTxt;
+#[deprecated(note = "use `NetworkUpdateSection::DnsSrv`")]
+pub const VIR_NETWORK_SECTION_DNS_SRV: NetworkUpdateSection = NetworkUpdateSection::DnsSrv;
+
+bitflags! {
+    /// Flags for `Network::update`.
+    pub struct NetworkUpdateFlags: libc::c_uint {
+        const CURRENT = 0;
+        const LIVE = 1 << 0;
+        const CONFIG = 1 << 1;
+    }
+}
+
+#[deprecated(note = "use `NetworkUpdateFlags::CURRENT`")]
+pub const VIR_NETWORK_UPDATE_AFFECT_CURRENT: NetworkUpdateFlags = NetworkUpdateFlags::CURRENT;
+#[deprecated(note = "use `NetworkUpdateFlags::LIVE`")]
+pub const VIR_NETWORK_UPDATE_AFFECT_LIVE: NetworkUpdateFlags = NetworkUpdateFlags::LIVE;
+#[deprecated(note = "use `NetworkUpdateFlags::CONFIG`")]
+pub const VIR_NETWORK_UPDATE_AFFECT_CONFIG: NetworkUpdateFlags = NetworkUpdateFlags::CONFIG;
+
+/// Family of an IP address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IPAddrType {
+    Ipv4 = 0,
+    Ipv6 = 1,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl IPAddrType {
+    pub fn from_raw(value: i32) -> IPAddrType {
+        match value {
+            0 => IPAddrType::Ipv4,
+            1 => IPAddrType::Ipv6,
+            _ => IPAddrType::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `IPAddrType::Ipv4`")]
+pub const VIR_IP_ADDR_TYPE_IPV4: IPAddrType = IPAddrType::Ipv4;
+#[deprecated(note = "use `IPAddrType::Ipv6`")]
+pub const VIR_IP_ADDR_TYPE_IPV6: IPAddrType = IPAddrType::Ipv6;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkDHCPLease {
+    /// Network interface name.
+    pub iface: String,
+    /// Seconds since epoch when the lease expires, 0 if the lease
+    /// never expires.
+    pub expiry_time: i64,
+    /// The type of IP address.
+    pub typed: IPAddrType,
+    /// MAC address of the guest interface.
+    pub mac: Option<String>,
+    /// IAID, only set for IPv6 leases.
+    pub iaid: Option<String>,
+    /// IP address assigned to the guest.
+    pub ip_addr: String,
+    /// IP address prefix.
+    pub prefix: u32,
+    /// Hostname reported by the guest, if any.
+    pub hostname: Option<String>,
+    /// Client ID or DUID, if any.
+    pub client_id: Option<String>,
+}
+
+impl NetworkDHCPLease {
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid lease returned by libvirt.
+    pub unsafe fn from_ptr(ptr: sys::virNetworkDHCPLeasePtr) -> NetworkDHCPLease {
+        unsafe fn to_opt(s: *mut libc::c_char) -> Option<String> {
+            if s.is_null() {
+                return None;
+            }
+            Some(c_chars_to_string!(s, nofree))
+        }
+
+        NetworkDHCPLease {
+            iface: to_opt((*ptr).iface).unwrap_or_default(),
+            expiry_time: (*ptr).expirytime,
+            typed: IPAddrType::from_raw((*ptr).typed),
+            mac: to_opt((*ptr).mac),
+            iaid: to_opt((*ptr).iaid),
+            ip_addr: to_opt((*ptr).ipaddr).unwrap_or_default(),
+            prefix: (*ptr).prefix,
+            hostname: to_opt((*ptr).hostname),
+            client_id: to_opt((*ptr).clientid),
+        }
+    }
+}
 
 /// Provides APIs for the management of networks.
 ///
@@ -116,6 +309,22 @@ impl Drop for Network {
     }
 }
 
+impl Clone for Network {
+    fn clone(&self) -> Network {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for Network, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for Network {}
+unsafe impl Sync for Network {}
+
 impl Network {
     pub fn new(ptr: sys::virNetworkPtr) -> Network {
         return Network { ptr: Some(ptr) };
@@ -125,12 +334,27 @@ impl Network {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the network and returns a new
+    /// handle on it, the network is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<Network, Error> {
+        unsafe {
+            if virNetworkRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(Network::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virNetworkGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -197,7 +421,7 @@ impl Network {
 
     pub fn get_xml_desc(&self, flags: NetworkXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virNetworkGetXMLDesc(self.as_ptr(), flags);
+            let xml = virNetworkGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -315,15 +539,227 @@ impl Network {
                   -> Result<(), Error> {
         unsafe {
             let ret = virNetworkUpdate(self.as_ptr(),
-                                       cmd,
-                                       section,
+                                       cmd as libc::c_uint,
+                                       section as libc::c_uint,
                                        index as libc::c_uint,
                                        string_to_c_chars!(xml),
-                                       flags);
+                                       flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
             return Ok(());
         }
     }
+
+    /// Serialize `fragment` and apply `cmd` to it in `section`, on
+    /// the first parent element matching it.
+    fn update_fragment<T: XmlElement>(&self,
+                                      cmd: NetworkUpdateCommand,
+                                      section: NetworkUpdateSection,
+                                      fragment: &T,
+                                      flags: NetworkUpdateFlags)
+                                      -> Result<(), Error> {
+        let xml = fragment.to_element().to_string();
+        self.update(cmd, section, -1, &xml, flags)
+    }
+
+    /// Reserve `ip` for the host of MAC address `mac` in the DHCP
+    /// server of the `<ip>` element of the same address family.
+    pub fn add_dhcp_host(&self,
+                         ip: &str,
+                         mac: &str,
+                         name: Option<&str>,
+                         flags: NetworkUpdateFlags)
+                         -> Result<(), Error> {
+        let mut host = DhcpHost::new(mac, ip);
+        host.name = name.map(|n| n.to_string());
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::IpDhcpHost,
+                             &host,
+                             flags)
+    }
+
+    /// Replace the DHCP host having the same MAC address, or DUID
+    /// for IPv6, by `host`.
+    pub fn modify_dhcp_host(&self,
+                            host: &DhcpHost,
+                            flags: NetworkUpdateFlags)
+                            -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Modify,
+                             NetworkUpdateSection::IpDhcpHost,
+                             host,
+                             flags)
+    }
+
+    /// Remove the DHCP host matching all the attributes set in
+    /// `host`.
+    pub fn remove_dhcp_host(&self,
+                            host: &DhcpHost,
+                            flags: NetworkUpdateFlags)
+                            -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::IpDhcpHost,
+                             host,
+                             flags)
+    }
+
+    pub fn add_ip_dhcp_range(&self,
+                             range: &DhcpRange,
+                             flags: NetworkUpdateFlags)
+                             -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::IpDhcpRange,
+                             range,
+                             flags)
+    }
+
+    pub fn remove_ip_dhcp_range(&self,
+                                range: &DhcpRange,
+                                flags: NetworkUpdateFlags)
+                                -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::IpDhcpRange,
+                             range,
+                             flags)
+    }
+
+    /// Replace the DHCP range `old` by `new`.
+    ///
+    /// libvirt cannot modify a range in place so `old` is removed
+    /// before `new` is added, if adding fails `old` is added back.
+    pub fn modify_ip_dhcp_range(&self,
+                                old: &DhcpRange,
+                                new: &DhcpRange,
+                                flags: NetworkUpdateFlags)
+                                -> Result<(), Error> {
+        self.remove_ip_dhcp_range(old, flags)?;
+        if let Err(e) = self.add_ip_dhcp_range(new, flags) {
+            let _ = self.add_ip_dhcp_range(old, flags);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub fn add_dns_host(&self, host: &DnsHost, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::DnsHost,
+                             host,
+                             flags)
+    }
+
+    /// Remove the DNS host having the IP address or one of the
+    /// hostnames of `host`.
+    pub fn remove_dns_host(&self, host: &DnsHost, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::DnsHost,
+                             host,
+                             flags)
+    }
+
+    pub fn add_dns_txt(&self, txt: &DnsTxt, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::DnsTxt,
+                             txt,
+                             flags)
+    }
+
+    pub fn remove_dns_txt(&self, txt: &DnsTxt, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::DnsTxt,
+                             txt,
+                             flags)
+    }
+
+    pub fn add_dns_srv(&self, srv: &DnsSrv, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::DnsSrv,
+                             srv,
+                             flags)
+    }
+
+    pub fn remove_dns_srv(&self, srv: &DnsSrv, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::DnsSrv,
+                             srv,
+                             flags)
+    }
+
+    pub fn add_portgroup(&self,
+                         portgroup: &Portgroup,
+                         flags: NetworkUpdateFlags)
+                         -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::AddLast,
+                             NetworkUpdateSection::Portgroup,
+                             portgroup,
+                             flags)
+    }
+
+    /// Replace the portgroup having the same name by `portgroup`.
+    pub fn modify_portgroup(&self,
+                            portgroup: &Portgroup,
+                            flags: NetworkUpdateFlags)
+                            -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Modify,
+                             NetworkUpdateSection::Portgroup,
+                             portgroup,
+                             flags)
+    }
+
+    pub fn remove_portgroup(&self, name: &str, flags: NetworkUpdateFlags) -> Result<(), Error> {
+        self.update_fragment(NetworkUpdateCommand::Delete,
+                             NetworkUpdateSection::Portgroup,
+                             &Portgroup::new(name),
+                             flags)
+    }
+
+    /// Get the list of leases currently handed out by the DHCP
+    /// server of this network.
+    ///
+    /// If `mac` is provided only the leases for that MAC address are
+    /// returned. This is the canonical way to find out which IP
+    /// address a guest has been given on a NAT network.
+    pub fn get_dhcp_leases(&self, mac: Option<&str>) -> Result<Vec<NetworkDHCPLease>, Error> {
+        unsafe {
+            let cmac = mac.map(|m| ::std::ffi::CString::new(m).unwrap());
+            let pmac = match cmac {
+                Some(ref m) => m.as_ptr(),
+                None => ptr::null(),
+            };
+            let mut leases: *mut sys::virNetworkDHCPLeasePtr = ptr::null_mut();
+            let size = virNetworkGetDHCPLeases(self.as_ptr(), pmac, &mut leases, 0);
+            if size == -1 {
+                return Err(Error::new());
+            }
+
+            let mut array: Vec<NetworkDHCPLease> = Vec::new();
+            for x in 0..size as isize {
+                let lease = *leases.offset(x);
+                array.push(NetworkDHCPLease::from_ptr(lease));
+                virNetworkDHCPLeaseFree(lease);
+            }
+            libc::free(leases as *mut libc::c_void);
+
+            Ok(array)
+        }
+    }
+
+    /// Get the ports allocated on this network, one for each guest
+    /// interface connected to it.
+    pub fn list_all_ports(&self, flags: u32) -> Result<Vec<NetworkPort>, Error> {
+        unsafe {
+            let mut ports: *mut virNetworkPortPtr = ptr::null_mut();
+            let size = virNetworkListAllPorts(self.as_ptr(), &mut ports, flags as libc::c_uint);
+            if size == -1 {
+                return Err(Error::new());
+            }
+
+            let mut array: Vec<NetworkPort> = Vec::new();
+            for x in 0..size as isize {
+                array.push(NetworkPort::new(*ports.offset(x)));
+            }
+            libc::free(ports as *mut libc::c_void);
+
+            Ok(array)
+        }
+    }
 }
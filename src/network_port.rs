@@ -0,0 +1,323 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate libc;
+
+use std::ffi::CStr;
+use std::{str, ptr};
+
+use network::sys::virNetworkPtr;
+use typedparam::sys::{virTypedParameter, virTypedParameterPtr};
+
+use error::Error;
+use typedparam::TypedParameterType;
+use network::Network;
+
+pub mod sys {
+    #[repr(C)]
+    pub struct virNetworkPort {}
+
+    pub type virNetworkPortPtr = *mut virNetworkPort;
+}
+
+#[link(name = "virt")]
+extern "C" {
+    fn virNetworkPortGetNetwork(ptr: sys::virNetworkPortPtr) -> virNetworkPtr;
+    fn virNetworkRef(ptr: virNetworkPtr) -> libc::c_int;
+    fn virNetworkPortGetUUIDString(ptr: sys::virNetworkPortPtr,
+                                   uuid: *mut libc::c_char)
+                                   -> libc::c_int;
+    fn virNetworkPortLookupByUUIDString(net: virNetworkPtr,
+                                        uuid: *const libc::c_char)
+                                        -> sys::virNetworkPortPtr;
+    fn virNetworkPortCreateXML(net: virNetworkPtr,
+                               xml: *const libc::c_char,
+                               flags: libc::c_uint)
+                               -> sys::virNetworkPortPtr;
+    fn virNetworkPortGetXMLDesc(ptr: sys::virNetworkPortPtr,
+                                flags: libc::c_uint)
+                                -> *mut libc::c_char;
+    fn virNetworkPortGetParameters(ptr: sys::virNetworkPortPtr,
+                                   params: *mut virTypedParameterPtr,
+                                   nparams: *mut libc::c_int,
+                                   flags: libc::c_uint)
+                                   -> libc::c_int;
+    fn virNetworkPortSetParameters(ptr: sys::virNetworkPortPtr,
+                                   params: virTypedParameterPtr,
+                                   nparams: libc::c_int,
+                                   flags: libc::c_uint)
+                                   -> libc::c_int;
+    fn virNetworkPortDelete(ptr: sys::virNetworkPortPtr, flags: libc::c_uint) -> libc::c_int;
+    fn virNetworkPortFree(ptr: sys::virNetworkPortPtr) -> libc::c_int;
+    fn virNetworkPortRef(ptr: sys::virNetworkPortPtr) -> libc::c_int;
+    fn virTypedParamsFree(params: virTypedParameterPtr, nparams: libc::c_int);
+}
+
+bitflags! {
+    /// Flags for `NetworkPort::create_xml`.
+    pub struct NetworkPortCreateFlags: libc::c_uint {
+        /// Reclaim a port previously allocated to a running guest,
+        /// when the network driver restarted.
+        const RECLAIM = 1 << 0;
+        const VALIDATE = 1 << 1;
+    }
+}
+
+/// Quality of service applied to the traffic of a port.
+///
+/// Averages, peaks and floors are in KiB/s, bursts in KiB. Only the
+/// parameters set are changed by `NetworkPort::set_parameters`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkPortParameters {
+    pub inbound_average: Option<u32>,
+    pub inbound_peak: Option<u32>,
+    pub inbound_burst: Option<u32>,
+    /// Minimal inbound throughput guaranteed to the port.
+    pub inbound_floor: Option<u32>,
+    pub outbound_average: Option<u32>,
+    pub outbound_peak: Option<u32>,
+    pub outbound_burst: Option<u32>,
+}
+
+impl NetworkPortParameters {
+    pub fn from_vec(vec: Vec<virTypedParameter>) -> NetworkPortParameters {
+        unsafe {
+            let mut ret = NetworkPortParameters::default();
+            for param in vec {
+                let value = Some(param.value as u32);
+                match str::from_utf8(CStr::from_ptr(param.field.as_ptr()).to_bytes()).unwrap() {
+                    "inbound.average" => ret.inbound_average = value,
+                    "inbound.peak" => ret.inbound_peak = value,
+                    "inbound.burst" => ret.inbound_burst = value,
+                    "inbound.floor" => ret.inbound_floor = value,
+                    "outbound.average" => ret.outbound_average = value,
+                    "outbound.peak" => ret.outbound_peak = value,
+                    "outbound.burst" => ret.outbound_burst = value,
+                    _ => (),
+                }
+            }
+            ret
+        }
+    }
+
+    fn to_vec(&self) -> Vec<virTypedParameter> {
+        fn to_arr(name: &str) -> [libc::c_char; 80] {
+            let mut field: [libc::c_char; 80] = [0; 80];
+            for (a, c) in field.iter_mut().zip(name.as_bytes()) {
+                *a = *c as libc::c_char
+            }
+            field
+        }
+
+        let params = [("inbound.average", self.inbound_average),
+                      ("inbound.peak", self.inbound_peak),
+                      ("inbound.burst", self.inbound_burst),
+                      ("inbound.floor", self.inbound_floor),
+                      ("outbound.average", self.outbound_average),
+                      ("outbound.peak", self.outbound_peak),
+                      ("outbound.burst", self.outbound_burst)];
+        params.iter()
+            .filter_map(|&(name, value)| {
+                value.map(|v| {
+                    virTypedParameter {
+                        field: to_arr(name),
+                        typed: TypedParameterType::UInt.to_raw(),
+                        value: v as libc::c_ulonglong,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// Provides APIs for the management of the ports of a network, each
+/// one connecting a guest interface to the network.
+///
+/// See https://libvirt.org/formatnetworkport.html
+#[derive(Debug)]
+pub struct NetworkPort {
+    ptr: Option<sys::virNetworkPortPtr>,
+}
+
+impl Drop for NetworkPort {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.free() {
+                panic!("Unable to drop memory for NetworkPort, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+impl Clone for NetworkPort {
+    fn clone(&self) -> NetworkPort {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for NetworkPort, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for NetworkPort {}
+unsafe impl Sync for NetworkPort {}
+
+impl NetworkPort {
+    pub fn new(ptr: sys::virNetworkPortPtr) -> NetworkPort {
+        NetworkPort { ptr: Some(ptr) }
+    }
+
+    pub fn as_ptr(&self) -> sys::virNetworkPortPtr {
+        self.ptr.unwrap()
+    }
+
+    /// Increments the reference count of the port and returns a new
+    /// handle on it, the port is released once every handle has been
+    /// freed.
+    pub fn add_ref(&self) -> Result<NetworkPort, Error> {
+        unsafe {
+            if virNetworkPortRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(NetworkPort::new(self.as_ptr()))
+    }
+
+    pub fn get_network(&self) -> Result<Network, Error> {
+        unsafe {
+            let ptr = virNetworkPortGetNetwork(self.as_ptr());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            if virNetworkRef(ptr) == -1 {
+                return Err(Error::new());
+            }
+            Ok(Network::new(ptr))
+        }
+    }
+
+    pub fn get_uuid_string(&self) -> Result<String, Error> {
+        unsafe {
+            let mut uuid: [libc::c_char; 37] = [0; 37];
+            if virNetworkPortGetUUIDString(self.as_ptr(), uuid.as_mut_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(uuid.as_ptr(), nofree))
+        }
+    }
+
+    pub fn lookup_by_uuid_string(net: &Network, uuid: &str) -> Result<NetworkPort, Error> {
+        unsafe {
+            let ptr = virNetworkPortLookupByUUIDString(net.as_ptr(), string_to_c_chars!(uuid));
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(NetworkPort::new(ptr))
+        }
+    }
+
+    /// Allocate a new port on `net` described by `xml`.
+    ///
+    /// This is usually done by the hypervisor driver when starting a
+    /// guest, a port created by hand is not attached to any guest.
+    pub fn create_xml(net: &Network,
+                      xml: &str,
+                      flags: NetworkPortCreateFlags)
+                      -> Result<NetworkPort, Error> {
+        unsafe {
+            let ptr = virNetworkPortCreateXML(net.as_ptr(), string_to_c_chars!(xml), flags.bits());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(NetworkPort::new(ptr))
+        }
+    }
+
+    pub fn get_xml_desc(&self, flags: u32) -> Result<String, Error> {
+        unsafe {
+            let xml = virNetworkPortGetXMLDesc(self.as_ptr(), flags as libc::c_uint);
+            if xml.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(xml))
+        }
+    }
+
+    /// Get the bandwidth parameters of the port.
+    pub fn get_parameters(&self, flags: u32) -> Result<NetworkPortParameters, Error> {
+        unsafe {
+            let mut params: virTypedParameterPtr = ptr::null_mut();
+            let mut nparams: libc::c_int = 0;
+            let ret = virNetworkPortGetParameters(self.as_ptr(),
+                                                  &mut params,
+                                                  &mut nparams,
+                                                  flags as libc::c_uint);
+            if ret == -1 {
+                return Err(Error::new());
+            }
+            let mut vec: Vec<virTypedParameter> = Vec::new();
+            for x in 0..nparams as isize {
+                vec.push(*params.offset(x));
+            }
+            virTypedParamsFree(params, nparams);
+            Ok(NetworkPortParameters::from_vec(vec))
+        }
+    }
+
+    /// Change the bandwidth parameters of the port, applied right
+    /// away to the traffic of the guest interface.
+    pub fn set_parameters(&self, params: &NetworkPortParameters, flags: u32) -> Result<(), Error> {
+        unsafe {
+            let mut cparams = params.to_vec();
+            let ret = virNetworkPortSetParameters(self.as_ptr(),
+                                                  cparams.as_mut_ptr(),
+                                                  cparams.len() as libc::c_int,
+                                                  flags as libc::c_uint);
+            if ret == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    /// Delete the port, releasing the resources it holds on the
+    /// network.
+    pub fn delete(&self, flags: u32) -> Result<(), Error> {
+        unsafe {
+            if virNetworkPortDelete(self.as_ptr(), flags as libc::c_uint) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    pub fn free(&mut self) -> Result<(), Error> {
+        unsafe {
+            if virNetworkPortFree(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            self.ptr = None;
+            Ok(())
+        }
+    }
+}
@@ -48,6 +48,7 @@ extern "C" {
                               -> sys::virNodeDevicePtr;
     fn virNodeDeviceDestroy(ptr: sys::virNodeDevicePtr) -> libc::c_int;
     fn virNodeDeviceFree(ptr: sys::virNodeDevicePtr) -> libc::c_int;
+    fn virNodeDeviceRef(ptr: sys::virNodeDevicePtr) -> libc::c_int;
     fn virNodeDeviceGetName(ptr: sys::virNodeDevicePtr) -> *const libc::c_char;
     fn virNodeDeviceGetParent(ptr: sys::virNodeDevicePtr) -> *const libc::c_char;
     fn virNodeDeviceGetXMLDesc(ptr: sys::virNodeDevicePtr,
@@ -75,8 +76,15 @@ extern "C" {
                              -> libc::c_int;
 }
 
-pub type NodeDeviceXMLFlags = self::libc::c_uint;
-pub const VIR_INTERFACE_XML_INACTIVE: NodeDeviceXMLFlags = 1 << 0;
+bitflags! {
+    /// Flags for `NodeDevice::get_xml_desc`.
+    pub struct NodeDeviceXMLFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `NodeDeviceXMLFlags::INACTIVE`")]
+pub const VIR_INTERFACE_XML_INACTIVE: NodeDeviceXMLFlags = NodeDeviceXMLFlags::INACTIVE;
 
 /// Provides APIs for the management of nodedevs.
 ///
@@ -98,6 +106,22 @@ impl Drop for NodeDevice {
     }
 }
 
+impl Clone for NodeDevice {
+    fn clone(&self) -> NodeDevice {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for NodeDevice, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for NodeDevice {}
+unsafe impl Sync for NodeDevice {}
+
 impl NodeDevice {
     pub fn new(ptr: sys::virNodeDevicePtr) -> NodeDevice {
         return NodeDevice { ptr: Some(ptr) };
@@ -107,6 +131,18 @@ impl NodeDevice {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the node device and returns a new
+    /// handle on it, the node device is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<NodeDevice, Error> {
+        unsafe {
+            if virNodeDeviceRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(NodeDevice::new(self.as_ptr()))
+    }
+
     pub fn lookup_by_name(conn: &Connect, id: &str) -> Result<NodeDevice, Error> {
         unsafe {
             let ptr = virNodeDeviceLookupByName(conn.as_ptr(), string_to_c_chars!(id));
@@ -176,9 +212,9 @@ impl NodeDevice {
         }
     }
 
-    pub fn get_xml_desc(&self, flags: u32) -> Result<String, Error> {
+    pub fn get_xml_desc(&self, flags: NodeDeviceXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virNodeDeviceGetXMLDesc(self.as_ptr(), flags as libc::c_uint);
+            let xml = virNodeDeviceGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -42,6 +42,7 @@ extern "C" {
     fn virNWFilterDefineXML(c: virConnectPtr, xml: *const libc::c_char) -> sys::virNWFilterPtr;
     fn virNWFilterUndefine(ptr: sys::virNWFilterPtr) -> libc::c_int;
     fn virNWFilterFree(ptr: sys::virNWFilterPtr) -> libc::c_int;
+    fn virNWFilterRef(ptr: sys::virNWFilterPtr) -> libc::c_int;
     fn virNWFilterGetName(ptr: sys::virNWFilterPtr) -> *const libc::c_char;
     fn virNWFilterGetUUIDString(ptr: sys::virNWFilterPtr, uuid: *mut libc::c_char) -> libc::c_int;
     fn virNWFilterGetXMLDesc(ptr: sys::virNWFilterPtr, flags: libc::c_uint) -> *mut libc::c_char;
@@ -67,6 +68,22 @@ impl Drop for NWFilter {
     }
 }
 
+impl Clone for NWFilter {
+    fn clone(&self) -> NWFilter {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for NWFilter, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for NWFilter {}
+unsafe impl Sync for NWFilter {}
+
 impl NWFilter {
     pub fn new(ptr: sys::virNWFilterPtr) -> NWFilter {
         return NWFilter { ptr: Some(ptr) };
@@ -76,6 +93,18 @@ impl NWFilter {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the network filter and returns a new
+    /// handle on it, the network filter is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<NWFilter, Error> {
+        unsafe {
+            if virNWFilterRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(NWFilter::new(self.as_ptr()))
+    }
+
     pub fn lookup_by_id(conn: &Connect, id: u32) -> Result<NWFilter, Error> {
         unsafe {
             let ptr = virNWFilterLookupByID(conn.as_ptr(), id as libc::c_int);
@@ -0,0 +1,202 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate libc;
+
+use std::str;
+
+use connect::sys::virConnectPtr;
+
+use connect::Connect;
+use error::Error;
+
+pub mod sys {
+    #[repr(C)]
+    pub struct virNWFilterBinding {}
+
+    pub type virNWFilterBindingPtr = *mut virNWFilterBinding;
+}
+
+#[link(name = "virt")]
+extern "C" {
+    fn virNWFilterBindingCreateXML(c: virConnectPtr,
+                                   xml: *const libc::c_char,
+                                   flags: libc::c_uint)
+                                   -> sys::virNWFilterBindingPtr;
+    fn virNWFilterBindingLookupByPortDev(c: virConnectPtr,
+                                         portdev: *const libc::c_char)
+                                         -> sys::virNWFilterBindingPtr;
+    fn virNWFilterBindingGetPortDev(ptr: sys::virNWFilterBindingPtr) -> *const libc::c_char;
+    fn virNWFilterBindingGetFilterName(ptr: sys::virNWFilterBindingPtr) -> *const libc::c_char;
+    fn virNWFilterBindingGetXMLDesc(ptr: sys::virNWFilterBindingPtr,
+                                    flags: libc::c_uint)
+                                    -> *mut libc::c_char;
+    fn virNWFilterBindingDelete(ptr: sys::virNWFilterBindingPtr) -> libc::c_int;
+    fn virNWFilterBindingFree(ptr: sys::virNWFilterBindingPtr) -> libc::c_int;
+    fn virNWFilterBindingRef(ptr: sys::virNWFilterBindingPtr) -> libc::c_int;
+}
+
+bitflags! {
+    /// Flags for `NWFilterBinding::create_xml`.
+    pub struct NWFilterBindingCreateFlags: libc::c_uint {
+        const VALIDATE = 1 << 0;
+    }
+}
+
+/// Provides APIs for the management of network filter bindings, each
+/// one applying a filter to the port device of a guest interface.
+///
+/// Looking up, listing and dumping bindings only needs a read-only
+/// connection.
+///
+/// See http://libvirt.org/formatnwfilterbinding.html
+#[derive(Debug)]
+pub struct NWFilterBinding {
+    ptr: Option<sys::virNWFilterBindingPtr>,
+}
+
+impl Drop for NWFilterBinding {
+    fn drop(&mut self) {
+        if self.ptr.is_some() {
+            if let Err(e) = self.free() {
+                panic!("Unable to drop memory for NWFilterBinding, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+impl Clone for NWFilterBinding {
+    fn clone(&self) -> NWFilterBinding {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for NWFilterBinding, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for NWFilterBinding {}
+unsafe impl Sync for NWFilterBinding {}
+
+impl NWFilterBinding {
+    pub fn new(ptr: sys::virNWFilterBindingPtr) -> NWFilterBinding {
+        NWFilterBinding { ptr: Some(ptr) }
+    }
+
+    pub fn as_ptr(&self) -> sys::virNWFilterBindingPtr {
+        self.ptr.unwrap()
+    }
+
+    /// Increments the reference count of the binding and returns a
+    /// new handle on it, the binding is released once every handle
+    /// has been freed.
+    pub fn add_ref(&self) -> Result<NWFilterBinding, Error> {
+        unsafe {
+            if virNWFilterBindingRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(NWFilterBinding::new(self.as_ptr()))
+    }
+
+    /// Get the binding of the filter applied to the port device
+    /// `portdev` like `vnet0`.
+    pub fn lookup_by_port_dev(conn: &Connect, portdev: &str) -> Result<NWFilterBinding, Error> {
+        unsafe {
+            let ptr = virNWFilterBindingLookupByPortDev(conn.as_ptr(), string_to_c_chars!(portdev));
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(NWFilterBinding::new(ptr))
+        }
+    }
+
+    /// Apply a filter to a port device as described by `xml`.
+    ///
+    /// This is usually done by the hypervisor driver when starting a
+    /// guest, a binding created by hand is kept until deleted.
+    pub fn create_xml(conn: &Connect,
+                      xml: &str,
+                      flags: NWFilterBindingCreateFlags)
+                      -> Result<NWFilterBinding, Error> {
+        unsafe {
+            let ptr = virNWFilterBindingCreateXML(conn.as_ptr(),
+                                                  string_to_c_chars!(xml),
+                                                  flags.bits());
+            if ptr.is_null() {
+                return Err(Error::new());
+            }
+            Ok(NWFilterBinding::new(ptr))
+        }
+    }
+
+    pub fn get_port_dev(&self) -> Result<String, Error> {
+        unsafe {
+            let n = virNWFilterBindingGetPortDev(self.as_ptr());
+            if n.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(n, nofree))
+        }
+    }
+
+    pub fn get_filter_name(&self) -> Result<String, Error> {
+        unsafe {
+            let n = virNWFilterBindingGetFilterName(self.as_ptr());
+            if n.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(n, nofree))
+        }
+    }
+
+    pub fn get_xml_desc(&self, flags: u32) -> Result<String, Error> {
+        unsafe {
+            let xml = virNWFilterBindingGetXMLDesc(self.as_ptr(), flags as libc::c_uint);
+            if xml.is_null() {
+                return Err(Error::new());
+            }
+            Ok(c_chars_to_string!(xml))
+        }
+    }
+
+    /// Remove the filter from the port device.
+    pub fn delete(&self) -> Result<(), Error> {
+        unsafe {
+            if virNWFilterBindingDelete(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            Ok(())
+        }
+    }
+
+    pub fn free(&mut self) -> Result<(), Error> {
+        unsafe {
+            if virNWFilterBindingFree(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+            self.ptr = None;
+            Ok(())
+        }
+    }
+}
@@ -20,7 +20,7 @@ extern crate libc;
 
 use connect::sys::virConnectPtr;
 
-use connect::Connect;
+use connect::{Connect, ConnectListAllSecretsFlags};
 use error::Error;
 
 pub mod sys {
@@ -41,6 +41,7 @@ extern "C" {
                               -> sys::virSecretPtr;
     fn virSecretUndefine(ptr: sys::virSecretPtr) -> libc::c_int;
     fn virSecretFree(ptr: sys::virSecretPtr) -> libc::c_int;
+    fn virSecretRef(ptr: sys::virSecretPtr) -> libc::c_int;
     fn virSecretGetName(ptr: sys::virSecretPtr) -> *const libc::c_char;
     fn virSecretGetUUIDString(ptr: sys::virSecretPtr, uuid: *mut libc::c_char) -> libc::c_int;
     fn virSecretGetUsageID(ptr: sys::virSecretPtr) -> *const libc::c_char;
@@ -56,6 +57,7 @@ extern "C" {
                          flags: libc::c_uint)
                          -> *const libc::c_uchar;
     fn virSecretGetConnect(ptr: sys::virSecretPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
     fn virSecretGetUsageType(ptr: sys::virSecretPtr) -> libc::c_int;
     fn virSecretDefineXML(c: virConnectPtr,
                           xml: *const libc::c_char,
@@ -63,21 +65,72 @@ extern "C" {
                           -> sys::virSecretPtr;
 }
 
-pub type SecretXMLFlags = self::libc::c_uint;
-pub const VIR_SECRET_XML_INACTIVE: SecretXMLFlags = 1 << 0;
+bitflags! {
+    /// Flags for `Secret::get_xml_desc`.
+    pub struct SecretXMLFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `SecretXMLFlags::INACTIVE`")]
+pub const VIR_SECRET_XML_INACTIVE: SecretXMLFlags = SecretXMLFlags::INACTIVE;
+
+/// Kind of object a secret is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecretSecretUsageType {
+    None = 0,
+    Volume = 1,
+    Ceph = 2,
+    Iscsi = 3,
+    Tls = 4,
+    Vtpm = 5,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl SecretSecretUsageType {
+    pub fn from_raw(value: i32) -> SecretSecretUsageType {
+        match value {
+            0 => SecretSecretUsageType::None,
+            1 => SecretSecretUsageType::Volume,
+            2 => SecretSecretUsageType::Ceph,
+            3 => SecretSecretUsageType::Iscsi,
+            4 => SecretSecretUsageType::Tls,
+            5 => SecretSecretUsageType::Vtpm,
+            _ => SecretSecretUsageType::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
 
-pub type SecretSecretUsageType = self::libc::c_uint;
-pub const VIR_SECRET_USAGE_TYPE_NONE: SecretSecretUsageType = 0;
-pub const VIR_SECRET_USAGE_TYPE_VOLUME: SecretSecretUsageType = 1;
-pub const VIR_SECRET_USAGE_TYPE_CEPH: SecretSecretUsageType = 2;
-pub const VIR_SECRET_USAGE_TYPE_ISCSI: SecretSecretUsageType = 3;
-pub const VIR_SECRET_USAGE_TYPE_TLS: SecretSecretUsageType = 4;
+#[deprecated(note = "use `SecretSecretUsageType::None`")]
+pub const VIR_SECRET_USAGE_TYPE_NONE: SecretSecretUsageType = SecretSecretUsageType::None;
+#[deprecated(note = "use `SecretSecretUsageType::Volume`")]
+pub const VIR_SECRET_USAGE_TYPE_VOLUME: SecretSecretUsageType = SecretSecretUsageType::Volume;
+#[deprecated(note = "use `SecretSecretUsageType::Ceph`")]
+pub const VIR_SECRET_USAGE_TYPE_CEPH: SecretSecretUsageType = SecretSecretUsageType::Ceph;
+#[deprecated(note = "use `SecretSecretUsageType::Iscsi`")]
+pub const VIR_SECRET_USAGE_TYPE_ISCSI: SecretSecretUsageType = SecretSecretUsageType::Iscsi;
+#[deprecated(note = "use `SecretSecretUsageType::Tls`")]
+pub const VIR_SECRET_USAGE_TYPE_TLS: SecretSecretUsageType = SecretSecretUsageType::Tls;
 
-pub type SecretsFlags = self::libc::c_uint;
-pub const VIR_CONNECT_LIST_SECRETS_EPHEMERAL: SecretsFlags = 1 << 0;
-pub const VIR_CONNECT_LIST_SECRETS_NO_EPHEMERAL: SecretsFlags = 1 << 1;
-pub const VIR_CONNECT_LIST_SECRETS_PRIVATE: SecretsFlags = 1 << 2;
-pub const VIR_CONNECT_LIST_SECRETS_NO_PRIVATE: SecretsFlags = 1 << 3;
+#[deprecated(note = "use `ConnectListAllSecretsFlags`")]
+pub type SecretsFlags = ConnectListAllSecretsFlags;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::EPHEMERAL`")]
+pub const VIR_CONNECT_LIST_SECRETS_EPHEMERAL: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::EPHEMERAL;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::NO_EPHEMERAL`")]
+pub const VIR_CONNECT_LIST_SECRETS_NO_EPHEMERAL: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::NO_EPHEMERAL;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::PRIVATE`")]
+pub const VIR_CONNECT_LIST_SECRETS_PRIVATE: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::PRIVATE;
+#[deprecated(note = "use `ConnectListAllSecretsFlags::NO_PRIVATE`")]
+pub const VIR_CONNECT_LIST_SECRETS_NO_PRIVATE: ConnectListAllSecretsFlags =
+    ConnectListAllSecretsFlags::NO_PRIVATE;
 
 /// Provides APIs for the management of secrets.
 ///
@@ -99,6 +152,22 @@ impl Drop for Secret {
     }
 }
 
+impl Clone for Secret {
+    fn clone(&self) -> Secret {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for Secret, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for Secret {}
+unsafe impl Sync for Secret {}
+
 impl Secret {
     pub fn new(ptr: sys::virSecretPtr) -> Secret {
         return Secret { ptr: Some(ptr) };
@@ -108,12 +177,27 @@ impl Secret {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the secret and returns a new
+    /// handle on it, the secret is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<Secret, Error> {
+        unsafe {
+            if virSecretRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(Secret::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virSecretGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -172,13 +256,13 @@ impl Secret {
         }
     }
 
-    pub fn get_usage_type(&self) -> Result<u32, Error> {
+    pub fn get_usage_type(&self) -> Result<SecretSecretUsageType, Error> {
         unsafe {
             let t = virSecretGetUsageType(self.as_ptr());
             if t == -1 {
                 return Err(Error::new());
             }
-            return Ok(t as u32);
+            return Ok(SecretSecretUsageType::from_raw(t as i32));
         }
     }
 
@@ -194,7 +278,7 @@ impl Secret {
 
     pub fn get_xml_desc(&self, flags: SecretXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virSecretGetXMLDesc(self.as_ptr(), flags);
+            let xml = virSecretGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
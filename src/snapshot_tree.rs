@@ -0,0 +1,331 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! A tree view over the snapshots of a domain.
+//!
+//! libvirt only exposes flat lists of snapshots, the hierarchy has
+//! to be rebuilt from the `<parent>` element of each snapshot XML.
+//! `SnapshotTree` does that once and then allows to walk the
+//! hierarchy without further round trips to the hypervisor.
+//!
+//! ```
+//! use virt::snapshot_tree::SnapshotTree;
+//!
+//! let tree = SnapshotTree::from_xml_descs(vec![
+//!     (String::from("<domainsnapshot><name>base</name></domainsnapshot>"), false),
+//!     (String::from("<domainsnapshot><name>top</name>
+//!                      <parent><name>base</name></parent>
+//!                    </domainsnapshot>"), true),
+//! ]);
+//! assert_eq!(1, tree.get("top").unwrap().depth);
+//! assert_eq!("top", tree.current().unwrap().name);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use domain::Domain;
+use domain_snapshot::{DomainSnapshot, DomainSnapshotDeleteFlags, DomainSnapshotListFlags,
+                      DomainSnapshotXMLFlags};
+use error::Error;
+use xml::domain_snapshot::DomainSnapshotDef;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotNode {
+    /// Name of the snapshot.
+    pub name: String,
+    /// Name of the parent snapshot, `None` for roots.
+    pub parent: Option<String>,
+    /// Names of the direct children, ordered by creation time.
+    pub children: Vec<String>,
+    /// Distance from the root of the tree, roots have a depth of 0.
+    pub depth: usize,
+    /// Whether this snapshot is the current snapshot of the domain.
+    pub current: bool,
+    /// Creation time in seconds since epoch, if reported.
+    pub creation_time: Option<i64>,
+}
+
+/// Hierarchy of the snapshots of a domain.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotTree {
+    nodes: HashMap<String, SnapshotNode>,
+    roots: Vec<String>,
+}
+
+impl SnapshotTree {
+    /// Build the tree of all the snapshots of `dom`.
+    pub fn from_domain(dom: &Domain) -> Result<SnapshotTree, Error> {
+        let mut descs = Vec::new();
+        for snap in dom.list_all_snapshots(DomainSnapshotListFlags::empty())? {
+            descs.push((snap.get_xml_desc(DomainSnapshotXMLFlags::empty())?, snap.is_current(0)?));
+        }
+        Ok(SnapshotTree::from_xml_descs(descs))
+    }
+
+    /// Build a tree from snapshot XML descriptions as returned by
+    /// `DomainSnapshot::get_xml_desc`, each paired with its current
+    /// marker.
+    ///
+    /// A snapshot whose parent is not part of `descs` is considered
+    /// as a root, descriptions which cannot be parsed or have no
+    /// name are skipped.
+    pub fn from_xml_descs<I>(descs: I) -> SnapshotTree
+        where I: IntoIterator<Item = (String, bool)>
+    {
+        let mut nodes = Vec::new();
+        for (xml, current) in descs {
+            let def = match xml.parse::<DomainSnapshotDef>() {
+                Ok(def) => def,
+                Err(_) => continue,
+            };
+            let name = match def.name {
+                Some(ref name) if !name.is_empty() => name.clone(),
+                _ => continue,
+            };
+            nodes.push(SnapshotNode {
+                name,
+                parent: def.parent,
+                children: Vec::new(),
+                depth: 0,
+                current,
+                creation_time: def.creation_time,
+            });
+        }
+        SnapshotTree::build(nodes)
+    }
+
+    fn build(nodes: Vec<SnapshotNode>) -> SnapshotTree {
+        let mut nodes: HashMap<String, SnapshotNode> =
+            nodes.into_iter().map(|n| (n.name.clone(), n)).collect();
+
+        let mut names: Vec<(Option<i64>, String)> =
+            nodes.values().map(|n| (n.creation_time, n.name.clone())).collect();
+        names.sort();
+
+        let mut roots = Vec::new();
+        for (_, name) in &names {
+            let parent = nodes[name].parent.clone();
+            match parent {
+                Some(ref p) if nodes.contains_key(p) => {
+                    nodes.get_mut(p).unwrap().children.push(name.clone())
+                }
+                _ => {
+                    nodes.get_mut(name).unwrap().parent = None;
+                    roots.push(name.clone())
+                }
+            }
+        }
+
+        let mut tree = SnapshotTree { nodes, roots };
+
+        // Snapshots whose parents form a cycle are not reachable from
+        // any root, the oldest one of each cycle becomes a root.
+        loop {
+            let reached: HashSet<&str> =
+                tree.pre_order().iter().map(|n| n.name.as_str()).collect();
+            let name = match names.iter().find(|(_, n)| !reached.contains(n.as_str())) {
+                Some((_, name)) => name.clone(),
+                None => break,
+            };
+            if let Some(p) = tree.nodes.get_mut(&name).unwrap().parent.take() {
+                tree.nodes.get_mut(&p).unwrap().children.retain(|c| *c != name);
+            }
+            tree.roots.push(name);
+        }
+        {
+            let nodes = &tree.nodes;
+            tree.roots.sort_by_key(|r| (nodes[r].creation_time, r.clone()));
+        }
+
+        let order: Vec<String> = tree.pre_order().iter().map(|n| n.name.clone()).collect();
+        for name in order {
+            let depth = match tree.nodes[&name].parent {
+                Some(ref p) => tree.nodes[p].depth + 1,
+                None => 0,
+            };
+            tree.nodes.get_mut(&name).unwrap().depth = depth;
+        }
+        tree
+    }
+
+    /// Number of snapshots in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get a snapshot of the tree by its name.
+    pub fn get(&self, name: &str) -> Option<&SnapshotNode> {
+        self.nodes.get(name)
+    }
+
+    /// Snapshots without parent, ordered by creation time.
+    pub fn roots(&self) -> Vec<&SnapshotNode> {
+        self.roots.iter().map(|r| &self.nodes[r]).collect()
+    }
+
+    /// The current snapshot of the domain, if any.
+    pub fn current(&self) -> Option<&SnapshotNode> {
+        self.nodes.values().find(|n| n.current)
+    }
+
+    /// Direct children of the snapshot `name`.
+    pub fn children(&self, name: &str) -> Vec<&SnapshotNode> {
+        match self.nodes.get(name) {
+            Some(node) => node.children.iter().map(|c| &self.nodes[c]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every snapshot of the tree, parents before their children.
+    pub fn pre_order(&self) -> Vec<&SnapshotNode> {
+        let mut ret = Vec::new();
+        for root in &self.roots {
+            self.walk_pre(root, &mut ret);
+        }
+        ret
+    }
+
+    /// Every snapshot of the tree, children before their parents.
+    pub fn post_order(&self) -> Vec<&SnapshotNode> {
+        let mut ret = Vec::new();
+        for root in &self.roots {
+            self.walk_post(root, &mut ret);
+        }
+        ret
+    }
+
+    /// The snapshot `name` and all its descendants, children before
+    /// their parents.
+    pub fn subtree_post_order(&self, name: &str) -> Vec<&SnapshotNode> {
+        let mut ret = Vec::new();
+        if self.nodes.contains_key(name) {
+            self.walk_post(name, &mut ret);
+        }
+        ret
+    }
+
+    fn walk_pre<'a>(&'a self, name: &str, ret: &mut Vec<&'a SnapshotNode>) {
+        let node = &self.nodes[name];
+        ret.push(node);
+        for child in &node.children {
+            self.walk_pre(child, ret);
+        }
+    }
+
+    fn walk_post<'a>(&'a self, name: &str, ret: &mut Vec<&'a SnapshotNode>) {
+        let node = &self.nodes[name];
+        for child in &node.children {
+            self.walk_post(child, ret);
+        }
+        ret.push(node);
+    }
+
+    /// Names of the ancestors of `name`, starting with the snapshot
+    /// itself and ending with its root.
+    fn lineage(&self, name: &str) -> Vec<&str> {
+        let mut ret = Vec::new();
+        let mut visited = HashSet::new();
+        let mut cur = self.nodes.get(name);
+        while let Some(node) = cur {
+            if !visited.insert(node.name.as_str()) {
+                break;
+            }
+            ret.push(node.name.as_str());
+            cur = node.parent.as_ref().and_then(|p| self.nodes.get(p));
+        }
+        ret
+    }
+
+    /// Path going from snapshot `from` to snapshot `to`, both
+    /// included, through their closest common ancestor.
+    ///
+    /// Returns `None` if one of the snapshots does not exist or if
+    /// they do not share a root.
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let up = self.lineage(from);
+        let down = self.lineage(to);
+        if up.is_empty() || down.is_empty() {
+            return None;
+        }
+        let pivot = up.iter().position(|n| down.contains(n))?;
+        let pivot_down = down.iter().position(|n| *n == up[pivot]).unwrap();
+
+        let mut ret: Vec<String> = up[..pivot + 1].iter().map(|n| n.to_string()).collect();
+        ret.extend(down[..pivot_down].iter().rev().map(|n| n.to_string()));
+        Some(ret)
+    }
+
+    /// Delete the snapshot `name` and all its descendants from `dom`,
+    /// leaves first.
+    ///
+    /// `DomainSnapshotDeleteFlags::CHILDREN_ONLY` keeps `name` and
+    /// only removes its descendants, `DomainSnapshotDeleteFlags::METADATA_ONLY`
+    /// is passed through to every deletion so that only libvirt
+    /// metadata are removed. The tree is updated accordingly and the
+    /// names of the deleted snapshots are returned in deletion order.
+    pub fn delete_subtree(&mut self,
+                          dom: &Domain,
+                          name: &str,
+                          flags: DomainSnapshotDeleteFlags)
+                          -> Result<Vec<String>, Error> {
+        let children_only = flags.contains(DomainSnapshotDeleteFlags::CHILDREN_ONLY);
+        let flags = flags -
+                    (DomainSnapshotDeleteFlags::CHILDREN |
+                     DomainSnapshotDeleteFlags::CHILDREN_ONLY);
+
+        let mut order: Vec<String> =
+            self.subtree_post_order(name).iter().map(|n| n.name.clone()).collect();
+        if children_only {
+            order.retain(|n| n != name);
+        }
+
+        let mut deleted = Vec::new();
+        let mut ret = Ok(());
+        for snap in &order {
+            let res = DomainSnapshot::lookup_by_name(dom, snap, 0)
+                .and_then(|s| s.delete(flags));
+            if let Err(e) = res {
+                ret = Err(e);
+                break;
+            }
+            deleted.push(snap.clone());
+        }
+        self.remove(&deleted);
+        ret.map(|_| deleted)
+    }
+
+    fn remove(&mut self, names: &[String]) {
+        if names.is_empty() {
+            return;
+        }
+        let nodes = self.nodes
+            .drain()
+            .map(|(_, n)| n)
+            .filter(|n| !names.contains(&n.name))
+            .map(|mut n| {
+                n.children.clear();
+                n
+            })
+            .collect();
+        *self = SnapshotTree::build(nodes);
+    }
+}
@@ -72,6 +72,7 @@ extern "C" {
     fn virStoragePoolDelete(ptr: sys::virStoragePoolPtr, flags: libc::c_uint) -> libc::c_int;
     fn virStoragePoolUndefine(ptr: sys::virStoragePoolPtr) -> libc::c_int;
     fn virStoragePoolFree(ptr: sys::virStoragePoolPtr) -> libc::c_int;
+    fn virStoragePoolRef(ptr: sys::virStoragePoolPtr) -> libc::c_int;
     fn virStoragePoolIsActive(ptr: sys::virStoragePoolPtr) -> libc::c_int;
     fn virStoragePoolIsPersistent(ptr: sys::virStoragePoolPtr) -> libc::c_int;
     fn virStoragePoolGetName(ptr: sys::virStoragePoolPtr) -> *const libc::c_char;
@@ -82,6 +83,7 @@ extern "C" {
                                    uuid: *mut libc::c_char)
                                    -> libc::c_int;
     fn virStoragePoolGetConnect(ptr: sys::virStoragePoolPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
     fn virStoragePoolGetAutostart(ptr: sys::virStoragePoolPtr,
                                   autostart: *mut libc::c_int)
                                   -> libc::c_int;
@@ -94,26 +96,112 @@ extern "C" {
     fn virStoragePoolNumOfVolumes(ptr: sys::virStoragePoolPtr) -> libc::c_int;
 }
 
-pub type StoragePoolXMLFlags = self::libc::c_uint;
-pub const VIR_STORAGE_POOL_XML_INACTIVE: StoragePoolXMLFlags = 1 << 0;
+bitflags! {
+    /// Flags for `StoragePool::get_xml_desc`.
+    pub struct StoragePoolXMLFlags: libc::c_uint {
+        const INACTIVE = 1 << 0;
+    }
+}
+
+#[deprecated(note = "use `StoragePoolXMLFlags::INACTIVE`")]
+pub const VIR_STORAGE_POOL_XML_INACTIVE: StoragePoolXMLFlags = StoragePoolXMLFlags::INACTIVE;
+
+bitflags! {
+    /// Flags for starting a storage pool.
+    pub struct StoragePoolCreateFlags: libc::c_uint {
+        const NORMAL = 0;
+        const WITH_BUILD = 1 << 0;
+        const WITH_BUILD_OVERWRITE = 1 << 1;
+        const WITH_BUILD_NO_OVERWRITE = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `StoragePoolCreateFlags::NORMAL`")]
+pub const STORAGE_POOL_CREATE_NORMAL: StoragePoolCreateFlags = StoragePoolCreateFlags::NORMAL;
+#[deprecated(note = "use `StoragePoolCreateFlags::WITH_BUILD`")]
+pub const STORAGE_POOL_CREATE_WITH_BUILD: StoragePoolCreateFlags =
+    StoragePoolCreateFlags::WITH_BUILD;
+#[deprecated(note = "use `StoragePoolCreateFlags::WITH_BUILD_OVERWRITE`")]
+pub const STORAGE_POOL_CREATE_WITH_BUILD_OVERWRITE: StoragePoolCreateFlags =
+    StoragePoolCreateFlags::WITH_BUILD_OVERWRITE;
+#[deprecated(note = "use `StoragePoolCreateFlags::WITH_BUILD_NO_OVERWRITE`")]
+pub const STORAGE_POOL_CREATE_WITH_BUILD_NO_OVERWRITE: StoragePoolCreateFlags =
+    StoragePoolCreateFlags::WITH_BUILD_NO_OVERWRITE;
+
+bitflags! {
+    /// Flags for `StoragePool::build`.
+    pub struct StoragePoolBuildFlags: libc::c_uint {
+        const NEW = 0;
+        /// Repair or reinitialize.
+        const REPAIR = 1 << 0;
+        /// Extend the existing pool.
+        const RESIZE = 1 << 1;
+        /// Do not overwrite an existing pool.
+        const NO_OVERWRITE = 1 << 2;
+        /// Overwrite data.
+        const OVERWRITE = 1 << 3;
+    }
+}
 
-pub type StoragePoolCreateFlags = self::libc::c_uint;
-pub const STORAGE_POOL_CREATE_NORMAL: StoragePoolCreateFlags = 0;
-pub const STORAGE_POOL_CREATE_WITH_BUILD: StoragePoolCreateFlags = 1 << 0;
-pub const STORAGE_POOL_CREATE_WITH_BUILD_OVERWRITE: StoragePoolCreateFlags = 1 << 1;
-pub const STORAGE_POOL_CREATE_WITH_BUILD_NO_OVERWRITE: StoragePoolCreateFlags = 1 << 2;
+bitflags! {
+    /// Flags for `StoragePool::delete`.
+    pub struct StoragePoolDeleteFlags: libc::c_uint {
+        /// Delete metadata only.
+        const NORMAL = 0;
+        /// Clear all data to zeros, slow.
+        const ZEROED = 1 << 0;
+    }
+}
+
+/// State of a storage pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoragePoolState {
+    /// Not running.
+    Inactive = 0,
+    /// Initializing pool, not available.
+    Building = 1,
+    /// Running normally.
+    Running = 2,
+    /// Running degraded.
+    Degraded = 3,
+    /// Running, but not accessible.
+    Inaccessible = 4,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl StoragePoolState {
+    pub fn from_raw(value: i32) -> StoragePoolState {
+        match value {
+            0 => StoragePoolState::Inactive,
+            1 => StoragePoolState::Building,
+            2 => StoragePoolState::Running,
+            3 => StoragePoolState::Degraded,
+            4 => StoragePoolState::Inaccessible,
+            _ => StoragePoolState::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
 
-pub type StoragePoolState = self::libc::c_uint;
-pub const VIR_STORAGE_POOL_INACTIVE: StoragePoolState = 0;
-pub const VIR_STORAGE_POOL_BUILDING: StoragePoolState = 1;
-pub const VIR_STORAGE_POOL_RUNNING: StoragePoolState = 2;
-pub const VIR_STORAGE_POOL_DEGRADED: StoragePoolState = 3;
-pub const VIR_STORAGE_POOL_INACCESSIBLE: StoragePoolState = 4;
+#[deprecated(note = "use `StoragePoolState::Inactive`")]
+pub const VIR_STORAGE_POOL_INACTIVE: StoragePoolState = StoragePoolState::Inactive;
+#[deprecated(note = "use `StoragePoolState::Building`")]
+pub const VIR_STORAGE_POOL_BUILDING: StoragePoolState = StoragePoolState::Building;
+#[deprecated(note = "use `StoragePoolState::Running`")]
+pub const VIR_STORAGE_POOL_RUNNING: StoragePoolState = StoragePoolState::Running;
+#[deprecated(note = "use `StoragePoolState::Degraded`")]
+pub const VIR_STORAGE_POOL_DEGRADED: StoragePoolState = StoragePoolState::Degraded;
+#[deprecated(note = "use `StoragePoolState::Inaccessible`")]
+pub const VIR_STORAGE_POOL_INACCESSIBLE: StoragePoolState = StoragePoolState::Inaccessible;
 
 #[derive(Clone, Debug)]
 pub struct StoragePoolInfo {
-    /// A `StoragePoolState` flags
-    pub state: u32,
+    /// The state of the pool.
+    pub state: StoragePoolState,
     /// Logical size bytes.
     pub capacity: u64,
     /// Current allocation bytes.
@@ -126,7 +214,7 @@ impl StoragePoolInfo {
     pub fn from_ptr(ptr: sys::virStoragePoolInfoPtr) -> StoragePoolInfo {
         unsafe {
             StoragePoolInfo {
-                state: (*ptr).state as StoragePoolState,
+                state: StoragePoolState::from_raw((*ptr).state),
                 capacity: (*ptr).capacity as u64,
                 allocation: (*ptr).allocation as u64,
                 available: (*ptr).available as u64,
@@ -155,6 +243,22 @@ impl Drop for StoragePool {
     }
 }
 
+impl Clone for StoragePool {
+    fn clone(&self) -> StoragePool {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for StoragePool, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for StoragePool {}
+unsafe impl Sync for StoragePool {}
+
 impl StoragePool {
     pub fn new(ptr: sys::virStoragePoolPtr) -> StoragePool {
         return StoragePool { ptr: Some(ptr) };
@@ -164,12 +268,27 @@ impl StoragePool {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the storage pool and returns a new
+    /// handle on it, the storage pool is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<StoragePool, Error> {
+        unsafe {
+            if virStoragePoolRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(StoragePool::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virStoragePoolGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -193,7 +312,7 @@ impl StoragePool {
         unsafe {
             let ptr = virStoragePoolCreateXML(conn.as_ptr(),
                                               string_to_c_chars!(xml),
-                                              flags as libc::c_uint);
+                                              flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -273,7 +392,7 @@ impl StoragePool {
 
     pub fn get_xml_desc(&self, flags: StoragePoolXMLFlags) -> Result<String, Error> {
         unsafe {
-            let xml = virStoragePoolGetXMLDesc(self.as_ptr(), flags);
+            let xml = virStoragePoolGetXMLDesc(self.as_ptr(), flags.bits());
             if xml.is_null() {
                 return Err(Error::new());
             }
@@ -283,7 +402,7 @@ impl StoragePool {
 
     pub fn create(&self, flags: StoragePoolCreateFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virStoragePoolCreate(self.as_ptr(), flags);
+            let ret = virStoragePoolCreate(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -291,9 +410,9 @@ impl StoragePool {
         }
     }
 
-    pub fn build(&self, flags: u32) -> Result<u32, Error> {
+    pub fn build(&self, flags: StoragePoolBuildFlags) -> Result<u32, Error> {
         unsafe {
-            let ret = virStoragePoolBuild(self.as_ptr(), flags);
+            let ret = virStoragePoolBuild(self.as_ptr(), flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
@@ -310,9 +429,9 @@ impl StoragePool {
         }
     }
 
-    pub fn delete(&self, flags: u32) -> Result<(), Error> {
+    pub fn delete(&self, flags: StoragePoolDeleteFlags) -> Result<(), Error> {
         unsafe {
-            if virStoragePoolDelete(self.as_ptr(), flags as libc::c_uint) == -1 {
+            if virStoragePoolDelete(self.as_ptr(), flags.bits()) == -1 {
                 return Err(Error::new());
             }
             return Ok(());
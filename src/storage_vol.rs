@@ -79,10 +79,12 @@ extern "C" {
                                 flags: libc::c_uint)
                                 -> libc::c_int;
     fn virStorageVolFree(ptr: sys::virStorageVolPtr) -> libc::c_int;
+    fn virStorageVolRef(ptr: sys::virStorageVolPtr) -> libc::c_int;
     fn virStorageVolGetXMLDesc(ptr: sys::virStorageVolPtr,
                                flags: libc::c_uint)
                                -> *mut libc::c_char;
     fn virStorageVolGetConnect(ptr: sys::virStorageVolPtr) -> virConnectPtr;
+    fn virConnectRef(ptr: virConnectPtr) -> libc::c_int;
     fn virStorageVolResize(ptr: sys::virStorageVolPtr,
                            capacity: libc::c_ulonglong,
                            flags: libc::c_uint)
@@ -109,39 +111,156 @@ extern "C" {
                            -> libc::c_int;
 }
 
-pub type StorageVolCreateFlags = self::libc::c_uint;
-pub const VIR_STORAGE_VOL_CREATE_PREALLOC_METADATA: StorageVolCreateFlags = 1 << 0;
-pub const VIR_STORAGE_VOL_CREATE_REFLINK: StorageVolCreateFlags = 1 << 1;
-
-pub type StorageVolResizeFlags = self::libc::c_uint;
-pub const VIR_STORAGE_VOL_RESIZE_ALLOCATE: StorageVolResizeFlags = 1 << 0;
-pub const VIR_STORAGE_VOL_RESIZE_DELTA: StorageVolResizeFlags = 1 << 1;
-pub const VIR_STORAGE_VOL_RESIZE_SHRINK: StorageVolResizeFlags = 1 << 2;
-
-pub type StorageVolWipeAlgorithm = self::libc::c_uint;
-pub const VIR_STORAGE_VOL_WIPE_ALG_ZERO: StorageVolWipeAlgorithm = 0;
-pub const VIR_STORAGE_VOL_WIPE_ALG_NNSA: StorageVolWipeAlgorithm = 1;
-pub const VIR_STORAGE_VOL_WIPE_ALG_DOD: StorageVolWipeAlgorithm = 2;
-pub const VIR_STORAGE_VOL_WIPE_ALG_BSI: StorageVolWipeAlgorithm = 3;
-pub const VIR_STORAGE_VOL_WIPE_ALG_GUTMANN: StorageVolWipeAlgorithm = 4;
-pub const VIR_STORAGE_VOL_WIPE_ALG_SCHNEIER: StorageVolWipeAlgorithm = 5;
-pub const VIR_STORAGE_VOL_WIPE_ALG_PFITZNER7: StorageVolWipeAlgorithm = 6;
-pub const VIR_STORAGE_VOL_WIPE_ALG_PFITZNER33: StorageVolWipeAlgorithm = 7;
-pub const VIR_STORAGE_VOL_WIPE_ALG_RANDOM: StorageVolWipeAlgorithm = 8;
-pub const VIR_STORAGE_VOL_WIPE_ALG_TRIM: StorageVolWipeAlgorithm = 9;
-
-pub type StorageVolType = self::libc::c_uint;
-pub const VIR_STORAGE_VOL_FILE: StorageVolType = 0;
-pub const VIR_STORAGE_VOL_BLOCK: StorageVolType = 1;
-pub const VIR_STORAGE_VOL_DIR: StorageVolType = 2;
-pub const VIR_STORAGE_VOL_NETWORK: StorageVolType = 3;
-pub const VIR_STORAGE_VOL_NETDIR: StorageVolType = 4;
-pub const VIR_STORAGE_VOL_PLOOP: StorageVolType = 5;
+bitflags! {
+    /// Flags for creating a storage volume.
+    pub struct StorageVolCreateFlags: libc::c_uint {
+        const PREALLOC_METADATA = 1 << 0;
+        const REFLINK = 1 << 1;
+    }
+}
+
+#[deprecated(note = "use `StorageVolCreateFlags::PREALLOC_METADATA`")]
+pub const VIR_STORAGE_VOL_CREATE_PREALLOC_METADATA: StorageVolCreateFlags =
+    StorageVolCreateFlags::PREALLOC_METADATA;
+#[deprecated(note = "use `StorageVolCreateFlags::REFLINK`")]
+pub const VIR_STORAGE_VOL_CREATE_REFLINK: StorageVolCreateFlags = StorageVolCreateFlags::REFLINK;
+
+bitflags! {
+    /// Flags for `StorageVol::resize`.
+    pub struct StorageVolResizeFlags: libc::c_uint {
+        const ALLOCATE = 1 << 0;
+        const DELTA = 1 << 1;
+        const SHRINK = 1 << 2;
+    }
+}
+
+#[deprecated(note = "use `StorageVolResizeFlags::ALLOCATE`")]
+pub const VIR_STORAGE_VOL_RESIZE_ALLOCATE: StorageVolResizeFlags = StorageVolResizeFlags::ALLOCATE;
+#[deprecated(note = "use `StorageVolResizeFlags::DELTA`")]
+pub const VIR_STORAGE_VOL_RESIZE_DELTA: StorageVolResizeFlags = StorageVolResizeFlags::DELTA;
+#[deprecated(note = "use `StorageVolResizeFlags::SHRINK`")]
+pub const VIR_STORAGE_VOL_RESIZE_SHRINK: StorageVolResizeFlags = StorageVolResizeFlags::SHRINK;
+
+bitflags! {
+    /// Flags for `StorageVol::delete`.
+    pub struct StorageVolDeleteFlags: libc::c_uint {
+        /// Delete metadata only.
+        const NORMAL = 0;
+        /// Clear all data to zeros, slow.
+        const ZEROED = 1 << 0;
+        /// Force removal of the volume even if it has snapshots.
+        const WITH_SNAPSHOTS = 1 << 1;
+    }
+}
+
+/// Algorithm used by `StorageVol::wipe_pattern`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageVolWipeAlgorithm {
+    /// 1-pass, all zeroes.
+    Zero = 0,
+    /// 4-pass NNSA Policy Letter NAP-14.1-C (XVI-8).
+    Nnsa = 1,
+    /// 4-pass DoD 5220.22-M section 8-306 procedure.
+    Dod = 2,
+    /// 9-pass method recommended by the German Center of Security
+    /// in Information Technologies.
+    Bsi = 3,
+    /// The canonical 35-pass sequence.
+    Gutmann = 4,
+    /// 7-pass method described by Bruce Schneier.
+    Schneier = 5,
+    /// 7-pass method described by Roy Pfitzner.
+    Pfitzner7 = 6,
+    /// 33-pass method described by Roy Pfitzner.
+    Pfitzner33 = 7,
+    /// 1-pass, random.
+    Random = 8,
+    /// 1-pass, trim or discard, falls back to zeroes if not
+    /// supported.
+    Trim = 9,
+}
+
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Zero`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_ZERO: StorageVolWipeAlgorithm = StorageVolWipeAlgorithm::Zero;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Nnsa`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_NNSA: StorageVolWipeAlgorithm = StorageVolWipeAlgorithm::Nnsa;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Dod`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_DOD: StorageVolWipeAlgorithm = StorageVolWipeAlgorithm::Dod;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Bsi`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_BSI: StorageVolWipeAlgorithm = StorageVolWipeAlgorithm::Bsi;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Gutmann`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_GUTMANN: StorageVolWipeAlgorithm =
+    StorageVolWipeAlgorithm::Gutmann;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Schneier`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_SCHNEIER: StorageVolWipeAlgorithm =
+    StorageVolWipeAlgorithm::Schneier;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Pfitzner7`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_PFITZNER7: StorageVolWipeAlgorithm =
+    StorageVolWipeAlgorithm::Pfitzner7;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Pfitzner33`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_PFITZNER33: StorageVolWipeAlgorithm =
+    StorageVolWipeAlgorithm::Pfitzner33;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Random`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_RANDOM: StorageVolWipeAlgorithm =
+    StorageVolWipeAlgorithm::Random;
+#[deprecated(note = "use `StorageVolWipeAlgorithm::Trim`")]
+pub const VIR_STORAGE_VOL_WIPE_ALG_TRIM: StorageVolWipeAlgorithm = StorageVolWipeAlgorithm::Trim;
+
+/// Kind of a storage volume.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageVolType {
+    /// Regular file based volume.
+    File = 0,
+    /// Block based volume.
+    Block = 1,
+    /// Directory-passthrough based volume.
+    Dir = 2,
+    /// Network volume like RBD.
+    Network = 3,
+    /// Network accessible directory that can contain other
+    /// network volumes.
+    Netdir = 4,
+    /// Ploop based volume.
+    Ploop = 5,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl StorageVolType {
+    pub fn from_raw(value: i32) -> StorageVolType {
+        match value {
+            0 => StorageVolType::File,
+            1 => StorageVolType::Block,
+            2 => StorageVolType::Dir,
+            3 => StorageVolType::Network,
+            4 => StorageVolType::Netdir,
+            5 => StorageVolType::Ploop,
+            _ => StorageVolType::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `StorageVolType::File`")]
+pub const VIR_STORAGE_VOL_FILE: StorageVolType = StorageVolType::File;
+#[deprecated(note = "use `StorageVolType::Block`")]
+pub const VIR_STORAGE_VOL_BLOCK: StorageVolType = StorageVolType::Block;
+#[deprecated(note = "use `StorageVolType::Dir`")]
+pub const VIR_STORAGE_VOL_DIR: StorageVolType = StorageVolType::Dir;
+#[deprecated(note = "use `StorageVolType::Network`")]
+pub const VIR_STORAGE_VOL_NETWORK: StorageVolType = StorageVolType::Network;
+#[deprecated(note = "use `StorageVolType::Netdir`")]
+pub const VIR_STORAGE_VOL_NETDIR: StorageVolType = StorageVolType::Netdir;
+#[deprecated(note = "use `StorageVolType::Ploop`")]
+pub const VIR_STORAGE_VOL_PLOOP: StorageVolType = StorageVolType::Ploop;
 
 #[derive(Clone, Debug)]
 pub struct StorageVolInfo {
-    /// See: `virStorageVolType` flags
-    pub kind: u32,
+    /// The kind of the volume.
+    pub kind: StorageVolType,
     /// Logical size bytes.
     pub capacity: u64,
     /// Current allocation bytes
@@ -152,7 +271,7 @@ impl StorageVolInfo {
     pub fn from_ptr(ptr: sys::virStorageVolInfoPtr) -> StorageVolInfo {
         unsafe {
             StorageVolInfo {
-                kind: (*ptr).kind as StorageVolType,
+                kind: StorageVolType::from_raw((*ptr).kind),
                 capacity: (*ptr).capacity as u64,
                 allocation: (*ptr).allocation as u64,
             }
@@ -180,6 +299,22 @@ impl Drop for StorageVol {
     }
 }
 
+impl Clone for StorageVol {
+    fn clone(&self) -> StorageVol {
+        match self.add_ref() {
+            Ok(c) => c,
+            Err(e) => {
+                panic!("Unable to add reference for StorageVol, code {}, message: {}",
+                       e.code,
+                       e.message)
+            }
+        }
+    }
+}
+
+unsafe impl Send for StorageVol {}
+unsafe impl Sync for StorageVol {}
+
 impl StorageVol {
     pub fn new(ptr: sys::virStorageVolPtr) -> StorageVol {
         return StorageVol { ptr: Some(ptr) };
@@ -189,12 +324,27 @@ impl StorageVol {
         self.ptr.unwrap()
     }
 
+    /// Increments the reference count of the storage volume and returns a new
+    /// handle on it, the storage volume is released once every handle has
+    /// been freed.
+    pub fn add_ref(&self) -> Result<StorageVol, Error> {
+        unsafe {
+            if virStorageVolRef(self.as_ptr()) == -1 {
+                return Err(Error::new());
+            }
+        }
+        Ok(StorageVol::new(self.as_ptr()))
+    }
+
     pub fn get_connect(&self) -> Result<Connect, Error> {
         unsafe {
             let ptr = virStorageVolGetConnect(self.as_ptr());
             if ptr.is_null() {
                 return Err(Error::new());
             }
+            if virConnectRef(ptr) == -1 {
+                return Err(Error::new());
+            }
             return Ok(Connect::new(ptr));
         }
     }
@@ -206,7 +356,7 @@ impl StorageVol {
         unsafe {
             let ptr = virStorageVolCreateXML(pool.as_ptr(),
                                              string_to_c_chars!(xml),
-                                             flags as libc::c_uint);
+                                             flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -223,7 +373,7 @@ impl StorageVol {
             let ptr = virStorageVolCreateXMLFrom(pool.as_ptr(),
                                                  string_to_c_chars!(xml),
                                                  vol.as_ptr(),
-                                                 flags as libc::c_uint);
+                                                 flags.bits());
             if ptr.is_null() {
                 return Err(Error::new());
             }
@@ -301,9 +451,9 @@ impl StorageVol {
         }
     }
 
-    pub fn delete(&self, flags: u32) -> Result<(), Error> {
+    pub fn delete(&self, flags: StorageVolDeleteFlags) -> Result<(), Error> {
         unsafe {
-            if virStorageVolDelete(self.as_ptr(), flags as libc::c_uint) == -1 {
+            if virStorageVolDelete(self.as_ptr(), flags.bits()) == -1 {
                 return Err(Error::new());
             }
             return Ok(());
@@ -340,11 +490,11 @@ impl StorageVol {
         }
     }
 
-    pub fn resize(&self, capacity: u64, flags: u32) -> Result<u32, Error> {
+    pub fn resize(&self, capacity: u64, flags: StorageVolResizeFlags) -> Result<u32, Error> {
         unsafe {
             let ret = virStorageVolResize(self.as_ptr(),
                                           capacity as libc::c_ulonglong,
-                                          flags as libc::c_uint);
+                                          flags.bits());
             if ret == -1 {
                 return Err(Error::new());
             }
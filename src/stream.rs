@@ -45,11 +45,24 @@ extern "C" {
     fn virStreamFinish(c: sys::virStreamPtr) -> libc::c_int;
 }
 
-pub type StreamEventType = self::libc::c_uint;
-pub const VIR_STREAM_EVENT_READABLE: StreamEventType = (1 << 0);
-pub const VIR_STREAM_EVENT_WRITABLE: StreamEventType = (1 << 1);
-pub const VIR_STREAM_EVENT_ERROR: StreamEventType = (1 << 2);
-pub const VIR_STREAM_EVENT_HANGUP: StreamEventType = (1 << 3);
+bitflags! {
+    /// Events a stream can be watched for.
+    pub struct StreamEventType: libc::c_uint {
+        const READABLE = 1 << 0;
+        const WRITABLE = 1 << 1;
+        const ERROR = 1 << 2;
+        const HANGUP = 1 << 3;
+    }
+}
+
+#[deprecated(note = "use `StreamEventType::READABLE`")]
+pub const VIR_STREAM_EVENT_READABLE: StreamEventType = StreamEventType::READABLE;
+#[deprecated(note = "use `StreamEventType::WRITABLE`")]
+pub const VIR_STREAM_EVENT_WRITABLE: StreamEventType = StreamEventType::WRITABLE;
+#[deprecated(note = "use `StreamEventType::ERROR`")]
+pub const VIR_STREAM_EVENT_ERROR: StreamEventType = StreamEventType::ERROR;
+#[deprecated(note = "use `StreamEventType::HANGUP`")]
+pub const VIR_STREAM_EVENT_HANGUP: StreamEventType = StreamEventType::HANGUP;
 
 #[derive(Debug)]
 pub struct Stream {
@@ -68,6 +81,10 @@ impl Drop for Stream {
     }
 }
 
+// A stream may be handed over to another thread but concurrent
+// reads and writes on it are not something libvirt documents.
+unsafe impl Send for Stream {}
+
 impl Stream {
     pub fn new(ptr: sys::virStreamPtr) -> Stream {
         Stream { ptr: Some(ptr) }
@@ -46,11 +46,57 @@ pub mod sys {
     pub type virTypedParameterPtr = *mut virTypedParameter;
 }
 
-pub type TypedParameterType = self::libc::c_int;
-pub const VIR_TYPED_PARAM_INT: TypedParameterType = 1;
-pub const VIR_TYPED_PARAM_UINT: TypedParameterType = 2;
-pub const VIR_TYPED_PARAM_LLONG: TypedParameterType = 3;
-pub const VIR_TYPED_PARAM_ULLONG: TypedParameterType = 4;
-pub const VIR_TYPED_PARAM_DOUBLE: TypedParameterType = 5;
-pub const VIR_TYPED_PARAM_BOOLEAN: TypedParameterType = 6;
-pub const VIR_TYPED_PARAM_STRING: TypedParameterType = 7;
+/// Type of the value held by a typed parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypedParameterType {
+    /// Integer, `value` holds a C int.
+    Int = 1,
+    /// Unsigned integer, `value` holds a C unsigned int.
+    UInt = 2,
+    /// Long long integer.
+    LLong = 3,
+    /// Unsigned long long integer.
+    ULLong = 4,
+    /// Double.
+    Double = 5,
+    /// Boolean, `value` holds a C char.
+    Boolean = 6,
+    /// String, `value` holds a pointer to a C string.
+    String = 7,
+    /// Value not known by the binding.
+    Unknown = -1,
+}
+
+impl TypedParameterType {
+    pub fn from_raw(value: i32) -> TypedParameterType {
+        match value {
+            1 => TypedParameterType::Int,
+            2 => TypedParameterType::UInt,
+            3 => TypedParameterType::LLong,
+            4 => TypedParameterType::ULLong,
+            5 => TypedParameterType::Double,
+            6 => TypedParameterType::Boolean,
+            7 => TypedParameterType::String,
+            _ => TypedParameterType::Unknown,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
+#[deprecated(note = "use `TypedParameterType::Int`")]
+pub const VIR_TYPED_PARAM_INT: TypedParameterType = TypedParameterType::Int;
+#[deprecated(note = "use `TypedParameterType::UInt`")]
+pub const VIR_TYPED_PARAM_UINT: TypedParameterType = TypedParameterType::UInt;
+#[deprecated(note = "use `TypedParameterType::LLong`")]
+pub const VIR_TYPED_PARAM_LLONG: TypedParameterType = TypedParameterType::LLong;
+#[deprecated(note = "use `TypedParameterType::ULLong`")]
+pub const VIR_TYPED_PARAM_ULLONG: TypedParameterType = TypedParameterType::ULLong;
+#[deprecated(note = "use `TypedParameterType::Double`")]
+pub const VIR_TYPED_PARAM_DOUBLE: TypedParameterType = TypedParameterType::Double;
+#[deprecated(note = "use `TypedParameterType::Boolean`")]
+pub const VIR_TYPED_PARAM_BOOLEAN: TypedParameterType = TypedParameterType::Boolean;
+#[deprecated(note = "use `TypedParameterType::String`")]
+pub const VIR_TYPED_PARAM_STRING: TypedParameterType = TypedParameterType::String;
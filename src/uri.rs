@@ -0,0 +1,347 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Parsing and formatting of connection URIs.
+//!
+//! See http://libvirt.org/uri.html and
+//! http://libvirt.org/remote.html#Remote_URI_reference
+//!
+//! ```
+//! use virt::uri::{ConnectUri, Transport};
+//!
+//! let s = "qemu+ssh://admin@hv1.example.org/system?keyfile=%2Fhome%2Fadmin%2F.ssh%2Fid%20rsa";
+//! let uri = ConnectUri::new("qemu")
+//!     .transport(Transport::Ssh)
+//!     .user("admin")
+//!     .host("hv1.example.org")
+//!     .path("/system")
+//!     .param("keyfile", "/home/admin/.ssh/id rsa");
+//! assert_eq!(s, uri.to_string());
+//! assert_eq!(Ok(uri), s.parse());
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use connect::Connect;
+use error::{Error, ErrorDomain, ErrorLevel, ErrorNumber};
+
+/// Transport used to reach a remote libvirtd.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Unix,
+    Tcp,
+    Tls,
+    Ssh,
+    Libssh2,
+    Libssh,
+    Ext,
+    /// Transport not known by the binding.
+    Other(String),
+}
+
+impl Transport {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Transport::Unix => "unix",
+            Transport::Tcp => "tcp",
+            Transport::Tls => "tls",
+            Transport::Ssh => "ssh",
+            Transport::Libssh2 => "libssh2",
+            Transport::Libssh => "libssh",
+            Transport::Ext => "ext",
+            Transport::Other(ref t) => t,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Transport {
+        match name {
+            "unix" => Transport::Unix,
+            "tcp" => Transport::Tcp,
+            "tls" => Transport::Tls,
+            "ssh" => Transport::Ssh,
+            "libssh2" => Transport::Libssh2,
+            "libssh" => Transport::Libssh,
+            "ext" => Transport::Ext,
+            _ => Transport::Other(name.to_string()),
+        }
+    }
+}
+
+/// A connection URI split in its components, all of them unescaped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectUri {
+    /// Hypervisor driver like `qemu`, `xen` or `test`.
+    pub driver: String,
+    pub transport: Option<Transport>,
+    pub user: Option<String>,
+    /// Hostname or address, IPv6 addresses are stored without
+    /// brackets.
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// Path like `/system`, `/session` or `/embed`, empty if the
+    /// URI has none.
+    pub path: String,
+    /// Query parameters, in order.
+    pub params: Vec<(String, String)>,
+}
+
+fn invalid(uri: &str, reason: &str) -> Error {
+    Error {
+        code: ErrorNumber::InvalidArg,
+        domain: ErrorDomain::Uri,
+        message: format!("invalid URI '{}': {}", uri, reason),
+        level: ErrorLevel::ERROR,
+        str1: None,
+        str2: None,
+        str3: None,
+        int1: 0,
+        int2: 0,
+    }
+}
+
+/// Percent-encodes every byte of `s` except the unreserved characters
+/// and the ones in `keep`.
+fn escape(s: &str, keep: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for b in s.bytes() {
+        let c = b as char;
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || keep.contains(c) {
+            ret.push(c);
+        } else {
+            ret.push_str(&format!("%{:02X}", b));
+        }
+    }
+    ret
+}
+
+fn unescape(uri: &str, s: &str) -> Result<String, Error> {
+    let bytes = s.as_bytes();
+    let mut ret = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3).ok_or_else(|| invalid(uri, "truncated escape"))?;
+            let b = u8::from_str_radix(hex, 16).map_err(|_| invalid(uri, "invalid escape"))?;
+            ret.push(b);
+            i += 3;
+        } else {
+            ret.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(ret).map_err(|_| invalid(uri, "invalid UTF-8"))
+}
+
+impl ConnectUri {
+    pub fn new(driver: &str) -> ConnectUri {
+        ConnectUri { driver: driver.to_string(), ..ConnectUri::default() }
+    }
+
+    /// Parses the URI of an opened connection.
+    pub fn from_connect(conn: &Connect) -> Result<ConnectUri, Error> {
+        conn.get_uri()?.parse()
+    }
+
+    pub fn transport(mut self, transport: Transport) -> ConnectUri {
+        self.transport = Some(transport);
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> ConnectUri {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn host(mut self, host: &str) -> ConnectUri {
+        self.host = Some(host.to_string());
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> ConnectUri {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn path(mut self, path: &str) -> ConnectUri {
+        self.path = path.to_string();
+        self
+    }
+
+    /// Sets the query parameter `name`, replacing any previous value.
+    pub fn param(mut self, name: &str, value: &str) -> ConnectUri {
+        self.set_param(name, value);
+        self
+    }
+
+    /// Sets the query parameter `name`, replacing any previous value.
+    pub fn set_param(&mut self, name: &str, value: &str) {
+        match self.params.iter().position(|p| p.0 == name) {
+            Some(i) => self.params[i].1 = value.to_string(),
+            None => self.params.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Value of the query parameter `name`.
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|p| p.0 == name).map(|p| p.1.as_str())
+    }
+
+    /// Path of the UNIX socket of the remote daemon.
+    pub fn socket(&self) -> Option<&str> {
+        self.get_param("socket")
+    }
+
+    /// Private key used by the SSH transports.
+    pub fn keyfile(&self) -> Option<&str> {
+        self.get_param("keyfile")
+    }
+
+    /// Whether the server certificate checks are disabled.
+    pub fn no_verify(&self) -> bool {
+        self.get_param("no_verify").is_some_and(|v| v != "0")
+    }
+
+    /// Known hosts file used by the libssh transports.
+    pub fn known_hosts(&self) -> Option<&str> {
+        self.get_param("known_hosts")
+    }
+
+    /// Directory holding the TLS certificates.
+    pub fn pkipath(&self) -> Option<&str> {
+        self.get_param("pkipath")
+    }
+
+    /// Command run by the `ext` transport.
+    pub fn command(&self) -> Option<&str> {
+        self.get_param("command")
+    }
+
+    /// Daemon mode, `auto`, `legacy` or `direct`.
+    pub fn mode(&self) -> Option<&str> {
+        self.get_param("mode")
+    }
+}
+
+impl FromStr for ConnectUri {
+    type Err = Error;
+
+    fn from_str(uri: &str) -> Result<ConnectUri, Error> {
+        let colon = uri.find(':').ok_or_else(|| invalid(uri, "missing scheme"))?;
+        let scheme = &uri[..colon];
+        let mut rest = &uri[colon + 1..];
+
+        let (driver, transport) = match scheme.find('+') {
+            Some(i) => (&scheme[..i], Some(Transport::from_name(&scheme[i + 1..]))),
+            None => (scheme, None),
+        };
+        if driver.is_empty() || !driver.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid(uri, "invalid driver"));
+        }
+        let mut ret = ConnectUri::new(driver);
+        ret.transport = transport;
+
+        if let Some(i) = rest.find('#') {
+            rest = &rest[..i];
+        }
+        let query = match rest.find('?') {
+            Some(i) => {
+                let query = &rest[i + 1..];
+                rest = &rest[..i];
+                query
+            }
+            None => "",
+        };
+
+        if let Some(authority) = rest.strip_prefix("//") {
+            let end = authority.find('/').unwrap_or(authority.len());
+            ret.path = unescape(uri, &authority[end..])?;
+            let mut authority = &authority[..end];
+
+            if let Some(i) = authority.rfind('@') {
+                ret.user = Some(unescape(uri, &authority[..i])?);
+                authority = &authority[i + 1..];
+            }
+            let (host, port) = if authority.starts_with('[') {
+                let close = authority.find(']')
+                    .ok_or_else(|| invalid(uri, "unclosed IPv6 address"))?;
+                let port = &authority[close + 1..];
+                if !port.is_empty() && !port.starts_with(':') {
+                    return Err(invalid(uri, "invalid IPv6 address"));
+                }
+                (&authority[1..close], port.get(1..))
+            } else {
+                match authority.rfind(':') {
+                    Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+                    None => (authority, None),
+                }
+            };
+            if !host.is_empty() {
+                ret.host = Some(unescape(uri, host)?);
+            }
+            if let Some(port) = port {
+                if !port.is_empty() {
+                    ret.port = Some(port.parse().map_err(|_| invalid(uri, "invalid port"))?);
+                }
+            }
+        } else {
+            ret.path = unescape(uri, rest)?;
+        }
+
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let (name, value) = match param.find('=') {
+                Some(i) => (&param[..i], &param[i + 1..]),
+                None => (param, ""),
+            };
+            ret.params.push((unescape(uri, name)?, unescape(uri, value)?));
+        }
+        Ok(ret)
+    }
+}
+
+impl Display for ConnectUri {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.driver)?;
+        if let Some(ref transport) = self.transport {
+            write!(f, "+{}", transport.as_str())?;
+        }
+        write!(f, "://")?;
+        if let Some(ref user) = self.user {
+            write!(f, "{}@", escape(user, ""))?;
+        }
+        if let Some(ref host) = self.host {
+            if host.contains(':') {
+                write!(f, "[{}]", escape(host, ":"))?;
+            } else {
+                write!(f, "{}", escape(host, ""))?;
+            }
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        write!(f, "{}", escape(&self.path, "/"))?;
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            write!(f,
+                   "{}{}={}",
+                   if i == 0 { "?" } else { "&" },
+                   escape(name, ""),
+                   escape(value, ""))?;
+        }
+        Ok(())
+    }
+}
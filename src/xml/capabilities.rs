@@ -0,0 +1,626 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the host capabilities returned by
+//! `Connect::get_capabilities`.
+//!
+//! See http://libvirt.org/formatcaps.html
+//!
+//! ```
+//! use virt::xml::capabilities::Capabilities;
+//!
+//! let caps: Capabilities = "<capabilities>
+//!   <host>
+//!     <cpu><arch>x86_64</arch></cpu>
+//!     <topology>
+//!       <cells num='1'>
+//!         <cell id='0'>
+//!           <memory unit='KiB'>16777216</memory>
+//!           <cpus num='2'>
+//!             <cpu id='0' socket_id='0' core_id='0' siblings='0'/>
+//!             <cpu id='1' socket_id='0' core_id='1' siblings='1'/>
+//!           </cpus>
+//!         </cell>
+//!       </cells>
+//!     </topology>
+//!   </host>
+//! </capabilities>".parse().unwrap();
+//! assert_eq!(2, caps.host.cells[0].cpus.len());
+//! ```
+
+use connect::Connect;
+use error::Error;
+use xml::domain::CpuTopology;
+use xml::{parse_value, to_kib, yes_no, Element, Extra, Node, XmlElement};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Capabilities {
+    pub host: Host,
+    /// Every guest architecture and OS type the driver can run.
+    pub guests: Vec<Guest>,
+    pub extra: Extra,
+}
+
+impl Capabilities {
+    /// Fetch and parse the capabilities of the host `conn` is
+    /// connected to.
+    pub fn from_connect(conn: &Connect) -> Result<Capabilities, Error> {
+        conn.get_capabilities()?.parse()
+    }
+
+    /// The guest running `os_type` like `hvm` on `arch` like
+    /// `x86_64`.
+    pub fn guest(&self, os_type: &str, arch: &str) -> Option<&Guest> {
+        self.guests.iter().find(|g| g.os_type == os_type && g.arch.name == arch)
+    }
+}
+
+impl XmlElement for Capabilities {
+    fn from_element(mut el: Element) -> Result<Capabilities, Error> {
+        Ok(Capabilities {
+            host: el.take_model("host")?.unwrap_or_default(),
+            guests: el.take_models("guest")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capabilities")
+            .child(self.host.to_element())
+            .models(&self.guests)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(Capabilities);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Host {
+    pub uuid: Option<String>,
+    pub cpu: Option<HostCpu>,
+    /// Whether the host supports device assignment through an IOMMU.
+    pub iommu: Option<bool>,
+    pub migration: Option<MigrationFeatures>,
+    /// NUMA cells of the host.
+    pub cells: Vec<NumaCell>,
+    /// CPU cache banks.
+    pub cache: Vec<CacheBank>,
+    /// Security drivers, in order of preference.
+    pub secmodels: Vec<SecModel>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Host {
+    fn from_element(mut el: Element) -> Result<Host, Error> {
+        let mut cells = Vec::new();
+        if let Some(mut topology) = el.take("topology") {
+            if let Some(mut c) = topology.take("cells") {
+                cells = c.take_models("cell")?;
+                c.take_attr("num");
+                topology.keep_rest(0, c);
+            }
+            el.keep_rest(0, topology);
+        }
+        let mut cache = Vec::new();
+        if let Some(mut c) = el.take("cache") {
+            cache = c.take_models("bank")?;
+            el.keep_rest(0, c);
+        }
+        Ok(Host {
+            uuid: el.take_text("uuid"),
+            cpu: el.take_model("cpu")?,
+            iommu: el.take_child_bool("iommu", "support")?,
+            migration: el.take_model("migration_features")?,
+            cells,
+            cache,
+            secmodels: el.take_models("secmodel")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let topology = if self.cells.is_empty() {
+            None
+        } else {
+            let cells = Element::new("cells").attr("num", self.cells.len()).models(&self.cells);
+            Some(Element::new("topology").child(cells))
+        };
+        let cache = if self.cache.is_empty() {
+            None
+        } else {
+            Some(Element::new("cache").models(&self.cache))
+        };
+        Element::new("host")
+            .text_child("uuid", self.uuid.as_ref())
+            .model(self.cpu.as_ref())
+            .attr_child("iommu", "support", yes_no(self.iommu))
+            .model(self.migration.as_ref())
+            .child_opt(topology)
+            .child_opt(cache)
+            .models(&self.secmodels)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HostCpu {
+    pub arch: String,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub microcode: Option<String>,
+    pub topology: Option<CpuTopology>,
+    /// Names of the features on top of the model.
+    pub features: Vec<String>,
+    /// Sizes of the memory pages supported, in KiB.
+    pub pages: Vec<u64>,
+    pub extra: Extra,
+}
+
+impl HostCpu {
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|f| f == name)
+    }
+}
+
+impl XmlElement for HostCpu {
+    fn from_element(mut el: Element) -> Result<HostCpu, Error> {
+        let mut features = Vec::new();
+        for mut feature in el.take_all("feature") {
+            match feature.take_attr("name") {
+                Some(name) => {
+                    el.keep_rest(features.len(), feature);
+                    features.push(name);
+                }
+                None => el.children.push(Node::Element(feature)),
+            }
+        }
+        let mut pages = Vec::new();
+        for mut page in el.take_all("pages") {
+            let unit = page.take_attr("unit");
+            let size = page.take_parse("size")?.unwrap_or(0);
+            el.keep_rest(pages.len(), page);
+            pages.push(to_kib(size, unit.as_deref())?);
+        }
+        Ok(HostCpu {
+            arch: el.take_text("arch").unwrap_or_default(),
+            model: el.take_text("model"),
+            vendor: el.take_text("vendor"),
+            microcode: el.take_child_attr("microcode", "version"),
+            topology: el.take_model("topology")?,
+            features,
+            pages,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("cpu")
+            .child(Element::new("arch").text(&self.arch))
+            .text_child("model", self.model.as_ref())
+            .text_child("vendor", self.vendor.as_ref())
+            .attr_child("microcode", "version", self.microcode.as_ref())
+            .model(self.topology.as_ref());
+        for name in &self.features {
+            el = el.child(Element::new("feature").attr("name", name));
+        }
+        for size in &self.pages {
+            el = el.child(Element::new("pages").attr("unit", "KiB").attr("size", size));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationFeatures {
+    /// Whether live migration is supported.
+    pub live: bool,
+    /// URI transports like `tcp` or `rdma` usable for migration.
+    pub uri_transports: Vec<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for MigrationFeatures {
+    fn from_element(mut el: Element) -> Result<MigrationFeatures, Error> {
+        let uri_transports = match el.take("uri_transports") {
+            Some(mut t) => t.take_all("uri_transport").iter().map(|u| u.get_text()).collect(),
+            None => Vec::new(),
+        };
+        Ok(MigrationFeatures {
+            live: el.take_flag("live"),
+            uri_transports,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let transports = if self.uri_transports.is_empty() {
+            None
+        } else {
+            let mut el = Element::new("uri_transports");
+            for t in &self.uri_transports {
+                el = el.child(Element::new("uri_transport").text(t));
+            }
+            Some(el)
+        };
+        Element::new("migration_features")
+            .flag_child("live", self.live)
+            .child_opt(transports)
+            .extra(&self.extra)
+    }
+}
+
+/// A NUMA cell of the host.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumaCell {
+    pub id: u32,
+    /// Memory of the cell, in KiB.
+    pub memory: Option<u64>,
+    /// Memory pages of the cell, per size.
+    pub pages: Vec<NumaPages>,
+    /// Distances to the other cells, `(cell id, distance)`.
+    pub distances: Vec<(u32, u32)>,
+    pub cpus: Vec<NumaCpu>,
+    pub extra: Extra,
+}
+
+impl NumaCell {
+    /// Distance from this cell to the cell `id`, as reported by the
+    /// firmware.
+    pub fn distance(&self, id: u32) -> Option<u32> {
+        self.distances.iter().find(|d| d.0 == id).map(|d| d.1)
+    }
+}
+
+impl XmlElement for NumaCell {
+    fn from_element(mut el: Element) -> Result<NumaCell, Error> {
+        let mut distances = Vec::new();
+        if let Some(mut d) = el.take("distances") {
+            for mut sibling in d.take_all("sibling") {
+                let id = sibling.take_parse("id")?.unwrap_or(0);
+                let value = sibling.take_parse("value")?.unwrap_or(0);
+                d.keep_rest(distances.len(), sibling);
+                distances.push((id, value));
+            }
+            el.keep_rest(0, d);
+        }
+        let mut cpus = Vec::new();
+        if let Some(mut c) = el.take("cpus") {
+            cpus = c.take_models("cpu")?;
+            c.take_attr("num");
+            el.keep_rest(0, c);
+        }
+        Ok(NumaCell {
+            id: el.take_parse("id")?.unwrap_or(0),
+            memory: el.take_memory("memory")?,
+            pages: el.take_models("pages")?,
+            distances,
+            cpus,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let distances = if self.distances.is_empty() {
+            None
+        } else {
+            let mut el = Element::new("distances");
+            for &(id, value) in &self.distances {
+                el = el.child(Element::new("sibling").attr("id", id).attr("value", value));
+            }
+            Some(el)
+        };
+        let cpus = if self.cpus.is_empty() {
+            None
+        } else {
+            Some(Element::new("cpus").attr("num", self.cpus.len()).models(&self.cpus))
+        };
+        Element::new("cell")
+            .attr("id", self.id)
+            .memory_child("memory", self.memory)
+            .models(&self.pages)
+            .child_opt(distances)
+            .child_opt(cpus)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumaPages {
+    /// Size of a page, in KiB.
+    pub size: u64,
+    /// Number of free pages of that size.
+    pub count: u64,
+}
+
+impl XmlElement for NumaPages {
+    fn from_element(mut el: Element) -> Result<NumaPages, Error> {
+        let unit = el.take_attr("unit");
+        let size = el.take_parse("size")?.unwrap_or(0);
+        Ok(NumaPages {
+            size: to_kib(size, unit.as_deref())?,
+            count: parse_value("pages", "count", &el.get_text())?,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("pages").attr("unit", "KiB").attr("size", self.size).text(self.count)
+    }
+}
+
+/// A logical CPU of a host NUMA cell.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumaCpu {
+    pub id: u32,
+    pub socket_id: Option<u32>,
+    pub die_id: Option<u32>,
+    pub core_id: Option<u32>,
+    /// Logical CPUs sharing the same core, like `0,4`.
+    pub siblings: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for NumaCpu {
+    fn from_element(mut el: Element) -> Result<NumaCpu, Error> {
+        Ok(NumaCpu {
+            id: el.take_parse("id")?.unwrap_or(0),
+            socket_id: el.take_parse("socket_id")?,
+            die_id: el.take_parse("die_id")?,
+            core_id: el.take_parse("core_id")?,
+            siblings: el.take_attr("siblings"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("cpu")
+            .attr("id", self.id)
+            .attr_opt("socket_id", self.socket_id)
+            .attr_opt("die_id", self.die_id)
+            .attr_opt("core_id", self.core_id)
+            .attr_opt("siblings", self.siblings.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// A bank of CPU cache.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheBank {
+    pub id: u32,
+    pub level: u32,
+    /// Either `both`, `code` or `data`.
+    pub kind: String,
+    /// Size of the bank, in KiB.
+    pub size: u64,
+    /// Logical CPUs sharing the bank, like `0-7`.
+    pub cpus: String,
+    pub extra: Extra,
+}
+
+impl XmlElement for CacheBank {
+    fn from_element(mut el: Element) -> Result<CacheBank, Error> {
+        let unit = el.take_attr("unit");
+        let size = el.take_parse("size")?.unwrap_or(0);
+        Ok(CacheBank {
+            id: el.take_parse("id")?.unwrap_or(0),
+            level: el.take_parse("level")?.unwrap_or(0),
+            kind: el.take_attr("type").unwrap_or_default(),
+            size: to_kib(size, unit.as_deref())?,
+            cpus: el.take_attr("cpus").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("bank")
+            .attr("id", self.id)
+            .attr("level", self.level)
+            .attr("type", &self.kind)
+            .attr("size", self.size)
+            .attr("unit", "KiB")
+            .attr("cpus", &self.cpus)
+            .extra(&self.extra)
+    }
+}
+
+/// A security driver of the host.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SecModel {
+    /// Like `selinux`, `apparmor` or `dac`.
+    pub model: String,
+    pub doi: Option<String>,
+    /// Base labels, `(virtualization type, label)`.
+    pub baselabels: Vec<(String, String)>,
+    pub extra: Extra,
+}
+
+impl XmlElement for SecModel {
+    fn from_element(mut el: Element) -> Result<SecModel, Error> {
+        let baselabels = el.take_all("baselabel")
+            .into_iter()
+            .map(|mut b| (b.take_attr("type").unwrap_or_default(), b.get_text()))
+            .collect();
+        Ok(SecModel {
+            model: el.take_text("model").unwrap_or_default(),
+            doi: el.take_text("doi"),
+            baselabels,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("secmodel")
+            .child(Element::new("model").text(&self.model))
+            .text_child("doi", self.doi.as_ref());
+        for (kind, label) in &self.baselabels {
+            el = el.child(Element::new("baselabel").attr("type", kind).text(label));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Guest {
+    /// Like `hvm`, `xen` or `exe`.
+    pub os_type: String,
+    pub arch: GuestArch,
+    /// Features like `acpi`, `pae` or `cpuselection`, each one with
+    /// its `default` and `toggle` attributes if any.
+    pub features: Vec<Element>,
+    pub extra: Extra,
+}
+
+impl Guest {
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|f| f.name == name)
+    }
+
+    /// The domain type `kind`, like `kvm` or `qemu`.
+    pub fn domain(&self, kind: &str) -> Option<&GuestDomain> {
+        self.arch.domains.iter().find(|d| d.kind == kind)
+    }
+}
+
+impl XmlElement for Guest {
+    fn from_element(mut el: Element) -> Result<Guest, Error> {
+        Ok(Guest {
+            os_type: el.take_text("os_type").unwrap_or_default(),
+            arch: el.take_model("arch")?.unwrap_or_default(),
+            features: el.take("features").map(|f| f.into_extra().children).unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let features = if self.features.is_empty() {
+            None
+        } else {
+            Some(Element::new("features").extra(&Extra {
+                attributes: Vec::new(),
+                children: self.features.clone(),
+                ..Default::default()
+            }))
+        };
+        Element::new("guest")
+            .child(Element::new("os_type").text(&self.os_type))
+            .child(self.arch.to_element())
+            .child_opt(features)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuestArch {
+    /// Architecture like `x86_64` or `aarch64`.
+    pub name: String,
+    pub wordsize: Option<u32>,
+    /// Default emulator of the architecture.
+    pub emulator: Option<String>,
+    pub loader: Option<String>,
+    /// Machine types of the default emulator.
+    pub machines: Vec<Machine>,
+    /// Domain types able to run the architecture.
+    pub domains: Vec<GuestDomain>,
+    pub extra: Extra,
+}
+
+impl XmlElement for GuestArch {
+    fn from_element(mut el: Element) -> Result<GuestArch, Error> {
+        Ok(GuestArch {
+            name: el.take_attr("name").unwrap_or_default(),
+            wordsize: el.take_text_parse("wordsize")?,
+            emulator: el.take_text("emulator"),
+            loader: el.take_text("loader"),
+            machines: el.take_models("machine")?,
+            domains: el.take_models("domain")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("arch")
+            .attr("name", &self.name)
+            .text_child("wordsize", self.wordsize)
+            .text_child("emulator", self.emulator.as_ref())
+            .text_child("loader", self.loader.as_ref())
+            .models(&self.machines)
+            .models(&self.domains)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Machine {
+    pub name: String,
+    /// Name the machine type is an alias of, like
+    /// `pc-q35-6.2` for `q35`.
+    pub canonical: Option<String>,
+    pub max_cpus: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Machine {
+    fn from_element(mut el: Element) -> Result<Machine, Error> {
+        let name = el.get_text();
+        el.children.clear();
+        Ok(Machine {
+            name,
+            canonical: el.take_attr("canonical"),
+            max_cpus: el.take_parse("maxCpus")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("machine")
+            .attr_opt("canonical", self.canonical.as_ref())
+            .attr_opt("maxCpus", self.max_cpus)
+            .text(&self.name)
+            .extra(&self.extra)
+    }
+}
+
+/// A domain type able to run a guest architecture.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuestDomain {
+    /// Like `qemu` or `kvm`.
+    pub kind: String,
+    /// Emulator specific to the domain type, if it differs from the
+    /// default of the architecture.
+    pub emulator: Option<String>,
+    pub machines: Vec<Machine>,
+    pub extra: Extra,
+}
+
+impl XmlElement for GuestDomain {
+    fn from_element(mut el: Element) -> Result<GuestDomain, Error> {
+        Ok(GuestDomain {
+            kind: el.take_attr("type").unwrap_or_default(),
+            emulator: el.take_text("emulator"),
+            machines: el.take_models("machine")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("domain")
+            .attr("type", &self.kind)
+            .text_child("emulator", self.emulator.as_ref())
+            .models(&self.machines)
+            .extra(&self.extra)
+    }
+}
@@ -0,0 +1,2038 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the domain XML format.
+//!
+//! See http://libvirt.org/formatdomain.html
+//!
+//! ```
+//! use virt::xml::domain::{Disk, DomainDef, Interface, Os};
+//!
+//! let def = DomainDef::new("kvm", "guest")
+//!     .memory(1024 * 1024)
+//!     .vcpus(2)
+//!     .os(Os::hvm().arch("x86_64").boot("hd"))
+//!     .device(Disk::file("/var/lib/libvirt/images/guest.qcow2", "vda").driver("qemu", "qcow2"))
+//!     .device(Interface::network("default").model("virtio"));
+//!
+//! let parsed: DomainDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! assert_eq!(1, parsed.disks().len());
+//! ```
+
+use error::Error;
+use xml::{on_off, parse_value, to_kib, yes_no, Element, Extra, Node, XmlElement};
+
+/// A domain definition as used by `Domain::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainDef {
+    /// Hypervisor type like `kvm`, `qemu`, `xen` or `test`.
+    pub kind: String,
+    /// Identifier of a running domain, ignored when defining.
+    pub id: Option<i32>,
+    pub name: String,
+    pub uuid: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Children of `<metadata>`, each one in its own namespace.
+    pub metadata: Vec<Element>,
+    /// Maximum memory allocated at boot, in KiB.
+    pub memory: Option<u64>,
+    /// Memory actually allocated, in KiB.
+    pub current_memory: Option<u64>,
+    pub vcpu: Option<Vcpu>,
+    pub os: Option<Os>,
+    pub features: Option<Features>,
+    pub cpu: Option<Cpu>,
+    pub numatune: Option<Numatune>,
+    pub clock: Option<Clock>,
+    pub on_poweroff: Option<String>,
+    pub on_reboot: Option<String>,
+    pub on_crash: Option<String>,
+    pub emulator: Option<String>,
+    /// Devices, in document order.
+    pub devices: Vec<Device>,
+    pub extra: Extra,
+}
+
+impl DomainDef {
+    pub fn new(kind: &str, name: &str) -> DomainDef {
+        DomainDef { kind: kind.to_string(), name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> DomainDef {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> DomainDef {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> DomainDef {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an element to `<metadata>`, it should declare its own
+    /// namespace.
+    pub fn metadata(mut self, metadata: Element) -> DomainDef {
+        self.metadata.push(metadata);
+        self
+    }
+
+    /// Set both the maximum and the current memory, in KiB.
+    pub fn memory(mut self, kib: u64) -> DomainDef {
+        self.memory = Some(kib);
+        self.current_memory = Some(kib);
+        self
+    }
+
+    pub fn current_memory(mut self, kib: u64) -> DomainDef {
+        self.current_memory = Some(kib);
+        self
+    }
+
+    pub fn vcpus(mut self, count: u32) -> DomainDef {
+        self.vcpu = Some(Vcpu { count, ..Default::default() });
+        self
+    }
+
+    pub fn os(mut self, os: Os) -> DomainDef {
+        self.os = Some(os);
+        self
+    }
+
+    pub fn features(mut self, features: Features) -> DomainDef {
+        self.features = Some(features);
+        self
+    }
+
+    pub fn cpu(mut self, cpu: Cpu) -> DomainDef {
+        self.cpu = Some(cpu);
+        self
+    }
+
+    pub fn numatune(mut self, numatune: Numatune) -> DomainDef {
+        self.numatune = Some(numatune);
+        self
+    }
+
+    pub fn clock(mut self, clock: Clock) -> DomainDef {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Set the actions taken on poweroff, reboot and crash, like
+    /// `destroy`, `restart` or `preserve`.
+    pub fn lifecycle(mut self, poweroff: &str, reboot: &str, crash: &str) -> DomainDef {
+        self.on_poweroff = Some(poweroff.to_string());
+        self.on_reboot = Some(reboot.to_string());
+        self.on_crash = Some(crash.to_string());
+        self
+    }
+
+    pub fn emulator(mut self, emulator: &str) -> DomainDef {
+        self.emulator = Some(emulator.to_string());
+        self
+    }
+
+    pub fn device<D: Into<Device>>(mut self, device: D) -> DomainDef {
+        self.devices.push(device.into());
+        self
+    }
+
+    pub fn disks(&self) -> Vec<&Disk> {
+        self.devices
+            .iter()
+            .filter_map(|d| match *d {
+                Device::Disk(ref disk) => Some(disk),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn interfaces(&self) -> Vec<&Interface> {
+        self.devices
+            .iter()
+            .filter_map(|d| match *d {
+                Device::Interface(ref iface) => Some(iface),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl XmlElement for DomainDef {
+    fn from_element(mut el: Element) -> Result<DomainDef, Error> {
+        let mut devices = el.take("devices").unwrap_or_default();
+        let emulator = devices.take_text("emulator");
+        Ok(DomainDef {
+            kind: el.take_attr("type").unwrap_or_default(),
+            id: el.take_parse("id")?,
+            name: el.take_text("name").unwrap_or_default(),
+            uuid: el.take_text("uuid"),
+            title: el.take_text("title"),
+            description: el.take_text("description"),
+            metadata: el.take("metadata").map(|m| m.into_extra().children).unwrap_or_default(),
+            memory: el.take_memory("memory")?,
+            current_memory: el.take_memory("currentMemory")?,
+            vcpu: el.take_model("vcpu")?,
+            os: el.take_model("os")?,
+            features: el.take_model("features")?,
+            cpu: el.take_model("cpu")?,
+            numatune: el.take_model("numatune")?,
+            clock: el.take_model("clock")?,
+            on_poweroff: el.take_text("on_poweroff"),
+            on_reboot: el.take_text("on_reboot"),
+            on_crash: el.take_text("on_crash"),
+            emulator,
+            devices: devices.into_extra()
+                .children
+                .into_iter()
+                .map(Device::from_element)
+                .collect::<Result<_, _>>()?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let metadata = if self.metadata.is_empty() {
+            None
+        } else {
+            Some(Element::new("metadata").extra(&Extra {
+                attributes: Vec::new(),
+                children: self.metadata.clone(),
+                ..Default::default()
+            }))
+        };
+        let mut devices = Element::new("devices").text_child("emulator", self.emulator.as_ref());
+        for device in &self.devices {
+            devices = devices.child(device.to_element());
+        }
+        Element::new("domain")
+            .attr("type", &self.kind)
+            .attr_opt("id", self.id)
+            .child(Element::new("name").text(&self.name))
+            .text_child("uuid", self.uuid.as_ref())
+            .text_child("title", self.title.as_ref())
+            .text_child("description", self.description.as_ref())
+            .child_opt(metadata)
+            .memory_child("memory", self.memory)
+            .memory_child("currentMemory", self.current_memory)
+            .model(self.vcpu.as_ref())
+            .model(self.os.as_ref())
+            .model(self.features.as_ref())
+            .model(self.cpu.as_ref())
+            .model(self.numatune.as_ref())
+            .model(self.clock.as_ref())
+            .text_child("on_poweroff", self.on_poweroff.as_ref())
+            .text_child("on_reboot", self.on_reboot.as_ref())
+            .text_child("on_crash", self.on_crash.as_ref())
+            .child(devices)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(DomainDef);
+
+/// Virtual CPUs allocated to the domain.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Vcpu {
+    /// Maximum number of virtual CPUs.
+    pub count: u32,
+    /// Number of virtual CPUs enabled at boot.
+    pub current: Option<u32>,
+    /// Either `static` or `auto`.
+    pub placement: Option<String>,
+    pub cpuset: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Vcpu {
+    fn from_element(mut el: Element) -> Result<Vcpu, Error> {
+        let count = parse_value("vcpu", "count", &el.get_text())?;
+        el.children.clear();
+        Ok(Vcpu {
+            count,
+            current: el.take_parse("current")?,
+            placement: el.take_attr("placement"),
+            cpuset: el.take_attr("cpuset"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("vcpu")
+            .attr_opt("placement", self.placement.as_ref())
+            .attr_opt("cpuset", self.cpuset.as_ref())
+            .attr_opt("current", self.current)
+            .text(self.count)
+            .extra(&self.extra)
+    }
+}
+
+/// Operating system booting configuration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Os {
+    /// Type of guest like `hvm`, `linux` or `exe`.
+    pub kind: String,
+    pub arch: Option<String>,
+    pub machine: Option<String>,
+    /// Firmware automatically selected, `bios` or `efi`.
+    pub firmware: Option<String>,
+    pub loader: Option<Loader>,
+    pub kernel: Option<String>,
+    pub initrd: Option<String>,
+    pub cmdline: Option<String>,
+    /// Boot devices like `hd`, `cdrom` or `network`, in order.
+    pub boot: Vec<String>,
+    pub extra: Extra,
+}
+
+impl Os {
+    pub fn hvm() -> Os {
+        Os { kind: String::from("hvm"), ..Default::default() }
+    }
+
+    pub fn arch(mut self, arch: &str) -> Os {
+        self.arch = Some(arch.to_string());
+        self
+    }
+
+    pub fn machine(mut self, machine: &str) -> Os {
+        self.machine = Some(machine.to_string());
+        self
+    }
+
+    pub fn firmware(mut self, firmware: &str) -> Os {
+        self.firmware = Some(firmware.to_string());
+        self
+    }
+
+    pub fn loader(mut self, loader: Loader) -> Os {
+        self.loader = Some(loader);
+        self
+    }
+
+    /// Boot directly `kernel` with `initrd` and `cmdline`.
+    pub fn direct(mut self, kernel: &str, initrd: Option<&str>, cmdline: Option<&str>) -> Os {
+        self.kernel = Some(kernel.to_string());
+        self.initrd = initrd.map(|s| s.to_string());
+        self.cmdline = cmdline.map(|s| s.to_string());
+        self
+    }
+
+    /// Append `dev` to the boot order.
+    pub fn boot(mut self, dev: &str) -> Os {
+        self.boot.push(dev.to_string());
+        self
+    }
+}
+
+impl XmlElement for Os {
+    fn from_element(mut el: Element) -> Result<Os, Error> {
+        let mut typ = el.take("type").unwrap_or_default();
+        let mut boot = Vec::new();
+        for mut b in el.take_all("boot") {
+            match b.take_attr("dev") {
+                Some(dev) => {
+                    el.keep_rest(boot.len(), b);
+                    boot.push(dev);
+                }
+                None => el.children.push(Node::Element(b)),
+            }
+        }
+        Ok(Os {
+            kind: typ.get_text(),
+            arch: typ.take_attr("arch"),
+            machine: typ.take_attr("machine"),
+            firmware: el.take_attr("firmware"),
+            loader: el.take_model("loader")?,
+            kernel: el.take_text("kernel"),
+            initrd: el.take_text("initrd"),
+            cmdline: el.take_text("cmdline"),
+            boot,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let typ = Element::new("type")
+            .attr_opt("arch", self.arch.as_ref())
+            .attr_opt("machine", self.machine.as_ref())
+            .text(&self.kind);
+        let mut el = Element::new("os")
+            .attr_opt("firmware", self.firmware.as_ref())
+            .child(typ)
+            .model(self.loader.as_ref())
+            .text_child("kernel", self.kernel.as_ref())
+            .text_child("initrd", self.initrd.as_ref())
+            .text_child("cmdline", self.cmdline.as_ref());
+        for dev in &self.boot {
+            el = el.child(Element::new("boot").attr("dev", dev));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+/// Firmware loader.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Loader {
+    pub path: String,
+    pub readonly: Option<bool>,
+    pub secure: Option<bool>,
+    /// Either `rom` or `pflash`.
+    pub kind: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Loader {
+    fn from_element(mut el: Element) -> Result<Loader, Error> {
+        let path = el.get_text();
+        el.children.clear();
+        Ok(Loader {
+            path,
+            readonly: el.take_bool("readonly")?,
+            secure: el.take_bool("secure")?,
+            kind: el.take_attr("type"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("loader")
+            .attr_opt("readonly", yes_no(self.readonly))
+            .attr_opt("secure", yes_no(self.secure))
+            .attr_opt("type", self.kind.as_ref())
+            .text(&self.path)
+            .extra(&self.extra)
+    }
+}
+
+/// Hypervisor features to turn on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Features {
+    pub acpi: bool,
+    pub apic: bool,
+    pub pae: bool,
+    pub hap: bool,
+    pub viridian: bool,
+    pub privnet: bool,
+    pub vmport: Option<bool>,
+    pub smm: Option<bool>,
+    /// Other features, like `<hyperv>` or `<kvm>`.
+    pub extra: Extra,
+}
+
+impl XmlElement for Features {
+    fn from_element(mut el: Element) -> Result<Features, Error> {
+        Ok(Features {
+            acpi: el.take_flag("acpi"),
+            apic: el.take_flag("apic"),
+            pae: el.take_flag("pae"),
+            hap: el.take_flag("hap"),
+            viridian: el.take_flag("viridian"),
+            privnet: el.take_flag("privnet"),
+            vmport: el.take_child_bool("vmport", "state")?,
+            smm: el.take_child_bool("smm", "state")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("features")
+            .flag_child("acpi", self.acpi)
+            .flag_child("apic", self.apic)
+            .flag_child("pae", self.pae)
+            .flag_child("hap", self.hap)
+            .flag_child("viridian", self.viridian)
+            .flag_child("privnet", self.privnet)
+            .attr_child("vmport", "state", on_off(self.vmport))
+            .attr_child("smm", "state", on_off(self.smm))
+            .extra(&self.extra)
+    }
+}
+
+/// CPU model and topology exposed to the guest.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cpu {
+    /// Either `custom`, `host-model` or `host-passthrough`.
+    pub mode: Option<String>,
+    /// Either `minimum`, `exact` or `strict`.
+    pub match_mode: Option<String>,
+    pub check: Option<String>,
+    pub model: Option<String>,
+    /// Either `allow` or `forbid`.
+    pub model_fallback: Option<String>,
+    pub vendor: Option<String>,
+    pub topology: Option<CpuTopology>,
+    pub features: Vec<CpuFeature>,
+    /// Guest NUMA topology.
+    pub numa: Vec<NumaCell>,
+    pub extra: Extra,
+}
+
+impl Cpu {
+    pub fn host_passthrough() -> Cpu {
+        Cpu { mode: Some(String::from("host-passthrough")), ..Default::default() }
+    }
+
+    pub fn host_model() -> Cpu {
+        Cpu { mode: Some(String::from("host-model")), ..Default::default() }
+    }
+
+    pub fn custom(model: &str) -> Cpu {
+        Cpu {
+            mode: Some(String::from("custom")),
+            match_mode: Some(String::from("exact")),
+            model: Some(model.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn topology(mut self, sockets: u32, cores: u32, threads: u32) -> Cpu {
+        self.topology = Some(CpuTopology { sockets, dies: None, cores, threads });
+        self
+    }
+
+    /// Add the feature `name` with `policy` like `require` or
+    /// `disable`.
+    pub fn feature(mut self, policy: &str, name: &str) -> Cpu {
+        self.features.push(CpuFeature { policy: Some(policy.to_string()), name: name.to_string() });
+        self
+    }
+
+    /// Add a guest NUMA cell of `memory` KiB holding `cpus`.
+    pub fn numa_cell(mut self, id: u32, cpus: &str, memory: u64) -> Cpu {
+        self.numa.push(NumaCell {
+            id: Some(id),
+            cpus: Some(cpus.to_string()),
+            memory: Some(memory),
+            ..Default::default()
+        });
+        self
+    }
+}
+
+impl XmlElement for Cpu {
+    fn from_element(mut el: Element) -> Result<Cpu, Error> {
+        let mut model = el.take("model");
+        let numa = match el.take("numa") {
+            Some(mut numa) => numa.take_models("cell")?,
+            None => Vec::new(),
+        };
+        Ok(Cpu {
+            mode: el.take_attr("mode"),
+            match_mode: el.take_attr("match"),
+            check: el.take_attr("check"),
+            model: model.as_ref().map(|m| m.get_text()),
+            model_fallback: model.as_mut().and_then(|m| m.take_attr("fallback")),
+            vendor: el.take_text("vendor"),
+            topology: el.take_model("topology")?,
+            features: el.take_models("feature")?,
+            numa,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let model = self.model.as_ref().map(|m| {
+            Element::new("model").attr_opt("fallback", self.model_fallback.as_ref()).text(m)
+        });
+        let numa = if self.numa.is_empty() {
+            None
+        } else {
+            Some(Element::new("numa").models(&self.numa))
+        };
+        Element::new("cpu")
+            .attr_opt("mode", self.mode.as_ref())
+            .attr_opt("match", self.match_mode.as_ref())
+            .attr_opt("check", self.check.as_ref())
+            .child_opt(model)
+            .text_child("vendor", self.vendor.as_ref())
+            .model(self.topology.as_ref())
+            .models(&self.features)
+            .child_opt(numa)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CpuTopology {
+    pub sockets: u32,
+    pub dies: Option<u32>,
+    pub cores: u32,
+    pub threads: u32,
+}
+
+impl XmlElement for CpuTopology {
+    fn from_element(mut el: Element) -> Result<CpuTopology, Error> {
+        Ok(CpuTopology {
+            sockets: el.take_parse("sockets")?.unwrap_or(1),
+            dies: el.take_parse("dies")?,
+            cores: el.take_parse("cores")?.unwrap_or(1),
+            threads: el.take_parse("threads")?.unwrap_or(1),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("topology")
+            .attr("sockets", self.sockets)
+            .attr_opt("dies", self.dies)
+            .attr("cores", self.cores)
+            .attr("threads", self.threads)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CpuFeature {
+    /// Like `require`, `disable` or `force`.
+    pub policy: Option<String>,
+    pub name: String,
+}
+
+impl XmlElement for CpuFeature {
+    fn from_element(mut el: Element) -> Result<CpuFeature, Error> {
+        Ok(CpuFeature {
+            policy: el.take_attr("policy"),
+            name: el.take_attr("name").unwrap_or_default(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("feature").attr_opt("policy", self.policy.as_ref()).attr("name", &self.name)
+    }
+}
+
+/// Guest NUMA cell.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumaCell {
+    pub id: Option<u32>,
+    pub cpus: Option<String>,
+    /// Memory of the cell, in KiB.
+    pub memory: Option<u64>,
+    /// Either `shared` or `private`.
+    pub mem_access: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for NumaCell {
+    fn from_element(mut el: Element) -> Result<NumaCell, Error> {
+        let memory = el.take_parse("memory")?;
+        let unit = el.take_attr("unit");
+        Ok(NumaCell {
+            id: el.take_parse("id")?,
+            cpus: el.take_attr("cpus"),
+            memory: match memory {
+                Some(m) => Some(to_kib(m, unit.as_deref())?),
+                None => None,
+            },
+            mem_access: el.take_attr("memAccess"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("cell")
+            .attr_opt("id", self.id)
+            .attr_opt("cpus", self.cpus.as_ref())
+            .attr_opt("memory", self.memory)
+            .attr_opt("unit", self.memory.map(|_| "KiB"))
+            .attr_opt("memAccess", self.mem_access.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Placement of the domain memory on the host NUMA nodes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Numatune {
+    /// Either `strict`, `preferred` or `interleave`.
+    pub mode: Option<String>,
+    pub nodeset: Option<String>,
+    /// Either `static` or `auto`.
+    pub placement: Option<String>,
+    /// Per guest cell placement, `<memnode>` elements.
+    pub extra: Extra,
+}
+
+impl Numatune {
+    pub fn strict(nodeset: &str) -> Numatune {
+        Numatune {
+            mode: Some(String::from("strict")),
+            nodeset: Some(nodeset.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Numatune {
+    fn from_element(mut el: Element) -> Result<Numatune, Error> {
+        let mut memory = el.take("memory").unwrap_or_default();
+        Ok(Numatune {
+            mode: memory.take_attr("mode"),
+            nodeset: memory.take_attr("nodeset"),
+            placement: memory.take_attr("placement"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let memory = Element::new("memory")
+            .attr_opt("mode", self.mode.as_ref())
+            .attr_opt("nodeset", self.nodeset.as_ref())
+            .attr_opt("placement", self.placement.as_ref());
+        Element::new("numatune").child(memory).extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Clock {
+    /// Like `utc`, `localtime`, `timezone` or `variable`.
+    pub offset: Option<String>,
+    pub timezone: Option<String>,
+    pub adjustment: Option<String>,
+    pub timers: Vec<Timer>,
+    pub extra: Extra,
+}
+
+impl Clock {
+    pub fn utc() -> Clock {
+        Clock { offset: Some(String::from("utc")), ..Default::default() }
+    }
+
+    pub fn localtime() -> Clock {
+        Clock { offset: Some(String::from("localtime")), ..Default::default() }
+    }
+
+    pub fn timer(mut self, timer: Timer) -> Clock {
+        self.timers.push(timer);
+        self
+    }
+}
+
+impl XmlElement for Clock {
+    fn from_element(mut el: Element) -> Result<Clock, Error> {
+        Ok(Clock {
+            offset: el.take_attr("offset"),
+            timezone: el.take_attr("timezone"),
+            adjustment: el.take_attr("adjustment"),
+            timers: el.take_models("timer")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("clock")
+            .attr_opt("offset", self.offset.as_ref())
+            .attr_opt("timezone", self.timezone.as_ref())
+            .attr_opt("adjustment", self.adjustment.as_ref())
+            .models(&self.timers)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timer {
+    /// Like `rtc`, `pit`, `hpet` or `kvmclock`.
+    pub name: String,
+    pub present: Option<bool>,
+    /// Like `delay`, `catchup`, `merge` or `discard`.
+    pub tickpolicy: Option<String>,
+    pub track: Option<String>,
+    pub extra: Extra,
+}
+
+impl Timer {
+    pub fn new(name: &str) -> Timer {
+        Timer { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn present(mut self, present: bool) -> Timer {
+        self.present = Some(present);
+        self
+    }
+
+    pub fn tickpolicy(mut self, tickpolicy: &str) -> Timer {
+        self.tickpolicy = Some(tickpolicy.to_string());
+        self
+    }
+}
+
+impl XmlElement for Timer {
+    fn from_element(mut el: Element) -> Result<Timer, Error> {
+        Ok(Timer {
+            name: el.take_attr("name").unwrap_or_default(),
+            present: el.take_bool("present")?,
+            tickpolicy: el.take_attr("tickpolicy"),
+            track: el.take_attr("track"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("timer")
+            .attr("name", &self.name)
+            .attr_opt("present", yes_no(self.present))
+            .attr_opt("tickpolicy", self.tickpolicy.as_ref())
+            .attr_opt("track", self.track.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// A device of the domain, devices not known by the binding are kept
+/// as raw elements.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Device {
+    Disk(Disk),
+    Interface(Interface),
+    Controller(Controller),
+    Graphics(Graphics),
+    Video(Video),
+    Serial(Chardev),
+    Console(Chardev),
+    Channel(Chardev),
+    Hostdev(Hostdev),
+    Rng(Rng),
+    Tpm(Tpm),
+    Watchdog(Watchdog),
+    Filesystem(Filesystem),
+    Other(Element),
+}
+
+impl XmlElement for Device {
+    fn from_element(el: Element) -> Result<Device, Error> {
+        Ok(match el.name.as_str() {
+            "disk" => Device::Disk(Disk::from_element(el)?),
+            "interface" => Device::Interface(Interface::from_element(el)?),
+            "controller" => Device::Controller(Controller::from_element(el)?),
+            "graphics" => Device::Graphics(Graphics::from_element(el)?),
+            "video" => Device::Video(Video::from_element(el)?),
+            "serial" => Device::Serial(Chardev::from_element(el)?),
+            "console" => Device::Console(Chardev::from_element(el)?),
+            "channel" => Device::Channel(Chardev::from_element(el)?),
+            "hostdev" => Device::Hostdev(Hostdev::from_element(el)?),
+            "rng" => Device::Rng(Rng::from_element(el)?),
+            "tpm" => Device::Tpm(Tpm::from_element(el)?),
+            "watchdog" => Device::Watchdog(Watchdog::from_element(el)?),
+            "filesystem" => Device::Filesystem(Filesystem::from_element(el)?),
+            _ => Device::Other(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        match *self {
+            Device::Disk(ref d) => d.to_element(),
+            Device::Interface(ref d) => d.to_element(),
+            Device::Controller(ref d) => d.to_element(),
+            Device::Graphics(ref d) => d.to_element(),
+            Device::Video(ref d) => d.to_element(),
+            Device::Serial(ref d) => d.to_named("serial"),
+            Device::Console(ref d) => d.to_named("console"),
+            Device::Channel(ref d) => d.to_named("channel"),
+            Device::Hostdev(ref d) => d.to_element(),
+            Device::Rng(ref d) => d.to_element(),
+            Device::Tpm(ref d) => d.to_element(),
+            Device::Watchdog(ref d) => d.to_element(),
+            Device::Filesystem(ref d) => d.to_element(),
+            Device::Other(ref e) => e.clone(),
+        }
+    }
+}
+
+macro_rules! impl_into_device {
+    ($($t:ident),*) => {
+        $(impl From<$t> for Device {
+            fn from(device: $t) -> Device {
+                Device::$t(device)
+            }
+        })*
+    }
+}
+
+impl_into_device!(Disk, Interface, Controller, Graphics, Video, Hostdev, Rng, Tpm, Watchdog,
+                  Filesystem);
+
+/// Address of a device on its bus.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Address {
+    /// Like `pci`, `drive`, `usb` or `virtio-serial`.
+    pub kind: String,
+    pub domain: Option<String>,
+    pub bus: Option<String>,
+    pub slot: Option<String>,
+    pub function: Option<String>,
+    pub controller: Option<String>,
+    pub target: Option<String>,
+    pub unit: Option<String>,
+    pub port: Option<String>,
+    pub extra: Extra,
+}
+
+impl Address {
+    pub fn pci(domain: u32, bus: u32, slot: u32, function: u32) -> Address {
+        Address {
+            kind: String::from("pci"),
+            domain: Some(format!("{:#06x}", domain)),
+            bus: Some(format!("{:#04x}", bus)),
+            slot: Some(format!("{:#04x}", slot)),
+            function: Some(format!("{:#x}", function)),
+            ..Default::default()
+        }
+    }
+
+    pub fn drive(controller: u32, bus: u32, target: u32, unit: u32) -> Address {
+        Address {
+            kind: String::from("drive"),
+            controller: Some(controller.to_string()),
+            bus: Some(bus.to_string()),
+            target: Some(target.to_string()),
+            unit: Some(unit.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Address {
+    fn from_element(mut el: Element) -> Result<Address, Error> {
+        Ok(Address {
+            kind: el.take_attr("type").unwrap_or_default(),
+            domain: el.take_attr("domain"),
+            bus: el.take_attr("bus"),
+            slot: el.take_attr("slot"),
+            function: el.take_attr("function"),
+            controller: el.take_attr("controller"),
+            target: el.take_attr("target"),
+            unit: el.take_attr("unit"),
+            port: el.take_attr("port"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("address")
+            .attr_opt("type", if self.kind.is_empty() { None } else { Some(&self.kind) })
+            .attr_opt("domain", self.domain.as_ref())
+            .attr_opt("controller", self.controller.as_ref())
+            .attr_opt("bus", self.bus.as_ref())
+            .attr_opt("slot", self.slot.as_ref())
+            .attr_opt("target", self.target.as_ref())
+            .attr_opt("unit", self.unit.as_ref())
+            .attr_opt("port", self.port.as_ref())
+            .attr_opt("function", self.function.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Disk {
+    /// Like `file`, `block`, `network` or `volume`.
+    pub kind: String,
+    /// Like `disk`, `cdrom`, `floppy` or `lun`.
+    pub device: Option<String>,
+    pub driver: Option<DiskDriver>,
+    pub source: Option<DiskSource>,
+    pub target: Option<DiskTarget>,
+    pub readonly: bool,
+    pub shareable: bool,
+    pub serial: Option<String>,
+    pub boot_order: Option<u32>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Disk {
+    fn new(kind: &str, device: &str, target: &str) -> Disk {
+        Disk {
+            kind: kind.to_string(),
+            device: Some(device.to_string()),
+            target: Some(DiskTarget { dev: target.to_string(), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    /// A disk backed by the image `path` and seen as `target` like
+    /// `vda` by the guest.
+    pub fn file(path: &str, target: &str) -> Disk {
+        let mut disk = Disk::new("file", "disk", target);
+        disk.source = Some(DiskSource { file: Some(path.to_string()), ..Default::default() });
+        disk
+    }
+
+    /// A disk backed by the host block device `dev`.
+    pub fn block(dev: &str, target: &str) -> Disk {
+        let mut disk = Disk::new("block", "disk", target);
+        disk.source = Some(DiskSource { dev: Some(dev.to_string()), ..Default::default() });
+        disk
+    }
+
+    /// A disk backed by the volume `volume` of the storage pool
+    /// `pool`.
+    pub fn volume(pool: &str, volume: &str, target: &str) -> Disk {
+        let mut disk = Disk::new("volume", "disk", target);
+        disk.source = Some(DiskSource {
+            pool: Some(pool.to_string()),
+            volume: Some(volume.to_string()),
+            ..Default::default()
+        });
+        disk
+    }
+
+    /// A read only CD-ROM drive holding the image `path`.
+    pub fn cdrom(path: &str, target: &str) -> Disk {
+        let mut disk = Disk::new("file", "cdrom", target);
+        disk.source = Some(DiskSource { file: Some(path.to_string()), ..Default::default() });
+        disk.readonly = true;
+        disk
+    }
+
+    /// Set the driver `name` like `qemu` and the image format like
+    /// `raw` or `qcow2`.
+    pub fn driver(mut self, name: &str, format: &str) -> Disk {
+        self.driver = Some(DiskDriver {
+            name: Some(name.to_string()),
+            format: Some(format.to_string()),
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn bus(mut self, bus: &str) -> Disk {
+        if let Some(ref mut target) = self.target {
+            target.bus = Some(bus.to_string());
+        }
+        self
+    }
+
+    pub fn serial(mut self, serial: &str) -> Disk {
+        self.serial = Some(serial.to_string());
+        self
+    }
+
+    pub fn boot_order(mut self, order: u32) -> Disk {
+        self.boot_order = Some(order);
+        self
+    }
+
+    pub fn readonly(mut self) -> Disk {
+        self.readonly = true;
+        self
+    }
+
+    pub fn shareable(mut self) -> Disk {
+        self.shareable = true;
+        self
+    }
+}
+
+impl XmlElement for Disk {
+    fn from_element(mut el: Element) -> Result<Disk, Error> {
+        Ok(Disk {
+            kind: el.take_attr("type").unwrap_or_default(),
+            device: el.take_attr("device"),
+            driver: el.take_model("driver")?,
+            source: el.take_model("source")?,
+            target: el.take_model("target")?,
+            readonly: el.take_flag("readonly"),
+            shareable: el.take_flag("shareable"),
+            serial: el.take_text("serial"),
+            boot_order: el.take_child_parse("boot", "order")?,
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("disk")
+            .attr("type", &self.kind)
+            .attr_opt("device", self.device.as_ref())
+            .model(self.driver.as_ref())
+            .model(self.source.as_ref())
+            .model(self.target.as_ref())
+            .flag_child("readonly", self.readonly)
+            .flag_child("shareable", self.shareable)
+            .text_child("serial", self.serial.as_ref())
+            .attr_child("boot", "order", self.boot_order)
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskDriver {
+    pub name: Option<String>,
+    /// Format of the image like `raw` or `qcow2`.
+    pub format: Option<String>,
+    pub cache: Option<String>,
+    pub io: Option<String>,
+    pub discard: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for DiskDriver {
+    fn from_element(mut el: Element) -> Result<DiskDriver, Error> {
+        Ok(DiskDriver {
+            name: el.take_attr("name"),
+            format: el.take_attr("type"),
+            cache: el.take_attr("cache"),
+            io: el.take_attr("io"),
+            discard: el.take_attr("discard"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("driver")
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("type", self.format.as_ref())
+            .attr_opt("cache", self.cache.as_ref())
+            .attr_opt("io", self.io.as_ref())
+            .attr_opt("discard", self.discard.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Source of a disk, which attributes are set depends on the type of
+/// the disk.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskSource {
+    pub file: Option<String>,
+    pub dev: Option<String>,
+    pub dir: Option<String>,
+    pub pool: Option<String>,
+    pub volume: Option<String>,
+    /// Network protocol like `rbd`, `iscsi` or `nbd`.
+    pub protocol: Option<String>,
+    pub name: Option<String>,
+    /// Hosts serving a network disk.
+    pub hosts: Vec<DiskHost>,
+    pub extra: Extra,
+}
+
+impl XmlElement for DiskSource {
+    fn from_element(mut el: Element) -> Result<DiskSource, Error> {
+        Ok(DiskSource {
+            file: el.take_attr("file"),
+            dev: el.take_attr("dev"),
+            dir: el.take_attr("dir"),
+            pool: el.take_attr("pool"),
+            volume: el.take_attr("volume"),
+            protocol: el.take_attr("protocol"),
+            name: el.take_attr("name"),
+            hosts: el.take_models("host")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("source")
+            .attr_opt("file", self.file.as_ref())
+            .attr_opt("dev", self.dev.as_ref())
+            .attr_opt("dir", self.dir.as_ref())
+            .attr_opt("pool", self.pool.as_ref())
+            .attr_opt("volume", self.volume.as_ref())
+            .attr_opt("protocol", self.protocol.as_ref())
+            .attr_opt("name", self.name.as_ref())
+            .models(&self.hosts)
+            .extra(&self.extra)
+    }
+}
+
+/// Host serving a network disk.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskHost {
+    pub name: Option<String>,
+    pub port: Option<u16>,
+    /// Like `tcp`, `unix` or `rdma`.
+    pub transport: Option<String>,
+    /// Path of the socket of a `unix` transport.
+    pub socket: Option<String>,
+    pub extra: Extra,
+}
+
+impl DiskHost {
+    /// Host reached over TCP.
+    pub fn new(name: &str, port: Option<u16>) -> DiskHost {
+        DiskHost { name: Some(name.to_string()), port, ..Default::default() }
+    }
+
+    /// Host reached through the UNIX socket `socket`.
+    pub fn unix(socket: &str) -> DiskHost {
+        DiskHost {
+            transport: Some(String::from("unix")),
+            socket: Some(socket.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for DiskHost {
+    fn from_element(mut el: Element) -> Result<DiskHost, Error> {
+        Ok(DiskHost {
+            name: el.take_attr("name"),
+            port: el.take_parse("port")?,
+            transport: el.take_attr("transport"),
+            socket: el.take_attr("socket"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("host")
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("port", self.port)
+            .attr_opt("transport", self.transport.as_ref())
+            .attr_opt("socket", self.socket.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiskTarget {
+    /// Device name hinted to the guest like `vda` or `sdb`.
+    pub dev: String,
+    /// Like `virtio`, `scsi`, `sata` or `ide`.
+    pub bus: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for DiskTarget {
+    fn from_element(mut el: Element) -> Result<DiskTarget, Error> {
+        Ok(DiskTarget {
+            dev: el.take_attr("dev").unwrap_or_default(),
+            bus: el.take_attr("bus"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("target")
+            .attr("dev", &self.dev)
+            .attr_opt("bus", self.bus.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Network interface.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Interface {
+    /// Like `network`, `bridge`, `direct`, `user` or `ethernet`.
+    pub kind: String,
+    pub mac: Option<String>,
+    pub source: Option<InterfaceSource>,
+    /// Model of the virtual NIC like `virtio` or `e1000`.
+    pub model: Option<String>,
+    /// Name of the host side device, like `vnet0`.
+    pub target: Option<String>,
+    pub boot_order: Option<u32>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Interface {
+    /// An interface connected to the virtual network `network`.
+    pub fn network(network: &str) -> Interface {
+        Interface {
+            kind: String::from("network"),
+            source: Some(InterfaceSource {
+                network: Some(network.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// An interface connected to the host bridge `bridge`.
+    pub fn bridge(bridge: &str) -> Interface {
+        Interface {
+            kind: String::from("bridge"),
+            source: Some(InterfaceSource {
+                bridge: Some(bridge.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    pub fn mac(mut self, mac: &str) -> Interface {
+        self.mac = Some(mac.to_string());
+        self
+    }
+
+    pub fn model(mut self, model: &str) -> Interface {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    pub fn boot_order(mut self, order: u32) -> Interface {
+        self.boot_order = Some(order);
+        self
+    }
+}
+
+impl XmlElement for Interface {
+    fn from_element(mut el: Element) -> Result<Interface, Error> {
+        Ok(Interface {
+            kind: el.take_attr("type").unwrap_or_default(),
+            mac: el.take_child_attr("mac", "address"),
+            source: el.take_model("source")?,
+            model: el.take_child_attr("model", "type"),
+            target: el.take_child_attr("target", "dev"),
+            boot_order: el.take_child_parse("boot", "order")?,
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("interface")
+            .attr("type", &self.kind)
+            .attr_child("mac", "address", self.mac.as_ref())
+            .model(self.source.as_ref())
+            .attr_child("model", "type", self.model.as_ref())
+            .attr_child("target", "dev", self.target.as_ref())
+            .attr_child("boot", "order", self.boot_order)
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceSource {
+    pub network: Option<String>,
+    pub bridge: Option<String>,
+    pub dev: Option<String>,
+    /// Mode of a `direct` interface like `bridge`, `vepa` or
+    /// `passthrough`.
+    pub mode: Option<String>,
+    pub portgroup: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for InterfaceSource {
+    fn from_element(mut el: Element) -> Result<InterfaceSource, Error> {
+        Ok(InterfaceSource {
+            network: el.take_attr("network"),
+            bridge: el.take_attr("bridge"),
+            dev: el.take_attr("dev"),
+            mode: el.take_attr("mode"),
+            portgroup: el.take_attr("portgroup"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("source")
+            .attr_opt("network", self.network.as_ref())
+            .attr_opt("portgroup", self.portgroup.as_ref())
+            .attr_opt("bridge", self.bridge.as_ref())
+            .attr_opt("dev", self.dev.as_ref())
+            .attr_opt("mode", self.mode.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Controller {
+    /// Like `pci`, `usb`, `scsi`, `sata` or `virtio-serial`.
+    pub kind: String,
+    pub index: Option<u32>,
+    pub model: Option<String>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Controller {
+    pub fn new(kind: &str, index: u32) -> Controller {
+        Controller { kind: kind.to_string(), index: Some(index), ..Default::default() }
+    }
+
+    pub fn model(mut self, model: &str) -> Controller {
+        self.model = Some(model.to_string());
+        self
+    }
+}
+
+impl XmlElement for Controller {
+    fn from_element(mut el: Element) -> Result<Controller, Error> {
+        Ok(Controller {
+            kind: el.take_attr("type").unwrap_or_default(),
+            index: el.take_parse("index")?,
+            model: el.take_attr("model"),
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("controller")
+            .attr("type", &self.kind)
+            .attr_opt("index", self.index)
+            .attr_opt("model", self.model.as_ref())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Graphical framebuffer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Graphics {
+    /// Like `vnc`, `spice`, `sdl` or `egl-headless`.
+    pub kind: String,
+    /// Port, `-1` for historical autoport.
+    pub port: Option<i32>,
+    pub autoport: Option<bool>,
+    pub listen: Option<String>,
+    pub passwd: Option<String>,
+    pub keymap: Option<String>,
+    pub extra: Extra,
+}
+
+impl Graphics {
+    pub fn vnc() -> Graphics {
+        Graphics { kind: String::from("vnc"), autoport: Some(true), ..Default::default() }
+    }
+
+    pub fn spice() -> Graphics {
+        Graphics { kind: String::from("spice"), autoport: Some(true), ..Default::default() }
+    }
+
+    pub fn listen(mut self, address: &str) -> Graphics {
+        self.listen = Some(address.to_string());
+        self
+    }
+
+    pub fn port(mut self, port: i32) -> Graphics {
+        self.port = Some(port);
+        self.autoport = Some(false);
+        self
+    }
+}
+
+impl XmlElement for Graphics {
+    fn from_element(mut el: Element) -> Result<Graphics, Error> {
+        Ok(Graphics {
+            kind: el.take_attr("type").unwrap_or_default(),
+            port: el.take_parse("port")?,
+            autoport: el.take_bool("autoport")?,
+            listen: el.take_attr("listen"),
+            passwd: el.take_attr("passwd"),
+            keymap: el.take_attr("keymap"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("graphics")
+            .attr("type", &self.kind)
+            .attr_opt("port", self.port)
+            .attr_opt("autoport", yes_no(self.autoport))
+            .attr_opt("listen", self.listen.as_ref())
+            .attr_opt("passwd", self.passwd.as_ref())
+            .attr_opt("keymap", self.keymap.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Video {
+    pub model: VideoModel,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Video {
+    /// A primary video device of model `kind`.
+    pub fn new(kind: &str) -> Video {
+        Video {
+            model: VideoModel {
+                kind: kind.to_string(),
+                heads: Some(1),
+                primary: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Video {
+    fn from_element(mut el: Element) -> Result<Video, Error> {
+        Ok(Video {
+            model: el.take_model("model")?.unwrap_or_default(),
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("video")
+            .child(self.model.to_element())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VideoModel {
+    /// Like `virtio`, `qxl`, `vga` or `cirrus`.
+    pub kind: String,
+    /// Video memory, in KiB.
+    pub vram: Option<u64>,
+    pub heads: Option<u32>,
+    pub primary: Option<bool>,
+    pub extra: Extra,
+}
+
+impl XmlElement for VideoModel {
+    fn from_element(mut el: Element) -> Result<VideoModel, Error> {
+        Ok(VideoModel {
+            kind: el.take_attr("type").unwrap_or_default(),
+            vram: el.take_parse("vram")?,
+            heads: el.take_parse("heads")?,
+            primary: el.take_bool("primary")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("model")
+            .attr("type", &self.kind)
+            .attr_opt("vram", self.vram)
+            .attr_opt("heads", self.heads)
+            .attr_opt("primary", yes_no(self.primary))
+            .extra(&self.extra)
+    }
+}
+
+/// Character device used for `<serial>`, `<console>` and
+/// `<channel>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chardev {
+    /// Host side type like `pty`, `file`, `unix`, `tcp` or
+    /// `spicevmc`.
+    pub kind: String,
+    pub source: Option<ChardevSource>,
+    pub target: Option<ChardevTarget>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Chardev {
+    pub fn pty() -> Chardev {
+        Chardev { kind: String::from("pty"), ..Default::default() }
+    }
+
+    /// A device connected to the UNIX socket `path`, created by
+    /// libvirt when `mode` is `bind`.
+    pub fn unix(path: &str, mode: &str) -> Chardev {
+        Chardev {
+            kind: String::from("unix"),
+            source: Some(ChardevSource {
+                path: Some(path.to_string()),
+                mode: Some(mode.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Set the guest side `kind` like `isa-serial`, `serial` or
+    /// `virtio` and `port` for serial and console devices.
+    pub fn target(mut self, kind: &str, port: Option<u32>) -> Chardev {
+        self.target = Some(ChardevTarget {
+            kind: Some(kind.to_string()),
+            port,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Set a `virtio` channel target named `name`, like
+    /// `org.qemu.guest_agent.0`.
+    pub fn channel(mut self, name: &str) -> Chardev {
+        self.target = Some(ChardevTarget {
+            kind: Some(String::from("virtio")),
+            name: Some(name.to_string()),
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn to_named(&self, name: &str) -> Element {
+        Element::new(name)
+            .attr("type", &self.kind)
+            .model(self.source.as_ref())
+            .model(self.target.as_ref())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl XmlElement for Chardev {
+    fn from_element(mut el: Element) -> Result<Chardev, Error> {
+        Ok(Chardev {
+            kind: el.take_attr("type").unwrap_or_default(),
+            source: el.take_model("source")?,
+            target: el.take_model("target")?,
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    /// Writes a `<serial>` element, see `to_named`.
+    fn to_element(&self) -> Element {
+        self.to_named("serial")
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChardevSource {
+    pub path: Option<String>,
+    /// Either `bind` or `connect`.
+    pub mode: Option<String>,
+    pub host: Option<String>,
+    pub service: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for ChardevSource {
+    fn from_element(mut el: Element) -> Result<ChardevSource, Error> {
+        Ok(ChardevSource {
+            path: el.take_attr("path"),
+            mode: el.take_attr("mode"),
+            host: el.take_attr("host"),
+            service: el.take_attr("service"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("source")
+            .attr_opt("mode", self.mode.as_ref())
+            .attr_opt("path", self.path.as_ref())
+            .attr_opt("host", self.host.as_ref())
+            .attr_opt("service", self.service.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChardevTarget {
+    pub kind: Option<String>,
+    pub port: Option<u32>,
+    /// Name of a channel.
+    pub name: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for ChardevTarget {
+    fn from_element(mut el: Element) -> Result<ChardevTarget, Error> {
+        Ok(ChardevTarget {
+            kind: el.take_attr("type"),
+            port: el.take_parse("port")?,
+            name: el.take_attr("name"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("target")
+            .attr_opt("type", self.kind.as_ref())
+            .attr_opt("port", self.port)
+            .attr_opt("name", self.name.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Host device assigned to the domain.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Hostdev {
+    /// Either `subsystem` or `capabilities`.
+    pub mode: String,
+    /// Like `pci`, `usb`, `scsi` or `mdev`.
+    pub kind: String,
+    pub managed: Option<bool>,
+    pub source: Option<HostdevSource>,
+    pub boot_order: Option<u32>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Hostdev {
+    /// The host PCI device at `address`, detached from its host
+    /// driver by libvirt.
+    pub fn pci(address: Address) -> Hostdev {
+        Hostdev {
+            mode: String::from("subsystem"),
+            kind: String::from("pci"),
+            managed: Some(true),
+            source: Some(HostdevSource { address: Some(address), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    /// The host USB device identified by `vendor` and `product`, like
+    /// `0x1234`.
+    pub fn usb(vendor: &str, product: &str) -> Hostdev {
+        Hostdev {
+            mode: String::from("subsystem"),
+            kind: String::from("usb"),
+            source: Some(HostdevSource {
+                vendor: Some(vendor.to_string()),
+                product: Some(product.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Hostdev {
+    fn from_element(mut el: Element) -> Result<Hostdev, Error> {
+        Ok(Hostdev {
+            mode: el.take_attr("mode").unwrap_or_default(),
+            kind: el.take_attr("type").unwrap_or_default(),
+            managed: el.take_bool("managed")?,
+            source: el.take_model("source")?,
+            boot_order: el.take_child_parse("boot", "order")?,
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("hostdev")
+            .attr("mode", &self.mode)
+            .attr("type", &self.kind)
+            .attr_opt("managed", yes_no(self.managed))
+            .model(self.source.as_ref())
+            .attr_child("boot", "order", self.boot_order)
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HostdevSource {
+    /// Address of the device on the host.
+    pub address: Option<Address>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for HostdevSource {
+    fn from_element(mut el: Element) -> Result<HostdevSource, Error> {
+        Ok(HostdevSource {
+            address: el.take_model("address")?,
+            vendor: el.take_child_attr("vendor", "id"),
+            product: el.take_child_attr("product", "id"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("source")
+            .attr_child("vendor", "id", self.vendor.as_ref())
+            .attr_child("product", "id", self.product.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Random number generator.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rng {
+    /// Model of the device, `virtio`.
+    pub model: String,
+    pub backend: Option<RngBackend>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Rng {
+    /// A virtio device fed from the host device `path`.
+    pub fn random(path: &str) -> Rng {
+        Rng {
+            model: String::from("virtio"),
+            backend: Some(RngBackend {
+                model: String::from("random"),
+                path: Some(path.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Rng {
+    fn from_element(mut el: Element) -> Result<Rng, Error> {
+        Ok(Rng {
+            model: el.take_attr("model").unwrap_or_default(),
+            backend: el.take_model("backend")?,
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("rng")
+            .attr("model", &self.model)
+            .model(self.backend.as_ref())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RngBackend {
+    /// Either `random`, `egd` or `builtin`.
+    pub model: String,
+    /// Host source of entropy of a `random` backend.
+    pub path: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for RngBackend {
+    fn from_element(mut el: Element) -> Result<RngBackend, Error> {
+        let path = el.get_text();
+        el.children.retain(|n| match *n {
+            Node::Element(_) => true,
+            Node::Text(_) => false,
+        });
+        Ok(RngBackend {
+            model: el.take_attr("model").unwrap_or_default(),
+            path: if path.is_empty() { None } else { Some(path) },
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let el = Element::new("backend").attr("model", &self.model);
+        match self.path {
+            Some(ref path) => el.text(path),
+            None => el,
+        }
+        .extra(&self.extra)
+    }
+}
+
+/// Trusted Platform Module.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tpm {
+    /// Like `tpm-tis` or `tpm-crb`.
+    pub model: Option<String>,
+    pub backend: TpmBackend,
+    pub alias: Option<String>,
+    pub extra: Extra,
+}
+
+impl Tpm {
+    /// A TPM emulated by swtpm.
+    pub fn emulator(version: &str) -> Tpm {
+        Tpm {
+            model: Some(String::from("tpm-tis")),
+            backend: TpmBackend {
+                kind: String::from("emulator"),
+                version: Some(version.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Tpm {
+    fn from_element(mut el: Element) -> Result<Tpm, Error> {
+        Ok(Tpm {
+            model: el.take_attr("model"),
+            backend: el.take_model("backend")?.unwrap_or_default(),
+            alias: el.take_child_attr("alias", "name"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("tpm")
+            .attr_opt("model", self.model.as_ref())
+            .child(self.backend.to_element())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TpmBackend {
+    /// Either `passthrough` or `emulator`.
+    pub kind: String,
+    /// TPM version emulated, `1.2` or `2.0`.
+    pub version: Option<String>,
+    /// Host device of a `passthrough` backend.
+    pub device_path: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for TpmBackend {
+    fn from_element(mut el: Element) -> Result<TpmBackend, Error> {
+        Ok(TpmBackend {
+            kind: el.take_attr("type").unwrap_or_default(),
+            version: el.take_attr("version"),
+            device_path: el.take_child_attr("device", "path"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("backend")
+            .attr("type", &self.kind)
+            .attr_opt("version", self.version.as_ref())
+            .attr_child("device", "path", self.device_path.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Watchdog {
+    /// Like `i6300esb`, `ib700` or `diag288`.
+    pub model: String,
+    /// Like `reset`, `shutdown`, `poweroff`, `pause` or `dump`.
+    pub action: Option<String>,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Watchdog {
+    pub fn new(model: &str, action: &str) -> Watchdog {
+        Watchdog {
+            model: model.to_string(),
+            action: Some(action.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Watchdog {
+    fn from_element(mut el: Element) -> Result<Watchdog, Error> {
+        Ok(Watchdog {
+            model: el.take_attr("model").unwrap_or_default(),
+            action: el.take_attr("action"),
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("watchdog")
+            .attr("model", &self.model)
+            .attr_opt("action", self.action.as_ref())
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Host directory shared with the guest.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filesystem {
+    /// Like `mount`, `file` or `block`.
+    pub kind: Option<String>,
+    /// Like `passthrough`, `mapped` or `squash`.
+    pub accessmode: Option<String>,
+    /// Driver like `path` or `virtiofs`.
+    pub driver: Option<String>,
+    pub source: Option<String>,
+    /// Mount tag seen by the guest.
+    pub target: Option<String>,
+    pub readonly: bool,
+    pub alias: Option<String>,
+    pub address: Option<Address>,
+    pub extra: Extra,
+}
+
+impl Filesystem {
+    /// Share the host directory `source` tagged as `target`.
+    pub fn mount(source: &str, target: &str) -> Filesystem {
+        Filesystem {
+            kind: Some(String::from("mount")),
+            accessmode: Some(String::from("passthrough")),
+            source: Some(source.to_string()),
+            target: Some(target.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Share the directory through virtiofs.
+    pub fn virtiofs(mut self) -> Filesystem {
+        self.driver = Some(String::from("virtiofs"));
+        self
+    }
+}
+
+impl XmlElement for Filesystem {
+    fn from_element(mut el: Element) -> Result<Filesystem, Error> {
+        Ok(Filesystem {
+            kind: el.take_attr("type"),
+            accessmode: el.take_attr("accessmode"),
+            driver: el.take_child_attr("driver", "type"),
+            source: el.take_child_attr("source", "dir"),
+            target: el.take_child_attr("target", "dir"),
+            readonly: el.take_flag("readonly"),
+            alias: el.take_child_attr("alias", "name"),
+            address: el.take_model("address")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("filesystem")
+            .attr_opt("type", self.kind.as_ref())
+            .attr_opt("accessmode", self.accessmode.as_ref())
+            .attr_child("driver", "type", self.driver.as_ref())
+            .attr_child("source", "dir", self.source.as_ref())
+            .attr_child("target", "dir", self.target.as_ref())
+            .flag_child("readonly", self.readonly)
+            .attr_child("alias", "name", self.alias.as_ref())
+            .model(self.address.as_ref())
+            .extra(&self.extra)
+    }
+}
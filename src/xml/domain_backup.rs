@@ -0,0 +1,298 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the domain backup XML format.
+//!
+//! See http://libvirt.org/formatbackup.html
+//!
+//! ```
+//! use virt::xml::domain_backup::{BackupDisk, DomainBackupDef};
+//!
+//! // Incremental backup of `vda` since the checkpoint `monday`,
+//! // written by libvirt to a new qcow2 file.
+//! let def = DomainBackupDef::push()
+//!     .incremental("monday")
+//!     .disk(BackupDisk::push("vda", "/backups/vm-vda.qcow2", "qcow2"))
+//!     .disk(BackupDisk::no("vdb"));
+//!
+//! let parsed: DomainBackupDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! ```
+
+use domain::Domain;
+use error::Error;
+use xml::domain::{DiskDriver, DiskSource};
+use xml::{yes_no, Element, Extra, XmlElement};
+
+/// A backup job definition as used by `Domain::backup_begin`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainBackupDef {
+    /// Either `push`, libvirt writing the backup to files, or
+    /// `pull`, libvirt exporting the disks over NBD.
+    pub mode: Option<String>,
+    /// Name of the checkpoint the backup is incremental from, the
+    /// backup being full when missing.
+    pub incremental: Option<String>,
+    /// NBD server of a pull mode backup.
+    pub server: Option<BackupServer>,
+    pub disks: Vec<BackupDisk>,
+    pub extra: Extra,
+}
+
+impl DomainBackupDef {
+    /// A backup written by libvirt to the target files of the disks.
+    pub fn push() -> DomainBackupDef {
+        DomainBackupDef { mode: Some(String::from("push")), ..Default::default() }
+    }
+
+    /// A backup exported by libvirt through the NBD `server`, read by
+    /// the client until the job is aborted.
+    pub fn pull(server: BackupServer) -> DomainBackupDef {
+        DomainBackupDef {
+            mode: Some(String::from("pull")),
+            server: Some(server),
+            ..Default::default()
+        }
+    }
+
+    /// Fetch and parse the definition of the backup job running on
+    /// `dom`.
+    pub fn from_domain(dom: &Domain, flags: u32) -> Result<DomainBackupDef, Error> {
+        dom.backup_get_xml_desc(flags)?.parse()
+    }
+
+    /// Only back up the blocks changed since the `checkpoint`.
+    pub fn incremental(mut self, checkpoint: &str) -> DomainBackupDef {
+        self.incremental = Some(checkpoint.to_string());
+        self
+    }
+
+    pub fn disk(mut self, disk: BackupDisk) -> DomainBackupDef {
+        self.disks.push(disk);
+        self
+    }
+
+    pub fn is_pull(&self) -> bool {
+        self.mode.as_deref() == Some("pull")
+    }
+}
+
+impl XmlElement for DomainBackupDef {
+    fn from_element(mut el: Element) -> Result<DomainBackupDef, Error> {
+        let disks = match el.take("disks") {
+            Some(mut disks) => disks.take_models("disk")?,
+            None => Vec::new(),
+        };
+        Ok(DomainBackupDef {
+            mode: el.take_attr("mode"),
+            incremental: el.take_text("incremental"),
+            server: el.take_model("server")?,
+            disks,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let disks = if self.disks.is_empty() {
+            None
+        } else {
+            Some(Element::new("disks").models(&self.disks))
+        };
+        Element::new("domainbackup")
+            .attr_opt("mode", self.mode.as_ref())
+            .text_child("incremental", self.incremental.as_ref())
+            .model(self.server.as_ref())
+            .child_opt(disks)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(DomainBackupDef);
+
+/// NBD server exporting the disks of a pull mode backup.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackupServer {
+    /// Either `tcp` or `unix`.
+    pub transport: Option<String>,
+    /// Host name of a TCP server.
+    pub name: Option<String>,
+    pub port: Option<u16>,
+    /// Path of a UNIX socket server.
+    pub socket: Option<String>,
+    /// Whether TLS is required by a TCP server.
+    pub tls: Option<bool>,
+    pub extra: Extra,
+}
+
+impl BackupServer {
+    pub fn tcp(name: &str, port: u16) -> BackupServer {
+        BackupServer {
+            transport: Some(String::from("tcp")),
+            name: Some(name.to_string()),
+            port: Some(port),
+            ..Default::default()
+        }
+    }
+
+    pub fn unix(socket: &str) -> BackupServer {
+        BackupServer {
+            transport: Some(String::from("unix")),
+            socket: Some(socket.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn tls(mut self, tls: bool) -> BackupServer {
+        self.tls = Some(tls);
+        self
+    }
+}
+
+impl XmlElement for BackupServer {
+    fn from_element(mut el: Element) -> Result<BackupServer, Error> {
+        Ok(BackupServer {
+            transport: el.take_attr("transport"),
+            name: el.take_attr("name"),
+            port: el.take_parse("port")?,
+            socket: el.take_attr("socket"),
+            tls: el.take_bool("tls")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("server")
+            .attr_opt("transport", self.transport.as_ref())
+            .attr_opt("tls", yes_no(self.tls))
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("port", self.port)
+            .attr_opt("socket", self.socket.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// How a disk of the domain is backed up.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackupDisk {
+    /// Target like `vda` or source path of the disk.
+    pub name: String,
+    /// Whether the disk is part of the backup.
+    pub backup: Option<bool>,
+    /// Either `full` or `incremental`, reported by libvirt.
+    pub backup_mode: Option<String>,
+    /// Checkpoint the backup of this disk is incremental from,
+    /// reported by libvirt.
+    pub incremental: Option<String>,
+    /// Name of the NBD export of a pull mode backup.
+    pub export_name: Option<String>,
+    /// Name of the dirty bitmap exported along the disk by a pull
+    /// mode incremental backup.
+    pub export_bitmap: Option<String>,
+    /// Type of the target or scratch storage, like `file` or `block`.
+    pub kind: Option<String>,
+    /// Format of the target or scratch storage.
+    pub driver: Option<DiskDriver>,
+    /// Storage the backup is written to in push mode.
+    pub target: Option<DiskSource>,
+    /// Storage holding the data overwritten by the guest while a
+    /// pull mode backup is running.
+    pub scratch: Option<DiskSource>,
+    pub extra: Extra,
+}
+
+impl BackupDisk {
+    /// The disk `name` is left out of the backup.
+    pub fn no(name: &str) -> BackupDisk {
+        BackupDisk { name: name.to_string(), backup: Some(false), ..Default::default() }
+    }
+
+    /// The disk `name` is written to the new `file` in `format` like
+    /// `raw` or `qcow2`.
+    pub fn push(name: &str, file: &str, format: &str) -> BackupDisk {
+        BackupDisk {
+            name: name.to_string(),
+            backup: Some(true),
+            kind: Some(String::from("file")),
+            driver: Some(DiskDriver { format: Some(format.to_string()), ..Default::default() }),
+            target: Some(DiskSource { file: Some(file.to_string()), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    /// The disk `name` is exported, the data overwritten by the guest
+    /// during the backup being kept in the `scratch` file.
+    pub fn pull(name: &str, scratch: &str) -> BackupDisk {
+        BackupDisk {
+            name: name.to_string(),
+            backup: Some(true),
+            kind: Some(String::from("file")),
+            scratch: Some(DiskSource { file: Some(scratch.to_string()), ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    pub fn export_name(mut self, name: &str) -> BackupDisk {
+        self.export_name = Some(name.to_string());
+        self
+    }
+
+    pub fn export_bitmap(mut self, bitmap: &str) -> BackupDisk {
+        self.export_bitmap = Some(bitmap.to_string());
+        self
+    }
+}
+
+impl XmlElement for BackupDisk {
+    fn from_element(mut el: Element) -> Result<BackupDisk, Error> {
+        Ok(BackupDisk {
+            name: el.take_attr("name").unwrap_or_default(),
+            backup: el.take_bool("backup")?,
+            backup_mode: el.take_attr("backupmode"),
+            incremental: el.take_attr("incremental"),
+            export_name: el.take_attr("exportname"),
+            export_bitmap: el.take_attr("exportbitmap"),
+            kind: el.take_attr("type"),
+            driver: el.take_model("driver")?,
+            target: el.take_model("target")?,
+            scratch: el.take_model("scratch")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let storage = |source: Option<&DiskSource>, name: &str| {
+            source.map(|s| {
+                let mut el = s.to_element();
+                el.name = name.to_string();
+                el
+            })
+        };
+        Element::new("disk")
+            .attr("name", &self.name)
+            .attr_opt("backup", yes_no(self.backup))
+            .attr_opt("backupmode", self.backup_mode.as_ref())
+            .attr_opt("incremental", self.incremental.as_ref())
+            .attr_opt("exportname", self.export_name.as_ref())
+            .attr_opt("exportbitmap", self.export_bitmap.as_ref())
+            .attr_opt("type", self.kind.as_ref())
+            .model(self.driver.as_ref())
+            .child_opt(storage(self.target.as_ref(), "target"))
+            .child_opt(storage(self.scratch.as_ref(), "scratch"))
+            .extra(&self.extra)
+    }
+}
@@ -0,0 +1,424 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the domain capabilities returned by
+//! `Connect::get_domain_capabilities`.
+//!
+//! See http://libvirt.org/formatdomaincaps.html
+//!
+//! ```
+//! use virt::xml::domain_capabilities::{DomainCapabilities, DomainCapsFeature};
+//!
+//! let caps: DomainCapabilities = "<domainCapabilities>
+//!   <domain>kvm</domain>
+//!   <vcpu max='255'/>
+//!   <devices>
+//!     <disk supported='yes'>
+//!       <enum name='bus'><value>sata</value><value>virtio</value></enum>
+//!     </disk>
+//!   </devices>
+//!   <features>
+//!     <vmcoreinfo supported='yes'/>
+//!     <sev supported='no'/>
+//!   </features>
+//! </domainCapabilities>".parse().unwrap();
+//! assert_eq!(Some(255), caps.max_vcpus);
+//! assert_eq!(vec!["sata", "virtio"], caps.disk_buses());
+//! assert!(caps.has_feature(DomainCapsFeature::VmCoreInfo));
+//! assert!(!caps.has_feature(DomainCapsFeature::Sev));
+//! ```
+
+use connect::Connect;
+use error::Error;
+use xml::domain::CpuFeature;
+use xml::{yes_no, Element, Extra, XmlElement};
+
+/// Features reported in the `<features>` element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainCapsFeature {
+    /// ARM Generic Interrupt Controller.
+    Gic,
+    VmCoreInfo,
+    GenId,
+    BackingStoreInput,
+    Backup,
+    AsyncTeardown,
+    /// AMD Secure Encrypted Virtualization.
+    Sev,
+    /// Intel Software Guard Extensions.
+    Sgx,
+    /// s390 protected virtualization.
+    S390Pv,
+}
+
+impl DomainCapsFeature {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DomainCapsFeature::Gic => "gic",
+            DomainCapsFeature::VmCoreInfo => "vmcoreinfo",
+            DomainCapsFeature::GenId => "genid",
+            DomainCapsFeature::BackingStoreInput => "backingStoreInput",
+            DomainCapsFeature::Backup => "backup",
+            DomainCapsFeature::AsyncTeardown => "async-teardown",
+            DomainCapsFeature::Sev => "sev",
+            DomainCapsFeature::Sgx => "sgx",
+            DomainCapsFeature::S390Pv => "s390-pv",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainCapabilities {
+    /// Path of the emulator binary.
+    pub path: Option<String>,
+    /// Virtualization type like `kvm` or `qemu`.
+    pub domain: String,
+    pub machine: Option<String>,
+    pub arch: Option<String>,
+    pub max_vcpus: Option<u32>,
+    pub iothreads: Option<bool>,
+    pub os: Option<Support>,
+    /// CPU modes, like `host-passthrough`, `host-model` or `custom`.
+    pub cpu_modes: Vec<CpuMode>,
+    pub memory_backing: Option<Support>,
+    /// Supported devices, one entry per device type like `disk`,
+    /// `graphics` or `tpm`.
+    pub devices: Vec<Support>,
+    pub features: Vec<Support>,
+    pub extra: Extra,
+}
+
+impl DomainCapabilities {
+    /// Fetch and parse the capabilities of domains run by
+    /// `emulatorbin` for `arch`, `machine` and `virttype`.
+    pub fn from_connect(conn: &Connect,
+                        emulatorbin: &str,
+                        arch: &str,
+                        machine: &str,
+                        virttype: &str)
+                        -> Result<DomainCapabilities, Error> {
+        conn.get_domain_capabilities(emulatorbin, arch, machine, virttype, 0)?.parse()
+    }
+
+    /// The device type `name`, like `disk` or `rng`, if supported.
+    pub fn device(&self, name: &str) -> Option<&Support> {
+        self.devices.iter().find(|d| d.name == name && d.supported != Some(false))
+    }
+
+    /// Whether `feature` is supported.
+    pub fn has_feature(&self, feature: DomainCapsFeature) -> bool {
+        self.feature(feature).is_some()
+    }
+
+    /// The `feature` element if supported, to look at its details
+    /// like the SEV `cbitpos`.
+    pub fn feature(&self, feature: DomainCapsFeature) -> Option<&Support> {
+        self.features.iter().find(|f| f.name == feature.as_str() && f.supported == Some(true))
+    }
+
+    /// The CPU mode `name` if supported.
+    pub fn cpu_mode(&self, name: &str) -> Option<&CpuMode> {
+        self.cpu_modes.iter().find(|m| m.name == name && m.supported == Some(true))
+    }
+
+    /// Firmwares which can be selected automatically, like `bios`
+    /// or `efi`.
+    pub fn firmwares(&self) -> Vec<&str> {
+        self.os.as_ref().map(|o| o.values("firmware")).unwrap_or_default()
+    }
+
+    /// Paths of the firmware loaders.
+    pub fn loaders(&self) -> Vec<&str> {
+        self.os
+            .as_ref()
+            .and_then(|o| o.child("loader"))
+            .map(|l| l.values.iter().map(|v| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    fn device_values(&self, device: &str, name: &str) -> Vec<&str> {
+        self.device(device).map(|d| d.values(name)).unwrap_or_default()
+    }
+
+    pub fn disk_buses(&self) -> Vec<&str> {
+        self.device_values("disk", "bus")
+    }
+
+    pub fn graphics_types(&self) -> Vec<&str> {
+        self.device_values("graphics", "type")
+    }
+
+    pub fn video_models(&self) -> Vec<&str> {
+        self.device_values("video", "modelType")
+    }
+
+    pub fn hostdev_subsys_types(&self) -> Vec<&str> {
+        self.device_values("hostdev", "subsysType")
+    }
+
+    pub fn rng_backends(&self) -> Vec<&str> {
+        self.device_values("rng", "backendModel")
+    }
+
+    pub fn tpm_models(&self) -> Vec<&str> {
+        self.device_values("tpm", "model")
+    }
+}
+
+impl XmlElement for DomainCapabilities {
+    fn from_element(mut el: Element) -> Result<DomainCapabilities, Error> {
+        let cpu_modes = match el.take("cpu") {
+            Some(mut cpu) => cpu.take_models("mode")?,
+            None => Vec::new(),
+        };
+        let devices = match el.take("devices") {
+            Some(devices) => Support::from_children(devices)?,
+            None => Vec::new(),
+        };
+        let features = match el.take("features") {
+            Some(features) => Support::from_children(features)?,
+            None => Vec::new(),
+        };
+        Ok(DomainCapabilities {
+            path: el.take_text("path"),
+            domain: el.take_text("domain").unwrap_or_default(),
+            machine: el.take_text("machine"),
+            arch: el.take_text("arch"),
+            max_vcpus: el.take_child_parse("vcpu", "max")?,
+            iothreads: el.take_child_bool("iothreads", "supported")?,
+            os: el.take_model("os")?,
+            cpu_modes,
+            memory_backing: el.take_model("memoryBacking")?,
+            devices,
+            features,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let cpu = if self.cpu_modes.is_empty() {
+            None
+        } else {
+            Some(Element::new("cpu").models(&self.cpu_modes))
+        };
+        let devices = if self.devices.is_empty() {
+            None
+        } else {
+            Some(Element::new("devices").models(&self.devices))
+        };
+        let features = if self.features.is_empty() {
+            None
+        } else {
+            Some(Element::new("features").models(&self.features))
+        };
+        Element::new("domainCapabilities")
+            .text_child("path", self.path.as_ref())
+            .child(Element::new("domain").text(&self.domain))
+            .text_child("machine", self.machine.as_ref())
+            .text_child("arch", self.arch.as_ref())
+            .attr_child("vcpu", "max", self.max_vcpus)
+            .attr_child("iothreads", "supported", yes_no(self.iothreads))
+            .model(self.os.as_ref())
+            .child_opt(cpu)
+            .model(self.memory_backing.as_ref())
+            .child_opt(devices)
+            .child_opt(features)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(DomainCapabilities);
+
+/// An element telling whether something is supported, along with
+/// the values its attributes accept.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Support {
+    /// Name of the element like `disk`, `loader` or `sev`.
+    pub name: String,
+    pub supported: Option<bool>,
+    /// Values accepted per attribute, like `bus` for a disk.
+    pub enums: Vec<(String, Vec<String>)>,
+    /// Values listed directly, like the paths of the loaders.
+    pub values: Vec<String>,
+    /// Nested elements like `<loader>` in `<os>`.
+    pub children: Vec<Support>,
+    pub extra: Extra,
+}
+
+impl Support {
+    fn from_children(el: Element) -> Result<Vec<Support>, Error> {
+        el.into_extra().children.into_iter().map(Support::from_element).collect()
+    }
+
+    /// Values accepted by the attribute `name`.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.enums
+            .iter()
+            .find(|e| e.0 == name)
+            .map(|e| e.1.iter().map(|v| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the attribute `name` accepts `value`.
+    pub fn accepts(&self, name: &str, value: &str) -> bool {
+        self.values(name).contains(&value)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Support> {
+        self.children.iter().find(|c| c.name == name)
+    }
+}
+
+impl XmlElement for Support {
+    fn from_element(mut el: Element) -> Result<Support, Error> {
+        let mut enums = Vec::new();
+        for mut e in el.take_all("enum") {
+            let name = e.take_attr("name").unwrap_or_default();
+            enums.push((name, e.take_all("value").iter().map(|v| v.get_text()).collect()));
+        }
+        let values = el.take_all("value").iter().map(|v| v.get_text()).collect();
+        let mut children = Vec::new();
+        while let Some(pos) = el.elements().iter().position(|e| e.get_attr("supported").is_some()) {
+            let name = el.elements()[pos].name.clone();
+            children.push(Support::from_element(el.take(&name).unwrap())?);
+        }
+        Ok(Support {
+            name: el.name.clone(),
+            supported: el.take_bool("supported")?,
+            enums,
+            values,
+            children,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new(&self.name).attr_opt("supported", yes_no(self.supported));
+        for value in &self.values {
+            el = el.child(Element::new("value").text(value));
+        }
+        el = el.models(&self.children);
+        for (name, values) in &self.enums {
+            let mut e = Element::new("enum").attr("name", name);
+            for value in values {
+                e = e.child(Element::new("value").text(value));
+            }
+            el = el.child(e);
+        }
+        el.extra(&self.extra)
+    }
+}
+
+/// Whether a CPU model can be used on the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpuModelUsable {
+    Yes,
+    No,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuModel {
+    pub name: String,
+    /// Usability on the host, only reported for the `custom` mode.
+    pub usable: Option<CpuModelUsable>,
+    pub fallback: Option<String>,
+    pub deprecated: Option<bool>,
+    pub extra: Extra,
+}
+
+impl XmlElement for CpuModel {
+    fn from_element(mut el: Element) -> Result<CpuModel, Error> {
+        let name = el.get_text();
+        el.children.clear();
+        let usable = el.take_attr("usable").map(|u| match u.as_str() {
+            "yes" => CpuModelUsable::Yes,
+            "no" => CpuModelUsable::No,
+            _ => CpuModelUsable::Unknown,
+        });
+        Ok(CpuModel {
+            name,
+            usable,
+            fallback: el.take_attr("fallback"),
+            deprecated: el.take_bool("deprecated")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let usable = self.usable.map(|u| match u {
+            CpuModelUsable::Yes => "yes",
+            CpuModelUsable::No => "no",
+            CpuModelUsable::Unknown => "unknown",
+        });
+        Element::new("model")
+            .attr_opt("usable", usable)
+            .attr_opt("fallback", self.fallback.as_ref())
+            .attr_opt("deprecated", yes_no(self.deprecated))
+            .text(&self.name)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CpuMode {
+    /// Like `host-passthrough`, `maximum`, `host-model` or `custom`.
+    pub name: String,
+    pub supported: Option<bool>,
+    /// For `host-model`, the model the host CPU is expanded to, for
+    /// `custom` every model known by the hypervisor.
+    pub models: Vec<CpuModel>,
+    pub vendor: Option<String>,
+    /// Features added to the `host-model` model.
+    pub features: Vec<CpuFeature>,
+    pub extra: Extra,
+}
+
+impl CpuMode {
+    /// Names of the models usable on the host.
+    pub fn usable_models(&self) -> Vec<&str> {
+        self.models
+            .iter()
+            .filter(|m| m.usable == Some(CpuModelUsable::Yes))
+            .map(|m| m.name.as_str())
+            .collect()
+    }
+}
+
+impl XmlElement for CpuMode {
+    fn from_element(mut el: Element) -> Result<CpuMode, Error> {
+        Ok(CpuMode {
+            name: el.take_attr("name").unwrap_or_default(),
+            supported: el.take_bool("supported")?,
+            models: el.take_models("model")?,
+            vendor: el.take_text("vendor"),
+            features: el.take_models("feature")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("mode")
+            .attr("name", &self.name)
+            .attr_opt("supported", yes_no(self.supported))
+            .models(&self.models)
+            .text_child("vendor", self.vendor.as_ref())
+            .models(&self.features)
+            .extra(&self.extra)
+    }
+}
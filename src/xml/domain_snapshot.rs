@@ -0,0 +1,361 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the domain snapshot XML format.
+//!
+//! See http://libvirt.org/formatsnapshot.html
+//!
+//! ```
+//! use virt::xml::domain_snapshot::{DomainSnapshotDef, SnapshotDisk, SnapshotMemory};
+//!
+//! // An external disk-only snapshot, to be created with
+//! // `DomainSnapshotCreateFlags::DISK_ONLY`.
+//! let def = DomainSnapshotDef::new("backup")
+//!     .description("Nightly backup")
+//!     .memory(SnapshotMemory::no())
+//!     .disk(SnapshotDisk::external("vda", "/var/lib/libvirt/images/vm.backup.qcow2")
+//!         .driver("qcow2"))
+//!     .disk(SnapshotDisk::no("vdb"));
+//!
+//! let parsed: DomainSnapshotDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! ```
+
+use domain_snapshot::{DomainSnapshot, DomainSnapshotXMLFlags};
+use error::Error;
+use xml::domain::{DiskDriver, DiskSource, DomainDef};
+use xml::{Element, Extra, XmlElement};
+
+/// How a snapshot saves the memory or a disk of a domain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// Not part of the snapshot.
+    #[default]
+    No,
+    /// Saved inside the image itself, like a qcow2 internal
+    /// snapshot.
+    Internal,
+    /// Saved to a separate file, the original image becoming the
+    /// backing store of a new overlay for disks.
+    External,
+    /// Left to be snapshotted by the user.
+    Manual,
+    /// Mode not known by the binding.
+    Other(String),
+}
+
+impl SnapshotMode {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            SnapshotMode::No => "no",
+            SnapshotMode::Internal => "internal",
+            SnapshotMode::External => "external",
+            SnapshotMode::Manual => "manual",
+            SnapshotMode::Other(ref m) => m,
+        }
+    }
+
+    pub fn from_name(name: &str) -> SnapshotMode {
+        match name {
+            "no" => SnapshotMode::No,
+            "internal" => SnapshotMode::Internal,
+            "external" => SnapshotMode::External,
+            "manual" => SnapshotMode::Manual,
+            _ => SnapshotMode::Other(name.to_string()),
+        }
+    }
+}
+
+/// State of the domain when a snapshot was taken.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotState {
+    NoState,
+    Running,
+    Blocked,
+    Paused,
+    Shutdown,
+    Shutoff,
+    Crashed,
+    PmSuspended,
+    /// Only the disks were saved, without the memory.
+    DiskSnapshot,
+    /// State not known by the binding.
+    Other(String),
+}
+
+impl SnapshotState {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            SnapshotState::NoState => "nostate",
+            SnapshotState::Running => "running",
+            SnapshotState::Blocked => "blocked",
+            SnapshotState::Paused => "paused",
+            SnapshotState::Shutdown => "shutdown",
+            SnapshotState::Shutoff => "shutoff",
+            SnapshotState::Crashed => "crashed",
+            SnapshotState::PmSuspended => "pmsuspended",
+            SnapshotState::DiskSnapshot => "disk-snapshot",
+            SnapshotState::Other(ref s) => s,
+        }
+    }
+
+    pub fn from_name(name: &str) -> SnapshotState {
+        match name {
+            "nostate" => SnapshotState::NoState,
+            "running" => SnapshotState::Running,
+            "blocked" => SnapshotState::Blocked,
+            "paused" => SnapshotState::Paused,
+            "shutdown" => SnapshotState::Shutdown,
+            "shutoff" => SnapshotState::Shutoff,
+            "crashed" => SnapshotState::Crashed,
+            "pmsuspended" => SnapshotState::PmSuspended,
+            "disk-snapshot" => SnapshotState::DiskSnapshot,
+            _ => SnapshotState::Other(name.to_string()),
+        }
+    }
+}
+
+/// A snapshot definition as used by `DomainSnapshot::create_xml`.
+///
+/// Only `name`, `description`, `memory` and `disks` are considered
+/// when creating a snapshot, the other fields are reported by
+/// `DomainSnapshot::get_xml_desc`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainSnapshotDef {
+    /// Generated by libvirt from the creation time when missing.
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub state: Option<SnapshotState>,
+    /// Creation time, in seconds since the Epoch.
+    pub creation_time: Option<i64>,
+    /// Name of the parent snapshot.
+    pub parent: Option<String>,
+    pub memory: Option<SnapshotMemory>,
+    pub disks: Vec<SnapshotDisk>,
+    /// Definition of the domain when the snapshot was taken.
+    pub domain: Option<DomainDef>,
+    /// Inactive definition of a domain running when the snapshot
+    /// was taken.
+    pub inactive_domain: Option<DomainDef>,
+    pub extra: Extra,
+}
+
+impl DomainSnapshotDef {
+    pub fn new(name: &str) -> DomainSnapshotDef {
+        DomainSnapshotDef { name: Some(name.to_string()), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `snapshot`.
+    pub fn from_snapshot(snapshot: &DomainSnapshot,
+                         flags: DomainSnapshotXMLFlags)
+                         -> Result<DomainSnapshotDef, Error> {
+        snapshot.get_xml_desc(flags)?.parse()
+    }
+
+    pub fn description(mut self, description: &str) -> DomainSnapshotDef {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn memory(mut self, memory: SnapshotMemory) -> DomainSnapshotDef {
+        self.memory = Some(memory);
+        self
+    }
+
+    pub fn disk(mut self, disk: SnapshotDisk) -> DomainSnapshotDef {
+        self.disks.push(disk);
+        self
+    }
+
+    /// Return the disk `name` of the snapshot.
+    pub fn find_disk(&self, name: &str) -> Option<&SnapshotDisk> {
+        self.disks.iter().find(|d| d.name == name)
+    }
+}
+
+impl XmlElement for DomainSnapshotDef {
+    fn from_element(mut el: Element) -> Result<DomainSnapshotDef, Error> {
+        let disks = match el.take("disks") {
+            Some(mut disks) => disks.take_models("disk")?,
+            None => Vec::new(),
+        };
+        Ok(DomainSnapshotDef {
+            name: el.take_text("name"),
+            description: el.take_text("description"),
+            state: el.take_text("state").map(|s| SnapshotState::from_name(&s)),
+            creation_time: el.take_text_parse("creationTime")?,
+            parent: el.take("parent").and_then(|mut p| p.take_text("name")),
+            memory: el.take_model("memory")?,
+            disks,
+            domain: el.take_model("domain")?,
+            inactive_domain: el.take_model("inactiveDomain")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let parent = self.parent
+            .as_ref()
+            .map(|p| Element::new("parent").text_child("name", Some(p)));
+        let disks = if self.disks.is_empty() {
+            None
+        } else {
+            Some(Element::new("disks").models(&self.disks))
+        };
+        let inactive_domain = self.inactive_domain.as_ref().map(|d| {
+            let mut el = d.to_element();
+            el.name = String::from("inactiveDomain");
+            el
+        });
+        Element::new("domainsnapshot")
+            .text_child("name", self.name.as_ref())
+            .text_child("description", self.description.as_ref())
+            .text_child("state", self.state.as_ref().map(|s| s.as_str()))
+            .text_child("creationTime", self.creation_time)
+            .child_opt(parent)
+            .model(self.memory.as_ref())
+            .child_opt(disks)
+            .model(self.domain.as_ref())
+            .child_opt(inactive_domain)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(DomainSnapshotDef);
+
+/// How the memory of a running domain is saved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapshotMemory {
+    pub snapshot: SnapshotMode,
+    /// File the memory is saved to by an external snapshot.
+    pub file: Option<String>,
+    pub extra: Extra,
+}
+
+impl SnapshotMemory {
+    /// The memory is not saved, as required by disk-only snapshots.
+    pub fn no() -> SnapshotMemory {
+        SnapshotMemory { snapshot: SnapshotMode::No, ..Default::default() }
+    }
+
+    pub fn internal() -> SnapshotMemory {
+        SnapshotMemory { snapshot: SnapshotMode::Internal, ..Default::default() }
+    }
+
+    pub fn external(file: &str) -> SnapshotMemory {
+        SnapshotMemory {
+            snapshot: SnapshotMode::External,
+            file: Some(file.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for SnapshotMemory {
+    fn from_element(mut el: Element) -> Result<SnapshotMemory, Error> {
+        Ok(SnapshotMemory {
+            snapshot: SnapshotMode::from_name(&el.take_attr("snapshot").unwrap_or_default()),
+            file: el.take_attr("file"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("memory")
+            .attr("snapshot", self.snapshot.as_str())
+            .attr_opt("file", self.file.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// How a disk of the domain is saved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapshotDisk {
+    /// Target like `vda` or source path of the disk.
+    pub name: String,
+    /// Left to the default of the domain disk when missing.
+    pub snapshot: Option<SnapshotMode>,
+    /// Type of the new source of an external snapshot, like `file`
+    /// or `block`.
+    pub kind: Option<String>,
+    /// Format of the new source of an external snapshot.
+    pub driver: Option<DiskDriver>,
+    /// New source of the disk for an external snapshot, the current
+    /// one becoming its backing store.
+    pub source: Option<DiskSource>,
+    pub extra: Extra,
+}
+
+impl SnapshotDisk {
+    pub fn new(name: &str, snapshot: SnapshotMode) -> SnapshotDisk {
+        SnapshotDisk { name: name.to_string(), snapshot: Some(snapshot), ..Default::default() }
+    }
+
+    /// The disk `name` is left out of the snapshot.
+    pub fn no(name: &str) -> SnapshotDisk {
+        SnapshotDisk::new(name, SnapshotMode::No)
+    }
+
+    pub fn internal(name: &str) -> SnapshotDisk {
+        SnapshotDisk::new(name, SnapshotMode::Internal)
+    }
+
+    /// The disk `name` is switched to a new overlay `file` backed by
+    /// its current image.
+    pub fn external(name: &str, file: &str) -> SnapshotDisk {
+        SnapshotDisk {
+            kind: Some(String::from("file")),
+            source: Some(DiskSource { file: Some(file.to_string()), ..Default::default() }),
+            ..SnapshotDisk::new(name, SnapshotMode::External)
+        }
+    }
+
+    /// Set the format of the new overlay like `qcow2`.
+    pub fn driver(mut self, format: &str) -> SnapshotDisk {
+        self.driver = Some(DiskDriver { format: Some(format.to_string()), ..Default::default() });
+        self
+    }
+
+    /// Return the path of the new overlay of an external snapshot.
+    pub fn file(&self) -> Option<&str> {
+        self.source.as_ref().and_then(|s| s.file.as_ref()).map(|f| f.as_str())
+    }
+}
+
+impl XmlElement for SnapshotDisk {
+    fn from_element(mut el: Element) -> Result<SnapshotDisk, Error> {
+        Ok(SnapshotDisk {
+            name: el.take_attr("name").unwrap_or_default(),
+            snapshot: el.take_attr("snapshot").map(|s| SnapshotMode::from_name(&s)),
+            kind: el.take_attr("type"),
+            driver: el.take_model("driver")?,
+            source: el.take_model("source")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("disk")
+            .attr("name", &self.name)
+            .attr_opt("snapshot", self.snapshot.as_ref().map(|s| s.as_str()))
+            .attr_opt("type", self.kind.as_ref())
+            .model(self.driver.as_ref())
+            .model(self.source.as_ref())
+            .extra(&self.extra)
+    }
+}
@@ -0,0 +1,357 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the host interface XML format.
+//!
+//! See the `interface.rng` schema of libvirt.
+//!
+//! ```
+//! use virt::xml::interface::{InterfaceDef, InterfaceProtocol};
+//!
+//! let def = InterfaceDef::bridge("br0", &[InterfaceDef::ethernet("eth0")])
+//!     .start_mode("onboot")
+//!     .protocol(InterfaceProtocol::ipv4().ip("192.168.0.5", 24).gateway("192.168.0.1"));
+//!
+//! let parsed: InterfaceDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! ```
+
+use error::Error;
+use interface::{Interface, InterfaceXMLFlags};
+use xml::{on_off, yes_no, Element, Extra, XmlElement};
+
+/// A host interface definition as used by `Interface::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceDef {
+    /// Either `ethernet`, `bridge`, `bond` or `vlan`.
+    pub kind: String,
+    pub name: String,
+    /// When the interface is started, like `onboot`, `none` or
+    /// `hotplug`.
+    pub start_mode: Option<String>,
+    pub mac: Option<String>,
+    pub mtu: Option<u32>,
+    pub protocols: Vec<InterfaceProtocol>,
+    pub bridge: Option<InterfaceBridge>,
+    pub bond: Option<Bond>,
+    pub vlan: Option<Vlan>,
+    pub extra: Extra,
+}
+
+impl InterfaceDef {
+    pub fn new(kind: &str, name: &str) -> InterfaceDef {
+        InterfaceDef { kind: kind.to_string(), name: name.to_string(), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `iface`.
+    pub fn from_interface(iface: &Interface,
+                          flags: InterfaceXMLFlags)
+                          -> Result<InterfaceDef, Error> {
+        iface.get_xml_desc(flags)?.parse()
+    }
+
+    pub fn ethernet(name: &str) -> InterfaceDef {
+        InterfaceDef::new("ethernet", name)
+    }
+
+    /// A bridge `name` of the interfaces `ports`.
+    pub fn bridge(name: &str, ports: &[InterfaceDef]) -> InterfaceDef {
+        InterfaceDef {
+            bridge: Some(InterfaceBridge { interfaces: ports.to_vec(), ..Default::default() }),
+            ..InterfaceDef::new("bridge", name)
+        }
+    }
+
+    /// A bond `name` of the interfaces `slaves`, with `mode` like
+    /// `active-backup` or `802.3ad`.
+    pub fn bond(name: &str, mode: &str, slaves: &[InterfaceDef]) -> InterfaceDef {
+        InterfaceDef {
+            bond: Some(Bond {
+                mode: Some(mode.to_string()),
+                interfaces: slaves.to_vec(),
+                ..Default::default()
+            }),
+            ..InterfaceDef::new("bond", name)
+        }
+    }
+
+    /// A VLAN `name` of `tag` on the interface `device`.
+    pub fn vlan(name: &str, tag: u16, device: &str) -> InterfaceDef {
+        InterfaceDef {
+            vlan: Some(Vlan { tag, device: device.to_string(), ..Default::default() }),
+            ..InterfaceDef::new("vlan", name)
+        }
+    }
+
+    pub fn start_mode(mut self, mode: &str) -> InterfaceDef {
+        self.start_mode = Some(mode.to_string());
+        self
+    }
+
+    pub fn mac(mut self, mac: &str) -> InterfaceDef {
+        self.mac = Some(mac.to_string());
+        self
+    }
+
+    pub fn mtu(mut self, size: u32) -> InterfaceDef {
+        self.mtu = Some(size);
+        self
+    }
+
+    pub fn protocol(mut self, protocol: InterfaceProtocol) -> InterfaceDef {
+        self.protocols.push(protocol);
+        self
+    }
+}
+
+impl XmlElement for InterfaceDef {
+    fn from_element(mut el: Element) -> Result<InterfaceDef, Error> {
+        Ok(InterfaceDef {
+            kind: el.take_attr("type").unwrap_or_default(),
+            name: el.take_attr("name").unwrap_or_default(),
+            start_mode: el.take_child_attr("start", "mode"),
+            mac: el.take_child_attr("mac", "address"),
+            mtu: el.take_child_parse("mtu", "size")?,
+            protocols: el.take_models("protocol")?,
+            bridge: el.take_model("bridge")?,
+            bond: el.take_model("bond")?,
+            vlan: el.take_model("vlan")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("interface")
+            .attr_opt("type", if self.kind.is_empty() { None } else { Some(&self.kind) })
+            .attr("name", &self.name)
+            .attr_child("start", "mode", self.start_mode.as_ref())
+            .attr_child("mac", "address", self.mac.as_ref())
+            .attr_child("mtu", "size", self.mtu)
+            .models(&self.protocols)
+            .model(self.bridge.as_ref())
+            .model(self.bond.as_ref())
+            .model(self.vlan.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(InterfaceDef);
+
+/// Addressing of an interface for one address family.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceProtocol {
+    /// Either `ipv4` or `ipv6`.
+    pub family: String,
+    /// Whether addresses are obtained with DHCP.
+    pub dhcp: bool,
+    /// Whether the DNS servers given by DHCP are used.
+    pub peerdns: Option<bool>,
+    /// Whether IPv6 stateless autoconfiguration is used.
+    pub autoconf: bool,
+    /// Static addresses, `(address, prefix)`.
+    pub ips: Vec<(String, Option<u8>)>,
+    pub gateway: Option<String>,
+    pub extra: Extra,
+}
+
+impl InterfaceProtocol {
+    pub fn ipv4() -> InterfaceProtocol {
+        InterfaceProtocol { family: String::from("ipv4"), ..Default::default() }
+    }
+
+    pub fn ipv6() -> InterfaceProtocol {
+        InterfaceProtocol { family: String::from("ipv6"), ..Default::default() }
+    }
+
+    pub fn dhcp(mut self) -> InterfaceProtocol {
+        self.dhcp = true;
+        self
+    }
+
+    pub fn autoconf(mut self) -> InterfaceProtocol {
+        self.autoconf = true;
+        self
+    }
+
+    pub fn ip(mut self, address: &str, prefix: u8) -> InterfaceProtocol {
+        self.ips.push((address.to_string(), Some(prefix)));
+        self
+    }
+
+    pub fn gateway(mut self, gateway: &str) -> InterfaceProtocol {
+        self.gateway = Some(gateway.to_string());
+        self
+    }
+}
+
+impl XmlElement for InterfaceProtocol {
+    fn from_element(mut el: Element) -> Result<InterfaceProtocol, Error> {
+        let (dhcp, peerdns) = match el.take("dhcp") {
+            Some(mut d) => (true, d.take_bool("peerdns")?),
+            None => (false, None),
+        };
+        let mut ips = Vec::new();
+        for mut ip in el.take_all("ip") {
+            let address = ip.take_attr("address").unwrap_or_default();
+            ips.push((address, ip.take_parse("prefix")?));
+        }
+        Ok(InterfaceProtocol {
+            family: el.take_attr("family").unwrap_or_default(),
+            dhcp,
+            peerdns,
+            autoconf: el.take_flag("autoconf"),
+            ips,
+            gateway: el.take_child_attr("route", "gateway"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let dhcp = if self.dhcp {
+            Some(Element::new("dhcp").attr_opt("peerdns", yes_no(self.peerdns)))
+        } else {
+            None
+        };
+        let mut el = Element::new("protocol")
+            .attr("family", &self.family)
+            .flag_child("autoconf", self.autoconf)
+            .child_opt(dhcp);
+        for &(ref address, prefix) in &self.ips {
+            el = el.child(Element::new("ip").attr("address", address).attr_opt("prefix", prefix));
+        }
+        el.attr_child("route", "gateway", self.gateway.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceBridge {
+    /// Whether the Spanning Tree Protocol is enabled.
+    pub stp: Option<bool>,
+    /// Forward delay, in seconds.
+    pub delay: Option<f64>,
+    /// Interfaces attached to the bridge.
+    pub interfaces: Vec<InterfaceDef>,
+    pub extra: Extra,
+}
+
+impl XmlElement for InterfaceBridge {
+    fn from_element(mut el: Element) -> Result<InterfaceBridge, Error> {
+        Ok(InterfaceBridge {
+            stp: el.take_bool("stp")?,
+            delay: el.take_parse("delay")?,
+            interfaces: el.take_models("interface")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("bridge")
+            .attr_opt("stp", on_off(self.stp))
+            .attr_opt("delay", self.delay)
+            .models(&self.interfaces)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bond {
+    /// Like `balance-rr`, `active-backup` or `802.3ad`.
+    pub mode: Option<String>,
+    /// Link monitoring with MII.
+    pub miimon: Option<Miimon>,
+    /// Interfaces enslaved to the bond.
+    pub interfaces: Vec<InterfaceDef>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Bond {
+    fn from_element(mut el: Element) -> Result<Bond, Error> {
+        Ok(Bond {
+            mode: el.take_attr("mode"),
+            miimon: el.take_model("miimon")?,
+            interfaces: el.take_models("interface")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("bond")
+            .attr_opt("mode", self.mode.as_ref())
+            .model(self.miimon.as_ref())
+            .models(&self.interfaces)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Miimon {
+    /// Interval between checks, in milliseconds.
+    pub freq: u32,
+    pub updelay: Option<u32>,
+    pub downdelay: Option<u32>,
+    /// Either `ioctl` or `netif`.
+    pub carrier: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Miimon {
+    fn from_element(mut el: Element) -> Result<Miimon, Error> {
+        Ok(Miimon {
+            freq: el.take_parse("freq")?.unwrap_or_default(),
+            updelay: el.take_parse("updelay")?,
+            downdelay: el.take_parse("downdelay")?,
+            carrier: el.take_attr("carrier"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("miimon")
+            .attr("freq", self.freq)
+            .attr_opt("updelay", self.updelay)
+            .attr_opt("downdelay", self.downdelay)
+            .attr_opt("carrier", self.carrier.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Vlan {
+    pub tag: u16,
+    /// Name of the interface the VLAN is on.
+    pub device: String,
+    pub extra: Extra,
+}
+
+impl XmlElement for Vlan {
+    fn from_element(mut el: Element) -> Result<Vlan, Error> {
+        Ok(Vlan {
+            tag: el.take_parse("tag")?.unwrap_or_default(),
+            device: el.take_child_attr("interface", "name").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("vlan")
+            .attr("tag", self.tag)
+            .child(Element::new("interface").attr("name", &self.device))
+            .extra(&self.extra)
+    }
+}
@@ -0,0 +1,645 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed models of the XML documents exchanged with libvirt.
+//!
+//! Every model is parsed from and written back to a generic
+//! `Element` tree. Attributes and children a model does not know
+//! about are kept in its `extra` field and written back as is, so
+//! that parsing the output of a `get_xml_desc` and defining it again
+//! does not lose any configuration.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use xml_rs::name::OwnedName;
+use xml_rs::reader::{EventReader, ParserConfig, XmlEvent};
+
+use error::{Error, ErrorDomain, ErrorLevel, ErrorNumber};
+
+/// Implements `FromStr` and `Display` for a root model in terms of
+/// `XmlElement`.
+macro_rules! impl_xml_str {
+    ($t:ty) => {
+        impl ::std::str::FromStr for $t {
+            type Err = ::error::Error;
+
+            fn from_str(xml: &str) -> Result<$t, ::error::Error> {
+                ::xml::XmlElement::from_element(xml.parse()?)
+            }
+        }
+
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                ::xml::XmlElement::to_element(self).fmt(f)
+            }
+        }
+    }
+}
+
+pub mod capabilities;
+pub mod domain;
+pub mod domain_backup;
+pub mod domain_capabilities;
+pub mod domain_snapshot;
+pub mod interface;
+pub mod network;
+pub mod nodedev;
+pub mod nwfilter;
+pub mod secret;
+pub mod storage;
+
+pub(crate) fn invalid(reason: &str) -> Error {
+    Error {
+        code: ErrorNumber::XmlError,
+        domain: ErrorDomain::Xml,
+        message: format!("invalid XML: {}", reason),
+        level: ErrorLevel::ERROR,
+        str1: None,
+        str2: None,
+        str3: None,
+        int1: 0,
+        int2: 0,
+    }
+}
+
+/// Content of an element, either a child element or a run of text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// A generic XML element.
+///
+/// Names keep their namespace prefix, like `app:data`, and namespace
+/// declarations are kept as `xmlns` attributes of the element which
+/// declares them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Element {
+    pub name: String,
+    /// Attributes, in document order.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// What is left of the children a model only partly took, see
+    /// `Extra::remainders`.
+    pub(crate) remainders: Vec<(usize, Element)>,
+}
+
+impl Element {
+    pub fn new(name: &str) -> Element {
+        Element { name: name.to_string(), ..Default::default() }
+    }
+
+    /// Add the attribute `name`, replacing any previous value.
+    pub fn attr<T: Display>(mut self, name: &str, value: T) -> Element {
+        self.set_attr(name, value);
+        self
+    }
+
+    /// Add the attribute `name` if `value` is set.
+    pub fn attr_opt<T: Display>(mut self, name: &str, value: Option<T>) -> Element {
+        if let Some(value) = value {
+            self.set_attr(name, value);
+        }
+        self
+    }
+
+    /// Append a child element.
+    pub fn child(mut self, child: Element) -> Element {
+        self.children.push(Node::Element(child));
+        self
+    }
+
+    /// Append a child element if `child` is set.
+    pub fn child_opt(mut self, child: Option<Element>) -> Element {
+        if let Some(child) = child {
+            self.children.push(Node::Element(child));
+        }
+        self
+    }
+
+    /// Append a child element `name` holding `text` if set.
+    pub fn text_child<T: Display>(self, name: &str, text: Option<T>) -> Element {
+        self.child_opt(text.map(|t| Element::new(name).text(t)))
+    }
+
+    /// Append a run of text.
+    pub fn text<T: Display>(mut self, text: T) -> Element {
+        self.children.push(Node::Text(text.to_string()));
+        self
+    }
+
+    /// Append the unknown attributes and children of a model.
+    ///
+    /// Remainders are merged back into the child they were taken
+    /// from, or appended if the model did not write that child.
+    pub fn extra(mut self, extra: &Extra) -> Element {
+        for (name, value) in &extra.attributes {
+            if self.get_attr(name).is_none() {
+                self.attributes.push((name.clone(), value.clone()));
+            }
+        }
+        for &(index, ref rest) in &extra.remainders {
+            self.merge_rest(index, rest);
+        }
+        self.children.extend(extra.children.iter().cloned().map(Node::Element));
+        self
+    }
+
+    fn merge_rest(&mut self, index: usize, rest: &Element) {
+        let target = self.children
+            .iter_mut()
+            .filter_map(|n| match *n {
+                Node::Element(ref mut e) if e.name == rest.name => Some(e),
+                _ => None,
+            })
+            .nth(index);
+        match target {
+            Some(e) => {
+                for (name, value) in &rest.attributes {
+                    if e.get_attr(name).is_none() {
+                        e.attributes.push((name.clone(), value.clone()));
+                    }
+                }
+                e.children.extend(rest.children.iter().cloned());
+                for &(index, ref r) in &rest.remainders {
+                    e.merge_rest(index, r);
+                }
+            }
+            None => {
+                let mut e = Element {
+                    remainders: Vec::new(),
+                    ..rest.clone()
+                };
+                for &(index, ref r) in &rest.remainders {
+                    e.merge_rest(index, r);
+                }
+                self.children.push(Node::Element(e));
+            }
+        }
+    }
+
+    pub fn set_attr<T: Display>(&mut self, name: &str, value: T) {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|a| a.0 == name) {
+            Some(attr) => attr.1 = value,
+            None => self.attributes.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn get_attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.0 == name).map(|a| a.1.as_str())
+    }
+
+    /// Child elements, in document order.
+    pub fn elements(&self) -> Vec<&Element> {
+        self.children
+            .iter()
+            .filter_map(|n| match *n {
+                Node::Element(ref e) => Some(e),
+                Node::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// First child element named `name`.
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        self.elements().into_iter().find(|e| e.name == name)
+    }
+
+    /// Text of the element, its runs of text concatenated.
+    pub fn get_text(&self) -> String {
+        let mut ret = String::new();
+        for node in &self.children {
+            if let Node::Text(ref t) = *node {
+                ret.push_str(t);
+            }
+        }
+        ret
+    }
+
+    /// Keep what is left of `rest`, the `index`-th child named like
+    /// it a model took, so that it is written back along with the
+    /// parts the model knows about.
+    pub(crate) fn keep_rest(&mut self, index: usize, rest: Element) {
+        if !rest.attributes.is_empty() || !rest.children.is_empty() ||
+           !rest.remainders.is_empty() {
+            self.remainders.push((index, rest));
+        }
+    }
+
+    /// Remove the first child element named `name`, hand it to `f`
+    /// and keep what `f` left of it.
+    fn take_with<T, F>(&mut self, name: &str, f: F) -> Option<T>
+        where F: FnOnce(&mut Element) -> T
+    {
+        let mut e = self.take(name)?;
+        let ret = f(&mut e);
+        self.keep_rest(0, e);
+        Some(ret)
+    }
+
+    /// Remove the attribute `name` and return its value.
+    pub fn take_attr(&mut self, name: &str) -> Option<String> {
+        let pos = self.attributes.iter().position(|a| a.0 == name)?;
+        Some(self.attributes.remove(pos).1)
+    }
+
+    /// Remove the attribute `name` and parse its value.
+    pub fn take_parse<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, Error> {
+        match self.take_attr(name) {
+            Some(value) => parse_value(&self.name, name, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the attribute `name` and decode it as a `yes`/`no` or
+    /// `on`/`off` value.
+    pub fn take_bool(&mut self, name: &str) -> Result<Option<bool>, Error> {
+        match self.take_attr(name) {
+            Some(value) => {
+                match value.as_str() {
+                    "yes" | "on" => Ok(Some(true)),
+                    "no" | "off" => Ok(Some(false)),
+                    _ => Err(invalid(&format!("'{}' of <{}> is not a boolean", name, self.name))),
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the first child element named `name` and return it.
+    pub fn take(&mut self, name: &str) -> Option<Element> {
+        let pos = self.children.iter().position(|n| match *n {
+            Node::Element(ref e) => e.name == name,
+            Node::Text(_) => false,
+        })?;
+        match self.children.remove(pos) {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        }
+    }
+
+    /// Remove all the child elements named `name` and return them.
+    pub fn take_all(&mut self, name: &str) -> Vec<Element> {
+        let mut ret = Vec::new();
+        while let Some(e) = self.take(name) {
+            ret.push(e);
+        }
+        ret
+    }
+
+    /// Remove the first child element named `name` and return its
+    /// text.
+    pub fn take_text(&mut self, name: &str) -> Option<String> {
+        self.take_with(name, |e| e.take_text_nodes())
+    }
+
+    fn take_text_nodes(&mut self) -> String {
+        let text = self.get_text();
+        self.children.retain(|n| match *n {
+            Node::Text(_) => false,
+            Node::Element(_) => true,
+        });
+        text
+    }
+
+    /// Remove the first child element named `name` and parse its
+    /// text.
+    pub fn take_text_parse<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, Error> {
+        match self.take_text(name) {
+            Some(value) => parse_value(&self.name, name, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the first empty child element named `name` if present
+    /// and return whether it was. Elements carrying attributes or
+    /// children are left in place.
+    pub fn take_flag(&mut self, name: &str) -> bool {
+        let pos = self.children.iter().position(|n| match *n {
+            Node::Element(ref e) => {
+                e.name == name && e.attributes.is_empty() && e.children.is_empty()
+            }
+            Node::Text(_) => false,
+        });
+        match pos {
+            Some(pos) => {
+                self.children.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the first child element named `name` and return the
+    /// value of its attribute `attr`.
+    pub fn take_child_attr(&mut self, name: &str, attr: &str) -> Option<String> {
+        self.take_with(name, |e| e.take_attr(attr)).and_then(|v| v)
+    }
+
+    /// Remove the first child element named `name` and return the
+    /// amount of memory it holds, in KiB.
+    pub fn take_memory(&mut self, name: &str) -> Result<Option<u64>, Error> {
+        self.take_with(name, |e| {
+                let unit = e.take_attr("unit");
+                let value = parse_value(name, "text", &e.take_text_nodes())?;
+                to_kib(value, unit.as_deref())
+            })
+            .transpose()
+    }
+
+    /// Remove the first child element named `name` and return the
+    /// size it holds, in bytes.
+    pub fn take_bytes(&mut self, name: &str) -> Result<Option<u64>, Error> {
+        self.take_with(name, |e| {
+                let unit = e.take_attr("unit");
+                let value = parse_value(name, "text", &e.take_text_nodes())?;
+                to_bytes(value, unit.as_deref().unwrap_or("bytes"))
+            })
+            .transpose()
+    }
+
+    /// Remove the first child element named `name` and parse its
+    /// attribute `attr`.
+    pub fn take_child_parse<T: FromStr>(&mut self,
+                                        name: &str,
+                                        attr: &str)
+                                        -> Result<Option<T>, Error> {
+        self.take_with(name, |e| e.take_parse(attr)).unwrap_or(Ok(None))
+    }
+
+    /// Remove the first child element named `name` and decode its
+    /// attribute `attr` as a boolean.
+    pub fn take_child_bool(&mut self, name: &str, attr: &str) -> Result<Option<bool>, Error> {
+        self.take_with(name, |e| e.take_bool(attr)).unwrap_or(Ok(None))
+    }
+
+    /// Remove the first child element named `name` and parse it as
+    /// the model `T`.
+    pub fn take_model<T: XmlElement>(&mut self, name: &str) -> Result<Option<T>, Error> {
+        self.take(name).map(T::from_element).transpose()
+    }
+
+    /// Remove all the child elements named `name` and parse them as
+    /// the model `T`.
+    pub fn take_models<T: XmlElement>(&mut self, name: &str) -> Result<Vec<T>, Error> {
+        self.take_all(name).into_iter().map(T::from_element).collect()
+    }
+
+    /// Append the element of `model` if set.
+    pub fn model<T: XmlElement>(self, model: Option<&T>) -> Element {
+        self.child_opt(model.map(|m| m.to_element()))
+    }
+
+    /// Append the elements of `models`.
+    pub fn models<T: XmlElement>(mut self, models: &[T]) -> Element {
+        self.children.extend(models.iter().map(|m| Node::Element(m.to_element())));
+        self
+    }
+
+    /// Append a child element `name` with `attr` set to `value`, if
+    /// set.
+    pub fn attr_child<T: Display>(self, name: &str, attr: &str, value: Option<T>) -> Element {
+        self.child_opt(value.map(|v| Element::new(name).attr(attr, v)))
+    }
+
+    /// Append an empty child element `name` if `flag` is set.
+    pub fn flag_child(self, name: &str, flag: bool) -> Element {
+        self.child_opt(if flag { Some(Element::new(name)) } else { None })
+    }
+
+    /// Append a child element `name` holding `bytes` bytes, if set.
+    pub fn bytes_child(self, name: &str, bytes: Option<u64>) -> Element {
+        self.child_opt(bytes.map(|b| Element::new(name).attr("unit", "bytes").text(b)))
+    }
+
+    /// Append a child element `name` holding `kib` KiB of memory, if
+    /// set.
+    pub fn memory_child(self, name: &str, kib: Option<u64>) -> Element {
+        self.child_opt(kib.map(|k| Element::new(name).attr("unit", "KiB").text(k)))
+    }
+
+    /// Whatever is left of the element once a model took the
+    /// attributes and children it knows about.
+    pub fn into_extra(self) -> Extra {
+        Extra {
+            remainders: self.remainders,
+            attributes: self.attributes,
+            children: self.children
+                .into_iter()
+                .filter_map(|n| match n {
+                    Node::Element(e) => Some(e),
+                    Node::Text(_) => None,
+                })
+                .collect(),
+        }
+    }
+
+    fn write(&self, f: &mut Formatter, indent: usize) -> fmt::Result {
+        write!(f, "{:2$}<{}", "", self.name, indent)?;
+        for (name, value) in &self.attributes {
+            write!(f, " {}='{}'", name, escape(value))?;
+        }
+        if self.children.is_empty() {
+            return write!(f, "/>");
+        }
+        // Text is written as is, indenting children of an element
+        // holding text would change it.
+        if self.children.iter().any(|n| matches!(*n, Node::Text(_))) {
+            write!(f, ">")?;
+            for node in &self.children {
+                match *node {
+                    Node::Element(ref e) => e.write(f, 0)?,
+                    Node::Text(ref t) => write!(f, "{}", escape(t))?,
+                }
+            }
+            return write!(f, "</{}>", self.name);
+        }
+        writeln!(f, ">")?;
+        for e in self.elements() {
+            e.write(f, indent + 2)?;
+            writeln!(f)?;
+        }
+        write!(f, "{:2$}</{}>", "", self.name, indent)
+    }
+}
+
+/// Writes the element and its children indented by two spaces.
+impl Display for Element {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl FromStr for Element {
+    type Err = Error;
+
+    fn from_str(xml: &str) -> Result<Element, Error> {
+        let config = ParserConfig::new()
+            .trim_whitespace(false)
+            .whitespace_to_characters(true)
+            .cdata_to_characters(true)
+            .coalesce_characters(true)
+            .ignore_comments(true);
+        let mut stack: Vec<(Element, BTreeMap<String, String>)> = Vec::new();
+        for event in EventReader::new_with_config(xml.as_bytes(), config) {
+            match event.map_err(|e| invalid(&e.to_string()))? {
+                XmlEvent::StartElement { name, attributes, namespace } => {
+                    let mut el = Element::new(&qualified(&name));
+                    {
+                        let parent = stack.last().map(|s| &s.1);
+                        for (prefix, uri) in &namespace.0 {
+                            if prefix == "xml" || prefix == "xmlns" ||
+                               parent.and_then(|p| p.get(prefix)) == Some(uri) ||
+                               (prefix.is_empty() && uri.is_empty() && parent.is_none()) {
+                                continue;
+                            }
+                            let attr = if prefix.is_empty() {
+                                String::from("xmlns")
+                            } else {
+                                format!("xmlns:{}", prefix)
+                            };
+                            el.attributes.push((attr, uri.clone()));
+                        }
+                    }
+                    for attr in attributes {
+                        el.attributes.push((qualified(&attr.name), attr.value));
+                    }
+                    stack.push((el, namespace.0));
+                }
+                XmlEvent::EndElement { .. } => {
+                    let (mut el, _) = stack.pop().unwrap();
+                    if !el.elements().is_empty() {
+                        el.children.retain(|n| match *n {
+                            Node::Text(ref t) => !t.trim().is_empty(),
+                            Node::Element(_) => true,
+                        });
+                    }
+                    match stack.last_mut() {
+                        Some(parent) => parent.0.children.push(Node::Element(el)),
+                        None => return Ok(el),
+                    }
+                }
+                XmlEvent::Characters(text) => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.0.children.push(Node::Text(text));
+                    }
+                }
+                _ => (),
+            }
+        }
+        Err(invalid("no root element"))
+    }
+}
+
+/// Attributes and children of an element which are not part of a
+/// typed model.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Extra {
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Unknown parts of children the model only partly took, like
+    /// the attributes of `<memory>` besides its unit. Each one is
+    /// merged back into the child of the same name and position
+    /// among the children of that name written by the model.
+    pub remainders: Vec<(usize, Element)>,
+}
+
+impl Extra {
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty() && self.remainders.is_empty()
+    }
+}
+
+/// Conversion between a typed model and its XML element.
+pub trait XmlElement: Sized {
+    /// Build the model from `el`, whatever the model does not know
+    /// about is kept aside.
+    fn from_element(el: Element) -> Result<Self, Error>;
+
+    fn to_element(&self) -> Element;
+}
+
+fn qualified(name: &OwnedName) -> String {
+    match name.prefix {
+        Some(ref prefix) => format!("{}:{}", prefix, name.local_name),
+        None => name.local_name.clone(),
+    }
+}
+
+pub(crate) fn parse_value<T: FromStr>(element: &str, name: &str, value: &str) -> Result<T, Error> {
+    value.trim().parse().map_err(|_| {
+        invalid(&format!("unexpected value '{}' for '{}' of <{}>", value, name, element))
+    })
+}
+
+/// Writes a boolean the way most of the libvirt attributes expect.
+pub(crate) fn yes_no(value: Option<bool>) -> Option<&'static str> {
+    value.map(|v| if v { "yes" } else { "no" })
+}
+
+/// Writes a boolean for the attributes expecting `on` or `off`.
+pub(crate) fn on_off(value: Option<bool>) -> Option<&'static str> {
+    value.map(|v| if v { "on" } else { "off" })
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+/// Converts an amount expressed in `unit` to bytes, `unit` being
+/// one of the scaled integer units of libvirt like `KiB`, `MB` or
+/// `G`.
+pub fn to_bytes(value: u64, unit: &str) -> Result<u64, Error> {
+    let (base, exp): (u64, u32) = match unit {
+        "b" | "B" | "byte" | "bytes" => (1, 0),
+        "KB" => (1000, 1),
+        "k" | "K" | "KiB" => (1024, 1),
+        "MB" => (1000, 2),
+        "m" | "M" | "MiB" => (1024, 2),
+        "GB" => (1000, 3),
+        "g" | "G" | "GiB" => (1024, 3),
+        "TB" => (1000, 4),
+        "t" | "T" | "TiB" => (1024, 4),
+        "PB" => (1000, 5),
+        "p" | "P" | "PiB" => (1024, 5),
+        "EB" => (1000, 6),
+        "e" | "E" | "EiB" => (1024, 6),
+        unit => return Err(invalid(&format!("unknown unit '{}'", unit))),
+    };
+    base.checked_pow(exp)
+        .and_then(|mul| value.checked_mul(mul))
+        .ok_or_else(|| invalid(&format!("{} {} is too large", value, unit)))
+}
+
+/// Converts an amount of memory expressed in `unit` to KiB, the unit
+/// libvirt uses in its own output. Like libvirt, partial KiB are
+/// rounded up.
+pub fn to_kib(value: u64, unit: Option<&str>) -> Result<u64, Error> {
+    match unit {
+        None | Some("k") | Some("K") | Some("KiB") => Ok(value),
+        Some(unit) => to_bytes(value, unit).map(|b| b / 1024 + (b % 1024 != 0) as u64),
+    }
+}
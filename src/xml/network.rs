@@ -0,0 +1,844 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the network XML format.
+//!
+//! See http://libvirt.org/formatnetwork.html
+//!
+//! ```
+//! use virt::xml::network::{DhcpHost, Ip, NetworkDef};
+//!
+//! let def = NetworkDef::new("default")
+//!     .forward("nat")
+//!     .bridge("virbr0")
+//!     .ip(Ip::new("192.168.122.1", 24)
+//!         .dhcp_range("192.168.122.2", "192.168.122.254")
+//!         .dhcp_host(DhcpHost::new("52:54:00:6c:3c:01", "192.168.122.10").name("web")));
+//!
+//! let parsed: NetworkDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! assert_eq!(1, parsed.dhcp_hosts().len());
+//! ```
+
+use error::Error;
+use network::{Network, NetworkXMLFlags};
+use xml::{on_off, yes_no, Element, Extra, Node, XmlElement};
+
+/// A virtual network definition as used by `Network::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkDef {
+    pub name: String,
+    pub uuid: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub forward: Option<Forward>,
+    pub bridge: Option<Bridge>,
+    pub mtu: Option<u32>,
+    pub mac: Option<String>,
+    /// DNS domain of the DHCP clients, `(name, local_only)`.
+    pub domain: Option<(String, Option<bool>)>,
+    pub dns: Option<Dns>,
+    pub ips: Vec<Ip>,
+    pub routes: Vec<Route>,
+    pub portgroups: Vec<Portgroup>,
+    pub virtualport: Option<VirtualPort>,
+    pub bandwidth: Option<Bandwidth>,
+    pub extra: Extra,
+}
+
+impl NetworkDef {
+    pub fn new(name: &str) -> NetworkDef {
+        NetworkDef { name: name.to_string(), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `network`.
+    pub fn from_network(network: &Network, flags: NetworkXMLFlags) -> Result<NetworkDef, Error> {
+        network.get_xml_desc(flags)?.parse()
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> NetworkDef {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    /// Set the forward mode like `nat`, `route`, `open`, `bridge`
+    /// or `hostdev`.
+    pub fn forward(mut self, mode: &str) -> NetworkDef {
+        self.forward = Some(Forward { mode: Some(mode.to_string()), ..Default::default() });
+        self
+    }
+
+    /// Set the name of the bridge device created for the network.
+    pub fn bridge(mut self, name: &str) -> NetworkDef {
+        self.bridge = Some(Bridge { name: Some(name.to_string()), ..Default::default() });
+        self
+    }
+
+    pub fn mtu(mut self, size: u32) -> NetworkDef {
+        self.mtu = Some(size);
+        self
+    }
+
+    pub fn mac(mut self, mac: &str) -> NetworkDef {
+        self.mac = Some(mac.to_string());
+        self
+    }
+
+    pub fn domain(mut self, name: &str) -> NetworkDef {
+        self.domain = Some((name.to_string(), None));
+        self
+    }
+
+    pub fn ip(mut self, ip: Ip) -> NetworkDef {
+        self.ips.push(ip);
+        self
+    }
+
+    pub fn route(mut self, route: Route) -> NetworkDef {
+        self.routes.push(route);
+        self
+    }
+
+    pub fn dns_host(mut self, host: DnsHost) -> NetworkDef {
+        self.dns.get_or_insert_with(Dns::default).hosts.push(host);
+        self
+    }
+
+    pub fn portgroup(mut self, portgroup: Portgroup) -> NetworkDef {
+        self.portgroups.push(portgroup);
+        self
+    }
+
+    pub fn virtualport(mut self, virtualport: VirtualPort) -> NetworkDef {
+        self.virtualport = Some(virtualport);
+        self
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> NetworkDef {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+
+    /// The static DHCP hosts of all the IP ranges of the network.
+    pub fn dhcp_hosts(&self) -> Vec<&DhcpHost> {
+        self.ips
+            .iter()
+            .filter_map(|ip| ip.dhcp.as_ref())
+            .flat_map(|dhcp| dhcp.hosts.iter())
+            .collect()
+    }
+
+    /// The portgroup used by interfaces not asking for one.
+    pub fn default_portgroup(&self) -> Option<&Portgroup> {
+        self.portgroups.iter().find(|p| p.default == Some(true))
+    }
+}
+
+impl XmlElement for NetworkDef {
+    fn from_element(mut el: Element) -> Result<NetworkDef, Error> {
+        let domain = match el.take("domain") {
+            Some(mut d) => {
+                let name = d.take_attr("name").unwrap_or_default();
+                Some((name, d.take_bool("localOnly")?))
+            }
+            None => None,
+        };
+        Ok(NetworkDef {
+            name: el.take_text("name").unwrap_or_default(),
+            uuid: el.take_text("uuid"),
+            title: el.take_text("title"),
+            description: el.take_text("description"),
+            forward: el.take_model("forward")?,
+            bridge: el.take_model("bridge")?,
+            mtu: el.take_child_parse("mtu", "size")?,
+            mac: el.take_child_attr("mac", "address"),
+            domain,
+            dns: el.take_model("dns")?,
+            ips: el.take_models("ip")?,
+            routes: el.take_models("route")?,
+            portgroups: el.take_models("portgroup")?,
+            virtualport: el.take_model("virtualport")?,
+            bandwidth: el.take_model("bandwidth")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let domain = self.domain.as_ref().map(|&(ref name, local_only)| {
+            Element::new("domain").attr("name", name).attr_opt("localOnly", yes_no(local_only))
+        });
+        Element::new("network")
+            .child(Element::new("name").text(&self.name))
+            .text_child("uuid", self.uuid.as_ref())
+            .text_child("title", self.title.as_ref())
+            .text_child("description", self.description.as_ref())
+            .model(self.forward.as_ref())
+            .model(self.bridge.as_ref())
+            .attr_child("mtu", "size", self.mtu)
+            .attr_child("mac", "address", self.mac.as_ref())
+            .child_opt(domain)
+            .model(self.dns.as_ref())
+            .models(&self.ips)
+            .models(&self.routes)
+            .models(&self.portgroups)
+            .model(self.virtualport.as_ref())
+            .model(self.bandwidth.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(NetworkDef);
+
+/// How the traffic of the network reaches the outside.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Forward {
+    /// Like `nat`, `route`, `open`, `bridge`, `passthrough` or
+    /// `hostdev`, no forward mode means an isolated network.
+    pub mode: Option<String>,
+    pub dev: Option<String>,
+    /// Devices of the pool of `<interface>` elements.
+    pub interfaces: Vec<String>,
+    /// Physical function of an SR-IOV network.
+    pub pf: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Forward {
+    fn from_element(mut el: Element) -> Result<Forward, Error> {
+        let mut interfaces = Vec::new();
+        for mut iface in el.take_all("interface") {
+            match iface.take_attr("dev") {
+                Some(dev) => {
+                    el.keep_rest(interfaces.len(), iface);
+                    interfaces.push(dev);
+                }
+                None => el.children.push(Node::Element(iface)),
+            }
+        }
+        Ok(Forward {
+            mode: el.take_attr("mode"),
+            dev: el.take_attr("dev"),
+            interfaces,
+            pf: el.take_child_attr("pf", "dev"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("forward")
+            .attr_opt("mode", self.mode.as_ref())
+            .attr_opt("dev", self.dev.as_ref())
+            .attr_child("pf", "dev", self.pf.as_ref());
+        for dev in &self.interfaces {
+            el = el.child(Element::new("interface").attr("dev", dev));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bridge {
+    pub name: Option<String>,
+    /// Whether the Spanning Tree Protocol is enabled.
+    pub stp: Option<bool>,
+    /// Forward delay, in seconds.
+    pub delay: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Bridge {
+    fn from_element(mut el: Element) -> Result<Bridge, Error> {
+        Ok(Bridge {
+            name: el.take_attr("name"),
+            stp: el.take_bool("stp")?,
+            delay: el.take_parse("delay")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("bridge")
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("stp", on_off(self.stp))
+            .attr_opt("delay", self.delay)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dns {
+    pub enable: Option<bool>,
+    /// Addresses of the upstream servers.
+    pub forwarders: Vec<String>,
+    pub txts: Vec<DnsTxt>,
+    pub hosts: Vec<DnsHost>,
+    pub srvs: Vec<DnsSrv>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Dns {
+    fn from_element(mut el: Element) -> Result<Dns, Error> {
+        // Forwarders limited to a domain, or without address to
+        // resolve a domain locally, are kept as is.
+        let mut forwarders = Vec::new();
+        for mut forwarder in el.take_all("forwarder") {
+            match forwarder.take_attr("addr") {
+                Some(addr) => {
+                    el.keep_rest(forwarders.len(), forwarder);
+                    forwarders.push(addr);
+                }
+                None => el.children.push(Node::Element(forwarder)),
+            }
+        }
+        Ok(Dns {
+            enable: el.take_bool("enable")?,
+            forwarders,
+            txts: el.take_models("txt")?,
+            hosts: el.take_models("host")?,
+            srvs: el.take_models("srv")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("dns").attr_opt("enable", yes_no(self.enable));
+        for addr in &self.forwarders {
+            el = el.child(Element::new("forwarder").attr("addr", addr));
+        }
+        el.models(&self.txts)
+            .models(&self.hosts)
+            .models(&self.srvs)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DnsTxt {
+    pub name: String,
+    pub value: String,
+    pub extra: Extra,
+}
+
+impl DnsTxt {
+    pub fn new(name: &str, value: &str) -> DnsTxt {
+        DnsTxt { name: name.to_string(), value: value.to_string(), ..Default::default() }
+    }
+}
+
+impl XmlElement for DnsTxt {
+    fn from_element(mut el: Element) -> Result<DnsTxt, Error> {
+        Ok(DnsTxt {
+            name: el.take_attr("name").unwrap_or_default(),
+            value: el.take_attr("value").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("txt")
+            .attr("name", &self.name)
+            .attr("value", &self.value)
+            .extra(&self.extra)
+    }
+}
+
+/// Names resolved to `ip` by the DNS server of the network.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DnsHost {
+    pub ip: String,
+    pub hostnames: Vec<String>,
+    pub extra: Extra,
+}
+
+impl DnsHost {
+    pub fn new(ip: &str, hostnames: &[&str]) -> DnsHost {
+        DnsHost {
+            ip: ip.to_string(),
+            hostnames: hostnames.iter().map(|h| h.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for DnsHost {
+    fn from_element(mut el: Element) -> Result<DnsHost, Error> {
+        Ok(DnsHost {
+            ip: el.take_attr("ip").unwrap_or_default(),
+            hostnames: el.take_all("hostname").iter().map(|h| h.get_text()).collect(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("host").attr("ip", &self.ip);
+        for hostname in &self.hostnames {
+            el = el.child(Element::new("hostname").text(hostname));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DnsSrv {
+    pub service: String,
+    /// Either `tcp` or `udp`.
+    pub protocol: String,
+    pub domain: Option<String>,
+    pub target: Option<String>,
+    pub port: Option<u16>,
+    pub priority: Option<u16>,
+    pub weight: Option<u16>,
+    pub extra: Extra,
+}
+
+impl DnsSrv {
+    pub fn new(service: &str, protocol: &str) -> DnsSrv {
+        DnsSrv {
+            service: service.to_string(),
+            protocol: protocol.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the host and port providing the service.
+    pub fn target(mut self, target: &str, port: u16) -> DnsSrv {
+        self.target = Some(target.to_string());
+        self.port = Some(port);
+        self
+    }
+}
+
+impl XmlElement for DnsSrv {
+    fn from_element(mut el: Element) -> Result<DnsSrv, Error> {
+        Ok(DnsSrv {
+            service: el.take_attr("service").unwrap_or_default(),
+            protocol: el.take_attr("protocol").unwrap_or_default(),
+            domain: el.take_attr("domain"),
+            target: el.take_attr("target"),
+            port: el.take_parse("port")?,
+            priority: el.take_parse("priority")?,
+            weight: el.take_parse("weight")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("srv")
+            .attr("service", &self.service)
+            .attr("protocol", &self.protocol)
+            .attr_opt("domain", self.domain.as_ref())
+            .attr_opt("target", self.target.as_ref())
+            .attr_opt("port", self.port)
+            .attr_opt("priority", self.priority)
+            .attr_opt("weight", self.weight)
+            .extra(&self.extra)
+    }
+}
+
+/// Address of the host on the network, with the DHCP server
+/// handing out addresses of the same subnet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ip {
+    /// Either `ipv4` or `ipv6`, `ipv4` when not set.
+    pub family: Option<String>,
+    pub address: Option<String>,
+    pub prefix: Option<u8>,
+    pub netmask: Option<String>,
+    pub dhcp: Option<Dhcp>,
+    pub extra: Extra,
+}
+
+impl Ip {
+    /// An IPv4 `address` of the subnet of `prefix` bits.
+    pub fn new(address: &str, prefix: u8) -> Ip {
+        Ip { address: Some(address.to_string()), prefix: Some(prefix), ..Default::default() }
+    }
+
+    /// An IPv6 `address` of the subnet of `prefix` bits.
+    pub fn ipv6(address: &str, prefix: u8) -> Ip {
+        Ip { family: Some(String::from("ipv6")), ..Ip::new(address, prefix) }
+    }
+
+    pub fn dhcp_range(mut self, start: &str, end: &str) -> Ip {
+        self.dhcp.get_or_insert_with(Dhcp::default).ranges.push(DhcpRange::new(start, end));
+        self
+    }
+
+    pub fn dhcp_host(mut self, host: DhcpHost) -> Ip {
+        self.dhcp.get_or_insert_with(Dhcp::default).hosts.push(host);
+        self
+    }
+}
+
+impl XmlElement for Ip {
+    fn from_element(mut el: Element) -> Result<Ip, Error> {
+        Ok(Ip {
+            family: el.take_attr("family"),
+            address: el.take_attr("address"),
+            prefix: el.take_parse("prefix")?,
+            netmask: el.take_attr("netmask"),
+            dhcp: el.take_model("dhcp")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("ip")
+            .attr_opt("family", self.family.as_ref())
+            .attr_opt("address", self.address.as_ref())
+            .attr_opt("prefix", self.prefix)
+            .attr_opt("netmask", self.netmask.as_ref())
+            .model(self.dhcp.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dhcp {
+    pub ranges: Vec<DhcpRange>,
+    pub hosts: Vec<DhcpHost>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Dhcp {
+    fn from_element(mut el: Element) -> Result<Dhcp, Error> {
+        Ok(Dhcp {
+            ranges: el.take_models("range")?,
+            hosts: el.take_models("host")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("dhcp")
+            .models(&self.ranges)
+            .models(&self.hosts)
+            .extra(&self.extra)
+    }
+}
+
+/// Addresses handed out dynamically by the DHCP server.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DhcpRange {
+    pub start: String,
+    pub end: String,
+    pub extra: Extra,
+}
+
+impl DhcpRange {
+    pub fn new(start: &str, end: &str) -> DhcpRange {
+        DhcpRange { start: start.to_string(), end: end.to_string(), ..Default::default() }
+    }
+}
+
+impl XmlElement for DhcpRange {
+    fn from_element(mut el: Element) -> Result<DhcpRange, Error> {
+        Ok(DhcpRange {
+            start: el.take_attr("start").unwrap_or_default(),
+            end: el.take_attr("end").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("range")
+            .attr("start", &self.start)
+            .attr("end", &self.end)
+            .extra(&self.extra)
+    }
+}
+
+/// Address reserved by the DHCP server for a host, identified by
+/// its MAC address for IPv4 or by its DUID for IPv6.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DhcpHost {
+    pub mac: Option<String>,
+    /// DHCP unique identifier of an IPv6 host.
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub ip: Option<String>,
+    pub extra: Extra,
+}
+
+impl DhcpHost {
+    /// Reserve `ip` for the host of MAC address `mac`.
+    pub fn new(mac: &str, ip: &str) -> DhcpHost {
+        DhcpHost { mac: Some(mac.to_string()), ip: Some(ip.to_string()), ..Default::default() }
+    }
+
+    /// Set the hostname given to the host.
+    pub fn name(mut self, name: &str) -> DhcpHost {
+        self.name = Some(name.to_string());
+        self
+    }
+}
+
+impl XmlElement for DhcpHost {
+    fn from_element(mut el: Element) -> Result<DhcpHost, Error> {
+        Ok(DhcpHost {
+            mac: el.take_attr("mac"),
+            id: el.take_attr("id"),
+            name: el.take_attr("name"),
+            ip: el.take_attr("ip"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("host")
+            .attr_opt("mac", self.mac.as_ref())
+            .attr_opt("id", self.id.as_ref())
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("ip", self.ip.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Static route added on the host.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Route {
+    pub family: Option<String>,
+    pub address: String,
+    pub prefix: Option<u8>,
+    pub netmask: Option<String>,
+    pub gateway: String,
+    pub metric: Option<u32>,
+    pub extra: Extra,
+}
+
+impl Route {
+    /// Route the subnet `address`/`prefix` through `gateway`.
+    pub fn new(address: &str, prefix: u8, gateway: &str) -> Route {
+        Route {
+            address: address.to_string(),
+            prefix: Some(prefix),
+            gateway: gateway.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Route {
+    fn from_element(mut el: Element) -> Result<Route, Error> {
+        Ok(Route {
+            family: el.take_attr("family"),
+            address: el.take_attr("address").unwrap_or_default(),
+            prefix: el.take_parse("prefix")?,
+            netmask: el.take_attr("netmask"),
+            gateway: el.take_attr("gateway").unwrap_or_default(),
+            metric: el.take_parse("metric")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("route")
+            .attr_opt("family", self.family.as_ref())
+            .attr("address", &self.address)
+            .attr_opt("prefix", self.prefix)
+            .attr_opt("netmask", self.netmask.as_ref())
+            .attr("gateway", &self.gateway)
+            .attr_opt("metric", self.metric)
+            .extra(&self.extra)
+    }
+}
+
+/// Set of settings applied to the interfaces asking for it by
+/// name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Portgroup {
+    pub name: String,
+    pub default: Option<bool>,
+    pub virtualport: Option<VirtualPort>,
+    pub bandwidth: Option<Bandwidth>,
+    pub extra: Extra,
+}
+
+impl Portgroup {
+    pub fn new(name: &str) -> Portgroup {
+        Portgroup { name: name.to_string(), ..Default::default() }
+    }
+
+    /// Use the portgroup for the interfaces not asking for one.
+    pub fn as_default(mut self) -> Portgroup {
+        self.default = Some(true);
+        self
+    }
+
+    pub fn virtualport(mut self, virtualport: VirtualPort) -> Portgroup {
+        self.virtualport = Some(virtualport);
+        self
+    }
+
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Portgroup {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+}
+
+impl XmlElement for Portgroup {
+    fn from_element(mut el: Element) -> Result<Portgroup, Error> {
+        Ok(Portgroup {
+            name: el.take_attr("name").unwrap_or_default(),
+            default: el.take_bool("default")?,
+            virtualport: el.take_model("virtualport")?,
+            bandwidth: el.take_model("bandwidth")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("portgroup")
+            .attr("name", &self.name)
+            .attr_opt("default", yes_no(self.default))
+            .model(self.virtualport.as_ref())
+            .model(self.bandwidth.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Port of a virtual switch like Open vSwitch or of an 802.1Qbg
+/// or 802.1Qbh capable switch.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VirtualPort {
+    /// Like `openvswitch`, `802.1Qbg` or `802.1Qbh`.
+    pub kind: Option<String>,
+    /// Attributes of `<parameters>` like `profileid` or
+    /// `interfaceid`.
+    pub parameters: Vec<(String, String)>,
+    pub extra: Extra,
+}
+
+impl VirtualPort {
+    pub fn new(kind: &str) -> VirtualPort {
+        VirtualPort { kind: Some(kind.to_string()), ..Default::default() }
+    }
+
+    pub fn parameter(mut self, name: &str, value: &str) -> VirtualPort {
+        self.parameters.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl XmlElement for VirtualPort {
+    fn from_element(mut el: Element) -> Result<VirtualPort, Error> {
+        let parameters = match el.take("parameters") {
+            Some(p) => p.attributes,
+            None => Vec::new(),
+        };
+        Ok(VirtualPort {
+            kind: el.take_attr("type"),
+            parameters,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let parameters = if self.parameters.is_empty() {
+            None
+        } else {
+            let mut el = Element::new("parameters");
+            el.attributes = self.parameters.clone();
+            Some(el)
+        };
+        Element::new("virtualport")
+            .attr_opt("type", self.kind.as_ref())
+            .child_opt(parameters)
+            .extra(&self.extra)
+    }
+}
+
+/// Quality of service of the traffic, as seen from the guests.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bandwidth {
+    pub inbound: Option<BandwidthRate>,
+    pub outbound: Option<BandwidthRate>,
+    pub extra: Extra,
+}
+
+impl Bandwidth {
+    pub fn new(inbound: Option<BandwidthRate>, outbound: Option<BandwidthRate>) -> Bandwidth {
+        Bandwidth { inbound, outbound, ..Default::default() }
+    }
+}
+
+impl XmlElement for Bandwidth {
+    fn from_element(mut el: Element) -> Result<Bandwidth, Error> {
+        Ok(Bandwidth {
+            inbound: el.take_model("inbound")?,
+            outbound: el.take_model("outbound")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("bandwidth")
+            .child_opt(self.inbound.as_ref().map(|r| r.to_named("inbound")))
+            .child_opt(self.outbound.as_ref().map(|r| r.to_named("outbound")))
+            .extra(&self.extra)
+    }
+}
+
+/// Rates in KiB/s and sizes in KiB of one direction of the traffic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BandwidthRate {
+    pub average: Option<u64>,
+    pub peak: Option<u64>,
+    pub burst: Option<u64>,
+    /// Guaranteed rate, for inbound traffic only.
+    pub floor: Option<u64>,
+    pub extra: Extra,
+}
+
+impl BandwidthRate {
+    pub fn new(average: u64) -> BandwidthRate {
+        BandwidthRate { average: Some(average), ..Default::default() }
+    }
+
+    pub fn peak(mut self, peak: u64, burst: u64) -> BandwidthRate {
+        self.peak = Some(peak);
+        self.burst = Some(burst);
+        self
+    }
+
+    pub fn to_named(&self, name: &str) -> Element {
+        Element::new(name)
+            .attr_opt("average", self.average)
+            .attr_opt("peak", self.peak)
+            .attr_opt("burst", self.burst)
+            .attr_opt("floor", self.floor)
+            .extra(&self.extra)
+    }
+}
+
+impl XmlElement for BandwidthRate {
+    fn from_element(mut el: Element) -> Result<BandwidthRate, Error> {
+        Ok(BandwidthRate {
+            average: el.take_parse("average")?,
+            peak: el.take_parse("peak")?,
+            burst: el.take_parse("burst")?,
+            floor: el.take_parse("floor")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    /// Writes an `<inbound>` element, see `to_named`.
+    fn to_element(&self) -> Element {
+        self.to_named("inbound")
+    }
+}
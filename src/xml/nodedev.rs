@@ -0,0 +1,908 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the node device XML format.
+//!
+//! See http://libvirt.org/formatnode.html
+//!
+//! ```
+//! use virt::xml::nodedev::NodeDeviceInfo;
+//! use virt::xml::domain::Hostdev;
+//!
+//! let info: NodeDeviceInfo = "<device>
+//!                               <name>pci_0000_01_00_0</name>
+//!                               <parent>pci_0000_00_01_0</parent>
+//!                               <capability type='pci'>
+//!                                 <domain>0</domain>
+//!                                 <bus>1</bus>
+//!                                 <slot>0</slot>
+//!                                 <function>0</function>
+//!                                 <iommuGroup number='25'/>
+//!                               </capability>
+//!                             </device>"
+//!     .parse()
+//!     .unwrap();
+//! let pci = info.pci().unwrap();
+//! assert_eq!("0000:01:00.0", pci.address.to_string());
+//! assert_eq!(Some(25), pci.iommu_group.as_ref().map(|g| g.number));
+//! let hostdev = Hostdev::pci(pci.address.to_address());
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use error::Error;
+use nodedev::{NodeDevice, NodeDeviceXMLFlags};
+use xml::domain::Address;
+use xml::{invalid, parse_value, Element, Extra, Node, XmlElement};
+
+/// A host device as returned by `NodeDevice::get_xml_desc`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeDeviceInfo {
+    pub name: String,
+    /// Path of the device in sysfs.
+    pub path: Option<String>,
+    /// Path of the device in `/dev`.
+    pub devnode: Option<String>,
+    pub parent: Option<String>,
+    /// Name of the driver bound to the device.
+    pub driver: Option<String>,
+    pub capabilities: Vec<NodeDeviceCap>,
+    pub extra: Extra,
+}
+
+impl NodeDeviceInfo {
+    /// Fetch and parse the description of `dev`.
+    pub fn from_device(dev: &NodeDevice,
+                       flags: NodeDeviceXMLFlags)
+                       -> Result<NodeDeviceInfo, Error> {
+        dev.get_xml_desc(flags)?.parse()
+    }
+
+    pub fn pci(&self) -> Option<&PciCap> {
+        self.capabilities.iter().filter_map(|c| match *c {
+            NodeDeviceCap::Pci(ref pci) => Some(pci),
+            _ => None,
+        }).next()
+    }
+
+    pub fn usb_device(&self) -> Option<&UsbDeviceCap> {
+        self.capabilities.iter().filter_map(|c| match *c {
+            NodeDeviceCap::UsbDevice(ref usb) => Some(usb),
+            _ => None,
+        }).next()
+    }
+
+    pub fn net(&self) -> Option<&NetCap> {
+        self.capabilities.iter().filter_map(|c| match *c {
+            NodeDeviceCap::Net(ref net) => Some(net),
+            _ => None,
+        }).next()
+    }
+
+    pub fn storage(&self) -> Option<&StorageCap> {
+        self.capabilities.iter().filter_map(|c| match *c {
+            NodeDeviceCap::Storage(ref storage) => Some(storage),
+            _ => None,
+        }).next()
+    }
+}
+
+impl XmlElement for NodeDeviceInfo {
+    fn from_element(mut el: Element) -> Result<NodeDeviceInfo, Error> {
+        Ok(NodeDeviceInfo {
+            name: el.take_text("name").unwrap_or_default(),
+            path: el.take_text("path"),
+            devnode: el.take_text("devnode"),
+            parent: el.take_text("parent"),
+            driver: el.take("driver").and_then(|mut d| d.take_text("name")),
+            capabilities: el.take_models("capability")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let devnode = self.devnode
+            .as_ref()
+            .map(|path| Element::new("devnode").attr("type", "dev").text(path));
+        let driver = self.driver
+            .as_ref()
+            .map(|name| Element::new("driver").child(Element::new("name").text(name)));
+        Element::new("device")
+            .child(Element::new("name").text(&self.name))
+            .text_child("path", self.path.as_ref())
+            .child_opt(devnode)
+            .text_child("parent", self.parent.as_ref())
+            .child_opt(driver)
+            .models(&self.capabilities)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(NodeDeviceInfo);
+
+/// Capability of a host device, the `type` of its `<capability>`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeDeviceCap {
+    Pci(PciCap),
+    UsbDevice(UsbDeviceCap),
+    UsbInterface(UsbInterfaceCap),
+    Net(NetCap),
+    ScsiHost(ScsiHostCap),
+    ScsiTarget(ScsiTargetCap),
+    Scsi(ScsiCap),
+    Storage(StorageCap),
+    Drm(DrmCap),
+    Mdev(MdevCap),
+    /// Capability not known by the binding, like `system` or `ccw`.
+    Other(Element),
+}
+
+impl XmlElement for NodeDeviceCap {
+    fn from_element(el: Element) -> Result<NodeDeviceCap, Error> {
+        let kind = el.get_attr("type").unwrap_or_default().to_string();
+        Ok(match kind.as_str() {
+            "pci" => NodeDeviceCap::Pci(PciCap::from_element(el)?),
+            "usb_device" => NodeDeviceCap::UsbDevice(UsbDeviceCap::from_element(el)?),
+            "usb" => NodeDeviceCap::UsbInterface(UsbInterfaceCap::from_element(el)?),
+            "net" => NodeDeviceCap::Net(NetCap::from_element(el)?),
+            "scsi_host" => NodeDeviceCap::ScsiHost(ScsiHostCap::from_element(el)?),
+            "scsi_target" => NodeDeviceCap::ScsiTarget(ScsiTargetCap::from_element(el)?),
+            "scsi" => NodeDeviceCap::Scsi(ScsiCap::from_element(el)?),
+            "storage" => NodeDeviceCap::Storage(StorageCap::from_element(el)?),
+            "drm" => NodeDeviceCap::Drm(DrmCap::from_element(el)?),
+            "mdev" => NodeDeviceCap::Mdev(MdevCap::from_element(el)?),
+            _ => NodeDeviceCap::Other(el),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        match *self {
+            NodeDeviceCap::Pci(ref c) => c.to_element(),
+            NodeDeviceCap::UsbDevice(ref c) => c.to_element(),
+            NodeDeviceCap::UsbInterface(ref c) => c.to_element(),
+            NodeDeviceCap::Net(ref c) => c.to_element(),
+            NodeDeviceCap::ScsiHost(ref c) => c.to_element(),
+            NodeDeviceCap::ScsiTarget(ref c) => c.to_element(),
+            NodeDeviceCap::Scsi(ref c) => c.to_element(),
+            NodeDeviceCap::Storage(ref c) => c.to_element(),
+            NodeDeviceCap::Drm(ref c) => c.to_element(),
+            NodeDeviceCap::Mdev(ref c) => c.to_element(),
+            NodeDeviceCap::Other(ref e) => e.clone(),
+        }
+    }
+}
+
+/// Parse `value`, either decimal or hexadecimal with a `0x`
+/// prefix.
+fn parse_number(element: &str, name: &str, value: &str) -> Result<u32, Error> {
+    let value = value.trim();
+    if value.starts_with("0x") || value.starts_with("0X") {
+        u32::from_str_radix(&value[2..], 16).map_err(|_| {
+            invalid(&format!("unexpected value '{}' for '{}' of <{}>", value, name, element))
+        })
+    } else {
+        parse_value(element, name, value)
+    }
+}
+
+/// Remove the first child element named `name` and parse its text
+/// as a number, 0 when missing.
+fn take_number(el: &mut Element, name: &str) -> Result<u32, Error> {
+    match el.take_text(name) {
+        Some(text) => parse_number(name, "text", &text),
+        None => Ok(0),
+    }
+}
+
+/// Remove the child elements `<capability>` of `el` and return them
+/// with their type.
+fn take_sub_capabilities(el: &mut Element) -> Vec<(String, Element)> {
+    el.take_all("capability")
+        .into_iter()
+        .map(|c| (c.get_attr("type").unwrap_or_default().to_string(), c))
+        .collect()
+}
+
+/// Vendor or product of a device, `id` being like `0x10de`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HardwareId {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+impl HardwareId {
+    fn take(el: &mut Element, name: &str) -> Option<HardwareId> {
+        el.take(name).map(|mut e| {
+            let text = e.get_text();
+            HardwareId {
+                id: e.take_attr("id").unwrap_or_default(),
+                name: if text.is_empty() { None } else { Some(text) },
+            }
+        })
+    }
+
+    fn to_named(&self, name: &str) -> Element {
+        let el = Element::new(name).attr("id", &self.id);
+        match self.name {
+            Some(ref text) => el.text(text),
+            None => el,
+        }
+    }
+}
+
+/// Address of a PCI device, displayed like `0000:01:00.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PciAddress {
+    pub domain: u32,
+    pub bus: u32,
+    pub slot: u32,
+    pub function: u32,
+}
+
+impl PciAddress {
+    /// The address of a host device in a domain `<hostdev>`.
+    pub fn to_address(&self) -> Address {
+        Address::pci(self.domain, self.bus, self.slot, self.function)
+    }
+}
+
+impl Display for PciAddress {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:04x}:{:02x}:{:02x}.{:x}", self.domain, self.bus, self.slot, self.function)
+    }
+}
+
+impl XmlElement for PciAddress {
+    fn from_element(el: Element) -> Result<PciAddress, Error> {
+        let attr = |name| parse_number("address", name, el.get_attr(name).unwrap_or("0"));
+        Ok(PciAddress {
+            domain: attr("domain")?,
+            bus: attr("bus")?,
+            slot: attr("slot")?,
+            function: attr("function")?,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("address")
+            .attr("domain", format!("{:#06x}", self.domain))
+            .attr("bus", format!("{:#04x}", self.bus))
+            .attr("slot", format!("{:#04x}", self.slot))
+            .attr("function", format!("{:#x}", self.function))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PciCap {
+    /// PCI class like `0x030000` for a VGA controller.
+    pub class: Option<String>,
+    pub address: PciAddress,
+    pub product: Option<HardwareId>,
+    pub vendor: Option<HardwareId>,
+    /// Physical function of an SR-IOV virtual function.
+    pub phys_function: Option<PciAddress>,
+    /// Virtual functions of an SR-IOV physical function.
+    pub virt_functions: Vec<PciAddress>,
+    pub max_virt_functions: Option<u32>,
+    /// Mediated device types the device can create.
+    pub mdev_types: Vec<MdevType>,
+    pub iommu_group: Option<IommuGroup>,
+    pub numa_node: Option<u32>,
+    /// Capabilities and status of the PCI Express link.
+    pub links: Vec<PciLink>,
+    pub extra: Extra,
+}
+
+impl PciCap {
+    /// Whether the device is the physical function of SR-IOV
+    /// virtual functions.
+    pub fn is_physical_function(&self) -> bool {
+        self.max_virt_functions.is_some() || !self.virt_functions.is_empty()
+    }
+
+    /// Number of instances still available for the mediated device
+    /// type `id`.
+    pub fn mdev_available(&self, id: &str) -> Option<u32> {
+        self.mdev_types.iter().find(|t| t.id == id).and_then(|t| t.available_instances)
+    }
+}
+
+impl XmlElement for PciCap {
+    fn from_element(mut el: Element) -> Result<PciCap, Error> {
+        el.take_attr("type");
+        let mut cap = PciCap {
+            class: el.take_text("class"),
+            address: PciAddress {
+                domain: take_number(&mut el, "domain")?,
+                bus: take_number(&mut el, "bus")?,
+                slot: take_number(&mut el, "slot")?,
+                function: take_number(&mut el, "function")?,
+            },
+            ..Default::default()
+        };
+        cap.product = HardwareId::take(&mut el, "product");
+        cap.vendor = HardwareId::take(&mut el, "vendor");
+        for (kind, mut sub) in take_sub_capabilities(&mut el) {
+            match kind.as_str() {
+                "phys_function" => {
+                    cap.phys_function = sub.take_model("address")?;
+                }
+                "virt_functions" => {
+                    cap.max_virt_functions = sub.take_parse("maxCount")?;
+                    cap.virt_functions = sub.take_models("address")?;
+                }
+                "mdev_types" => {
+                    cap.mdev_types = sub.take_models("type")?;
+                }
+                _ => el.children.push(Node::Element(sub)),
+            }
+        }
+        cap.iommu_group = el.take_model("iommuGroup")?;
+        cap.numa_node = el.take_child_parse("numa", "node")?;
+        if let Some(mut express) = el.take("pci-express") {
+            cap.links = express.take_models("link")?;
+            if !express.children.is_empty() {
+                el.children.push(Node::Element(express));
+            }
+        }
+        cap.extra = el.into_extra();
+        Ok(cap)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("capability")
+            .attr("type", "pci")
+            .text_child("class", self.class.as_ref())
+            .child(Element::new("domain").text(self.address.domain))
+            .child(Element::new("bus").text(self.address.bus))
+            .child(Element::new("slot").text(self.address.slot))
+            .child(Element::new("function").text(self.address.function))
+            .child_opt(self.product.as_ref().map(|p| p.to_named("product")))
+            .child_opt(self.vendor.as_ref().map(|v| v.to_named("vendor")));
+        if let Some(ref address) = self.phys_function {
+            el = el.child(Element::new("capability")
+                .attr("type", "phys_function")
+                .child(address.to_element()));
+        }
+        if self.max_virt_functions.is_some() || !self.virt_functions.is_empty() {
+            el = el.child(Element::new("capability")
+                .attr("type", "virt_functions")
+                .attr_opt("maxCount", self.max_virt_functions)
+                .models(&self.virt_functions));
+        }
+        if !self.mdev_types.is_empty() {
+            el = el.child(Element::new("capability")
+                .attr("type", "mdev_types")
+                .models(&self.mdev_types));
+        }
+        let express = if self.links.is_empty() {
+            None
+        } else {
+            Some(Element::new("pci-express").models(&self.links))
+        };
+        el.model(self.iommu_group.as_ref())
+            .attr_child("numa", "node", self.numa_node)
+            .child_opt(express)
+            .extra(&self.extra)
+    }
+}
+
+/// Devices which can only be assigned to guests together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IommuGroup {
+    pub number: u32,
+    pub members: Vec<PciAddress>,
+}
+
+impl XmlElement for IommuGroup {
+    fn from_element(mut el: Element) -> Result<IommuGroup, Error> {
+        Ok(IommuGroup {
+            number: el.take_parse("number")?.unwrap_or_default(),
+            members: el.take_models("address")?,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("iommuGroup").attr("number", self.number).models(&self.members)
+    }
+}
+
+/// PCI Express link, either its capability or its status.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PciLink {
+    /// Either `cap` or `sta`.
+    pub validity: String,
+    pub port: Option<u32>,
+    /// Speed per lane, in GT/s.
+    pub speed: Option<f64>,
+    /// Number of lanes.
+    pub width: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlElement for PciLink {
+    fn from_element(mut el: Element) -> Result<PciLink, Error> {
+        Ok(PciLink {
+            validity: el.take_attr("validity").unwrap_or_default(),
+            port: el.take_parse("port")?,
+            speed: el.take_parse("speed")?,
+            width: el.take_parse("width")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("link")
+            .attr("validity", &self.validity)
+            .attr_opt("port", self.port)
+            .attr_opt("speed", self.speed)
+            .attr_opt("width", self.width)
+            .extra(&self.extra)
+    }
+}
+
+/// Type of mediated device a parent device can create, like the
+/// vGPU profiles of a GPU.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MdevType {
+    pub id: String,
+    pub name: Option<String>,
+    /// Like `vfio-pci`.
+    pub device_api: Option<String>,
+    pub available_instances: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlElement for MdevType {
+    fn from_element(mut el: Element) -> Result<MdevType, Error> {
+        Ok(MdevType {
+            id: el.take_attr("id").unwrap_or_default(),
+            name: el.take_text("name"),
+            device_api: el.take_text("deviceAPI"),
+            available_instances: el.take_text_parse("availableInstances")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("type")
+            .attr("id", &self.id)
+            .text_child("name", self.name.as_ref())
+            .text_child("deviceAPI", self.device_api.as_ref())
+            .text_child("availableInstances", self.available_instances)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsbDeviceCap {
+    pub bus: u32,
+    pub device: u32,
+    pub product: Option<HardwareId>,
+    pub vendor: Option<HardwareId>,
+    pub extra: Extra,
+}
+
+impl XmlElement for UsbDeviceCap {
+    fn from_element(mut el: Element) -> Result<UsbDeviceCap, Error> {
+        el.take_attr("type");
+        Ok(UsbDeviceCap {
+            bus: el.take_text_parse("bus")?.unwrap_or_default(),
+            device: el.take_text_parse("device")?.unwrap_or_default(),
+            product: HardwareId::take(&mut el, "product"),
+            vendor: HardwareId::take(&mut el, "vendor"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "usb_device")
+            .child(Element::new("bus").text(self.bus))
+            .child(Element::new("device").text(self.device))
+            .child_opt(self.product.as_ref().map(|p| p.to_named("product")))
+            .child_opt(self.vendor.as_ref().map(|v| v.to_named("vendor")))
+            .extra(&self.extra)
+    }
+}
+
+/// Interface of a USB device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsbInterfaceCap {
+    pub number: Option<u32>,
+    pub class: Option<u32>,
+    pub subclass: Option<u32>,
+    pub protocol: Option<u32>,
+    pub description: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for UsbInterfaceCap {
+    fn from_element(mut el: Element) -> Result<UsbInterfaceCap, Error> {
+        el.take_attr("type");
+        Ok(UsbInterfaceCap {
+            number: el.take_text_parse("number")?,
+            class: el.take_text_parse("class")?,
+            subclass: el.take_text_parse("subclass")?,
+            protocol: el.take_text_parse("protocol")?,
+            description: el.take_text("description"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "usb")
+            .text_child("number", self.number)
+            .text_child("class", self.class)
+            .text_child("subclass", self.subclass)
+            .text_child("protocol", self.protocol)
+            .text_child("description", self.description.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetCap {
+    pub interface: String,
+    /// MAC address.
+    pub address: Option<String>,
+    /// Operational state like `up`, `down` or `unknown`.
+    pub link_state: Option<String>,
+    /// Speed of the link, in Mbit/s.
+    pub link_speed: Option<u32>,
+    /// Offloading features like `rx`, `tso` or `gro`.
+    pub features: Vec<String>,
+    pub extra: Extra,
+}
+
+impl NetCap {
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|f| f == name)
+    }
+}
+
+impl XmlElement for NetCap {
+    fn from_element(mut el: Element) -> Result<NetCap, Error> {
+        el.take_attr("type");
+        let (mut link_state, mut link_speed) = (None, None);
+        if let Some(mut link) = el.take("link") {
+            link_state = link.take_attr("state");
+            link_speed = link.take_parse("speed")?;
+            el.keep_rest(0, link);
+        }
+        let mut features = Vec::new();
+        for mut feature in el.take_all("feature") {
+            match feature.take_attr("name") {
+                Some(name) => {
+                    el.keep_rest(features.len(), feature);
+                    features.push(name);
+                }
+                None => el.children.push(Node::Element(feature)),
+            }
+        }
+        Ok(NetCap {
+            interface: el.take_text("interface").unwrap_or_default(),
+            address: el.take_text("address"),
+            link_state,
+            link_speed,
+            features,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let link = if self.link_state.is_some() || self.link_speed.is_some() {
+            Some(Element::new("link")
+                .attr_opt("speed", self.link_speed)
+                .attr_opt("state", self.link_state.as_ref()))
+        } else {
+            None
+        };
+        let mut el = Element::new("capability")
+            .attr("type", "net")
+            .child(Element::new("interface").text(&self.interface))
+            .text_child("address", self.address.as_ref())
+            .child_opt(link);
+        for name in &self.features {
+            el = el.child(Element::new("feature").attr("name", name));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScsiHostCap {
+    pub host: u32,
+    pub unique_id: Option<u32>,
+    pub fc_host: Option<FcHost>,
+    pub extra: Extra,
+}
+
+impl ScsiHostCap {
+    /// Whether NPIV virtual ports can be created on the HBA.
+    pub fn is_vport_capable(&self) -> bool {
+        self.extra.children.iter().any(|c| c.get_attr("type") == Some("vport_ops"))
+    }
+}
+
+impl XmlElement for ScsiHostCap {
+    fn from_element(mut el: Element) -> Result<ScsiHostCap, Error> {
+        el.take_attr("type");
+        let mut cap = ScsiHostCap {
+            host: el.take_text_parse("host")?.unwrap_or_default(),
+            unique_id: el.take_text_parse("unique_id")?,
+            ..Default::default()
+        };
+        for (kind, sub) in take_sub_capabilities(&mut el) {
+            if kind == "fc_host" && cap.fc_host.is_none() {
+                cap.fc_host = Some(FcHost::from_element(sub)?);
+            } else {
+                el.children.push(Node::Element(sub));
+            }
+        }
+        cap.extra = el.into_extra();
+        Ok(cap)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "scsi_host")
+            .child(Element::new("host").text(self.host))
+            .text_child("unique_id", self.unique_id)
+            .model(self.fc_host.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Fibre Channel HBA.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FcHost {
+    pub wwnn: String,
+    pub wwpn: String,
+    pub fabric_wwn: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for FcHost {
+    fn from_element(mut el: Element) -> Result<FcHost, Error> {
+        el.take_attr("type");
+        Ok(FcHost {
+            wwnn: el.take_text("wwnn").unwrap_or_default(),
+            wwpn: el.take_text("wwpn").unwrap_or_default(),
+            fabric_wwn: el.take_text("fabric_wwn"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "fc_host")
+            .child(Element::new("wwnn").text(&self.wwnn))
+            .child(Element::new("wwpn").text(&self.wwpn))
+            .text_child("fabric_wwn", self.fabric_wwn.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScsiTargetCap {
+    /// Name of the target like `target0:0:0`.
+    pub target: String,
+    pub extra: Extra,
+}
+
+impl XmlElement for ScsiTargetCap {
+    fn from_element(mut el: Element) -> Result<ScsiTargetCap, Error> {
+        el.take_attr("type");
+        Ok(ScsiTargetCap {
+            target: el.take_text("target").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "scsi_target")
+            .child(Element::new("target").text(&self.target))
+            .extra(&self.extra)
+    }
+}
+
+/// SCSI logical unit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScsiCap {
+    pub host: u32,
+    pub bus: u32,
+    pub target: u32,
+    pub lun: u32,
+    /// Like `disk`, `tape` or `cdrom`.
+    pub kind: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for ScsiCap {
+    fn from_element(mut el: Element) -> Result<ScsiCap, Error> {
+        el.take_attr("type");
+        Ok(ScsiCap {
+            host: el.take_text_parse("host")?.unwrap_or_default(),
+            bus: el.take_text_parse("bus")?.unwrap_or_default(),
+            target: el.take_text_parse("target")?.unwrap_or_default(),
+            lun: el.take_text_parse("lun")?.unwrap_or_default(),
+            kind: el.take_text("type"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "scsi")
+            .child(Element::new("host").text(self.host))
+            .child(Element::new("bus").text(self.bus))
+            .child(Element::new("target").text(self.target))
+            .child(Element::new("lun").text(self.lun))
+            .text_child("type", self.kind.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Block device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageCap {
+    /// Path of the block device like `/dev/sda`.
+    pub block: Option<String>,
+    pub bus: Option<String>,
+    pub drive_type: Option<String>,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub serial: Option<String>,
+    /// Size in bytes.
+    pub size: Option<u64>,
+    pub logical_block_size: Option<u64>,
+    pub num_blocks: Option<u64>,
+    /// Media of a removable drive like a CD-ROM.
+    pub removable: Option<RemovableMedia>,
+    pub extra: Extra,
+}
+
+impl XmlElement for StorageCap {
+    fn from_element(mut el: Element) -> Result<StorageCap, Error> {
+        el.take_attr("type");
+        let mut cap = StorageCap {
+            block: el.take_text("block"),
+            bus: el.take_text("bus"),
+            drive_type: el.take_text("drive_type"),
+            model: el.take_text("model"),
+            vendor: el.take_text("vendor"),
+            serial: el.take_text("serial"),
+            size: el.take_text_parse("size")?,
+            logical_block_size: el.take_text_parse("logical_block_size")?,
+            num_blocks: el.take_text_parse("num_blocks")?,
+            ..Default::default()
+        };
+        for (kind, sub) in take_sub_capabilities(&mut el) {
+            if kind == "removable" && cap.removable.is_none() {
+                cap.removable = Some(RemovableMedia::from_element(sub)?);
+            } else {
+                el.children.push(Node::Element(sub));
+            }
+        }
+        cap.extra = el.into_extra();
+        Ok(cap)
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "storage")
+            .text_child("block", self.block.as_ref())
+            .text_child("bus", self.bus.as_ref())
+            .text_child("drive_type", self.drive_type.as_ref())
+            .text_child("model", self.model.as_ref())
+            .text_child("vendor", self.vendor.as_ref())
+            .text_child("serial", self.serial.as_ref())
+            .text_child("size", self.size)
+            .text_child("logical_block_size", self.logical_block_size)
+            .text_child("num_blocks", self.num_blocks)
+            .model(self.removable.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RemovableMedia {
+    pub media_available: Option<bool>,
+    /// Size of the media, in bytes.
+    pub media_size: Option<u64>,
+    pub extra: Extra,
+}
+
+impl XmlElement for RemovableMedia {
+    fn from_element(mut el: Element) -> Result<RemovableMedia, Error> {
+        el.take_attr("type");
+        let available: Option<u32> = el.take_text_parse("media_available")?;
+        Ok(RemovableMedia {
+            media_available: available.map(|a| a != 0),
+            media_size: el.take_text_parse("media_size")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "removable")
+            .text_child("media_available", self.media_available.map(|a| a as u32))
+            .text_child("media_size", self.media_size)
+            .extra(&self.extra)
+    }
+}
+
+/// Direct Rendering Manager device of a GPU.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DrmCap {
+    /// Either `primary`, `control` or `render`.
+    pub kind: String,
+    pub extra: Extra,
+}
+
+impl XmlElement for DrmCap {
+    fn from_element(mut el: Element) -> Result<DrmCap, Error> {
+        el.take_attr("type");
+        Ok(DrmCap {
+            kind: el.take_text("type").unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "drm")
+            .child(Element::new("type").text(&self.kind))
+            .extra(&self.extra)
+    }
+}
+
+/// Mediated device created from a parent device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MdevCap {
+    /// Identifier of the `MdevType` of the device.
+    pub kind: String,
+    pub uuid: Option<String>,
+    pub iommu_group: Option<u32>,
+    pub extra: Extra,
+}
+
+impl XmlElement for MdevCap {
+    fn from_element(mut el: Element) -> Result<MdevCap, Error> {
+        el.take_attr("type");
+        Ok(MdevCap {
+            kind: el.take_child_attr("type", "id").unwrap_or_default(),
+            uuid: el.take_text("uuid"),
+            iommu_group: el.take_child_parse("iommuGroup", "number")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("capability")
+            .attr("type", "mdev")
+            .child(Element::new("type").attr("id", &self.kind))
+            .text_child("uuid", self.uuid.as_ref())
+            .attr_child("iommuGroup", "number", self.iommu_group)
+            .extra(&self.extra)
+    }
+}
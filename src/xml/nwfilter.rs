@@ -0,0 +1,552 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the network filter XML format.
+//!
+//! See http://libvirt.org/formatnwfilter.html
+//!
+//! ```
+//! use virt::xml::nwfilter::{FilterProtocol, NWFilterDef, Rule, RuleMatch};
+//!
+//! let def = NWFilterDef::new("allow-ssh")
+//!     .chain("ipv4")
+//!     .filterref("clean-traffic", &[("IP", "10.0.0.5")])
+//!     .rule(Rule::accept("in").matching(RuleMatch::new(FilterProtocol::Tcp)
+//!         .dst_port(22, None)))
+//!     .rule(Rule::drop("inout").matching(RuleMatch::new(FilterProtocol::All)));
+//!
+//! let parsed: NWFilterDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! assert_eq!(2, parsed.rules().len());
+//! ```
+
+use error::Error;
+use nwfilter::NWFilter;
+use nwfilter_binding::NWFilterBinding;
+use xml::{Element, Extra, Node, XmlElement};
+
+/// A network filter definition as used by `NWFilter::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NWFilterDef {
+    pub name: String,
+    pub uuid: Option<String>,
+    /// Chain the rules are added to, like `root`, `mac`, `ipv4` or
+    /// `arp-xyz`.
+    pub chain: Option<String>,
+    /// Order of the chain among the other chains, from -1000 to
+    /// 1000.
+    pub priority: Option<i32>,
+    /// Rules and references to other filters, in order.
+    pub entries: Vec<FilterEntry>,
+    pub extra: Extra,
+}
+
+impl NWFilterDef {
+    pub fn new(name: &str) -> NWFilterDef {
+        NWFilterDef { name: name.to_string(), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `filter`.
+    pub fn from_nwfilter(filter: &NWFilter) -> Result<NWFilterDef, Error> {
+        filter.get_xml_desc(0)?.parse()
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> NWFilterDef {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    pub fn chain(mut self, chain: &str) -> NWFilterDef {
+        self.chain = Some(chain.to_string());
+        self
+    }
+
+    pub fn priority(mut self, priority: i32) -> NWFilterDef {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn rule(mut self, rule: Rule) -> NWFilterDef {
+        self.entries.push(FilterEntry::Rule(rule));
+        self
+    }
+
+    /// Include the filter `filter`, with its variables set to
+    /// `parameters`.
+    pub fn filterref(mut self, filter: &str, parameters: &[(&str, &str)]) -> NWFilterDef {
+        self.entries.push(FilterEntry::FilterRef(FilterRef::new(filter, parameters)));
+        self
+    }
+
+    pub fn rules(&self) -> Vec<&Rule> {
+        self.entries
+            .iter()
+            .filter_map(|e| match *e {
+                FilterEntry::Rule(ref r) => Some(r),
+                FilterEntry::FilterRef(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn filterrefs(&self) -> Vec<&FilterRef> {
+        self.entries
+            .iter()
+            .filter_map(|e| match *e {
+                FilterEntry::FilterRef(ref f) => Some(f),
+                FilterEntry::Rule(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl XmlElement for NWFilterDef {
+    fn from_element(mut el: Element) -> Result<NWFilterDef, Error> {
+        let mut entries = Vec::new();
+        let mut children = Vec::new();
+        for child in el.children.drain(..) {
+            match child {
+                Node::Element(e) if e.name == "rule" || e.name == "filterref" => {
+                    entries.push(FilterEntry::from_element(e)?)
+                }
+                other => children.push(other),
+            }
+        }
+        el.children = children;
+        Ok(NWFilterDef {
+            name: el.take_attr("name").unwrap_or_default(),
+            uuid: el.take_text("uuid"),
+            chain: el.take_attr("chain"),
+            priority: el.take_parse("priority")?,
+            entries,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("filter")
+            .attr("name", &self.name)
+            .attr_opt("chain", self.chain.as_ref())
+            .attr_opt("priority", self.priority)
+            .text_child("uuid", self.uuid.as_ref())
+            .models(&self.entries)
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(NWFilterDef);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterEntry {
+    Rule(Rule),
+    FilterRef(FilterRef),
+}
+
+impl XmlElement for FilterEntry {
+    fn from_element(el: Element) -> Result<FilterEntry, Error> {
+        if el.name == "filterref" {
+            Ok(FilterEntry::FilterRef(FilterRef::from_element(el)?))
+        } else {
+            Ok(FilterEntry::Rule(Rule::from_element(el)?))
+        }
+    }
+
+    fn to_element(&self) -> Element {
+        match *self {
+            FilterEntry::Rule(ref r) => r.to_element(),
+            FilterEntry::FilterRef(ref f) => f.to_element(),
+        }
+    }
+}
+
+/// Reference to another filter whose rules are included.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FilterRef {
+    pub filter: String,
+    /// Values of the variables of the filter, like `IP`.
+    pub parameters: Vec<(String, String)>,
+    pub extra: Extra,
+}
+
+impl FilterRef {
+    pub fn new(filter: &str, parameters: &[(&str, &str)]) -> FilterRef {
+        FilterRef {
+            filter: filter.to_string(),
+            parameters: parameters.iter().map(|&(n, v)| (n.to_string(), v.to_string())).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Return the value of the variable `name`.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.iter().find(|p| p.0 == name).map(|p| p.1.as_str())
+    }
+}
+
+impl XmlElement for FilterRef {
+    fn from_element(mut el: Element) -> Result<FilterRef, Error> {
+        let mut parameters = Vec::new();
+        for mut param in el.take_all("parameter") {
+            let name = param.take_attr("name").unwrap_or_default();
+            parameters.push((name, param.take_attr("value").unwrap_or_default()));
+        }
+        Ok(FilterRef {
+            filter: el.take_attr("filter").unwrap_or_default(),
+            parameters,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("filterref").attr("filter", &self.filter);
+        for (name, value) in &self.parameters {
+            el = el.child(Element::new("parameter").attr("name", name).attr("value", value));
+        }
+        el.extra(&self.extra)
+    }
+}
+
+/// A binding of a filter to a port device as used by
+/// `NWFilterBinding::create_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NWFilterBindingDef {
+    /// Name of the guest owning the port.
+    pub owner_name: String,
+    /// UUID of the guest owning the port.
+    pub owner_uuid: String,
+    /// Host side device of the port, like `vnet0`.
+    pub port_dev: String,
+    /// MAC address of the guest interface.
+    pub mac: String,
+    /// Filter applied, with the values of its variables.
+    pub filter: FilterRef,
+    pub extra: Extra,
+}
+
+impl NWFilterBindingDef {
+    pub fn new(owner_name: &str,
+               owner_uuid: &str,
+               port_dev: &str,
+               mac: &str,
+               filter: FilterRef)
+               -> NWFilterBindingDef {
+        NWFilterBindingDef {
+            owner_name: owner_name.to_string(),
+            owner_uuid: owner_uuid.to_string(),
+            port_dev: port_dev.to_string(),
+            mac: mac.to_string(),
+            filter,
+            ..Default::default()
+        }
+    }
+
+    /// Fetch and parse the definition of `binding`.
+    pub fn from_binding(binding: &NWFilterBinding) -> Result<NWFilterBindingDef, Error> {
+        binding.get_xml_desc(0)?.parse()
+    }
+}
+
+impl XmlElement for NWFilterBindingDef {
+    fn from_element(mut el: Element) -> Result<NWFilterBindingDef, Error> {
+        let mut owner = el.take("owner").unwrap_or_default();
+        Ok(NWFilterBindingDef {
+            owner_name: owner.take_text("name").unwrap_or_default(),
+            owner_uuid: owner.take_text("uuid").unwrap_or_default(),
+            port_dev: el.take_child_attr("portdev", "name").unwrap_or_default(),
+            mac: el.take_child_attr("mac", "address").unwrap_or_default(),
+            filter: el.take_model("filterref")?.unwrap_or_default(),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let owner = Element::new("owner")
+            .text_child("name", Some(&self.owner_name))
+            .text_child("uuid", Some(&self.owner_uuid));
+        Element::new("filterbinding")
+            .child(owner)
+            .child(Element::new("portdev").attr("name", &self.port_dev))
+            .child(Element::new("mac").attr("address", &self.mac))
+            .model(Some(&self.filter))
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(NWFilterBindingDef);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rule {
+    /// Like `accept`, `drop`, `reject`, `return` or `continue`.
+    pub action: String,
+    /// Either `in`, `out` or `inout`.
+    pub direction: String,
+    pub priority: Option<i32>,
+    /// Whether connection state tracking is used.
+    pub statematch: Option<bool>,
+    /// Traffic the rule applies to, all of it when not set.
+    pub matching: Option<RuleMatch>,
+    pub extra: Extra,
+}
+
+impl Rule {
+    pub fn new(action: &str, direction: &str) -> Rule {
+        Rule { action: action.to_string(), direction: direction.to_string(), ..Default::default() }
+    }
+
+    pub fn accept(direction: &str) -> Rule {
+        Rule::new("accept", direction)
+    }
+
+    pub fn drop(direction: &str) -> Rule {
+        Rule::new("drop", direction)
+    }
+
+    pub fn priority(mut self, priority: i32) -> Rule {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn statematch(mut self, statematch: bool) -> Rule {
+        self.statematch = Some(statematch);
+        self
+    }
+
+    pub fn matching(mut self, matching: RuleMatch) -> Rule {
+        self.matching = Some(matching);
+        self
+    }
+}
+
+impl XmlElement for Rule {
+    fn from_element(mut el: Element) -> Result<Rule, Error> {
+        let mut matching = None;
+        if let Some(pos) = el.children.iter().position(|n| match *n {
+            Node::Element(_) => true,
+            Node::Text(_) => false,
+        }) {
+            if let Node::Element(e) = el.children.remove(pos) {
+                matching = Some(RuleMatch::from_element(e)?);
+            }
+        }
+        let statematch = match el.take_attr("statematch") {
+            Some(ref s) if s == "false" || s == "0" => Some(false),
+            Some(_) => Some(true),
+            None => None,
+        };
+        Ok(Rule {
+            action: el.take_attr("action").unwrap_or_default(),
+            direction: el.take_attr("direction").unwrap_or_default(),
+            priority: el.take_parse("priority")?,
+            statematch,
+            matching,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("rule")
+            .attr("action", &self.action)
+            .attr("direction", &self.direction)
+            .attr_opt("priority", self.priority)
+            .attr_opt("statematch", self.statematch)
+            .model(self.matching.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Protocol matched by a rule, the name of its element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterProtocol {
+    Mac,
+    Vlan,
+    Stp,
+    Arp,
+    Rarp,
+    Ip,
+    Ipv6,
+    Tcp,
+    Udp,
+    Sctp,
+    Icmp,
+    Igmp,
+    Esp,
+    Ah,
+    UdpLite,
+    All,
+    TcpIpv6,
+    UdpIpv6,
+    SctpIpv6,
+    Icmpv6,
+    EspIpv6,
+    AhIpv6,
+    UdpLiteIpv6,
+    AllIpv6,
+    /// Protocol not known by the binding.
+    Other(String),
+}
+
+impl FilterProtocol {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            FilterProtocol::Mac => "mac",
+            FilterProtocol::Vlan => "vlan",
+            FilterProtocol::Stp => "stp",
+            FilterProtocol::Arp => "arp",
+            FilterProtocol::Rarp => "rarp",
+            FilterProtocol::Ip => "ip",
+            FilterProtocol::Ipv6 => "ipv6",
+            FilterProtocol::Tcp => "tcp",
+            FilterProtocol::Udp => "udp",
+            FilterProtocol::Sctp => "sctp",
+            FilterProtocol::Icmp => "icmp",
+            FilterProtocol::Igmp => "igmp",
+            FilterProtocol::Esp => "esp",
+            FilterProtocol::Ah => "ah",
+            FilterProtocol::UdpLite => "udplite",
+            FilterProtocol::All => "all",
+            FilterProtocol::TcpIpv6 => "tcp-ipv6",
+            FilterProtocol::UdpIpv6 => "udp-ipv6",
+            FilterProtocol::SctpIpv6 => "sctp-ipv6",
+            FilterProtocol::Icmpv6 => "icmpv6",
+            FilterProtocol::EspIpv6 => "esp-ipv6",
+            FilterProtocol::AhIpv6 => "ah-ipv6",
+            FilterProtocol::UdpLiteIpv6 => "udplite-ipv6",
+            FilterProtocol::AllIpv6 => "all-ipv6",
+            FilterProtocol::Other(ref p) => p,
+        }
+    }
+
+    pub fn from_name(name: &str) -> FilterProtocol {
+        match name {
+            "mac" => FilterProtocol::Mac,
+            "vlan" => FilterProtocol::Vlan,
+            "stp" => FilterProtocol::Stp,
+            "arp" => FilterProtocol::Arp,
+            "rarp" => FilterProtocol::Rarp,
+            "ip" => FilterProtocol::Ip,
+            "ipv6" => FilterProtocol::Ipv6,
+            "tcp" => FilterProtocol::Tcp,
+            "udp" => FilterProtocol::Udp,
+            "sctp" => FilterProtocol::Sctp,
+            "icmp" => FilterProtocol::Icmp,
+            "igmp" => FilterProtocol::Igmp,
+            "esp" => FilterProtocol::Esp,
+            "ah" => FilterProtocol::Ah,
+            "udplite" => FilterProtocol::UdpLite,
+            "all" => FilterProtocol::All,
+            "tcp-ipv6" => FilterProtocol::TcpIpv6,
+            "udp-ipv6" => FilterProtocol::UdpIpv6,
+            "sctp-ipv6" => FilterProtocol::SctpIpv6,
+            "icmpv6" => FilterProtocol::Icmpv6,
+            "esp-ipv6" => FilterProtocol::EspIpv6,
+            "ah-ipv6" => FilterProtocol::AhIpv6,
+            "udplite-ipv6" => FilterProtocol::UdpLiteIpv6,
+            "all-ipv6" => FilterProtocol::AllIpv6,
+            _ => FilterProtocol::Other(name.to_string()),
+        }
+    }
+}
+
+/// Traffic matched by a rule.
+///
+/// Attributes depend on the protocol, like `srcmacaddr` for `mac`,
+/// `arpop` for `arp` or `dstportstart` for `tcp`. Their value can
+/// be a variable like `$IP` set by a `FilterRef`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleMatch {
+    pub protocol: FilterProtocol,
+    pub attributes: Vec<(String, String)>,
+    pub extra: Extra,
+}
+
+impl RuleMatch {
+    pub fn new(protocol: FilterProtocol) -> RuleMatch {
+        RuleMatch { protocol, attributes: Vec::new(), extra: Extra::default() }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.0 == name).map(|a| a.1.as_str())
+    }
+
+    /// Set the attribute `name` to `value`, replacing any previous
+    /// value.
+    pub fn attr<T: ToString>(mut self, name: &str, value: T) -> RuleMatch {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|a| a.0 == name) {
+            Some(a) => a.1 = value,
+            None => self.attributes.push((name.to_string(), value)),
+        }
+        self
+    }
+
+    pub fn src_mac(self, mac: &str) -> RuleMatch {
+        self.attr("srcmacaddr", mac)
+    }
+
+    pub fn src_ip(self, ip: &str) -> RuleMatch {
+        self.attr("srcipaddr", ip)
+    }
+
+    pub fn dst_ip(self, ip: &str) -> RuleMatch {
+        self.attr("dstipaddr", ip)
+    }
+
+    /// Match the source ports from `start` to `end`, or only `start`.
+    pub fn src_port(self, start: u16, end: Option<u16>) -> RuleMatch {
+        match end {
+            Some(end) => self.attr("srcportstart", start).attr("srcportend", end),
+            None => self.attr("srcportstart", start),
+        }
+    }
+
+    /// Match the destination ports from `start` to `end`, or only
+    /// `start`.
+    pub fn dst_port(self, start: u16, end: Option<u16>) -> RuleMatch {
+        match end {
+            Some(end) => self.attr("dstportstart", start).attr("dstportend", end),
+            None => self.attr("dstportstart", start),
+        }
+    }
+
+    /// Match the connection states like `NEW` or
+    /// `ESTABLISHED,RELATED`.
+    pub fn state(self, state: &str) -> RuleMatch {
+        self.attr("state", state)
+    }
+}
+
+impl XmlElement for RuleMatch {
+    fn from_element(el: Element) -> Result<RuleMatch, Error> {
+        let protocol = FilterProtocol::from_name(&el.name);
+        let extra = el.into_extra();
+        Ok(RuleMatch {
+            protocol,
+            attributes: extra.attributes,
+            extra: Extra {
+                attributes: Vec::new(),
+                children: extra.children,
+                remainders: extra.remainders,
+            },
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new(self.protocol.as_str());
+        el.attributes = self.attributes.clone();
+        el.extra(&self.extra)
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed model of the secret XML format.
+//!
+//! See http://libvirt.org/formatsecret.html
+//!
+//! ```
+//! use virt::xml::secret::{SecretDef, SecretUsage};
+//!
+//! let def = SecretDef::new(SecretUsage::Ceph(String::from("client.libvirt secret")))
+//!     .private(true)
+//!     .description("Ceph client key");
+//! let parsed: SecretDef = def.to_string().parse().unwrap();
+//! assert_eq!(def, parsed);
+//! ```
+
+use error::Error;
+use secret::{Secret, SecretSecretUsageType, SecretXMLFlags};
+use xml::{yes_no, Element, Extra, XmlElement};
+
+/// A secret definition as used by `Secret::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SecretDef {
+    /// Whether the secret is only kept in memory.
+    pub ephemeral: Option<bool>,
+    /// Whether the value of the secret can not be read back.
+    pub private: Option<bool>,
+    pub uuid: Option<String>,
+    pub description: Option<String>,
+    pub usage: Option<SecretUsage>,
+    pub extra: Extra,
+}
+
+impl SecretDef {
+    pub fn new(usage: SecretUsage) -> SecretDef {
+        SecretDef { usage: Some(usage), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `secret`.
+    pub fn from_secret(secret: &Secret, flags: SecretXMLFlags) -> Result<SecretDef, Error> {
+        secret.get_xml_desc(flags)?.parse()
+    }
+
+    pub fn ephemeral(mut self, ephemeral: bool) -> SecretDef {
+        self.ephemeral = Some(ephemeral);
+        self
+    }
+
+    pub fn private(mut self, private: bool) -> SecretDef {
+        self.private = Some(private);
+        self
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> SecretDef {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> SecretDef {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+impl XmlElement for SecretDef {
+    fn from_element(mut el: Element) -> Result<SecretDef, Error> {
+        Ok(SecretDef {
+            ephemeral: el.take_bool("ephemeral")?,
+            private: el.take_bool("private")?,
+            uuid: el.take_text("uuid"),
+            description: el.take_text("description"),
+            usage: el.take_model("usage")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("secret")
+            .attr_opt("ephemeral", yes_no(self.ephemeral))
+            .attr_opt("private", yes_no(self.private))
+            .text_child("uuid", self.uuid.as_ref())
+            .text_child("description", self.description.as_ref())
+            .model(self.usage.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(SecretDef);
+
+/// Object a secret is used for, each variant holding the
+/// identifier used by `Secret::lookup_by_usage`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SecretUsage {
+    /// Path of an encrypted volume.
+    Volume(String),
+    /// Name of a Ceph client key.
+    Ceph(String),
+    /// Target of an iSCSI CHAP authentication.
+    Iscsi(String),
+    /// Name of a TLS certificate passphrase.
+    Tls(String),
+    /// Name of the state encryption key of a vTPM.
+    Vtpm(String),
+    /// Usage not known by the binding.
+    Other(Element),
+}
+
+impl SecretUsage {
+    pub fn usage_type(&self) -> SecretSecretUsageType {
+        match *self {
+            SecretUsage::Volume(_) => SecretSecretUsageType::Volume,
+            SecretUsage::Ceph(_) => SecretSecretUsageType::Ceph,
+            SecretUsage::Iscsi(_) => SecretSecretUsageType::Iscsi,
+            SecretUsage::Tls(_) => SecretSecretUsageType::Tls,
+            SecretUsage::Vtpm(_) => SecretSecretUsageType::Vtpm,
+            SecretUsage::Other(_) => SecretSecretUsageType::Unknown,
+        }
+    }
+
+    pub fn usage_id(&self) -> Option<&str> {
+        match *self {
+            SecretUsage::Volume(ref id) |
+            SecretUsage::Ceph(ref id) |
+            SecretUsage::Iscsi(ref id) |
+            SecretUsage::Tls(ref id) |
+            SecretUsage::Vtpm(ref id) => Some(id),
+            SecretUsage::Other(_) => None,
+        }
+    }
+}
+
+impl XmlElement for SecretUsage {
+    fn from_element(el: Element) -> Result<SecretUsage, Error> {
+        let (name, usage): (&str, fn(String) -> SecretUsage) = match el.get_attr("type") {
+            Some("volume") => ("volume", SecretUsage::Volume),
+            Some("ceph") => ("name", SecretUsage::Ceph),
+            Some("iscsi") => ("target", SecretUsage::Iscsi),
+            Some("tls") => ("name", SecretUsage::Tls),
+            Some("vtpm") => ("name", SecretUsage::Vtpm),
+            _ => return Ok(SecretUsage::Other(el)),
+        };
+        // Usages with more than their identifier are kept as is.
+        let id = match el.find(name) {
+            Some(id) if el.elements().len() == 1 && el.attributes.len() == 1 => id.get_text(),
+            _ => return Ok(SecretUsage::Other(el)),
+        };
+        Ok(usage(id))
+    }
+
+    fn to_element(&self) -> Element {
+        let (kind, name, id) = match *self {
+            SecretUsage::Volume(ref id) => ("volume", "volume", id),
+            SecretUsage::Ceph(ref id) => ("ceph", "name", id),
+            SecretUsage::Iscsi(ref id) => ("iscsi", "target", id),
+            SecretUsage::Tls(ref id) => ("tls", "name", id),
+            SecretUsage::Vtpm(ref id) => ("vtpm", "name", id),
+            SecretUsage::Other(ref e) => return e.clone(),
+        };
+        Element::new("usage").attr("type", kind).child(Element::new(name).text(id))
+    }
+}
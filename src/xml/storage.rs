@@ -0,0 +1,734 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+//! Typed models of the storage pool and volume XML formats.
+//!
+//! See http://libvirt.org/formatstorage.html
+//!
+//! ```
+//! use virt::xml::storage::{StoragePoolDef, StorageVolDef};
+//!
+//! let pool = StoragePoolDef::dir("images", "/var/lib/libvirt/images");
+//! assert!(pool.to_string().contains("<path>/var/lib/libvirt/images</path>"));
+//!
+//! let vol = StorageVolDef::new("top.qcow2", 10 * 1024 * 1024 * 1024)
+//!     .format("qcow2")
+//!     .backing_store("/var/lib/libvirt/images/base.qcow2", "qcow2");
+//! let parsed: StorageVolDef = vol.to_string().parse().unwrap();
+//! assert_eq!(Some(10737418240), parsed.capacity);
+//! ```
+
+use error::Error;
+use storage_pool::{StoragePool, StoragePoolXMLFlags};
+use storage_vol::StorageVol;
+use xml::{yes_no, Element, Extra, Node, XmlElement};
+
+/// Type of a storage pool, matching the pool types of
+/// `ConnectListAllStoragePoolsFlags`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StoragePoolType {
+    #[default]
+    Dir,
+    Fs,
+    Netfs,
+    Logical,
+    Disk,
+    Iscsi,
+    IscsiDirect,
+    Scsi,
+    Mpath,
+    Rbd,
+    Sheepdog,
+    Gluster,
+    Zfs,
+    Vstorage,
+    /// Pool type not known by the binding.
+    Other(String),
+}
+
+impl StoragePoolType {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            StoragePoolType::Dir => "dir",
+            StoragePoolType::Fs => "fs",
+            StoragePoolType::Netfs => "netfs",
+            StoragePoolType::Logical => "logical",
+            StoragePoolType::Disk => "disk",
+            StoragePoolType::Iscsi => "iscsi",
+            StoragePoolType::IscsiDirect => "iscsi-direct",
+            StoragePoolType::Scsi => "scsi",
+            StoragePoolType::Mpath => "mpath",
+            StoragePoolType::Rbd => "rbd",
+            StoragePoolType::Sheepdog => "sheepdog",
+            StoragePoolType::Gluster => "gluster",
+            StoragePoolType::Zfs => "zfs",
+            StoragePoolType::Vstorage => "vstorage",
+            StoragePoolType::Other(ref t) => t,
+        }
+    }
+
+    pub fn from_name(name: &str) -> StoragePoolType {
+        match name {
+            "dir" => StoragePoolType::Dir,
+            "fs" => StoragePoolType::Fs,
+            "netfs" => StoragePoolType::Netfs,
+            "logical" => StoragePoolType::Logical,
+            "disk" => StoragePoolType::Disk,
+            "iscsi" => StoragePoolType::Iscsi,
+            "iscsi-direct" => StoragePoolType::IscsiDirect,
+            "scsi" => StoragePoolType::Scsi,
+            "mpath" => StoragePoolType::Mpath,
+            "rbd" => StoragePoolType::Rbd,
+            "sheepdog" => StoragePoolType::Sheepdog,
+            "gluster" => StoragePoolType::Gluster,
+            "zfs" => StoragePoolType::Zfs,
+            "vstorage" => StoragePoolType::Vstorage,
+            _ => StoragePoolType::Other(name.to_string()),
+        }
+    }
+}
+
+/// A storage pool definition as used by `StoragePool::define_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StoragePoolDef {
+    pub kind: StoragePoolType,
+    pub name: String,
+    pub uuid: Option<String>,
+    /// Sizes reported by libvirt for an active pool, in bytes.
+    pub capacity: Option<u64>,
+    pub allocation: Option<u64>,
+    pub available: Option<u64>,
+    /// Whether copy on write is enabled on the files of the pool,
+    /// for `dir`, `fs` and `netfs` pools on btrfs.
+    pub cow: Option<bool>,
+    pub source: Option<PoolSource>,
+    pub target: Option<PoolTarget>,
+    pub extra: Extra,
+}
+
+impl StoragePoolDef {
+    pub fn new(kind: StoragePoolType, name: &str) -> StoragePoolDef {
+        StoragePoolDef { kind, name: name.to_string(), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `pool`.
+    pub fn from_pool(pool: &StoragePool,
+                     flags: StoragePoolXMLFlags)
+                     -> Result<StoragePoolDef, Error> {
+        pool.get_xml_desc(flags)?.parse()
+    }
+
+    /// A pool of the files of the directory `path`.
+    pub fn dir(name: &str, path: &str) -> StoragePoolDef {
+        StoragePoolDef::new(StoragePoolType::Dir, name).target_path(path)
+    }
+
+    /// A pool of the files of the directory `dir` exported by `host`
+    /// and mounted on `path`.
+    pub fn netfs(name: &str, host: &str, dir: &str, path: &str) -> StoragePoolDef {
+        let source = PoolSource::default().host(host, None).dir(dir).format("auto");
+        StoragePoolDef::new(StoragePoolType::Netfs, name).source(source).target_path(path)
+    }
+
+    /// A pool of the logical volumes of the volume group `vg`, made
+    /// of the physical volumes `devices`.
+    pub fn logical(name: &str, vg: &str, devices: &[&str]) -> StoragePoolDef {
+        let mut source = PoolSource::default().name(vg);
+        for dev in devices {
+            source = source.device(dev);
+        }
+        StoragePoolDef::new(StoragePoolType::Logical, name)
+            .source(source)
+            .target_path(&format!("/dev/{}", vg))
+    }
+
+    /// A pool of the LUNs of the iSCSI target `iqn` served by
+    /// `host`.
+    pub fn iscsi(name: &str, host: &str, iqn: &str) -> StoragePoolDef {
+        let source = PoolSource::default().host(host, None).device(iqn);
+        StoragePoolDef::new(StoragePoolType::Iscsi, name)
+            .source(source)
+            .target_path("/dev/disk/by-path")
+    }
+
+    /// A pool of the images of the RADOS pool `pool` served by the
+    /// monitors `hosts`.
+    pub fn rbd(name: &str, pool: &str, hosts: &[&str]) -> StoragePoolDef {
+        let mut source = PoolSource::default().name(pool);
+        for host in hosts {
+            source = source.host(host, None);
+        }
+        StoragePoolDef::new(StoragePoolType::Rbd, name).source(source)
+    }
+
+    pub fn uuid(mut self, uuid: &str) -> StoragePoolDef {
+        self.uuid = Some(uuid.to_string());
+        self
+    }
+
+    pub fn source(mut self, source: PoolSource) -> StoragePoolDef {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn target_path(mut self, path: &str) -> StoragePoolDef {
+        self.target.get_or_insert_with(PoolTarget::default).path = Some(path.to_string());
+        self
+    }
+
+    pub fn permissions(mut self, permissions: Permissions) -> StoragePoolDef {
+        self.target.get_or_insert_with(PoolTarget::default).permissions = Some(permissions);
+        self
+    }
+}
+
+impl XmlElement for StoragePoolDef {
+    fn from_element(mut el: Element) -> Result<StoragePoolDef, Error> {
+        let cow = match el.take("features") {
+            Some(mut features) => {
+                let cow = features.take_child_bool("cow", "state")?;
+                if !features.children.is_empty() {
+                    el.children.push(Node::Element(features));
+                }
+                cow
+            }
+            None => None,
+        };
+        Ok(StoragePoolDef {
+            kind: StoragePoolType::from_name(&el.take_attr("type").unwrap_or_default()),
+            name: el.take_text("name").unwrap_or_default(),
+            uuid: el.take_text("uuid"),
+            capacity: el.take_bytes("capacity")?,
+            allocation: el.take_bytes("allocation")?,
+            available: el.take_bytes("available")?,
+            cow,
+            source: el.take_model("source")?,
+            target: el.take_model("target")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let features = yes_no(self.cow)
+            .map(|cow| Element::new("features").child(Element::new("cow").attr("state", cow)));
+        Element::new("pool")
+            .attr("type", self.kind.as_str())
+            .child(Element::new("name").text(&self.name))
+            .text_child("uuid", self.uuid.as_ref())
+            .bytes_child("capacity", self.capacity)
+            .bytes_child("allocation", self.allocation)
+            .bytes_child("available", self.available)
+            .child_opt(features)
+            .model(self.source.as_ref())
+            .model(self.target.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(StoragePoolDef);
+
+/// Where the storage of a pool comes from, which fields are used
+/// depends on the pool type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolSource {
+    /// Servers of a network pool, `(name, port)`.
+    pub hosts: Vec<(String, Option<u16>)>,
+    /// Devices like physical volumes, disks or iSCSI targets.
+    pub devices: Vec<String>,
+    pub dir: Option<String>,
+    /// Name of a volume group, RADOS pool, gluster volume or zpool.
+    pub name: Option<String>,
+    /// Format of the source, like `nfs` or `ext4` for filesystems
+    /// or `dos` or `gpt` for disks.
+    pub format: Option<String>,
+    pub adapter: Option<Adapter>,
+    pub auth: Option<SourceAuth>,
+    /// iSCSI initiator IQN.
+    pub initiator: Option<String>,
+    pub extra: Extra,
+}
+
+impl PoolSource {
+    pub fn host(mut self, name: &str, port: Option<u16>) -> PoolSource {
+        self.hosts.push((name.to_string(), port));
+        self
+    }
+
+    pub fn device(mut self, path: &str) -> PoolSource {
+        self.devices.push(path.to_string());
+        self
+    }
+
+    pub fn dir(mut self, dir: &str) -> PoolSource {
+        self.dir = Some(dir.to_string());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> PoolSource {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn format(mut self, format: &str) -> PoolSource {
+        self.format = Some(format.to_string());
+        self
+    }
+
+    pub fn auth(mut self, auth: SourceAuth) -> PoolSource {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn initiator(mut self, iqn: &str) -> PoolSource {
+        self.initiator = Some(iqn.to_string());
+        self
+    }
+}
+
+impl XmlElement for PoolSource {
+    fn from_element(mut el: Element) -> Result<PoolSource, Error> {
+        let mut hosts = Vec::new();
+        for mut host in el.take_all("host") {
+            let name = host.take_attr("name").unwrap_or_default();
+            let port = host.take_parse("port")?;
+            el.keep_rest(hosts.len(), host);
+            hosts.push((name, port));
+        }
+        // Devices also hold the free extents of the physical volumes
+        // of logical pools, these are kept as is.
+        let mut devices = Vec::new();
+        for mut device in el.take_all("device") {
+            match device.take_attr("path") {
+                Some(path) => {
+                    el.keep_rest(devices.len(), device);
+                    devices.push(path);
+                }
+                None => el.children.push(Node::Element(device)),
+            }
+        }
+        let mut initiator = None;
+        if let Some(mut i) = el.take("initiator") {
+            initiator = i.take_child_attr("iqn", "name");
+            el.keep_rest(0, i);
+        }
+        Ok(PoolSource {
+            hosts,
+            devices,
+            dir: el.take_child_attr("dir", "path"),
+            name: el.take_text("name"),
+            format: el.take_child_attr("format", "type"),
+            adapter: el.take_model("adapter")?,
+            auth: el.take_model("auth")?,
+            initiator,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut el = Element::new("source");
+        for &(ref name, port) in &self.hosts {
+            el = el.child(Element::new("host").attr("name", name).attr_opt("port", port));
+        }
+        for path in &self.devices {
+            el = el.child(Element::new("device").attr("path", path));
+        }
+        let initiator = self.initiator
+            .as_ref()
+            .map(|iqn| Element::new("initiator").child(Element::new("iqn").attr("name", iqn)));
+        el.attr_child("dir", "path", self.dir.as_ref())
+            .text_child("name", self.name.as_ref())
+            .attr_child("format", "type", self.format.as_ref())
+            .model(self.adapter.as_ref())
+            .model(self.auth.as_ref())
+            .child_opt(initiator)
+            .extra(&self.extra)
+    }
+}
+
+/// SCSI host adapter of a `scsi` pool.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Adapter {
+    /// Either `scsi_host` or `fc_host`.
+    pub kind: Option<String>,
+    pub name: Option<String>,
+    pub parent: Option<String>,
+    pub wwnn: Option<String>,
+    pub wwpn: Option<String>,
+    pub extra: Extra,
+}
+
+impl XmlElement for Adapter {
+    fn from_element(mut el: Element) -> Result<Adapter, Error> {
+        Ok(Adapter {
+            kind: el.take_attr("type"),
+            name: el.take_attr("name"),
+            parent: el.take_attr("parent"),
+            wwnn: el.take_attr("wwnn"),
+            wwpn: el.take_attr("wwpn"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("adapter")
+            .attr_opt("type", self.kind.as_ref())
+            .attr_opt("name", self.name.as_ref())
+            .attr_opt("parent", self.parent.as_ref())
+            .attr_opt("wwnn", self.wwnn.as_ref())
+            .attr_opt("wwpn", self.wwpn.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Authentication to the source of a pool, the credentials being
+/// held by a libvirt secret.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceAuth {
+    /// Either `chap` or `ceph`.
+    pub kind: String,
+    pub username: String,
+    pub secret_usage: Option<String>,
+    pub secret_uuid: Option<String>,
+    pub extra: Extra,
+}
+
+impl SourceAuth {
+    /// Authenticate as `username` with the secret used for `usage`.
+    pub fn new(kind: &str, username: &str, usage: &str) -> SourceAuth {
+        SourceAuth {
+            kind: kind.to_string(),
+            username: username.to_string(),
+            secret_usage: Some(usage.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for SourceAuth {
+    fn from_element(mut el: Element) -> Result<SourceAuth, Error> {
+        let mut secret = el.take("secret").unwrap_or_default();
+        Ok(SourceAuth {
+            kind: el.take_attr("type").unwrap_or_default(),
+            username: el.take_attr("username").unwrap_or_default(),
+            secret_usage: secret.take_attr("usage"),
+            secret_uuid: secret.take_attr("uuid"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let secret = Element::new("secret")
+            .attr_opt("usage", self.secret_usage.as_ref())
+            .attr_opt("uuid", self.secret_uuid.as_ref());
+        Element::new("auth")
+            .attr("type", &self.kind)
+            .attr("username", &self.username)
+            .child(secret)
+            .extra(&self.extra)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolTarget {
+    pub path: Option<String>,
+    pub permissions: Option<Permissions>,
+    pub extra: Extra,
+}
+
+impl XmlElement for PoolTarget {
+    fn from_element(mut el: Element) -> Result<PoolTarget, Error> {
+        Ok(PoolTarget {
+            path: el.take_text("path"),
+            permissions: el.take_model("permissions")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("target")
+            .text_child("path", self.path.as_ref())
+            .model(self.permissions.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Ownership and mode of a pool, a volume or a backing store.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Permissions {
+    /// Octal mode like `0755`.
+    pub mode: Option<String>,
+    pub owner: Option<u32>,
+    pub group: Option<u32>,
+    /// MAC label like a SELinux context.
+    pub label: Option<String>,
+    pub extra: Extra,
+}
+
+impl Permissions {
+    pub fn new(mode: &str, owner: u32, group: u32) -> Permissions {
+        Permissions {
+            mode: Some(mode.to_string()),
+            owner: Some(owner),
+            group: Some(group),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Permissions {
+    fn from_element(mut el: Element) -> Result<Permissions, Error> {
+        Ok(Permissions {
+            mode: el.take_text("mode"),
+            owner: el.take_text_parse("owner")?,
+            group: el.take_text_parse("group")?,
+            label: el.take_text("label"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("permissions")
+            .text_child("mode", self.mode.as_ref())
+            .text_child("owner", self.owner)
+            .text_child("group", self.group)
+            .text_child("label", self.label.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// A storage volume definition as used by `StorageVol::create_xml`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageVolDef {
+    /// Like `file`, `block`, `dir`, `network` or `ploop`.
+    pub kind: Option<String>,
+    pub name: String,
+    /// Identifier of the volume, reported by libvirt.
+    pub key: Option<String>,
+    /// Sizes of the volume, in bytes.
+    pub capacity: Option<u64>,
+    pub allocation: Option<u64>,
+    pub physical: Option<u64>,
+    pub target: Option<VolTarget>,
+    pub backing_store: Option<BackingStore>,
+    pub extra: Extra,
+}
+
+impl StorageVolDef {
+    /// A volume `name` of `capacity` bytes.
+    pub fn new(name: &str, capacity: u64) -> StorageVolDef {
+        StorageVolDef { name: name.to_string(), capacity: Some(capacity), ..Default::default() }
+    }
+
+    /// Fetch and parse the definition of `vol`.
+    pub fn from_vol(vol: &StorageVol) -> Result<StorageVolDef, Error> {
+        vol.get_xml_desc(0)?.parse()
+    }
+
+    /// Set how much space is allocated at creation, in bytes.
+    pub fn allocation(mut self, allocation: u64) -> StorageVolDef {
+        self.allocation = Some(allocation);
+        self
+    }
+
+    /// Set the format like `raw` or `qcow2`.
+    pub fn format(mut self, format: &str) -> StorageVolDef {
+        self.target.get_or_insert_with(VolTarget::default).format = Some(format.to_string());
+        self
+    }
+
+    pub fn permissions(mut self, permissions: Permissions) -> StorageVolDef {
+        self.target.get_or_insert_with(VolTarget::default).permissions = Some(permissions);
+        self
+    }
+
+    /// Set the qcow2 `compat` level like `1.1`.
+    pub fn compat(mut self, compat: &str) -> StorageVolDef {
+        self.target.get_or_insert_with(VolTarget::default).compat = Some(compat.to_string());
+        self
+    }
+
+    pub fn encryption(mut self, encryption: Encryption) -> StorageVolDef {
+        self.target.get_or_insert_with(VolTarget::default).encryption = Some(encryption);
+        self
+    }
+
+    /// Create the volume on top of the image `path` of `format`.
+    pub fn backing_store(mut self, path: &str, format: &str) -> StorageVolDef {
+        self.backing_store = Some(BackingStore {
+            path: path.to_string(),
+            format: Some(format.to_string()),
+            ..Default::default()
+        });
+        self
+    }
+}
+
+impl XmlElement for StorageVolDef {
+    fn from_element(mut el: Element) -> Result<StorageVolDef, Error> {
+        Ok(StorageVolDef {
+            kind: el.take_attr("type"),
+            name: el.take_text("name").unwrap_or_default(),
+            key: el.take_text("key"),
+            capacity: el.take_bytes("capacity")?,
+            allocation: el.take_bytes("allocation")?,
+            physical: el.take_bytes("physical")?,
+            target: el.take_model("target")?,
+            backing_store: el.take_model("backingStore")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("volume")
+            .attr_opt("type", self.kind.as_ref())
+            .child(Element::new("name").text(&self.name))
+            .text_child("key", self.key.as_ref())
+            .bytes_child("capacity", self.capacity)
+            .bytes_child("allocation", self.allocation)
+            .bytes_child("physical", self.physical)
+            .model(self.target.as_ref())
+            .model(self.backing_store.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+impl_xml_str!(StorageVolDef);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VolTarget {
+    pub path: Option<String>,
+    /// Format like `raw`, `qcow2` or `iso`.
+    pub format: Option<String>,
+    pub permissions: Option<Permissions>,
+    /// Compatibility level of a qcow2 image, like `0.10` or `1.1`.
+    pub compat: Option<String>,
+    /// Format features like `lazy_refcounts`.
+    pub features: Vec<String>,
+    pub nocow: bool,
+    pub encryption: Option<Encryption>,
+    pub extra: Extra,
+}
+
+impl XmlElement for VolTarget {
+    fn from_element(mut el: Element) -> Result<VolTarget, Error> {
+        let features = el.take("features")
+            .map(|f| f.into_extra().children.into_iter().map(|e| e.name).collect())
+            .unwrap_or_default();
+        Ok(VolTarget {
+            path: el.take_text("path"),
+            format: el.take_child_attr("format", "type"),
+            permissions: el.take_model("permissions")?,
+            compat: el.take_text("compat"),
+            features,
+            nocow: el.take_flag("nocow"),
+            encryption: el.take_model("encryption")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let features = if self.features.is_empty() {
+            None
+        } else {
+            let mut el = Element::new("features");
+            for f in &self.features {
+                el = el.child(Element::new(f));
+            }
+            Some(el)
+        };
+        Element::new("target")
+            .text_child("path", self.path.as_ref())
+            .attr_child("format", "type", self.format.as_ref())
+            .model(self.permissions.as_ref())
+            .text_child("compat", self.compat.as_ref())
+            .child_opt(features)
+            .flag_child("nocow", self.nocow)
+            .model(self.encryption.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Image a copy on write volume is based on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackingStore {
+    pub path: String,
+    pub format: Option<String>,
+    pub permissions: Option<Permissions>,
+    pub extra: Extra,
+}
+
+impl XmlElement for BackingStore {
+    fn from_element(mut el: Element) -> Result<BackingStore, Error> {
+        Ok(BackingStore {
+            path: el.take_text("path").unwrap_or_default(),
+            format: el.take_child_attr("format", "type"),
+            permissions: el.take_model("permissions")?,
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        Element::new("backingStore")
+            .child(Element::new("path").text(&self.path))
+            .attr_child("format", "type", self.format.as_ref())
+            .model(self.permissions.as_ref())
+            .extra(&self.extra)
+    }
+}
+
+/// Encryption of a volume, the passphrase being held by a libvirt
+/// secret.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Encryption {
+    /// Either `luks` or `qcow`.
+    pub format: String,
+    /// UUID of the `passphrase` secret.
+    pub secret_uuid: Option<String>,
+    pub extra: Extra,
+}
+
+impl Encryption {
+    pub fn luks(secret_uuid: &str) -> Encryption {
+        Encryption {
+            format: String::from("luks"),
+            secret_uuid: Some(secret_uuid.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl XmlElement for Encryption {
+    fn from_element(mut el: Element) -> Result<Encryption, Error> {
+        let mut secret = el.take("secret").unwrap_or_default();
+        Ok(Encryption {
+            format: el.take_attr("format").unwrap_or_default(),
+            secret_uuid: secret.take_attr("uuid"),
+            extra: el.into_extra(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let secret = self.secret_uuid
+            .as_ref()
+            .map(|uuid| Element::new("secret").attr("type", "passphrase").attr("uuid", uuid));
+        Element::new("encryption")
+            .attr("format", &self.format)
+            .child_opt(secret)
+            .extra(&self.extra)
+    }
+}
@@ -23,11 +23,11 @@
 extern crate virt;
 
 use virt::connect::Connect;
-use virt::domain::Domain;
+use virt::domain::{Domain, DomainCreateFlags};
 use virt::error::Error;
 use virt::interface::Interface;
-use virt::storage_pool::StoragePool;
-use virt::storage_vol::StorageVol;
+use virt::storage_pool::{StoragePool, StoragePoolCreateFlags};
+use virt::storage_vol::{StorageVol, StorageVolCreateFlags, StorageVolDeleteFlags};
 use virt::network::Network;
 
 
@@ -82,7 +82,7 @@ pub fn clean_net(mut net: Network) {
 }
 
 pub fn clean_vol(mut vol: StorageVol) {
-    if let Err(_) = vol.delete(0) {}
+    if let Err(_) = vol.delete(StorageVolDeleteFlags::empty()) {}
     assert_eq!(Ok(()), vol.free())
 }
 
@@ -108,7 +108,7 @@ pub fn build_qemu_domain(conn: &Connect, name: &str, transient: bool) -> Domain
 
     let result: Result<Domain, Error>;
     if transient {
-        result = Domain::create_xml(&conn, &xml, 0);
+        result = Domain::create_xml(&conn, &xml, DomainCreateFlags::empty());
     } else {
         result = Domain::define_xml(&conn, &xml);
     }
@@ -145,7 +145,7 @@ pub fn build_test_domain(conn: &Connect, name: &str, transient: bool) -> Domain
 
     let result: Result<Domain, Error>;
     if transient {
-        result = Domain::create_xml(&conn, &xml, 0);
+        result = Domain::create_xml(&conn, &xml, DomainCreateFlags::empty());
     } else {
         result = Domain::define_xml(&conn, &xml);
     }
@@ -177,7 +177,7 @@ pub fn build_storage_pool(conn: &Connect, name: &str, transient: bool) -> Storag
 
     let result: Result<StoragePool, Error>;
     if transient {
-        result = StoragePool::create_xml(&conn, &xml, 0);
+        result = StoragePool::create_xml(&conn, &xml, StoragePoolCreateFlags::empty());
     } else {
         result = StoragePool::define_xml(&conn, &xml, 0);
     }
@@ -205,7 +205,7 @@ pub fn build_storage_vol(pool: &StoragePool, name: &str, size: u64) -> StorageVo
                       name,
                       size,
                       size);
-    match StorageVol::create_xml(&pool, &xml, 0) {
+    match StorageVol::create_xml(&pool, &xml, StorageVolCreateFlags::empty()) {
         Ok(vol) => vol,
         Err(e) => {
             panic!("Build vol failed with code {}, message: {}",
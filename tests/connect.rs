@@ -20,7 +20,10 @@ extern crate virt;
 
 mod common;
 
-use virt::connect::Connect;
+use std::sync::Arc;
+
+use virt::connect::{Connect, ConnectAuth, ConnectCredential, ConnectFlags,
+                    ConnectListAllDomainsFlags};
 
 
 #[test]
@@ -53,6 +56,15 @@ fn test_read_only_connection() {
     }
 }
 
+#[test]
+fn test_clone() {
+    let mut c = common::conn();
+    let other = c.clone();
+    assert_eq!(Ok(1), c.close());
+    assert_eq!(Ok(String::from("test:///default")), other.get_uri());
+    common::close(other);
+}
+
 #[test]
 #[should_panic]
 fn test_connection_invalid() {
@@ -183,7 +195,7 @@ fn test_list_storage_pools() {
 #[test]
 fn test_list_all_domains() {
     let c = common::conn();
-    let v = c.list_all_domains(0).unwrap_or(vec![]);
+    let v = c.list_all_domains(ConnectListAllDomainsFlags::empty()).unwrap_or(vec![]);
     assert!(0 < v.len(), "At least one domain should exist");
     drop(v);
     common::close(c);
@@ -206,3 +218,61 @@ fn test_get_max_vcpus() {
     assert!(0 < m, "At least one cpu should exist");
     common::close(c);
 }
+
+#[test]
+fn test_close_callback() {
+    // The test driver does not implement close callbacks, the
+    // callback must still be released on unregister and on close.
+    let c = common::conn();
+    let data = Arc::new(());
+    let d = data.clone();
+    assert_eq!(Ok(()), c.register_close_callback(move |_, _| drop(d.clone())));
+    assert_eq!(2, Arc::strong_count(&data));
+    assert_eq!(Ok(()), c.unregister_close_callback());
+    assert_eq!(1, Arc::strong_count(&data));
+
+    let d = data.clone();
+    assert_eq!(Ok(()), c.register_close_callback(move |_, _| drop(d.clone())));
+    assert_eq!(2, Arc::strong_count(&data));
+    common::close(c);
+    assert_eq!(1, Arc::strong_count(&data));
+}
+
+#[test]
+fn test_close_callback_outlived() {
+    // The domain keeps a reference on the connection, closing the
+    // handle does not release it but must drop the callback.
+    let mut c = common::conn();
+    let dom = common::build_test_domain(&c, "close-callback", false);
+    let data = Arc::new(());
+    let d = data.clone();
+    assert_eq!(Ok(()), c.register_close_callback(move |_, _| drop(d.clone())));
+    let d = data.clone();
+    c.set_error_func(move |_| drop(d.clone()));
+    assert_eq!(3, Arc::strong_count(&data));
+    assert!(c.close().unwrap() > 0);
+    assert_eq!(1, Arc::strong_count(&data));
+    common::clean(dom);
+}
+
+#[test]
+fn test_close_callback_clone() {
+    // Only the handle which registered the callback releases it.
+    let c = common::conn();
+    let data = Arc::new(());
+    let d = data.clone();
+    assert_eq!(Ok(()), c.register_close_callback(move |_, _| drop(d.clone())));
+    assert_eq!(Ok(1), c.clone().close());
+    assert_eq!(2, Arc::strong_count(&data));
+    common::close(c);
+    assert_eq!(1, Arc::strong_count(&data));
+}
+
+#[test]
+fn test_open_auth_without_creds() {
+    let mut auth = ConnectAuth::new(Vec::new(), |_: &mut Vec<ConnectCredential>| {});
+    match Connect::open_auth("test:///default", &mut auth, ConnectFlags::empty()) {
+        Ok(conn) => common::close(conn),
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+}
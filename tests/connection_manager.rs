@@ -0,0 +1,118 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use virt::connection_manager::{Backoff, ConnectionManager};
+
+
+#[test]
+fn test_backoff_delay() {
+    let backoff = Backoff {
+        initial: Duration::from_millis(100),
+        max: Duration::from_secs(1),
+        max_attempts: None,
+    };
+    assert_eq!(Duration::from_millis(100), backoff.delay(0));
+    assert_eq!(Duration::from_millis(200), backoff.delay(1));
+    assert_eq!(Duration::from_millis(800), backoff.delay(3));
+    assert_eq!(Duration::from_secs(1), backoff.delay(4));
+    assert_eq!(Duration::from_secs(1), backoff.delay(100));
+}
+
+#[test]
+fn test_get() {
+    let manager = ConnectionManager::new("test:///default");
+    assert_eq!(0, manager.generation());
+    let conn = manager.get().unwrap();
+    assert_eq!(Ok(true), conn.is_alive());
+    let other = manager.get().unwrap();
+    assert_eq!(conn.as_ptr(), other.as_ptr());
+    assert_eq!(1, manager.generation());
+}
+
+#[test]
+fn test_subscriptions() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let manager = ConnectionManager::new("test:///default");
+    let c = calls.clone();
+    manager.subscribe(move |conn| {
+               assert_eq!(String::from("test:///default"), conn.get_uri()?);
+               c.fetch_add(1, Ordering::SeqCst);
+               Ok(())
+           })
+           .unwrap();
+    assert_eq!(0, calls.load(Ordering::SeqCst));
+    manager.get().unwrap();
+    assert_eq!(1, calls.load(Ordering::SeqCst));
+    manager.reconnect().unwrap();
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+    assert_eq!(2, manager.generation());
+    manager.close();
+    manager.get().unwrap();
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_give_up() {
+    let manager = ConnectionManager::new("invalid:///uri").backoff(Backoff {
+        initial: Duration::from_millis(1),
+        max: Duration::from_millis(5),
+        max_attempts: Some(3),
+    });
+    assert!(manager.get().is_err());
+    assert_eq!(0, manager.generation());
+}
+
+#[test]
+fn test_backoff_does_not_block() {
+    let manager = Arc::new(ConnectionManager::new("invalid:///uri").backoff(Backoff {
+        initial: Duration::from_millis(300),
+        max: Duration::from_millis(300),
+        max_attempts: Some(3),
+    }));
+    let m = manager.clone();
+    let getter = thread::spawn(move || m.get().is_err());
+    thread::sleep(Duration::from_millis(50));
+    let start = Instant::now();
+    assert_eq!(0, manager.generation());
+    manager.close();
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert!(getter.join().unwrap());
+}
+
+#[test]
+fn test_subscribe_waits_for_reopen() {
+    let manager = Arc::new(ConnectionManager::new("invalid:///uri").backoff(Backoff {
+        initial: Duration::from_millis(300),
+        max: Duration::from_millis(300),
+        max_attempts: Some(3),
+    }));
+    let m = manager.clone();
+    let getter = thread::spawn(move || m.get().is_err());
+    thread::sleep(Duration::from_millis(50));
+    let start = Instant::now();
+    manager.subscribe(|_| Ok(())).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert!(getter.join().unwrap());
+}
@@ -0,0 +1,99 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+
+use virt::connect::{ConnectAuth, ConnectCredential, ConnectCredentialType, CredentialProvider};
+use virt::credentials::{EnvCredentials, FileCredentials, StaticCredentials};
+
+
+fn creds() -> Vec<ConnectCredential> {
+    [ConnectCredentialType::Authname,
+     ConnectCredentialType::Passphrase,
+     ConnectCredentialType::Realm]
+        .iter()
+        .map(|typed| {
+            ConnectCredential {
+                typed: *typed,
+                prompt: String::new(),
+                challenge: String::new(),
+                def_result: String::new(),
+                result: None,
+            }
+        })
+        .collect()
+}
+
+fn results(creds: &[ConnectCredential]) -> Vec<Option<&str>> {
+    creds.iter().map(|c| c.result.as_ref().map(|r| r.as_str())).collect()
+}
+
+#[test]
+fn test_static() {
+    let mut c = creds();
+    c[2].def_result = String::from("realm");
+    StaticCredentials::new("user", "pass").provide(&mut c);
+    assert_eq!(vec![Some("user"), Some("pass"), Some("realm")], results(&c));
+
+    let mut c = creds();
+    StaticCredentials::password("pass").provide(&mut c);
+    assert_eq!(vec![None, Some("pass"), None], results(&c));
+}
+
+#[test]
+fn test_env() {
+    env::set_var("LIBVIRT_RS_TEST_USER", "user");
+    env::remove_var("LIBVIRT_RS_TEST_PASS");
+    let mut c = creds();
+    EnvCredentials::with_vars("LIBVIRT_RS_TEST_USER", "LIBVIRT_RS_TEST_PASS").provide(&mut c);
+    assert_eq!(vec![Some("user"), None, None], results(&c));
+}
+
+#[test]
+fn test_file() {
+    let path = env::temp_dir().join("libvirt-rs-test-credentials");
+    {
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "# test\n\nusername = user\npassword=p=ss").unwrap();
+    }
+    let mut c = creds();
+    FileCredentials::new(&path).provide(&mut c);
+    assert_eq!(vec![Some("user"), Some("p=ss"), None], results(&c));
+
+    let _ = ::std::fs::remove_file(&path);
+    let mut c = creds();
+    FileCredentials::new(&path).provide(&mut c);
+    assert_eq!(vec![None, None, None], results(&c));
+}
+
+#[test]
+fn test_closure() {
+    let secret = String::from("from-vault");
+    let mut provider = move |creds: &mut Vec<ConnectCredential>| for cred in creds {
+        cred.result = Some(secret.clone());
+    };
+    let mut c = creds();
+    provider.provide(&mut c);
+    assert_eq!(vec![Some("from-vault"); 3], results(&c));
+    let _ = ConnectAuth::new(vec![ConnectCredentialType::Passphrase], provider);
+    let _ = ConnectAuth::new(Vec::new(), StaticCredentials::new("user", "pass"));
+}
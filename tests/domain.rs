@@ -20,7 +20,9 @@ extern crate virt;
 
 mod common;
 
-use virt::domain::Domain;
+use virt::domain::{Domain, DomainControlState, DomainCreateFlags, DomainPausedReason,
+                   DomainRunningReason, DomainShutoffReason, DomainState, DomainStateReason,
+                   DomainVcpuFlags, DomainXMLFlags};
 
 
 fn tdom(exec_test: fn(dom: Domain)) {
@@ -63,7 +65,7 @@ fn test_id() {
 #[test]
 fn test_get_xml_desc() {
     fn t(dom: Domain) {
-        assert!("" != dom.get_xml_desc(0).unwrap_or(String::new()),
+        assert!("" != dom.get_xml_desc(DomainXMLFlags::empty()).unwrap_or(String::new()),
                 "Should not be empty");
     }
     tdom(t);
@@ -73,7 +75,7 @@ fn test_get_xml_desc() {
 fn test_get_info() {
     fn t(dom: Domain) {
         match dom.get_info() {
-            Ok(info) => assert_eq!(1, info.state),
+            Ok(info) => assert_eq!(DomainState::Running, info.state),
             Err(_) => panic!("should have a node info"),
         }
     }
@@ -83,7 +85,7 @@ fn test_get_info() {
 #[test]
 fn test_get_vcpus_flags() {
     fn t(dom: Domain) {
-        assert_eq!(2, dom.get_vcpus_flags(0).unwrap_or(0));
+        assert_eq!(2, dom.get_vcpus_flags(DomainVcpuFlags::empty()).unwrap_or(0));
     }
     tdom(t);
 }
@@ -115,8 +117,10 @@ fn test_lookup_domain_by_name() {
 fn test_create_with_flags() {
     let c = common::conn();
     let d = common::build_test_domain(&c, "create", false);
-    assert_eq!(Ok(0), d.create_with_flags(0));
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_RUNNING, 1)), d.get_state());
+    assert_eq!(Ok(0), d.create_with_flags(DomainCreateFlags::empty()));
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Booted))),
+               d.get_state());
     assert_eq!(Ok(String::from("libvirt-rs-test-create")), d.get_name());
     common::clean(d);
     common::close(c);
@@ -126,10 +130,14 @@ fn test_create_with_flags() {
 fn test_shutdown() {
     let c = common::conn();
     let d = common::build_test_domain(&c, "shutdown", false);
-    assert_eq!(Ok(0), d.create_with_flags(0));
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_RUNNING, 1)), d.get_state());
+    assert_eq!(Ok(0), d.create_with_flags(DomainCreateFlags::empty()));
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Booted))),
+               d.get_state());
     assert_eq!(Ok(0), d.shutdown());
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_SHUTOFF, 1)), d.get_state());
+    assert_eq!(Ok((DomainState::Shutoff,
+                   DomainStateReason::Shutoff(DomainShutoffReason::Shutdown))),
+               d.get_state());
     common::clean(d);
     common::close(c);
 }
@@ -138,12 +146,57 @@ fn test_shutdown() {
 fn test_pause_resume() {
     let c = common::conn();
     let d = common::build_test_domain(&c, "pause_resume", false);
-    assert_eq!(Ok(0), d.create_with_flags(0));
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_RUNNING, 1)), d.get_state());
+    assert_eq!(Ok(0), d.create_with_flags(DomainCreateFlags::empty()));
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Booted))),
+               d.get_state());
     assert_eq!(Ok(0), d.suspend());
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_PAUSED, 1)), d.get_state());
+    assert_eq!(Ok((DomainState::Paused,
+                   DomainStateReason::Paused(DomainPausedReason::User))),
+               d.get_state());
     assert_eq!(Ok(0), d.resume());
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_RUNNING, 5)), d.get_state());
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Unpaused))),
+               d.get_state());
     common::clean(d);
     common::close(c);
 }
+
+#[test]
+fn test_get_control_info() {
+    fn t(dom: Domain) {
+        match dom.get_control_info(0) {
+            Ok(info) => {
+                assert_eq!(DomainControlState::Ok, info.state);
+                assert_eq!(0, info.state_time);
+            }
+            Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+        }
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_clone() {
+    fn t(dom: Domain) {
+        let other = dom.clone();
+        drop(dom);
+        assert_eq!(Ok(String::from("test")), other.get_name());
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_get_connect() {
+    fn t(dom: Domain) {
+        match dom.get_connect() {
+            Ok(conn) => {
+                assert_eq!(Ok(String::from("test:///default")), conn.get_uri());
+                drop(conn);
+            }
+            Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+        }
+        assert_eq!(Ok(String::from("test")), dom.get_name());
+    }
+    tdom(t);
+}
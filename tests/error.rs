@@ -0,0 +1,82 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use virt::domain::Domain;
+use virt::error::{self, Error, ErrorDomain, ErrorNumber};
+
+
+#[test]
+fn test_raw_values() {
+    assert_eq!(ErrorNumber::NoDomain, ErrorNumber::from_raw(42));
+    assert_eq!(ErrorNumber::OperationInvalid, ErrorNumber::from_raw(55));
+    assert_eq!(ErrorNumber::Unknown, ErrorNumber::from_raw(10000));
+    assert_eq!(45, ErrorNumber::AuthFailed.to_raw());
+    assert_eq!(ErrorDomain::Test, ErrorDomain::from_raw(12));
+    assert_eq!(ErrorDomain::Unknown, ErrorDomain::from_raw(-5));
+    assert_eq!(35, ErrorDomain::DomainSnapshot.to_raw());
+}
+
+#[test]
+fn test_no_domain() {
+    let c = common::conn();
+    match Domain::lookup_by_name(&c, "missing") {
+        Ok(_) => panic!("domain should not exist"),
+        Err(e) => {
+            assert_eq!(ErrorNumber::NoDomain, e.code);
+            assert_eq!(ErrorDomain::Test, e.domain);
+            assert!(e.message.contains("missing"));
+        }
+    }
+    assert_eq!(None, Error::last_error());
+    common::close(c);
+}
+
+#[test]
+fn test_no_error() {
+    Error::clear();
+    assert_eq!(None, Error::last_error());
+    let e = Error::new();
+    assert_eq!(ErrorNumber::Ok, e.code);
+    assert_eq!(ErrorDomain::None, e.domain);
+}
+
+#[test]
+fn test_error_func() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let c = common::conn();
+    let cc = count.clone();
+    c.set_error_func(move |e| {
+        assert_eq!(ErrorNumber::NoDomain, e.code);
+        cc.fetch_add(1, Ordering::SeqCst);
+    });
+    assert!(Domain::lookup_by_name(&c, "missing").is_err());
+    assert_eq!(1, count.load(Ordering::SeqCst));
+    c.reset_error_func();
+    error::set_error_func(error::log_error);
+    assert!(Domain::lookup_by_name(&c, "missing").is_err());
+    assert_eq!(1, count.load(Ordering::SeqCst));
+    error::reset_error_func();
+    common::close(c);
+}
@@ -0,0 +1,107 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use virt::connect::{ConnectCredentialType, ConnectGetAllDomainStatsFlags,
+                    ConnectListAllDomainsFlags, CPUCompareResult};
+use virt::domain::{DomainControlErrorReason, DomainControlState, DomainCoreDumpFlags,
+                   DomainCoreDumpFormat, DomainJobOperation, DomainJobType, DomainMigrateFlags,
+                   DomainPausedReason, DomainShutoffReason, DomainState, DomainStateReason,
+                   DomainStatsTypes};
+use virt::network::NetworkUpdateSection;
+use virt::secret::SecretSecretUsageType;
+use virt::storage_pool::{StoragePoolBuildFlags, StoragePoolDeleteFlags, StoragePoolState};
+use virt::storage_vol::{StorageVolDeleteFlags, StorageVolType, StorageVolWipeAlgorithm};
+use virt::typedparam::TypedParameterType;
+
+#[test]
+fn test_flags_bits() {
+    let flags = ConnectListAllDomainsFlags::ACTIVE | ConnectListAllDomainsFlags::PERSISTENT;
+    assert_eq!(0b101, flags.bits());
+    assert!(flags.contains(ConnectListAllDomainsFlags::ACTIVE));
+    assert!(!flags.contains(ConnectListAllDomainsFlags::INACTIVE));
+    assert_eq!(0, ConnectListAllDomainsFlags::empty().bits());
+    assert_eq!(Some(DomainMigrateFlags::LIVE | DomainMigrateFlags::TLS),
+               DomainMigrateFlags::from_bits(1 | 1 << 16));
+    assert_eq!(None, DomainMigrateFlags::from_bits(1 << 31));
+    assert_eq!(1 << 2, StoragePoolBuildFlags::NO_OVERWRITE.bits());
+    assert_eq!(1, StoragePoolDeleteFlags::ZEROED.bits());
+    assert_eq!(2, StorageVolDeleteFlags::WITH_SNAPSHOTS.bits());
+    assert_eq!(1 << 4, DomainCoreDumpFlags::MEMORY_DUMP.bits());
+    assert_eq!(1 << 10, DomainStatsTypes::VM.bits());
+    assert_eq!(1 << 31, ConnectGetAllDomainStatsFlags::ENFORCE_STATS.bits());
+}
+
+#[test]
+fn test_enums_raw() {
+    assert_eq!(DomainState::Running, DomainState::from_raw(1));
+    assert_eq!(DomainState::PmSuspended, DomainState::from_raw(7));
+    assert_eq!(DomainState::Unknown, DomainState::from_raw(42));
+    assert_eq!(StoragePoolState::Inaccessible, StoragePoolState::from_raw(4));
+    assert_eq!(StorageVolType::Ploop, StorageVolType::from_raw(5));
+    assert_eq!(CPUCompareResult::Superset, CPUCompareResult::from_raw(2));
+    assert_eq!(CPUCompareResult::Unknown, CPUCompareResult::from_raw(3));
+    assert_eq!(ConnectCredentialType::External, ConnectCredentialType::from_raw(9));
+    assert_eq!(9, StorageVolWipeAlgorithm::Trim as i32);
+    assert_eq!(12, NetworkUpdateSection::DnsSrv as i32);
+    assert_eq!(DomainJobType::Cancelled, DomainJobType::from_raw(5));
+    assert!(DomainJobType::from_raw(3).is_finished());
+    assert!(!DomainJobType::Unbounded.is_finished());
+    assert_eq!(DomainJobOperation::Backup, DomainJobOperation::from_raw(9));
+    assert_eq!(DomainJobOperation::Unknown, DomainJobOperation::from_raw(42));
+    assert_eq!(TypedParameterType::ULLong, TypedParameterType::from_raw(4));
+    assert_eq!(TypedParameterType::Unknown, TypedParameterType::from_raw(8));
+    assert_eq!(7, TypedParameterType::String.to_raw());
+    assert_eq!(4, DomainCoreDumpFormat::WinDmp as i32);
+    assert_eq!(SecretSecretUsageType::Ceph, SecretSecretUsageType::from_raw(2));
+}
+
+#[test]
+fn test_state_reason() {
+    let reason = DomainStateReason::from_raw(DomainState::Paused, 5);
+    assert_eq!(DomainStateReason::Paused(DomainPausedReason::IoError), reason);
+    assert_eq!("paused (I/O error)", format!("{} ({})", DomainState::Paused, reason));
+    assert_eq!(DomainStateReason::Shutoff(DomainShutoffReason::Unknown(42)),
+               DomainStateReason::from_raw(DomainState::Shutoff, 42));
+    assert_eq!("unknown (42)", DomainStateReason::from_raw(DomainState::Shutoff, 42).to_string());
+    assert_eq!("unknown", DomainStateReason::from_raw(DomainState::Running, 0).to_string());
+    assert_eq!(DomainControlState::Unknown(7), DomainControlState::from_raw(7));
+    assert_eq!(DomainControlErrorReason::Unknown(1), DomainControlErrorReason::from_raw(1));
+    assert_eq!(DomainStateReason::Unknown(3),
+               DomainStateReason::from_raw(DomainState::from_raw(42), 3));
+    assert_eq!("shut off (saved)",
+               format!("{} ({})",
+                       DomainState::Shutoff,
+                       DomainStateReason::from_raw(DomainState::Shutoff, 5)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_aliases() {
+    use virt::connect::{VIR_CONNECT_LIST_DOMAINS_ACTIVE, VIR_CRED_AUTHNAME};
+    use virt::domain::{VIR_DOMAIN_SHUTOFF, VIR_MIGRATE_PEER2PEER};
+
+    assert_eq!(ConnectListAllDomainsFlags::ACTIVE, VIR_CONNECT_LIST_DOMAINS_ACTIVE);
+    assert_eq!(DomainMigrateFlags::PEER2PEER, VIR_MIGRATE_PEER2PEER);
+    assert_eq!(DomainState::Shutoff, VIR_DOMAIN_SHUTOFF);
+    match ConnectCredentialType::from_raw(2) {
+        VIR_CRED_AUTHNAME => (),
+        other => panic!("unexpected credential type {:?}", other),
+    }
+}
@@ -23,15 +23,36 @@ extern crate virt;
 
 mod common;
 
-use virt::connect::{Connect, ConnectAuth, ConnectCredential};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use virt::connect::{CloseReason, Connect, ConnectAuth, ConnectCredential, ConnectCredentialType,
+                    ConnectFlags};
+use virt::connection_manager::ConnectionManager;
+use virt::error::ErrorNumber;
+use virt::event;
+use virt::domain::{DomainCreateFlags, DomainRunningReason, DomainState, DomainStateReason};
+use virt::domain_checkpoint::{DomainCheckpoint, DomainCheckpointCreateFlags,
+                              DomainCheckpointDeleteFlags, DomainCheckpointListFlags,
+                              DomainCheckpointXMLFlags};
+use virt::domain_snapshot::{DomainSnapshot, DomainSnapshotCreateFlags, DomainSnapshotDeleteFlags,
+                            DomainSnapshotListFlags, DomainSnapshotRevertFlags};
+use virt::network_port::{NetworkPort, NetworkPortCreateFlags, NetworkPortParameters};
+use virt::nwfilter_binding::NWFilterBinding;
+use virt::storage_pool::{StoragePoolCreateFlags, StoragePoolState};
+use virt::storage_vol::{StorageVolResizeFlags, StorageVolType};
+use virt::xml::nwfilter::NWFilterBindingDef;
 
 #[test]
 #[ignore]
 fn test_create_domain_with_flags() {
     let c = common::qemu_conn();
     let d = common::build_qemu_domain(&c, "create", false);
-    assert_eq!(Ok(0), d.create_with_flags(0));
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_START_PAUSED, 1)),
+    assert_eq!(Ok(0), d.create_with_flags(DomainCreateFlags::empty()));
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Booted))),
                d.get_state());
     assert_eq!(Ok(String::from("libvirt-rs-test-create")), d.get_name());
     common::clean(d);
@@ -43,7 +64,7 @@ fn test_create_domain_with_flags() {
 fn test_create_storage_pool_and_vols() {
     let c = common::qemu_conn();
     let p = common::build_storage_pool(&c, "create", false);
-    assert_eq!(Ok(0), p.create(0));
+    assert_eq!(Ok(0), p.create(StoragePoolCreateFlags::empty()));
     assert_eq!(Ok(String::from("libvirt-rs-test-create")), p.get_name());
     let v = common::build_storage_vol(&p, "vol1", 8);
     assert_eq!(Ok(String::from("vol1")), v.get_name());
@@ -52,7 +73,7 @@ fn test_create_storage_pool_and_vols() {
     assert_eq!(Ok(String::from("/var/lib/libvirt/images/vol1")),
                v.get_key());
     if let Ok(info) = v.get_info() {
-        assert_eq!(0, info.kind);
+        assert_eq!(StorageVolType::File, info.kind);
         assert_eq!(8192, info.allocation);
         assert_eq!(8192, info.capacity);
     } else {
@@ -61,9 +82,9 @@ fn test_create_storage_pool_and_vols() {
         common::close(c);
         panic!("should not be here")
     }
-    assert_eq!(Ok(0), v.resize(10240, 0));
+    assert_eq!(Ok(0), v.resize(10240, StorageVolResizeFlags::empty()));
     if let Ok(info) = v.get_info() {
-        assert_eq!(0, info.kind);
+        assert_eq!(StorageVolType::File, info.kind);
         assert_eq!(8192, info.allocation);
         assert_eq!(10240, info.capacity);
     } else {
@@ -73,7 +94,7 @@ fn test_create_storage_pool_and_vols() {
         panic!("should not be here")
     }
     if let Ok(info) = p.get_info() {
-        assert_eq!(2, info.state);
+        assert_eq!(StoragePoolState::Running, info.state);
         assert_eq!(0, info.capacity - (info.allocation + info.available));
     } else {
         common::clean_vol(v);
@@ -92,10 +113,10 @@ fn test_connection_with_auth() {
     fn callback(creds: &mut Vec<ConnectCredential>) {
         for cred in creds {
             match cred.typed {
-                ::virt::connect::VIR_CRED_AUTHNAME => {
+                ConnectCredentialType::Authname => {
                     cred.result = Some(String::from("user"));
                 }
-                ::virt::connect::VIR_CRED_PASSPHRASE => {
+                ConnectCredentialType::Passphrase => {
                     cred.result = Some(String::from("pass"));
                 }
                 _ => {
@@ -105,10 +126,10 @@ fn test_connection_with_auth() {
         }
     };
 
-    let mut auth = ConnectAuth::new(vec![::virt::connect::VIR_CRED_AUTHNAME,
-                                         ::virt::connect::VIR_CRED_PASSPHRASE],
+    let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname,
+                                         ConnectCredentialType::Passphrase],
                                     callback);
-    let c = Connect::open_auth("test+tcp://127.0.0.1/default", &mut auth, 0);
+    let c = Connect::open_auth("test+tcp://127.0.0.1/default", &mut auth, ConnectFlags::empty());
     assert_eq!(true, c.is_ok());
     common::close(c.unwrap());
 }
@@ -120,10 +141,10 @@ fn test_connection_with_auth_wrong() {
     fn callback(creds: &mut Vec<ConnectCredential>) {
         for cred in creds {
             match cred.typed {
-                ::virt::connect::VIR_CRED_AUTHNAME => {
+                ConnectCredentialType::Authname => {
                     cred.result = Some(String::from("user"));
                 }
-                ::virt::connect::VIR_CRED_PASSPHRASE => {
+                ConnectCredentialType::Passphrase => {
                     cred.result = Some(String::from("passwrong"));
                 }
                 _ => {
@@ -133,10 +154,10 @@ fn test_connection_with_auth_wrong() {
         }
     };
 
-    let mut auth = ConnectAuth::new(vec![::virt::connect::VIR_CRED_AUTHNAME,
-                                         ::virt::connect::VIR_CRED_PASSPHRASE],
+    let mut auth = ConnectAuth::new(vec![ConnectCredentialType::Authname,
+                                         ConnectCredentialType::Passphrase],
                                     callback);
-    let c = Connect::open_auth("test+tcp://127.0.0.1/default", &mut auth, 0);
+    let c = Connect::open_auth("test+tcp://127.0.0.1/default", &mut auth, ConnectFlags::empty());
     assert_eq!(false, c.is_ok());
 }
 
@@ -145,10 +166,197 @@ fn test_connection_with_auth_wrong() {
 fn test_reset() {
     let c = common::qemu_conn();
     let d = common::build_qemu_domain(&c, "reset", false);
-    assert_eq!(Ok(0), d.create_with_flags(0));
-    assert_eq!(Ok((::virt::domain::VIR_DOMAIN_RUNNING, 1)), d.get_state());
+    assert_eq!(Ok(0), d.create_with_flags(DomainCreateFlags::empty()));
+    assert_eq!(Ok((DomainState::Running,
+                   DomainStateReason::Running(DomainRunningReason::Booted))),
+               d.get_state());
     assert_eq!(Ok(0), d.reset());
     // TODO assert something showing reset has the intended side effect
     common::clean(d);
     common::close(c);
 }
+
+#[test]
+#[ignore]
+fn test_network_dhcp_leases() {
+    let c = common::qemu_conn();
+    let n = common::build_network(&c, "leases", false);
+    assert_eq!(Ok(0), n.create());
+    match n.get_dhcp_leases(None) {
+        Ok(leases) => assert_eq!(0, leases.len()),
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+    match n.get_dhcp_leases(Some("52:54:00:00:00:01")) {
+        Ok(leases) => assert_eq!(0, leases.len()),
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+    common::clean_net(n);
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_network_port_parameters() {
+    let c = common::qemu_conn();
+    let n = common::build_network(&c, "ports", false);
+    assert_eq!(Ok(0), n.create());
+    let xml = "<networkport>
+                 <owner>
+                   <name>guest</name>
+                   <uuid>c7a5fdbd-edaf-9455-926a-d65c16db1809</uuid>
+                 </owner>
+                 <mac address='52:54:00:00:00:02'/>
+               </networkport>";
+    let port = match NetworkPort::create_xml(&n, xml, NetworkPortCreateFlags::empty()) {
+        Ok(port) => port,
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    };
+    let uuid = port.get_uuid_string().unwrap_or_default();
+    assert_eq!(1, n.list_all_ports(0).unwrap_or_default().len());
+    assert!(NetworkPort::lookup_by_uuid_string(&n, &uuid).is_ok());
+    let params = NetworkPortParameters {
+        inbound_average: Some(1000),
+        inbound_burst: Some(2048),
+        outbound_average: Some(500),
+        ..Default::default()
+    };
+    assert_eq!(Ok(()), port.set_parameters(&params, 0));
+    match port.get_parameters(0) {
+        Ok(p) => {
+            assert_eq!(Some(1000), p.inbound_average);
+            assert_eq!(Some(500), p.outbound_average);
+        }
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+    assert_eq!(Ok(()), port.delete(0));
+    drop(port);
+    common::clean_net(n);
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_nwfilter_bindings_read_only() {
+    let c = match Connect::open_read_only("qemu:///system") {
+        Ok(c) => c,
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    };
+    let bindings = match c.list_all_nw_filter_bindings(0) {
+        Ok(bindings) => bindings,
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    };
+    for binding in bindings {
+        let def = match NWFilterBindingDef::from_binding(&binding) {
+            Ok(def) => def,
+            Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+        };
+        assert_eq!(binding.get_port_dev(), Ok(def.port_dev.clone()));
+        assert_eq!(binding.get_filter_name(), Ok(def.filter.filter.clone()));
+        assert!(NWFilterBinding::lookup_by_port_dev(&c, &def.port_dev).is_ok());
+    }
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_snapshot_revert() {
+    let c = common::qemu_conn();
+    let d = common::build_qemu_domain(&c, "snapshot", false);
+    let s = match DomainSnapshot::create_xml(&d,
+                                             "<domainsnapshot>
+                                                <name>golden</name>
+                                              </domainsnapshot>",
+                                             DomainSnapshotCreateFlags::empty()) {
+        Ok(s) => s,
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    };
+    assert_eq!(Ok(String::from("golden")), s.get_name());
+    assert_eq!(Ok(1), DomainSnapshot::num(&d, DomainSnapshotListFlags::empty()));
+    assert_eq!(Ok(1),
+               DomainSnapshot::num(&d, DomainSnapshotListFlags::ROOTS));
+    assert_eq!(Ok(()), s.revert(DomainSnapshotRevertFlags::empty()));
+    assert_eq!(Ok(true), s.is_current(0));
+    assert_eq!(Ok(0),
+               s.delete(DomainSnapshotDeleteFlags::CHILDREN));
+    assert_eq!(Ok(0), DomainSnapshot::num(&d, DomainSnapshotListFlags::empty()));
+    drop(s);
+    common::clean(d);
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_checkpoint_children() {
+    let c = common::qemu_conn();
+    let d = common::build_qemu_domain(&c, "checkpoint", false);
+    let create = |name: &str| {
+        let xml = format!("<domaincheckpoint><name>{}</name></domaincheckpoint>", name);
+        match DomainCheckpoint::create_xml(&d, &xml, DomainCheckpointCreateFlags::empty()) {
+            Ok(cp) => cp,
+            Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+        }
+    };
+    let first = create("first");
+    let second = create("second");
+    assert_eq!(Ok(String::from("second")), second.get_name());
+    assert_eq!(Ok(String::from("first")), second.get_parent(0).and_then(|p| p.get_name()));
+    assert!(second.get_xml_desc(DomainCheckpointXMLFlags::NO_DOMAIN)
+        .unwrap_or_default()
+        .contains("<name>first</name>"));
+    let children = first.list_all_children(DomainCheckpointListFlags::empty()).unwrap_or_default();
+    assert_eq!(vec![Ok(String::from("second"))],
+               children.iter().map(|cp| cp.get_name()).collect::<Vec<_>>());
+    let all = d.list_all_checkpoints(DomainCheckpointListFlags::empty()).unwrap_or_default();
+    assert_eq!(2, all.len());
+    drop(all);
+    assert_eq!(Ok(()), first.delete(DomainCheckpointDeleteFlags::CHILDREN));
+    assert!(DomainCheckpoint::lookup_by_name(&d, "second", 0).is_err());
+    drop(second);
+    drop(first);
+    common::clean(d);
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_close_callback() {
+    // Close callbacks are only stored by the remote driver.
+    let c = common::qemu_conn();
+    assert_eq!(Ok(()),
+               c.register_close_callback(|_, reason| assert_eq!(CloseReason::Client, reason)));
+    match c.register_close_callback(|_, _| {}) {
+        Ok(_) => panic!("only one close callback can be registered"),
+        Err(e) => assert_eq!(ErrorNumber::OperationInvalid, e.code),
+    }
+    assert_eq!(Ok(()), c.unregister_close_callback());
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_connection_manager_reopen_on_close() {
+    // Without keepalive messages allowed to go unanswered, the remote
+    // driver closes the connection after a second of inactivity and
+    // invokes the close callback registered by the manager.
+    assert_eq!(Ok(()), event::register_default_impl());
+    thread::spawn(|| loop {
+                      let _ = event::run_default_impl();
+                  });
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let manager = ConnectionManager::new("qemu:///system").keep_alive(1, 0);
+    manager.subscribe(move |_| {
+               c.fetch_add(1, Ordering::SeqCst);
+               Ok(())
+           })
+           .unwrap();
+    let conn = manager.get().unwrap();
+    assert_eq!(1, manager.generation());
+    thread::sleep(Duration::from_secs(3));
+    assert_eq!(Ok(false), conn.is_alive());
+    drop(conn);
+    let conn = manager.get().unwrap();
+    assert_eq!(Ok(true), conn.is_alive());
+    assert_eq!(2, manager.generation());
+    assert_eq!(2, calls.load(Ordering::SeqCst));
+}
@@ -20,6 +20,8 @@ extern crate virt;
 
 mod common;
 
+use virt::interface::InterfaceXMLFlags;
+
 #[test]
 fn test_create() {
     let c = common::conn();
@@ -27,7 +29,7 @@ fn test_create() {
     assert_eq!(Ok(0), n.create(0));
     assert_eq!(Ok(String::from("libvirt-rs-test-wipes")), n.get_name());
     assert!(0 != n.get_mac_string().unwrap_or(String::new()).len());
-    assert!(0 != n.get_xml_desc(0).unwrap_or(String::new()).len());
+    assert!(0 != n.get_xml_desc(InterfaceXMLFlags::empty()).unwrap_or(String::new()).len());
     common::clean_iface(n);
     common::close(c);
 }
@@ -20,6 +20,9 @@ extern crate virt;
 
 mod common;
 
+use virt::network::{NetworkUpdateFlags, NetworkXMLFlags};
+use virt::xml::network::{DhcpHost, NetworkDef};
+
 
 #[test]
 fn test_create() {
@@ -28,7 +31,7 @@ fn test_create() {
     assert_eq!(Ok(0), n.create());
     assert_eq!(Ok(String::from("libvirt-rs-test-wipes")), n.get_name());
     assert!(0 != n.get_uuid_string().unwrap_or(String::new()).len());
-    assert!(0 != n.get_xml_desc(0).unwrap_or(String::new()).len());
+    assert!(0 != n.get_xml_desc(NetworkXMLFlags::empty()).unwrap_or(String::new()).len());
     common::clean_net(n);
     common::close(c);
 }
@@ -63,3 +66,20 @@ fn test_lookup_network_by_name() {
     assert!(0 < v.len(), "At least one network should exist");
     common::close(c);
 }
+
+#[test]
+fn test_update_dhcp_host() {
+    let c = common::conn();
+    let n = common::build_network(&c, "dhcp-host", false);
+    let flags = NetworkUpdateFlags::CURRENT;
+    assert_eq!(Ok(()),
+               n.add_dhcp_host("192.168.0.10", "52:54:00:00:00:10", Some("web"), flags));
+    let def = NetworkDef::from_network(&n, NetworkXMLFlags::empty()).unwrap();
+    assert_eq!(Some(String::from("web")), def.dhcp_hosts()[0].name);
+    let host = DhcpHost::new("52:54:00:00:00:10", "192.168.0.10");
+    assert_eq!(Ok(()), n.remove_dhcp_host(&host, flags));
+    let def = NetworkDef::from_network(&n, NetworkXMLFlags::empty()).unwrap();
+    assert!(def.dhcp_hosts().is_empty());
+    common::clean_net(n);
+    common::close(c);
+}
@@ -0,0 +1,152 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+mod common;
+
+use virt::domain_snapshot::{DomainSnapshot, DomainSnapshotCreateFlags,
+                            DomainSnapshotDeleteFlags};
+use virt::snapshot_tree::SnapshotTree;
+
+
+fn snap(name: &str, parent: Option<&str>, time: i64) -> String {
+    let parent = match parent {
+        Some(p) => format!("<parent><name>{}</name></parent>", p),
+        None => String::new(),
+    };
+    format!("<domainsnapshot>
+               <name>{}</name>
+               <state>shutoff</state>
+               {}
+               <creationTime>{}</creationTime>
+               <domain type='test'><name>guest</name></domain>
+             </domainsnapshot>",
+            name,
+            parent,
+            time)
+}
+
+//    a       e
+//   / \
+//  b   c
+//      |
+//      d
+fn tree() -> SnapshotTree {
+    SnapshotTree::from_xml_descs(vec![(snap("d", Some("c"), 4), true),
+                                      (snap("b", Some("a"), 2), false),
+                                      (snap("a", None, 1), false),
+                                      (snap("e", None, 5), false),
+                                      (snap("c", Some("a"), 3), false)])
+}
+
+fn names(nodes: Vec<&virt::snapshot_tree::SnapshotNode>) -> Vec<&str> {
+    nodes.iter().map(|n| n.name.as_str()).collect()
+}
+
+#[test]
+fn test_structure() {
+    let t = tree();
+    assert_eq!(5, t.len());
+    assert_eq!(vec!["a", "e"], names(t.roots()));
+    assert_eq!(vec!["b", "c"], names(t.children("a")));
+    assert_eq!(Some(String::from("c")), t.get("d").unwrap().parent);
+    assert_eq!(2, t.get("d").unwrap().depth);
+    assert_eq!(0, t.get("e").unwrap().depth);
+    assert_eq!(Some(4), t.get("d").unwrap().creation_time);
+    assert_eq!("d", t.current().unwrap().name);
+}
+
+#[test]
+fn test_traversal() {
+    let t = tree();
+    assert_eq!(vec!["a", "b", "c", "d", "e"], names(t.pre_order()));
+    assert_eq!(vec!["b", "d", "c", "a", "e"], names(t.post_order()));
+    assert_eq!(vec!["d", "c"], names(t.subtree_post_order("c")));
+}
+
+#[test]
+fn test_path() {
+    let t = tree();
+    assert_eq!(Some(vec![String::from("b"), String::from("a"), String::from("c"),
+                         String::from("d")]),
+               t.path("b", "d"));
+    assert_eq!(Some(vec![String::from("a"), String::from("c")]),
+               t.path("a", "c"));
+    assert_eq!(Some(vec![String::from("d")]), t.path("d", "d"));
+    assert_eq!(None, t.path("b", "e"));
+    assert_eq!(None, t.path("b", "unknown"));
+}
+
+#[test]
+fn test_orphan_is_root() {
+    let t = SnapshotTree::from_xml_descs(vec![(snap("x", Some("gone"), 1), false)]);
+    assert_eq!(vec!["x"], names(t.roots()));
+    assert_eq!(None, t.get("x").unwrap().parent);
+}
+
+#[test]
+fn test_parent_cycle() {
+    let t = SnapshotTree::from_xml_descs(vec![(snap("x", Some("y"), 1), false),
+                                              (snap("y", Some("x"), 2), false),
+                                              (snap("z", Some("y"), 3), false)]);
+    assert_eq!(vec!["x"], names(t.roots()));
+    assert_eq!(vec!["x", "y", "z"], names(t.pre_order()));
+    assert_eq!(2, t.get("z").unwrap().depth);
+    assert_eq!(Some(vec![String::from("z"), String::from("y"), String::from("x")]),
+               t.path("z", "x"));
+}
+
+#[test]
+fn test_parse_names() {
+    let t = SnapshotTree::from_xml_descs(vec![
+        (String::from("<domainsnapshot><name><![CDATA[a<b]]></name></domainsnapshot>"), false),
+        (String::from("<domainsnapshot>
+                         <name>c&#38;d</name>
+                         <parent><name>a&lt;b</name></parent>
+                       </domainsnapshot>"),
+         false),
+        (String::from("<domainsnapshot><name/></domainsnapshot>"), false),
+        (String::from("<domainsnapshot><name>"), false),
+    ]);
+    assert_eq!(2, t.len());
+    assert_eq!(vec!["a<b", "c&d"], names(t.pre_order()));
+}
+
+#[test]
+fn test_delete_subtree() {
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "snapshot-tree", false);
+    for name in &["a", "b", "c", "d"] {
+        let xml = format!("<domainsnapshot><name>{}</name></domainsnapshot>", name);
+        DomainSnapshot::create_xml(&d, &xml, DomainSnapshotCreateFlags::empty()).unwrap();
+    }
+
+    let mut t = SnapshotTree::from_domain(&d).unwrap();
+    assert_eq!(vec!["a", "b", "c", "d"], names(t.pre_order()));
+    assert_eq!(Ok(vec![String::from("d"), String::from("c")]),
+               t.delete_subtree(&d, "b", DomainSnapshotDeleteFlags::CHILDREN_ONLY));
+    assert_eq!(vec!["a", "b"], names(t.pre_order()));
+    assert_eq!(Ok(vec![String::from("b")]),
+               t.delete_subtree(&d, "b", DomainSnapshotDeleteFlags::empty()));
+    assert_eq!(vec!["a"], names(t.pre_order()));
+    assert_eq!(vec!["a"], names(SnapshotTree::from_domain(&d).unwrap().pre_order()));
+
+    common::clean(d);
+    common::close(c);
+}
@@ -19,7 +19,7 @@
 extern crate virt;
 
 use virt::connect::Connect;
-use virt::storage_pool::StoragePool;
+use virt::storage_pool::{StoragePool, StoragePoolXMLFlags};
 
 mod common;
 
@@ -38,7 +38,7 @@ fn exercices() {
                                 .len());
                     assert!(0 !=
                             storage_pool
-                                .get_xml_desc(0)
+                                .get_xml_desc(StoragePoolXMLFlags::empty())
                                 .unwrap_or(String::new())
                                 .len());
                 }
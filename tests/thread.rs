@@ -0,0 +1,109 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+
+use virt::connect::{Connect, ConnectListAllDomainsFlags};
+use virt::domain::Domain;
+
+const THREADS: usize = 16;
+const ITERATIONS: usize = 50;
+
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_markers() {
+    assert_send_sync::<Connect>();
+    assert_send_sync::<Domain>();
+    assert_send_sync::<virt::network::Network>();
+    assert_send_sync::<virt::storage_pool::StoragePool>();
+    assert_send_sync::<virt::storage_vol::StorageVol>();
+    assert_send_sync::<virt::secret::Secret>();
+    assert_send_sync::<virt::nodedev::NodeDevice>();
+    assert_send_sync::<virt::interface::Interface>();
+    assert_send_sync::<virt::nwfilter::NWFilter>();
+    assert_send_sync::<virt::nwfilter_binding::NWFilterBinding>();
+    assert_send_sync::<virt::domain_snapshot::DomainSnapshot>();
+    assert_send_sync::<virt::domain_checkpoint::DomainCheckpoint>();
+    assert_send_sync::<virt::network_port::NetworkPort>();
+}
+
+#[test]
+fn test_shared_connect() {
+    let c = Arc::new(common::conn());
+    let workers: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let c = c.clone();
+            thread::spawn(move || for _ in 0..ITERATIONS {
+                assert_eq!(Ok(String::from("test:///default")), c.get_uri());
+                let dom = Domain::lookup_by_name(&c, "test").unwrap();
+                assert_eq!(Ok(String::from("test")), dom.get_name());
+                let doms = c.list_all_domains(ConnectListAllDomainsFlags::empty()).unwrap();
+                assert_eq!(1, doms.len());
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    common::close(Arc::try_unwrap(c).unwrap());
+}
+
+#[test]
+fn test_errors_per_thread() {
+    let c = Arc::new(common::conn());
+    let workers: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let c = c.clone();
+            thread::spawn(move || for _ in 0..ITERATIONS {
+                let name = format!("missing-{}", i);
+                match Domain::lookup_by_name(&c, &name) {
+                    Ok(_) => panic!("domain {} should not exist", name),
+                    Err(e) => {
+                        assert!(e.message.contains(&name),
+                                "error of another thread: {}",
+                                e.message)
+                    }
+                }
+                assert!(Domain::lookup_by_name(&c, "test").is_ok());
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    common::close(Arc::try_unwrap(c).unwrap());
+}
+
+#[test]
+fn test_move_handles() {
+    let c = common::conn();
+    let dom = Domain::lookup_by_name(&c, "test").unwrap();
+    let other = dom.clone();
+    let worker = thread::spawn(move || dom.get_name());
+    assert_eq!(Ok(String::from("test")), worker.join().unwrap());
+    assert_eq!(Ok(String::from("test")), other.get_name());
+    drop(other);
+    common::close(c);
+}
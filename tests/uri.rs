@@ -0,0 +1,106 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+mod common;
+
+use virt::uri::{ConnectUri, Transport};
+
+
+fn roundtrip(s: &str) -> ConnectUri {
+    let uri: ConnectUri = match s.parse() {
+        Ok(uri) => uri,
+        Err(e) => panic!("failed to parse {}: {}", s, e.message),
+    };
+    assert_eq!(s, uri.to_string());
+    uri
+}
+
+#[test]
+fn test_local() {
+    let uri = roundtrip("qemu:///system");
+    assert_eq!("qemu", uri.driver);
+    assert_eq!(None, uri.transport);
+    assert_eq!(None, uri.host);
+    assert_eq!("/system", uri.path);
+    assert_eq!("xen://", roundtrip("xen://").to_string());
+    let uri = roundtrip("qemu:///embed?root=%2Ftmp%2Fvm");
+    assert_eq!("/embed", uri.path);
+    assert_eq!(Some("/tmp/vm"), uri.get_param("root"));
+}
+
+#[test]
+fn test_remote() {
+    let uri = roundtrip("qemu+ssh://root@hv1.example.org:2222/system?keyfile=k&no_verify=1");
+    assert_eq!(Some(Transport::Ssh), uri.transport);
+    assert_eq!(Some(String::from("root")), uri.user);
+    assert_eq!(Some(String::from("hv1.example.org")), uri.host);
+    assert_eq!(Some(2222), uri.port);
+    assert_eq!(Some("k"), uri.keyfile());
+    assert!(uri.no_verify());
+    assert_eq!(None, uri.pkipath());
+
+    let uri = roundtrip("qemu+tls://[fe80::1]:16514/session");
+    assert_eq!(Some(String::from("fe80::1")), uri.host);
+    assert_eq!(Some(16514), uri.port);
+
+    let uri = roundtrip("qemu+ext:///system?command=%2Fopt%2Fbin%2Fproxy&mode=direct");
+    assert_eq!(Some(Transport::Ext), uri.transport);
+    assert_eq!(Some("/opt/bin/proxy"), uri.command());
+    assert_eq!(Some("direct"), uri.mode());
+
+    let uri = roundtrip("lxc+custom://host/");
+    assert_eq!(Some(Transport::Other(String::from("custom"))), uri.transport);
+}
+
+#[test]
+fn test_escaping() {
+    let uri = ConnectUri::new("qemu")
+        .transport(Transport::Ssh)
+        .user("ad min")
+        .host("evil/host?x=1")
+        .path("/system")
+        .param("socket", "/run/a&b=c");
+    let s = uri.to_string();
+    assert_eq!("qemu+ssh://ad%20min@evil%2Fhost%3Fx%3D1/system?socket=%2Frun%2Fa%26b%3Dc", s);
+    assert_eq!(Ok(uri), s.parse());
+}
+
+#[test]
+fn test_set_param() {
+    let mut uri = ConnectUri::new("test").path("/default").param("mode", "auto");
+    uri.set_param("mode", "legacy");
+    assert_eq!("test:///default?mode=legacy", uri.to_string());
+}
+
+#[test]
+fn test_invalid() {
+    assert!("no-scheme".parse::<ConnectUri>().is_err());
+    assert!("qemu+ssh://host:port/system".parse::<ConnectUri>().is_err());
+    assert!("qemu+ssh://[::1/system".parse::<ConnectUri>().is_err());
+    assert!("qemu:///system?x=%G1".parse::<ConnectUri>().is_err());
+}
+
+#[test]
+fn test_from_connect() {
+    let c = common::conn();
+    let uri = ConnectUri::from_connect(&c).unwrap();
+    assert_eq!(ConnectUri::new("test").path("/default"), uri);
+    common::close(c);
+}
@@ -0,0 +1,235 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+mod common;
+
+use virt::xml::capabilities::Capabilities;
+
+const CAPS: &'static str = "
+<capabilities>
+  <host>
+    <uuid>b7ee6e4d-8f25-4b5b-9cbd-3f1d0e4b9a01</uuid>
+    <cpu>
+      <arch>x86_64</arch>
+      <model>Skylake-Client-IBRS</model>
+      <vendor>Intel</vendor>
+      <microcode version='240'/>
+      <counter name='tsc' frequency='2112000000' scaling='no'/>
+      <topology sockets='1' dies='1' cores='4' threads='2'/>
+      <feature name='ds'/>
+      <feature name='vmx'/>
+      <pages unit='KiB' size='4'/>
+      <pages unit='MiB' size='2'/>
+    </cpu>
+    <power_management>
+      <suspend_mem/>
+    </power_management>
+    <iommu support='yes'/>
+    <migration_features>
+      <live/>
+      <uri_transports>
+        <uri_transport>tcp</uri_transport>
+        <uri_transport>rdma</uri_transport>
+      </uri_transports>
+    </migration_features>
+    <topology>
+      <cells num='2'>
+        <cell id='0'>
+          <memory unit='KiB'>8388608</memory>
+          <pages unit='KiB' size='4'>2097152</pages>
+          <pages unit='KiB' size='2048'>0</pages>
+          <distances>
+            <sibling id='0' value='10'/>
+            <sibling id='1' value='21'/>
+          </distances>
+          <cpus num='2'>
+            <cpu id='0' socket_id='0' die_id='0' core_id='0' siblings='0,2'/>
+            <cpu id='2' socket_id='0' die_id='0' core_id='0' siblings='0,2'/>
+          </cpus>
+        </cell>
+        <cell id='1'>
+          <memory unit='KiB'>8388608</memory>
+          <cpus num='2'>
+            <cpu id='1' socket_id='1' die_id='0' core_id='0' siblings='1,3'/>
+            <cpu id='3' socket_id='1' die_id='0' core_id='0' siblings='1,3'/>
+          </cpus>
+        </cell>
+      </cells>
+    </topology>
+    <cache>
+      <bank id='0' level='3' type='both' size='8' unit='MiB' cpus='0-3'/>
+      <monitor level='3' reuseThreshold='270336' maxMonitors='176'>
+        <feature name='llc_occupancy'/>
+      </monitor>
+    </cache>
+    <secmodel>
+      <model>selinux</model>
+      <doi>0</doi>
+      <baselabel type='kvm'>system_u:system_r:svirt_t:s0</baselabel>
+      <baselabel type='qemu'>system_u:system_r:svirt_tcg_t:s0</baselabel>
+    </secmodel>
+    <secmodel>
+      <model>dac</model>
+      <doi>0</doi>
+      <baselabel type='kvm'>+107:+107</baselabel>
+    </secmodel>
+  </host>
+  <guest>
+    <os_type>hvm</os_type>
+    <arch name='x86_64'>
+      <wordsize>64</wordsize>
+      <emulator>/usr/bin/qemu-system-x86_64</emulator>
+      <machine maxCpus='255'>pc-i440fx-6.2</machine>
+      <machine canonical='pc-i440fx-6.2' maxCpus='255'>pc</machine>
+      <machine maxCpus='288'>pc-q35-6.2</machine>
+      <domain type='qemu'/>
+      <domain type='kvm'>
+        <emulator>/usr/libexec/qemu-kvm</emulator>
+        <machine maxCpus='710'>q35</machine>
+      </domain>
+    </arch>
+    <features>
+      <acpi default='on' toggle='yes'/>
+      <apic default='on' toggle='no'/>
+      <cpuselection/>
+    </features>
+  </guest>
+  <guest>
+    <os_type>hvm</os_type>
+    <arch name='i686'>
+      <wordsize>32</wordsize>
+      <emulator>/usr/bin/qemu-system-i386</emulator>
+      <domain type='qemu'/>
+    </arch>
+  </guest>
+</capabilities>
+";
+
+#[test]
+fn test_parse_host() {
+    let caps: Capabilities = CAPS.parse().unwrap();
+    let cpu = caps.host.cpu.as_ref().unwrap();
+    assert_eq!("x86_64", cpu.arch);
+    assert_eq!(Some(String::from("Skylake-Client-IBRS")), cpu.model);
+    assert_eq!(Some(String::from("Intel")), cpu.vendor);
+    assert_eq!(Some(String::from("240")), cpu.microcode);
+    assert_eq!(4, cpu.topology.as_ref().unwrap().cores);
+    assert!(cpu.has_feature("vmx"));
+    assert_eq!(vec![4, 2048], cpu.pages);
+    assert_eq!(Some(true), caps.host.iommu);
+
+    let migration = caps.host.migration.as_ref().unwrap();
+    assert!(migration.live);
+    assert_eq!(vec!["tcp", "rdma"], migration.uri_transports);
+
+    assert_eq!(2, caps.host.cells.len());
+    let cell = &caps.host.cells[0];
+    assert_eq!(Some(8388608), cell.memory);
+    assert_eq!((2048, 0), (cell.pages[1].size, cell.pages[1].count));
+    assert_eq!(Some(21), cell.distance(1));
+    assert_eq!(vec![0, 2], cell.cpus.iter().map(|c| c.id).collect::<Vec<_>>());
+    assert_eq!(Some(String::from("0,2")), cell.cpus[1].siblings);
+    assert_eq!(Some(1), caps.host.cells[1].cpus[0].socket_id);
+
+    assert_eq!(8192, caps.host.cache[0].size);
+    assert_eq!("0-3", caps.host.cache[0].cpus);
+    assert_eq!(vec!["selinux", "dac"],
+               caps.host.secmodels.iter().map(|s| s.model.as_str()).collect::<Vec<_>>());
+    assert_eq!((String::from("kvm"), String::from("+107:+107")),
+               caps.host.secmodels[1].baselabels[0]);
+}
+
+#[test]
+fn test_parse_guests() {
+    let caps: Capabilities = CAPS.parse().unwrap();
+    assert_eq!(2, caps.guests.len());
+    let guest = caps.guest("hvm", "x86_64").unwrap();
+    assert_eq!(Some(64), guest.arch.wordsize);
+    assert_eq!(Some(String::from("/usr/bin/qemu-system-x86_64")), guest.arch.emulator);
+    assert_eq!(3, guest.arch.machines.len());
+    assert_eq!(Some(String::from("pc-i440fx-6.2")), guest.arch.machines[1].canonical);
+    let kvm = guest.domain("kvm").unwrap();
+    assert_eq!(Some(String::from("/usr/libexec/qemu-kvm")), kvm.emulator);
+    assert_eq!(Some(710), kvm.machines[0].max_cpus);
+    assert!(guest.has_feature("cpuselection"));
+    assert!(caps.guest("hvm", "aarch64").is_none());
+}
+
+#[test]
+fn test_roundtrip() {
+    let caps: Capabilities = CAPS.parse().unwrap();
+    let xml = caps.to_string();
+    assert!(xml.contains("<counter name='tsc' frequency='2112000000' scaling='no'/>"));
+    assert!(xml.contains("<suspend_mem/>"));
+    assert!(xml.contains("<acpi default='on' toggle='yes'/>"));
+    assert!(xml.contains("<monitor level='3' reuseThreshold='270336' maxMonitors='176'>"));
+    assert!(xml.contains("<feature name='llc_occupancy'/>"));
+    assert_eq!(Ok(caps), xml.parse());
+}
+
+#[test]
+fn test_roundtrip_partly_known() {
+    let xml = "<capabilities>
+                 <host>
+                   <cpu>
+                     <arch>x86_64</arch>
+                     <feature policy='disable'/>
+                     <feature name='vmx' custom='yes'/>
+                   </cpu>
+                   <topology>
+                     <cells num='1'>
+                       <cell id='0'>
+                         <distances>
+                           <sibling id='0' value='10' custom='yes'/>
+                         </distances>
+                       </cell>
+                     </cells>
+                     <custom/>
+                   </topology>
+                 </host>
+               </capabilities>";
+    let caps: Capabilities = xml.parse().unwrap();
+    let cpu = caps.host.cpu.as_ref().unwrap();
+    assert_eq!(vec![String::from("vmx")], cpu.features);
+    assert_eq!(Some(10), caps.host.cells[0].distance(0));
+    let out = caps.to_string();
+    for kept in &["<feature policy='disable'/>",
+                  "<feature name='vmx' custom='yes'/>",
+                  "<sibling id='0' value='10' custom='yes'/>",
+                  "<custom/>"] {
+        assert!(out.contains(kept), "{} lost in {}", kept, out);
+    }
+    assert_eq!(1, out.matches("<topology>").count());
+    assert_eq!(Ok(caps), out.parse());
+}
+
+#[test]
+fn test_from_connect() {
+    let c = common::conn();
+    match Capabilities::from_connect(&c) {
+        Ok(caps) => {
+            assert!(caps.host.cpu.is_some());
+            assert!(!caps.host.cells.is_empty());
+            assert!(!caps.guests.is_empty());
+        }
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+    common::close(c);
+}
@@ -0,0 +1,390 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use virt::error::ErrorNumber;
+use virt::xml::Element;
+use virt::xml::domain::{Address, Chardev, Clock, Cpu, Device, Disk, DiskHost, DomainDef,
+                        Features, Graphics, Hostdev, Interface, Os, Rng, Timer, Tpm, Video,
+                        Watchdog};
+
+const GUEST: &'static str = "
+<domain type='kvm' id='4'>
+  <name>guest</name>
+  <uuid>4dea22b3-1d52-d8f3-2516-782e98ab3fa0</uuid>
+  <metadata>
+    <app:instance xmlns:app='http://example.org/app/1.0'>
+      <app:owner>ops &amp; co</app:owner>
+    </app:instance>
+  </metadata>
+  <memory unit='MiB'>2048</memory>
+  <currentMemory unit='KiB'>1048576</currentMemory>
+  <vcpu placement='static' current='2'>4</vcpu>
+  <iothreads>1</iothreads>
+  <os firmware='efi'>
+    <type arch='x86_64' machine='pc-q35-6.2'>hvm</type>
+    <boot dev='hd'/>
+    <boot dev='network'/>
+    <bootmenu enable='yes' timeout='3000'/>
+  </os>
+  <features>
+    <acpi/>
+    <apic eoi='on'/>
+    <vmport state='off'/>
+    <hyperv mode='custom'>
+      <relaxed state='on'/>
+    </hyperv>
+  </features>
+  <cpu mode='custom' match='exact' check='partial'>
+    <model fallback='forbid'>Skylake-Client</model>
+    <topology sockets='1' dies='1' cores='2' threads='2'/>
+    <feature policy='require' name='vmx'/>
+    <numa>
+      <cell id='0' cpus='0-3' memory='2' unit='GiB' memAccess='shared'/>
+    </numa>
+  </cpu>
+  <numatune>
+    <memory mode='strict' nodeset='0'/>
+    <memnode cellid='0' mode='strict' nodeset='0'/>
+  </numatune>
+  <clock offset='utc'>
+    <timer name='rtc' tickpolicy='catchup'/>
+    <timer name='hpet' present='no'/>
+  </clock>
+  <on_poweroff>destroy</on_poweroff>
+  <on_reboot>restart</on_reboot>
+  <on_crash>destroy</on_crash>
+  <pm>
+    <suspend-to-mem enabled='no'/>
+  </pm>
+  <devices>
+    <emulator>/usr/bin/qemu-system-x86_64</emulator>
+    <disk type='file' device='disk'>
+      <driver name='qemu' type='qcow2' discard='unmap'/>
+      <source file='/var/lib/libvirt/images/guest.qcow2' index='1'/>
+      <backingStore/>
+      <target dev='vda' bus='virtio'/>
+      <boot order='1'/>
+      <alias name='virtio-disk0'/>
+      <address type='pci' domain='0x0000' bus='0x04' slot='0x00' function='0x0'/>
+    </disk>
+    <disk type='network' device='disk'>
+      <source protocol='rbd' name='pool/image'>
+        <host name='mon1' port='6789'/>
+        <host name='mon2'/>
+      </source>
+      <target dev='vdb' bus='virtio'/>
+    </disk>
+    <interface type='network'>
+      <mac address='52:54:00:6d:90:02'/>
+      <source network='default' portid='0d2c8b63-cb5a-4b2d-8de1-c6ee34b7fb5d' bridge='virbr0'/>
+      <target dev='vnet0'/>
+      <model type='virtio'/>
+      <link state='up'/>
+    </interface>
+    <controller type='usb' index='0' model='qemu-xhci'/>
+    <serial type='pty'>
+      <source path='/dev/pts/3'/>
+      <target type='isa-serial' port='0'>
+        <model name='isa-serial'/>
+      </target>
+    </serial>
+    <console type='pty' tty='/dev/pts/3'>
+      <target type='serial' port='0'/>
+    </console>
+    <channel type='unix'>
+      <target type='virtio' name='org.qemu.guest_agent.0' state='connected'/>
+    </channel>
+    <input type='tablet' bus='usb'/>
+    <graphics type='vnc' port='5900' autoport='yes' listen='127.0.0.1'>
+      <listen type='address' address='127.0.0.1'/>
+    </graphics>
+    <video>
+      <model type='virtio' heads='1' primary='yes'>
+        <acceleration accel3d='no'/>
+      </model>
+    </video>
+    <hostdev mode='subsystem' type='pci' managed='yes'>
+      <source>
+        <address domain='0x0000' bus='0x06' slot='0x00' function='0x1'/>
+      </source>
+    </hostdev>
+    <watchdog model='i6300esb' action='reset'/>
+    <rng model='virtio'>
+      <backend model='random'>/dev/urandom</backend>
+    </rng>
+    <tpm model='tpm-crb'>
+      <backend type='emulator' version='2.0'>
+        <active_pcr_banks>
+          <sha256/>
+        </active_pcr_banks>
+      </backend>
+    </tpm>
+    <filesystem type='mount' accessmode='passthrough'>
+      <driver type='virtiofs'/>
+      <source dir='/srv/share'/>
+      <target dir='share'/>
+    </filesystem>
+  </devices>
+  <seclabel type='dynamic' model='selinux' relabel='yes'/>
+</domain>
+";
+
+#[test]
+fn test_parse() {
+    let def: DomainDef = GUEST.parse().unwrap();
+    assert_eq!("kvm", def.kind);
+    assert_eq!(Some(4), def.id);
+    assert_eq!("guest", def.name);
+    assert_eq!(Some(2048 * 1024), def.memory);
+    assert_eq!(Some(1048576), def.current_memory);
+    let vcpu = def.vcpu.as_ref().unwrap();
+    assert_eq!((4, Some(2)), (vcpu.count, vcpu.current));
+
+    let os = def.os.as_ref().unwrap();
+    assert_eq!("hvm", os.kind);
+    assert_eq!(Some(String::from("pc-q35-6.2")), os.machine);
+    assert_eq!(vec!["hd", "network"], os.boot);
+
+    let features = def.features.as_ref().unwrap();
+    assert!(features.acpi);
+    assert!(!features.apic);
+    assert_eq!(Some(false), features.vmport);
+
+    let cpu = def.cpu.as_ref().unwrap();
+    assert_eq!(Some(String::from("Skylake-Client")), cpu.model);
+    assert_eq!(Some(String::from("forbid")), cpu.model_fallback);
+    assert_eq!(2, cpu.topology.as_ref().unwrap().threads);
+    assert_eq!("vmx", cpu.features[0].name);
+    assert_eq!(Some(2 * 1024 * 1024), cpu.numa[0].memory);
+    assert_eq!(Some(String::from("0")), def.numatune.as_ref().unwrap().nodeset);
+
+    let clock = def.clock.as_ref().unwrap();
+    assert_eq!(Some(String::from("utc")), clock.offset);
+    assert_eq!(Some(false), clock.timers[1].present);
+
+    assert_eq!(Some(String::from("/usr/bin/qemu-system-x86_64")), def.emulator);
+    assert_eq!(15, def.devices.len());
+    let disks = def.disks();
+    assert_eq!(2, disks.len());
+    assert_eq!(Some(String::from("qcow2")), disks[0].driver.as_ref().unwrap().format);
+    assert_eq!(Some(1), disks[0].boot_order);
+    assert_eq!(Some(String::from("0x04")), disks[0].address.as_ref().unwrap().bus);
+    assert_eq!(vec![DiskHost::new("mon1", Some(6789)), DiskHost::new("mon2", None)],
+               disks[1].source.as_ref().unwrap().hosts);
+    let iface = def.interfaces()[0];
+    assert_eq!(Some(String::from("52:54:00:6d:90:02")), iface.mac);
+    assert_eq!(Some(String::from("default")), iface.source.as_ref().unwrap().network);
+    match def.devices[4] {
+        Device::Serial(ref serial) => assert_eq!("pty", serial.kind),
+        ref other => panic!("unexpected device {:?}", other),
+    }
+    match def.devices[7] {
+        Device::Other(ref input) => assert_eq!("input", input.name),
+        ref other => panic!("unexpected device {:?}", other),
+    }
+}
+
+#[test]
+fn test_roundtrip_preserves_unknown() {
+    let def: DomainDef = GUEST.parse().unwrap();
+    let xml = def.to_string();
+    for unknown in &["<iothreads>1</iothreads>",
+                     "<bootmenu enable='yes' timeout='3000'/>",
+                     "<apic eoi='on'/>",
+                     "<relaxed state='on'/>",
+                     "<memnode cellid='0' mode='strict' nodeset='0'/>",
+                     "<suspend-to-mem enabled='no'/>",
+                     "index='1'",
+                     "<backingStore/>",
+                     "portid='0d2c8b63-cb5a-4b2d-8de1-c6ee34b7fb5d'",
+                     "<link state='up'/>",
+                     "<model name='isa-serial'/>",
+                     "tty='/dev/pts/3'",
+                     "state='connected'",
+                     "<input type='tablet' bus='usb'/>",
+                     "<listen type='address' address='127.0.0.1'/>",
+                     "<acceleration accel3d='no'/>",
+                     "<sha256/>",
+                     "<seclabel type='dynamic' model='selinux' relabel='yes'/>",
+                     "<app:instance xmlns:app='http://example.org/app/1.0'>",
+                     "<app:owner>ops &amp; co</app:owner>"] {
+        assert!(xml.contains(unknown), "{} lost in {}", unknown, xml);
+    }
+    let again: DomainDef = xml.parse().unwrap();
+    assert_eq!(def, again);
+    assert_eq!(xml, again.to_string());
+}
+
+#[test]
+fn test_roundtrip_preserves_remainders() {
+    let xml = "<domain type='kvm'>
+                 <name>guest</name>
+                 <description>
+  two  lines
+</description>
+                 <memory unit='KiB' dumpCore='on'>1048576</memory>
+                 <os>
+                   <type>hvm</type>
+                   <boot dev='hd'/>
+                   <boot dev='cdrom' loadparm='x'/>
+                   <boot loadparm='y'/>
+                 </os>
+                 <devices>
+                   <interface type='network'>
+                     <mac address='52:54:00:00:00:01' type='static'/>
+                     <source network='default'/>
+                     <target dev='vnet0' managed='no'/>
+                     <boot order='1' loadparm='z'/>
+                   </interface>
+                 </devices>
+               </domain>";
+    let def: DomainDef = xml.parse().unwrap();
+    assert_eq!(Some(1048576), def.memory);
+    assert_eq!(Some(String::from("\n  two  lines\n")), def.description);
+    let os = def.os.as_ref().unwrap();
+    assert_eq!(vec![String::from("hd"), String::from("cdrom")], os.boot);
+
+    let out = def.to_string();
+    for kept in &["<memory unit='KiB' dumpCore='on'>1048576</memory>",
+                  "<description>\n  two  lines\n</description>",
+                  "<boot dev='hd'/>",
+                  "<boot dev='cdrom' loadparm='x'/>",
+                  "<boot loadparm='y'/>",
+                  "<mac address='52:54:00:00:00:01' type='static'/>",
+                  "<target dev='vnet0' managed='no'/>",
+                  "<boot order='1' loadparm='z'/>"] {
+        assert!(out.contains(kept), "{} lost in {}", kept, out);
+    }
+    let again: DomainDef = out.parse().unwrap();
+    assert_eq!(def, again);
+    assert_eq!(out, again.to_string());
+}
+
+#[test]
+fn test_roundtrip_network_disks() {
+    let xml = "<domain type='kvm'>
+                 <name>guest</name>
+                 <devices>
+                   <disk type='network' device='disk'>
+                     <source protocol='nbd' name='export'>
+                       <host transport='unix' socket='/run/nbd.sock'/>
+                     </source>
+                     <target dev='vda' bus='virtio'/>
+                   </disk>
+                   <disk type='network' device='disk'>
+                     <source protocol='gluster' name='volume/image'>
+                       <host name='gluster1' port='24007' transport='tcp'/>
+                       <host name='gluster2' transport='rdma' custom='yes'/>
+                     </source>
+                     <target dev='vdb' bus='virtio'/>
+                   </disk>
+                 </devices>
+               </domain>";
+    let def: DomainDef = xml.parse().unwrap();
+    let disks = def.disks();
+    assert_eq!(vec![DiskHost::unix("/run/nbd.sock")],
+               disks[0].source.as_ref().unwrap().hosts);
+    let gluster = &disks[1].source.as_ref().unwrap().hosts;
+    assert_eq!(Some(24007), gluster[0].port);
+    assert_eq!(Some(String::from("tcp")), gluster[0].transport);
+    let out = def.to_string();
+    for kept in &["<host transport='unix' socket='/run/nbd.sock'/>",
+                  "<host name='gluster1' port='24007' transport='tcp'/>",
+                  "<host name='gluster2' transport='rdma' custom='yes'/>"] {
+        assert!(out.contains(kept), "{} lost in {}", kept, out);
+    }
+    assert_eq!(Ok(def), out.parse());
+}
+
+#[test]
+fn test_memory_units() {
+    let memory = |unit: &str, value: u64| {
+        let xml = format!("<domain type='kvm'><name>guest</name>
+                             <memory unit='{}'>{}</memory>
+                           </domain>",
+                          unit,
+                          value);
+        xml.parse::<DomainDef>().unwrap().memory
+    };
+    assert_eq!(Some(977), memory("KB", 1000));
+    assert_eq!(Some(1), memory("bytes", 1));
+    assert_eq!(Some(976563), memory("MB", 1000));
+    assert_eq!(Some(2097152), memory("GiB", 2));
+
+    let def: DomainDef = "<domain type='kvm'><name>guest</name>
+                            <memory unit='KB'>1000</memory>
+                          </domain>"
+        .parse()
+        .unwrap();
+    assert!(def.to_string().contains("<memory unit='KiB'>977</memory>"));
+}
+
+#[test]
+fn test_builder() {
+    let def = DomainDef::new("kvm", "built")
+        .uuid("4dea22b3-1d52-d8f3-2516-782e98ab3fa1")
+        .memory(512 * 1024)
+        .vcpus(2)
+        .os(Os::hvm().arch("x86_64").machine("q35").boot("cdrom").boot("hd"))
+        .features(Features { acpi: true, apic: true, ..Default::default() })
+        .cpu(Cpu::host_passthrough().topology(1, 2, 1))
+        .clock(Clock::utc().timer(Timer::new("rtc").tickpolicy("catchup")))
+        .lifecycle("destroy", "restart", "destroy")
+        .metadata(Element::new("app:tag").attr("xmlns:app", "http://example.org/app").text("a"))
+        .device(Disk::file("/images/built.qcow2", "vda").driver("qemu", "qcow2").bus("virtio"))
+        .device(Disk::cdrom("/images/install.iso", "sda").bus("sata"))
+        .device(Interface::bridge("br0").mac("52:54:00:00:00:01").model("virtio"))
+        .device(Graphics::vnc().listen("0.0.0.0"))
+        .device(Video::new("virtio"))
+        .device(Device::Console(Chardev::pty().target("serial", Some(0))))
+        .device(Device::Channel(Chardev::unix("/run/guest.agent", "bind")
+            .channel("org.qemu.guest_agent.0")))
+        .device(Hostdev::pci(Address::pci(0, 6, 0, 1)))
+        .device(Rng::random("/dev/urandom"))
+        .device(Tpm::emulator("2.0"))
+        .device(Watchdog::new("i6300esb", "reset"));
+
+    let xml = def.to_string();
+    assert!(xml.starts_with("<domain type='kvm'>\n  <name>built</name>\n"));
+    assert!(xml.contains("  <memory unit='KiB'>524288</memory>\n"));
+    assert!(xml.contains("<type arch='x86_64' machine='q35'>hvm</type>"));
+    assert!(xml.contains("<source file='/images/install.iso'/>"));
+    assert!(xml.contains("<readonly/>"));
+    assert!(xml.contains("<address type='pci' domain='0x0000' bus='0x06' slot='0x00' \
+                          function='0x1'/>"));
+    assert!(xml.contains("<backend model='random'>/dev/urandom</backend>"));
+    assert!(xml.contains("<target type='virtio' name='org.qemu.guest_agent.0'/>"));
+    assert_eq!(Ok(def), xml.parse());
+}
+
+#[test]
+fn test_invalid() {
+    match "<domain><name>x</name>".parse::<DomainDef>() {
+        Err(e) => assert_eq!(ErrorNumber::XmlError, e.code),
+        Ok(def) => panic!("unexpected success {:?}", def),
+    }
+    match "<domain><vcpu>two</vcpu></domain>".parse::<DomainDef>() {
+        Err(e) => assert!(e.message.contains("vcpu"), "{}", e.message),
+        Ok(def) => panic!("unexpected success {:?}", def),
+    }    match "<domain><memory unit='EiB'>20</memory></domain>".parse::<DomainDef>() {
+        Err(e) => assert!(e.message.contains("too large"), "{}", e.message),
+        Ok(def) => panic!("unexpected success {:?}", def),
+    }
+}
@@ -0,0 +1,75 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use virt::xml::domain_backup::{BackupDisk, BackupServer, DomainBackupDef};
+
+#[test]
+fn test_parse_pull() {
+    let def: DomainBackupDef = "<domainbackup mode='pull'>
+                                  <incremental>cp-monday</incremental>
+                                  <server transport='unix' socket='/run/backup.sock'/>
+                                  <disks>
+                                    <disk name='vda' backup='yes' type='file'
+                                          backupmode='incremental' incremental='cp-monday'
+                                          exportname='vda' exportbitmap='backup-vda'>
+                                      <driver type='qcow2'/>
+                                      <scratch file='/var/lib/libvirt/qemu/vda.scratch'/>
+                                    </disk>
+                                    <disk name='vdb' backup='no'/>
+                                  </disks>
+                                </domainbackup>"
+        .parse()
+        .unwrap();
+    assert!(def.is_pull());
+    assert_eq!(Some(String::from("cp-monday")), def.incremental);
+    assert_eq!(Some(BackupServer::unix("/run/backup.sock")), def.server);
+    let vda = &def.disks[0];
+    assert_eq!(Some(true), vda.backup);
+    assert_eq!(Some(String::from("incremental")), vda.backup_mode);
+    assert_eq!(Some(String::from("backup-vda")), vda.export_bitmap);
+    assert_eq!(Some(String::from("/var/lib/libvirt/qemu/vda.scratch")),
+               vda.scratch.as_ref().unwrap().file);
+    assert!(vda.target.is_none());
+    assert_eq!(BackupDisk::no("vdb"), def.disks[1]);
+    assert_eq!(Ok(def.clone()), def.to_string().parse());
+}
+
+#[test]
+fn test_build() {
+    let def = DomainBackupDef::push()
+        .disk(BackupDisk::push("vda", "/backups/vda.raw", "raw"))
+        .disk(BackupDisk::push("vdb", "/backups/vdb.qcow2", "qcow2"));
+    let xml = def.to_string();
+    assert!(xml.starts_with("<domainbackup mode='push'>"));
+    assert!(!xml.contains("<incremental>"));
+    assert!(xml.contains("<disk name='vda' backup='yes' type='file'>"));
+    assert!(xml.contains("<driver type='raw'/>"));
+    assert!(xml.contains("<target file='/backups/vdb.qcow2'/>"));
+    assert_eq!(Ok(def), xml.parse());
+
+    let def = DomainBackupDef::pull(BackupServer::tcp("localhost", 10809).tls(true))
+        .incremental("cp-monday")
+        .disk(BackupDisk::pull("vda", "/tmp/vda.scratch").export_name("drive0"));
+    let xml = def.to_string();
+    assert!(xml.contains("<server transport='tcp' tls='yes' name='localhost' port='10809'/>"));
+    assert!(xml.contains("exportname='drive0'"));
+    assert!(xml.contains("<scratch file='/tmp/vda.scratch'/>"));
+    assert_eq!(Ok(def), xml.parse());
+}
@@ -0,0 +1,188 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use virt::xml::domain_capabilities::{CpuModelUsable, DomainCapabilities, DomainCapsFeature};
+
+const CAPS: &'static str = "
+<domainCapabilities>
+  <path>/usr/bin/qemu-system-x86_64</path>
+  <domain>kvm</domain>
+  <machine>pc-q35-6.2</machine>
+  <arch>x86_64</arch>
+  <vcpu max='288'/>
+  <iothreads supported='yes'/>
+  <os supported='yes'>
+    <enum name='firmware'>
+      <value>bios</value>
+      <value>efi</value>
+    </enum>
+    <loader supported='yes'>
+      <value>/usr/share/OVMF/OVMF_CODE.fd</value>
+      <value>/usr/share/OVMF/OVMF_CODE.secboot.fd</value>
+      <enum name='type'>
+        <value>rom</value>
+        <value>pflash</value>
+      </enum>
+      <enum name='secure'>
+        <value>yes</value>
+        <value>no</value>
+      </enum>
+    </loader>
+  </os>
+  <cpu>
+    <mode name='host-passthrough' supported='yes'>
+      <enum name='hostPassthroughMigratable'>
+        <value>on</value>
+        <value>off</value>
+      </enum>
+    </mode>
+    <mode name='host-model' supported='yes'>
+      <model fallback='forbid'>Skylake-Client-IBRS</model>
+      <vendor>Intel</vendor>
+      <feature policy='require' name='ss'/>
+      <feature policy='disable' name='hle'/>
+    </mode>
+    <mode name='custom' supported='yes'>
+      <model usable='yes'>qemu64</model>
+      <model usable='yes'>Nehalem</model>
+      <model usable='no'>Skylake-Server</model>
+      <model usable='unknown' deprecated='yes'>Icelake-Client</model>
+    </mode>
+  </cpu>
+  <memoryBacking supported='yes'>
+    <enum name='sourceType'>
+      <value>file</value>
+      <value>memfd</value>
+    </enum>
+  </memoryBacking>
+  <devices>
+    <disk supported='yes'>
+      <enum name='diskDevice'>
+        <value>disk</value>
+        <value>cdrom</value>
+      </enum>
+      <enum name='bus'>
+        <value>sata</value>
+        <value>scsi</value>
+        <value>virtio</value>
+      </enum>
+    </disk>
+    <graphics supported='yes'>
+      <enum name='type'>
+        <value>vnc</value>
+        <value>spice</value>
+      </enum>
+    </graphics>
+    <video supported='yes'>
+      <enum name='modelType'>
+        <value>vga</value>
+        <value>virtio</value>
+      </enum>
+    </video>
+    <hostdev supported='yes'>
+      <enum name='subsysType'>
+        <value>usb</value>
+        <value>pci</value>
+      </enum>
+    </hostdev>
+    <rng supported='yes'>
+      <enum name='model'>
+        <value>virtio</value>
+      </enum>
+      <enum name='backendModel'>
+        <value>random</value>
+        <value>egd</value>
+      </enum>
+    </rng>
+    <tpm supported='no'/>
+  </devices>
+  <features>
+    <gic supported='no'/>
+    <vmcoreinfo supported='yes'/>
+    <backup supported='yes'/>
+    <sev supported='yes'>
+      <cbitpos>47</cbitpos>
+      <reducedPhysBits>1</reducedPhysBits>
+    </sev>
+    <s390-pv supported='no'/>
+  </features>
+</domainCapabilities>
+";
+
+#[test]
+fn test_parse() {
+    let caps: DomainCapabilities = CAPS.parse().unwrap();
+    assert_eq!("kvm", caps.domain);
+    assert_eq!(Some(String::from("pc-q35-6.2")), caps.machine);
+    assert_eq!(Some(288), caps.max_vcpus);
+    assert_eq!(Some(true), caps.iothreads);
+    assert_eq!(vec!["bios", "efi"], caps.firmwares());
+    assert_eq!(2, caps.loaders().len());
+    let loader = caps.os.as_ref().unwrap().child("loader").unwrap();
+    assert!(loader.accepts("type", "pflash"));
+    assert!(!loader.accepts("type", "nvram"));
+
+    let model = caps.cpu_mode("host-model").unwrap();
+    assert_eq!("Skylake-Client-IBRS", model.models[0].name);
+    assert_eq!(Some(String::from("Intel")), model.vendor);
+    assert_eq!(2, model.features.len());
+    let custom = caps.cpu_mode("custom").unwrap();
+    assert_eq!(vec!["qemu64", "Nehalem"], custom.usable_models());
+    assert_eq!(Some(CpuModelUsable::Unknown), custom.models[3].usable);
+    assert_eq!(None, model.models[0].usable);
+    assert_eq!(Some(true), custom.models[3].deprecated);
+    assert!(caps.cpu_mode("maximum").is_none());
+    assert!(caps.memory_backing.as_ref().unwrap().accepts("sourceType", "memfd"));
+}
+
+#[test]
+fn test_devices() {
+    let caps: DomainCapabilities = CAPS.parse().unwrap();
+    assert_eq!(vec!["sata", "scsi", "virtio"], caps.disk_buses());
+    assert_eq!(vec!["vnc", "spice"], caps.graphics_types());
+    assert_eq!(vec!["vga", "virtio"], caps.video_models());
+    assert_eq!(vec!["usb", "pci"], caps.hostdev_subsys_types());
+    assert_eq!(vec!["random", "egd"], caps.rng_backends());
+    assert!(caps.tpm_models().is_empty());
+    assert!(caps.device("tpm").is_none());
+    assert!(caps.device("filesystem").is_none());
+}
+
+#[test]
+fn test_features() {
+    let caps: DomainCapabilities = CAPS.parse().unwrap();
+    assert!(!caps.has_feature(DomainCapsFeature::Gic));
+    assert!(caps.has_feature(DomainCapsFeature::VmCoreInfo));
+    assert!(caps.has_feature(DomainCapsFeature::Backup));
+    assert!(!caps.has_feature(DomainCapsFeature::S390Pv));
+    assert!(!caps.has_feature(DomainCapsFeature::Sgx));
+    let sev = caps.feature(DomainCapsFeature::Sev).unwrap();
+    assert_eq!(Some("47".to_string()),
+               sev.extra.children.iter().find(|e| e.name == "cbitpos").map(|e| e.get_text()));
+}
+
+#[test]
+fn test_roundtrip() {
+    let caps: DomainCapabilities = CAPS.parse().unwrap();
+    let xml = caps.to_string();
+    assert!(xml.contains("<cbitpos>47</cbitpos>"));
+    assert!(xml.contains("<model usable='unknown' deprecated='yes'>Icelake-Client</model>"));
+    assert_eq!(Ok(caps), xml.parse());
+}
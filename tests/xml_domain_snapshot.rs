@@ -0,0 +1,101 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+use virt::xml::domain_snapshot::{DomainSnapshotDef, SnapshotDisk, SnapshotMemory, SnapshotMode,
+                                 SnapshotState};
+
+#[test]
+fn test_parse() {
+    let def: DomainSnapshotDef = "<domainsnapshot>
+                                    <name>backup-1</name>
+                                    <description>Nightly backup</description>
+                                    <state>disk-snapshot</state>
+                                    <parent>
+                                      <name>backup-0</name>
+                                    </parent>
+                                    <creationTime>1589197000</creationTime>
+                                    <memory snapshot='no'/>
+                                    <disks>
+                                      <disk name='vda' snapshot='external' type='file'>
+                                        <driver type='qcow2'/>
+                                        <source file='/var/lib/libvirt/images/vm.backup-1'/>
+                                      </disk>
+                                      <disk name='hdc' snapshot='no'/>
+                                    </disks>
+                                    <domain type='kvm'>
+                                      <name>vm</name>
+                                      <uuid>7ba0a8d4-cb2f-4d6c-9f3c-4bd2d0a2d1b4</uuid>
+                                      <memory unit='KiB'>1048576</memory>
+                                      <devices>
+                                        <disk type='file' device='disk'>
+                                          <source file='/var/lib/libvirt/images/vm.qcow2'/>
+                                          <target dev='vda' bus='virtio'/>
+                                        </disk>
+                                      </devices>
+                                    </domain>
+                                    <active>0</active>
+                                  </domainsnapshot>"
+        .parse()
+        .unwrap();
+    assert_eq!(Some(String::from("backup-1")), def.name);
+    assert_eq!(Some(SnapshotState::DiskSnapshot), def.state);
+    assert_eq!(Some(1589197000), def.creation_time);
+    assert_eq!(Some(String::from("backup-0")), def.parent);
+    assert_eq!(Some(SnapshotMemory::no()), def.memory);
+    assert_eq!(2, def.disks.len());
+    let vda = def.find_disk("vda").unwrap();
+    assert_eq!(Some(SnapshotMode::External), vda.snapshot);
+    assert_eq!(Some("/var/lib/libvirt/images/vm.backup-1"), vda.file());
+    assert_eq!(Some(String::from("qcow2")), vda.driver.as_ref().unwrap().format);
+    assert_eq!(Some(SnapshotMode::No), def.find_disk("hdc").unwrap().snapshot);
+    let domain = def.domain.as_ref().unwrap();
+    assert_eq!("vm", domain.name);
+    assert_eq!(Some(1048576), domain.memory);
+    assert_eq!(1, domain.devices.len());
+    assert!(def.inactive_domain.is_none());
+    assert!(def.to_string().contains("<active>0</active>"));
+    assert_eq!(Ok(def.clone()), def.to_string().parse());
+}
+
+#[test]
+fn test_build() {
+    let def = DomainSnapshotDef::new("snap")
+        .memory(SnapshotMemory::external("/var/lib/libvirt/qemu/snap.mem"))
+        .disk(SnapshotDisk::external("vda", "/var/lib/libvirt/images/vm.snap").driver("qcow2"))
+        .disk(SnapshotDisk::internal("vdb"));
+    let xml = def.to_string();
+    assert!(xml.contains("<memory snapshot='external' file='/var/lib/libvirt/qemu/snap.mem'"));
+    assert!(xml.contains("<disk name='vda' snapshot='external' type='file'>"));
+    assert!(xml.contains("<driver type='qcow2'"));
+    assert!(xml.contains("<source file='/var/lib/libvirt/images/vm.snap'"));
+    assert!(xml.contains("<disk name='vdb' snapshot='internal'"));
+    assert!(!xml.contains("<state>"));
+    assert_eq!(Ok(def), xml.parse());
+
+    let def: DomainSnapshotDef = "<domainsnapshot><state>running</state>
+                                    <memory snapshot='later'/>
+                                  </domainsnapshot>"
+        .parse()
+        .unwrap();
+    assert_eq!(None, def.name);
+    assert_eq!(Some(SnapshotState::Running), def.state);
+    assert_eq!(SnapshotMode::Other(String::from("later")), def.memory.as_ref().unwrap().snapshot);
+    assert!(def.to_string().contains("<memory snapshot='later'"));
+}
@@ -0,0 +1,112 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+mod common;
+
+use virt::interface::InterfaceXMLFlags;
+use virt::xml::interface::{InterfaceDef, InterfaceProtocol};
+
+const BOND: &'static str = "
+<interface type='bond' name='bond0'>
+  <start mode='onboot'/>
+  <mtu size='9000'/>
+  <protocol family='ipv4'>
+    <dhcp peerdns='no'/>
+  </protocol>
+  <protocol family='ipv6'>
+    <autoconf/>
+    <ip address='3ffe:ffff:0:5::1' prefix='128'/>
+    <route gateway='3ffe:ffff:0:5::fffe'/>
+  </protocol>
+  <bond mode='active-backup'>
+    <miimon freq='100' updelay='10' carrier='ioctl'/>
+    <interface type='ethernet' name='eth0'>
+      <mac address='ab:bb:cc:dd:ee:ff'/>
+    </interface>
+    <interface type='ethernet' name='eth1'>
+      <link speed='1000' state='up'/>
+    </interface>
+  </bond>
+</interface>
+";
+
+#[test]
+fn test_parse_bond() {
+    let def: InterfaceDef = BOND.parse().unwrap();
+    assert_eq!(("bond", "bond0"), (def.kind.as_str(), def.name.as_str()));
+    assert_eq!(Some(String::from("onboot")), def.start_mode);
+    assert_eq!(Some(9000), def.mtu);
+    assert!(def.protocols[0].dhcp);
+    assert_eq!(Some(false), def.protocols[0].peerdns);
+    let ipv6 = &def.protocols[1];
+    assert!(ipv6.autoconf);
+    assert!(!ipv6.dhcp);
+    assert_eq!(vec![(String::from("3ffe:ffff:0:5::1"), Some(128))], ipv6.ips);
+    assert_eq!(Some(String::from("3ffe:ffff:0:5::fffe")), ipv6.gateway);
+    let bond = def.bond.as_ref().unwrap();
+    assert_eq!(Some(String::from("active-backup")), bond.mode);
+    assert_eq!(100, bond.miimon.as_ref().unwrap().freq);
+    assert_eq!(2, bond.interfaces.len());
+    assert_eq!(Some(String::from("ab:bb:cc:dd:ee:ff")), bond.interfaces[0].mac);
+
+    let xml = def.to_string();
+    assert!(xml.contains("<link speed='1000' state='up'/>"));
+    assert_eq!(Ok(def), xml.parse());
+}
+
+#[test]
+fn test_build() {
+    let bridge = InterfaceDef::bridge("br0", &[InterfaceDef::ethernet("eth0")])
+        .start_mode("onboot")
+        .protocol(InterfaceProtocol::ipv4().dhcp());
+    let xml = bridge.to_string();
+    assert!(xml.contains("<interface type='bridge' name='br0'>"));
+    assert!(xml.contains("<interface type='ethernet' name='eth0'/>"));
+    assert!(xml.contains("<dhcp/>"));
+    assert_eq!(Ok(bridge), xml.parse());
+
+    let vlan = InterfaceDef::vlan("eth0.42", 42, "eth0")
+        .protocol(InterfaceProtocol::ipv4().ip("10.0.42.5", 24).gateway("10.0.42.1"));
+    let xml = vlan.to_string();
+    assert!(xml.contains("<vlan tag='42'>"));
+    assert!(xml.contains("<interface name='eth0'/>"));
+    assert!(xml.contains("<ip address='10.0.42.5' prefix='24'/>"));
+    assert_eq!(Ok(vlan), xml.parse());
+
+    let slaves = [InterfaceDef::ethernet("eth1"), InterfaceDef::ethernet("eth2")];
+    let bond = InterfaceDef::bond("bond0", "802.3ad", &slaves);
+    assert_eq!(Ok(bond.clone()), bond.to_string().parse());
+}
+
+#[test]
+fn test_from_interface() {
+    let c = common::conn();
+    let iface = common::build_interface(&c, "xml");
+    match InterfaceDef::from_interface(&iface, InterfaceXMLFlags::empty()) {
+        Ok(def) => {
+            assert_eq!("ethernet", def.kind);
+            assert_eq!(Some(String::from("aa:bb:cc:dd:ee:ff")), def.mac);
+        }
+        Err(e) => panic!("failed with code {}, message: {}", e.code, e.message),
+    }
+    common::clean_iface(iface);
+    common::close(c);
+}
@@ -0,0 +1,183 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+use virt::xml::network::{Bandwidth, BandwidthRate, DnsHost, DnsSrv, Ip, NetworkDef, Portgroup,
+                         Route, VirtualPort};
+
+const NETWORK: &'static str = "
+<network connections='2'>
+  <name>default</name>
+  <uuid>a5d1ac1c-3b6b-4e6f-9f2c-6d58cb43f3a1</uuid>
+  <forward mode='nat'>
+    <nat>
+      <port start='1024' end='65535'/>
+    </nat>
+  </forward>
+  <bridge name='virbr0' stp='on' delay='0'/>
+  <mtu size='9000'/>
+  <mac address='52:54:00:0a:cd:21'/>
+  <domain name='example.com' localOnly='yes'/>
+  <dns>
+    <forwarder addr='8.8.8.8'/>
+    <txt name='example' value='example value'/>
+    <host ip='192.168.122.2'>
+      <hostname>myhost</hostname>
+      <hostname>myhostalias</hostname>
+    </host>
+    <srv service='name' protocol='tcp' domain='test-domain-name' target='.' port='1024' \
+priority='10' weight='10'/>
+  </dns>
+  <ip address='192.168.122.1' netmask='255.255.255.0' localPtr='yes'>
+    <tftp root='/var/lib/tftp'/>
+    <dhcp>
+      <range start='192.168.122.100' end='192.168.122.254'>
+        <lease expiry='1' unit='hours'/>
+      </range>
+      <host mac='00:16:3e:77:e2:ed' name='foo.example.com' ip='192.168.122.10'/>
+      <host mac='00:16:3e:3e:a9:1a' name='bar.example.com' ip='192.168.122.11'/>
+      <bootp file='pxelinux.0'/>
+    </dhcp>
+  </ip>
+  <ip family='ipv6' address='2001:db8:ca2:2::1' prefix='64'>
+    <dhcp>
+      <host id='0:3:0:1:0:16:3e:11:22:33' name='peter.xyz' ip='2001:db8:ca2:2:3::1'/>
+    </dhcp>
+  </ip>
+  <route address='192.168.222.0' prefix='24' gateway='192.168.122.2'/>
+  <portgroup name='engineering' default='yes'>
+    <virtualport type='802.1Qbh'>
+      <parameters profileid='test'/>
+    </virtualport>
+    <bandwidth>
+      <inbound average='1000' peak='5000' burst='5120'/>
+      <outbound average='1000' peak='5000' burst='5120'/>
+    </bandwidth>
+  </portgroup>
+  <portgroup name='sales'>
+    <vlan>
+      <tag id='49'/>
+    </vlan>
+  </portgroup>
+</network>
+";
+
+#[test]
+fn test_parse() {
+    let net: NetworkDef = NETWORK.parse().unwrap();
+    assert_eq!("default", net.name);
+    assert_eq!(Some(String::from("nat")), net.forward.as_ref().unwrap().mode);
+    let bridge = net.bridge.as_ref().unwrap();
+    assert_eq!(Some(String::from("virbr0")), bridge.name);
+    assert_eq!(Some(true), bridge.stp);
+    assert_eq!(Some(0), bridge.delay);
+    assert_eq!(Some(9000), net.mtu);
+    assert_eq!(Some((String::from("example.com"), Some(true))), net.domain);
+
+    let dns = net.dns.as_ref().unwrap();
+    assert_eq!(vec!["8.8.8.8"], dns.forwarders);
+    assert_eq!("example value", dns.txts[0].value);
+    assert_eq!(vec!["myhost", "myhostalias"], dns.hosts[0].hostnames);
+    assert_eq!(Some(1024), dns.srvs[0].port);
+
+    assert_eq!(2, net.ips.len());
+    let dhcp = net.ips[0].dhcp.as_ref().unwrap();
+    assert_eq!("192.168.122.100", dhcp.ranges[0].start);
+    assert_eq!(3, net.dhcp_hosts().len());
+    assert_eq!(Some(String::from("0:3:0:1:0:16:3e:11:22:33")), net.dhcp_hosts()[2].id);
+    assert_eq!(Some(64), net.ips[1].prefix);
+    assert_eq!("192.168.122.2", net.routes[0].gateway);
+
+    let group = net.default_portgroup().unwrap();
+    assert_eq!("engineering", group.name);
+    let virtualport = group.virtualport.as_ref().unwrap();
+    assert_eq!(vec![(String::from("profileid"), String::from("test"))], virtualport.parameters);
+    let bandwidth = group.bandwidth.as_ref().unwrap();
+    assert_eq!(Some(5120), bandwidth.outbound.as_ref().unwrap().burst);
+}
+
+#[test]
+fn test_roundtrip() {
+    let net: NetworkDef = NETWORK.parse().unwrap();
+    let xml = net.to_string();
+    assert!(xml.contains("<network connections='2'>"));
+    assert!(xml.contains("<port start='1024' end='65535'/>"));
+    assert!(xml.contains("<lease expiry='1' unit='hours'/>"));
+    assert!(xml.contains("<tag id='49'/>"));
+    assert!(xml.contains("<outbound average='1000' peak='5000' burst='5120'/>"));
+    assert_eq!(Ok(net), xml.parse());
+}
+
+#[test]
+fn test_roundtrip_partly_known() {
+    let xml = "<network>
+                 <name>pool</name>
+                 <forward mode='hostdev' managed='yes'>
+                   <interface dev='eth1' connections='1'/>
+                   <interface dev='eth2'/>
+                 </forward>
+                 <dns>
+                   <forwarder domain='example.com'/>
+                   <forwarder addr='8.8.8.8'/>
+                   <forwarder domain='example.org' addr='192.168.1.1'/>
+                 </dns>
+               </network>";
+    let net: NetworkDef = xml.parse().unwrap();
+    let forward = net.forward.as_ref().unwrap();
+    assert_eq!(vec![String::from("eth1"), String::from("eth2")], forward.interfaces);
+    let dns = net.dns.as_ref().unwrap();
+    assert_eq!(vec!["8.8.8.8", "192.168.1.1"], dns.forwarders);
+    let out = net.to_string();
+    for kept in &["<interface dev='eth1' connections='1'/>",
+                  "<interface dev='eth2'/>",
+                  "<forwarder domain='example.com'/>",
+                  "<forwarder addr='8.8.8.8'/>",
+                  "<forwarder addr='192.168.1.1' domain='example.org'/>"] {
+        assert!(out.contains(kept), "{} lost in {}", kept, out);
+    }
+    assert_eq!(Ok(net), out.parse());
+}
+
+#[test]
+fn test_build() {
+    let rate = BandwidthRate::new(1000).peak(5000, 5120);
+    let net = NetworkDef::new("isolated")
+        .bridge("virbr1")
+        .mtu(1400)
+        .ip(Ip::new("10.0.0.1", 24).dhcp_range("10.0.0.2", "10.0.0.254"))
+        .ip(Ip::ipv6("fd00::1", 64))
+        .route(Route::new("10.1.0.0", 24, "10.0.0.2"))
+        .dns_host(DnsHost::new("10.0.0.2", &["router"]))
+        .portgroup(Portgroup::new("ovs")
+            .as_default()
+            .virtualport(VirtualPort::new("openvswitch").parameter("interfaceid", "42"))
+            .bandwidth(Bandwidth::new(Some(rate.clone()), Some(rate))));
+    let xml = net.to_string();
+    assert!(!xml.contains("<forward"));
+    assert!(xml.contains("<bridge name='virbr1'/>"));
+    assert!(xml.contains("<ip family='ipv6' address='fd00::1' prefix='64'/>"));
+    assert!(xml.contains("<hostname>router</hostname>"));
+    assert!(xml.contains("<parameters interfaceid='42'/>"));
+    assert!(xml.contains("<inbound average='1000' peak='5000' burst='5120'/>"));
+    assert_eq!(Ok(net), xml.parse());
+
+    let srv = DnsSrv::new("ldap", "tcp").target("ldap.example.com", 389);
+    assert_eq!(Some(389), srv.port);
+}
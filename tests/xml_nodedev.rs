@@ -0,0 +1,318 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+use virt::xml::nodedev::{NodeDeviceCap, NodeDeviceInfo, PciAddress};
+
+const GPU: &'static str = "
+<device>
+  <name>pci_0000_3b_00_0</name>
+  <path>/sys/devices/pci0000:3a/0000:3a:00.0/0000:3b:00.0</path>
+  <parent>pci_0000_3a_00_0</parent>
+  <driver>
+    <name>nvidia</name>
+  </driver>
+  <capability type='pci'>
+    <class>0x030200</class>
+    <domain>0</domain>
+    <bus>59</bus>
+    <slot>0</slot>
+    <function>0</function>
+    <product id='0x1eb8'>TU104GL [Tesla T4]</product>
+    <vendor id='0x10de'>NVIDIA Corporation</vendor>
+    <capability type='virt_functions' maxCount='16'>
+      <address domain='0x0000' bus='0x3b' slot='0x00' function='0x4'/>
+      <address domain='0x0000' bus='0x3b' slot='0x00' function='0x5'/>
+    </capability>
+    <capability type='mdev_types'>
+      <type id='nvidia-222'>
+        <name>GRID T4-1B</name>
+        <deviceAPI>vfio-pci</deviceAPI>
+        <availableInstances>16</availableInstances>
+      </type>
+      <type id='nvidia-230'>
+        <name>GRID T4-16Q</name>
+        <deviceAPI>vfio-pci</deviceAPI>
+        <availableInstances>0</availableInstances>
+      </type>
+    </capability>
+    <iommuGroup number='25'>
+      <address domain='0x0000' bus='0x3b' slot='0x00' function='0x0'/>
+      <address domain='0x0000' bus='0x3b' slot='0x00' function='0x1'/>
+    </iommuGroup>
+    <numa node='0'/>
+    <pci-express>
+      <link validity='cap' port='0' speed='8' width='16'/>
+      <link validity='sta' speed='2.5' width='16'/>
+    </pci-express>
+  </capability>
+</device>
+";
+
+const VF: &'static str = "
+<device>
+  <name>pci_0000_3b_10_1</name>
+  <parent>pci_0000_3a_00_0</parent>
+  <driver>
+    <name>vfio-pci</name>
+  </driver>
+  <capability type='pci'>
+    <domain>0</domain>
+    <bus>59</bus>
+    <slot>16</slot>
+    <function>1</function>
+    <product id='0x154c'>Ethernet Virtual Function 700 Series</product>
+    <vendor id='0x8086'>Intel Corporation</vendor>
+    <capability type='phys_function'>
+      <address domain='0x0000' bus='0x3b' slot='0x00' function='0x1'/>
+    </capability>
+    <iommuGroup number='88'>
+      <address domain='0x0000' bus='0x3b' slot='0x10' function='0x1'/>
+    </iommuGroup>
+  </capability>
+</device>
+";
+
+const NET: &'static str = "
+<device>
+  <name>net_eth0_52_54_00_12_34_56</name>
+  <path>/sys/devices/pci0000:00/0000:00:03.0/virtio0/net/eth0</path>
+  <parent>pci_0000_00_03_0</parent>
+  <capability type='net'>
+    <interface>eth0</interface>
+    <address>52:54:00:12:34:56</address>
+    <link speed='1000' state='up'/>
+    <feature name='rx'/>
+    <feature name='tx'/>
+    <feature name='gro'/>
+    <capability type='80203'/>
+  </capability>
+</device>
+";
+
+const OTHERS: &'static str = "
+<devices>
+  <device>
+    <name>usb_1_3</name>
+    <parent>usb_usb1</parent>
+    <capability type='usb_device'>
+      <bus>1</bus>
+      <device>3</device>
+      <product id='0x0001'>Tablet</product>
+      <vendor id='0x0627'>Adomax Technology Co., Ltd</vendor>
+    </capability>
+  </device>
+  <device>
+    <name>scsi_host0</name>
+    <parent>pci_0000_00_1f_2</parent>
+    <capability type='scsi_host'>
+      <host>0</host>
+      <unique_id>1</unique_id>
+      <capability type='fc_host'>
+        <wwnn>20000000c9831b4b</wwnn>
+        <wwpn>10000000c9831b4b</wwpn>
+        <fabric_wwn>2002000573de9a81</fabric_wwn>
+      </capability>
+      <capability type='vport_ops'>
+        <max_vports>127</max_vports>
+        <vports>0</vports>
+      </capability>
+    </capability>
+  </device>
+  <device>
+    <name>block_sr0</name>
+    <devnode type='dev'>/dev/sr0</devnode>
+    <parent>scsi_0_0_0_0</parent>
+    <capability type='storage'>
+      <block>/dev/sr0</block>
+      <bus>ata</bus>
+      <drive_type>cdrom</drive_type>
+      <model>QEMU DVD-ROM</model>
+      <capability type='removable'>
+        <media_available>1</media_available>
+        <media_size>0</media_size>
+      </capability>
+    </capability>
+  </device>
+  <device>
+    <name>drm_renderD128</name>
+    <devnode type='dev'>/dev/dri/renderD128</devnode>
+    <parent>pci_0000_00_02_0</parent>
+    <capability type='drm'>
+      <type>render</type>
+    </capability>
+  </device>
+  <device>
+    <name>mdev_4b20d080_1b54_4048_85b3_a6a62d165c01</name>
+    <parent>pci_0000_3b_00_0</parent>
+    <capability type='mdev'>
+      <type id='nvidia-222'/>
+      <uuid>4b20d080-1b54-4048-85b3-a6a62d165c01</uuid>
+      <iommuGroup number='112'/>
+    </capability>
+  </device>
+  <device>
+    <name>computer</name>
+    <capability type='system'>
+      <product>Standard PC (Q35 + ICH9, 2009)</product>
+    </capability>
+  </device>
+</devices>
+";
+
+fn others() -> Vec<NodeDeviceInfo> {
+    let devices: virt::xml::Element = OTHERS.parse().unwrap();
+    devices.elements().into_iter().map(|e| e.to_string().parse().unwrap()).collect()
+}
+
+#[test]
+fn test_parse_pci() {
+    let info: NodeDeviceInfo = GPU.parse().unwrap();
+    assert_eq!(Some(String::from("pci_0000_3a_00_0")), info.parent);
+    assert_eq!(Some(String::from("nvidia")), info.driver);
+    let pci = info.pci().unwrap();
+    assert_eq!("0000:3b:00.0", pci.address.to_string());
+    assert_eq!(Some(String::from("0x030200")), pci.class);
+    let vendor = pci.vendor.as_ref().unwrap();
+    assert_eq!("0x10de", vendor.id);
+    assert_eq!(Some(String::from("NVIDIA Corporation")), vendor.name);
+    assert_eq!(Some(String::from("TU104GL [Tesla T4]")), pci.product.as_ref().unwrap().name);
+
+    assert!(pci.is_physical_function());
+    assert_eq!(Some(16), pci.max_virt_functions);
+    assert_eq!(PciAddress { domain: 0, bus: 0x3b, slot: 0, function: 5 }, pci.virt_functions[1]);
+    assert_eq!(Some(16), pci.mdev_available("nvidia-222"));
+    assert_eq!(Some(0), pci.mdev_available("nvidia-230"));
+    assert_eq!(None, pci.mdev_available("nvidia-999"));
+
+    let group = pci.iommu_group.as_ref().unwrap();
+    assert_eq!(25, group.number);
+    assert_eq!(2, group.members.len());
+    assert_eq!(Some(0), pci.numa_node);
+    assert_eq!(Some(8.0), pci.links[0].speed);
+    assert_eq!(Some(2.5), pci.links[1].speed);
+    assert_eq!(Some(16), pci.links[1].width);
+}
+
+#[test]
+fn test_parse_virtual_function() {
+    let info: NodeDeviceInfo = VF.parse().unwrap();
+    let pci = info.pci().unwrap();
+    assert!(!pci.is_physical_function());
+    assert_eq!("0000:3b:10.1", pci.address.to_string());
+    assert_eq!(Some(PciAddress { domain: 0, bus: 0x3b, slot: 0, function: 1 }),
+               pci.phys_function);
+    let address = pci.address.to_address();
+    assert_eq!(Some(String::from("0x10")), address.slot);
+}
+
+#[test]
+fn test_parse_net() {
+    let info: NodeDeviceInfo = NET.parse().unwrap();
+    let net = info.net().unwrap();
+    assert_eq!("eth0", net.interface);
+    assert_eq!(Some(String::from("52:54:00:12:34:56")), net.address);
+    assert_eq!(Some(String::from("up")), net.link_state);
+    assert_eq!(Some(1000), net.link_speed);
+    assert!(net.has_feature("gro"));
+    assert!(!net.has_feature("tso"));
+    assert!(info.pci().is_none());
+}
+
+#[test]
+fn test_parse_others() {
+    let devices = others();
+    let usb = devices[0].usb_device().unwrap();
+    assert_eq!((1, 3), (usb.bus, usb.device));
+    assert_eq!("0x0627", usb.vendor.as_ref().unwrap().id);
+
+    match devices[1].capabilities[0] {
+        NodeDeviceCap::ScsiHost(ref host) => {
+            assert_eq!(Some(1), host.unique_id);
+            let fc = host.fc_host.as_ref().unwrap();
+            assert_eq!("10000000c9831b4b", fc.wwpn);
+            assert_eq!(Some(String::from("2002000573de9a81")), fc.fabric_wwn);
+            assert!(host.is_vport_capable());
+        }
+        ref cap => panic!("unexpected capability {:?}", cap),
+    }
+
+    let storage = devices[2].storage().unwrap();
+    assert_eq!(Some(String::from("/dev/sr0")), devices[2].devnode);
+    assert_eq!(Some(String::from("cdrom")), storage.drive_type);
+    assert_eq!(Some(true), storage.removable.as_ref().unwrap().media_available);
+
+    match devices[3].capabilities[0] {
+        NodeDeviceCap::Drm(ref drm) => assert_eq!("render", drm.kind),
+        ref cap => panic!("unexpected capability {:?}", cap),
+    }
+    match devices[4].capabilities[0] {
+        NodeDeviceCap::Mdev(ref mdev) => {
+            assert_eq!("nvidia-222", mdev.kind);
+            assert_eq!(Some(112), mdev.iommu_group);
+        }
+        ref cap => panic!("unexpected capability {:?}", cap),
+    }
+    match devices[5].capabilities[0] {
+        NodeDeviceCap::Other(ref el) => assert_eq!(Some("system"), el.get_attr("type")),
+        ref cap => panic!("unexpected capability {:?}", cap),
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    for xml in &[GPU, VF, NET] {
+        let info: NodeDeviceInfo = xml.parse().unwrap();
+        assert_eq!(Ok(info.clone()), info.to_string().parse());
+    }
+    let gpu: NodeDeviceInfo = GPU.parse().unwrap();
+    let xml = gpu.to_string();
+    assert!(xml.contains("<address domain='0x0000' bus='0x3b' slot='0x00' function='0x4'/>"));
+    assert!(xml.contains("<link validity='sta' speed='2.5' width='16'/>"));
+    let net: NodeDeviceInfo = NET.parse().unwrap();
+    assert!(net.to_string().contains("<capability type='80203'/>"));
+    for info in others() {
+        assert_eq!(Ok(info.clone()), info.to_string().parse());
+    }
+}
+
+#[test]
+fn test_roundtrip_net_partly_known() {
+    let xml = "<device>
+                 <name>net_eth1</name>
+                 <capability type='net'>
+                   <interface>eth1</interface>
+                   <link speed='1000' state='up' duplex='full'/>
+                   <feature custom='yes'/>
+                   <feature name='rx' custom='yes'/>
+                   <feature name='tx'/>
+                 </capability>
+               </device>";
+    let info: NodeDeviceInfo = xml.parse().unwrap();
+    assert_eq!(vec![String::from("rx"), String::from("tx")], info.net().unwrap().features);
+    let out = info.to_string();
+    for kept in &["<link speed='1000' state='up' duplex='full'/>",
+                  "<feature custom='yes'/>",
+                  "<feature name='rx' custom='yes'/>",
+                  "<feature name='tx'/>"] {
+        assert!(out.contains(kept), "{} lost in {}", kept, out);
+    }
+    assert_eq!(Ok(info), out.parse());
+}
@@ -0,0 +1,140 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+use virt::xml::nwfilter::{FilterEntry, FilterProtocol, FilterRef, NWFilterBindingDef, NWFilterDef,
+                          Rule, RuleMatch};
+
+const FILTER: &'static str = "
+<filter name='no-ip-spoofing' chain='ipv4-ip' priority='-710'>
+  <uuid>fce8ae33-e69e-83bf-262e-30786c1f8072</uuid>
+  <filterref filter='no-mac-spoofing'/>
+  <rule action='return' direction='out' priority='100'>
+    <ip srcipaddr='0.0.0.0' protocol='udp' srcportstart='68' dstportstart='67'/>
+  </rule>
+  <filterref filter='allow-dhcp-server'>
+    <parameter name='DHCPSERVER' value='10.0.0.1'/>
+    <parameter name='DHCPSERVER' value='10.0.0.2'/>
+  </filterref>
+  <rule action='accept' direction='in' statematch='false'>
+    <tcp-ipv6 dstportstart='22' state='NEW,ESTABLISHED' comment='ssh'/>
+  </rule>
+  <rule action='drop' direction='out' priority='1000'/>
+</filter>
+";
+
+#[test]
+fn test_parse() {
+    let def: NWFilterDef = FILTER.parse().unwrap();
+    assert_eq!("no-ip-spoofing", def.name);
+    assert_eq!(Some(String::from("ipv4-ip")), def.chain);
+    assert_eq!(Some(-710), def.priority);
+    assert_eq!(5, def.entries.len());
+    match def.entries[0] {
+        FilterEntry::FilterRef(ref f) => assert_eq!("no-mac-spoofing", f.filter),
+        ref e => panic!("unexpected entry {:?}", e),
+    }
+
+    let rules = def.rules();
+    assert_eq!(3, rules.len());
+    assert_eq!(("return", "out", Some(100)),
+               (rules[0].action.as_str(), rules[0].direction.as_str(), rules[0].priority));
+    let ip = rules[0].matching.as_ref().unwrap();
+    assert_eq!(FilterProtocol::Ip, ip.protocol);
+    assert_eq!(Some("67"), ip.get("dstportstart"));
+    assert_eq!(Some(false), rules[1].statematch);
+    let tcp = rules[1].matching.as_ref().unwrap();
+    assert_eq!(FilterProtocol::TcpIpv6, tcp.protocol);
+    assert_eq!(Some("NEW,ESTABLISHED"), tcp.get("state"));
+    assert!(rules[2].matching.is_none());
+
+    let refs = def.filterrefs();
+    assert_eq!(2, refs[1].parameters.len());
+    assert_eq!(("DHCPSERVER".to_string(), "10.0.0.2".to_string()), refs[1].parameters[1]);
+}
+
+#[test]
+fn test_roundtrip() {
+    let def: NWFilterDef = FILTER.parse().unwrap();
+    let xml = def.to_string();
+    assert!(xml.contains("<ip srcipaddr='0.0.0.0' protocol='udp' srcportstart='68' \
+                          dstportstart='67'/>"));
+    assert_eq!(Ok(def), xml.parse());
+}
+
+#[test]
+fn test_build() {
+    let def = NWFilterDef::new("web")
+        .chain("root")
+        .filterref("clean-traffic", &[("IP", "$IP")])
+        .rule(Rule::accept("in")
+            .priority(500)
+            .matching(RuleMatch::new(FilterProtocol::Tcp).dst_port(80, Some(443)).state("NEW")))
+        .rule(Rule::new("reject", "in").matching(RuleMatch::new(FilterProtocol::Mac)
+            .src_mac("52:54:00:00:00:01")
+            .attr("protocolid", "arp")));
+    let xml = def.to_string();
+    assert!(xml.contains("<parameter name='IP' value='$IP'/>"));
+    assert!(xml.contains("<tcp dstportstart='80' dstportend='443' state='NEW'/>"));
+    assert!(xml.contains("<mac srcmacaddr='52:54:00:00:00:01' protocolid='arp'/>"));
+    assert_eq!(Ok(def), xml.parse());
+
+    let udp = RuleMatch::new(FilterProtocol::from_name("udp"))
+        .src_port(53, None)
+        .src_port(54, None);
+    assert_eq!(Some("54"), udp.get("srcportstart"));
+    assert_eq!(1, udp.attributes.len());
+    assert_eq!(FilterProtocol::Other(String::from("gre")), FilterProtocol::from_name("gre"));
+}
+
+#[test]
+fn test_binding() {
+    let def: NWFilterBindingDef = "<filterbinding>
+                                     <owner>
+                                       <name>guest</name>
+                                       <uuid>12ac8b8c-4f23-4248-ae42-fdcd50c400fd</uuid>
+                                     </owner>
+                                     <portdev name='vnet1'/>
+                                     <mac address='52:54:00:9d:81:b1'/>
+                                     <filterref filter='clean-traffic'>
+                                       <parameter name='MAC' value='52:54:00:9d:81:b1'/>
+                                       <parameter name='IP' value='192.168.122.10'/>
+                                     </filterref>
+                                   </filterbinding>"
+        .parse()
+        .unwrap();
+    assert_eq!("guest", def.owner_name);
+    assert_eq!("12ac8b8c-4f23-4248-ae42-fdcd50c400fd", def.owner_uuid);
+    assert_eq!("vnet1", def.port_dev);
+    assert_eq!("52:54:00:9d:81:b1", def.mac);
+    assert_eq!("clean-traffic", def.filter.filter);
+    assert_eq!(Some("192.168.122.10"), def.filter.parameter("IP"));
+    assert_eq!(None, def.filter.parameter("DHCPSERVER"));
+    assert_eq!(Ok(def.clone()), def.to_string().parse());
+
+    let built = NWFilterBindingDef::new("guest",
+                                        "12ac8b8c-4f23-4248-ae42-fdcd50c400fd",
+                                        "vnet1",
+                                        "52:54:00:9d:81:b1",
+                                        FilterRef::new("clean-traffic",
+                                                       &[("MAC", "52:54:00:9d:81:b1"),
+                                                         ("IP", "192.168.122.10")]));
+    assert_eq!(def, built);
+}
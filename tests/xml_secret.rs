@@ -0,0 +1,69 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+use virt::secret::SecretSecretUsageType;
+use virt::xml::secret::{SecretDef, SecretUsage};
+
+#[test]
+fn test_parse() {
+    let def: SecretDef = "<secret ephemeral='no' private='yes'>
+                            <uuid>f52a81b2-424e-490c-823d-6bd4235bc572</uuid>
+                            <description>LUKS passphrase</description>
+                            <usage type='volume'>
+                              <volume>/var/lib/libvirt/images/encrypted.qcow2</volume>
+                            </usage>
+                          </secret>"
+        .parse()
+        .unwrap();
+    assert_eq!(Some(false), def.ephemeral);
+    assert_eq!(Some(true), def.private);
+    assert_eq!(Some(String::from("LUKS passphrase")), def.description);
+    let usage = def.usage.as_ref().unwrap();
+    assert_eq!(SecretSecretUsageType::Volume, usage.usage_type());
+    assert_eq!(Some("/var/lib/libvirt/images/encrypted.qcow2"), usage.usage_id());
+    assert_eq!(Ok(def.clone()), def.to_string().parse());
+}
+
+#[test]
+fn test_usages() {
+    let usages = vec![(SecretUsage::Volume(String::from("/dev/vg/lv")), "<volume>"),
+                      (SecretUsage::Ceph(String::from("client.admin")), "<name>"),
+                      (SecretUsage::Iscsi(String::from("libvirtiscsi")), "<target>"),
+                      (SecretUsage::Tls(String::from("tls-passphrase")), "<name>"),
+                      (SecretUsage::Vtpm(String::from("vtpm-key")), "<name>")];
+    for (usage, element) in usages {
+        let def = SecretDef::new(usage).ephemeral(true).private(false);
+        let xml = def.to_string();
+        assert!(xml.contains("<secret ephemeral='yes' private='no'>"));
+        assert!(xml.contains(element));
+        assert_eq!(Ok(def), xml.parse());
+    }
+
+    let def: SecretDef = "<secret><usage type='future'><key>x</key></usage></secret>"
+        .parse()
+        .unwrap();
+    match def.usage {
+        Some(SecretUsage::Other(ref el)) => assert_eq!(Some("future"), el.get_attr("type")),
+        ref usage => panic!("unexpected usage {:?}", usage),
+    }
+    assert_eq!(SecretSecretUsageType::Unknown, def.usage.as_ref().unwrap().usage_type());
+    assert!(def.to_string().contains("<key>x</key>"));
+}
@@ -0,0 +1,232 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <http://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+extern crate virt;
+
+
+use virt::xml::storage::{Encryption, Permissions, PoolSource, SourceAuth, StoragePoolDef,
+                         StoragePoolType, StorageVolDef};
+
+const POOL: &'static str = "
+<pool type='dir'>
+  <name>default</name>
+  <uuid>4c9b1c5e-6c6b-4b47-9f0f-0c1b7a2b6a11</uuid>
+  <capacity unit='bytes'>105089261568</capacity>
+  <allocation unit='bytes'>32195989504</allocation>
+  <available unit='bytes'>72893272064</available>
+  <features>
+    <cow state='no'/>
+  </features>
+  <source>
+  </source>
+  <target>
+    <path>/var/lib/libvirt/images</path>
+    <permissions>
+      <mode>0711</mode>
+      <owner>0</owner>
+      <group>0</group>
+      <label>system_u:object_r:virt_image_t:s0</label>
+    </permissions>
+  </target>
+</pool>
+";
+
+const RBD_POOL: &'static str = "
+<pool type='rbd'>
+  <name>ceph</name>
+  <source>
+    <host name='mon1.example.com' port='6789'/>
+    <host name='mon2.example.com'/>
+    <name>libvirt-pool</name>
+    <auth type='ceph' username='libvirt'>
+      <secret usage='client.libvirt secret'/>
+    </auth>
+    <config file='/etc/ceph/ceph.conf'/>
+  </source>
+</pool>
+";
+
+const VOL: &'static str = "
+<volume type='file'>
+  <name>top.qcow2</name>
+  <key>/var/lib/libvirt/images/top.qcow2</key>
+  <capacity unit='G'>20</capacity>
+  <allocation unit='bytes'>200704</allocation>
+  <physical unit='bytes'>196616</physical>
+  <target>
+    <path>/var/lib/libvirt/images/top.qcow2</path>
+    <format type='qcow2'/>
+    <permissions>
+      <mode>0600</mode>
+      <owner>107</owner>
+      <group>107</group>
+    </permissions>
+    <timestamps>
+      <atime>1665742123.417292372</atime>
+    </timestamps>
+    <compat>1.1</compat>
+    <clusterSize unit='B'>65536</clusterSize>
+    <features>
+      <lazy_refcounts/>
+      <extended_l2/>
+    </features>
+    <encryption format='luks'>
+      <secret type='passphrase' uuid='f52a81b2-424e-490c-823d-6bd4235bc572'/>
+      <cipher name='aes' size='256' mode='xts'/>
+    </encryption>
+  </target>
+  <backingStore>
+    <path>/var/lib/libvirt/images/base.qcow2</path>
+    <format type='qcow2'/>
+  </backingStore>
+</volume>
+";
+
+#[test]
+fn test_parse_pool() {
+    let pool: StoragePoolDef = POOL.parse().unwrap();
+    assert_eq!(StoragePoolType::Dir, pool.kind);
+    assert_eq!("default", pool.name);
+    assert_eq!(Some(105089261568), pool.capacity);
+    assert_eq!(Some(72893272064), pool.available);
+    assert_eq!(Some(false), pool.cow);
+    let target = pool.target.as_ref().unwrap();
+    assert_eq!(Some(String::from("/var/lib/libvirt/images")), target.path);
+    let perms = target.permissions.as_ref().unwrap();
+    assert_eq!(Some(String::from("0711")), perms.mode);
+    assert_eq!(Some(0), perms.owner);
+    assert!(pool.source.as_ref().unwrap().extra.is_empty());
+}
+
+#[test]
+fn test_parse_network_pool() {
+    let pool: StoragePoolDef = RBD_POOL.parse().unwrap();
+    assert_eq!(StoragePoolType::Rbd, pool.kind);
+    let source = pool.source.as_ref().unwrap();
+    assert_eq!(vec![(String::from("mon1.example.com"), Some(6789)),
+                    (String::from("mon2.example.com"), None)],
+               source.hosts);
+    assert_eq!(Some(String::from("libvirt-pool")), source.name);
+    let auth = source.auth.as_ref().unwrap();
+    assert_eq!(("ceph", "libvirt"), (auth.kind.as_str(), auth.username.as_str()));
+    assert_eq!(Some(String::from("client.libvirt secret")), auth.secret_usage);
+    assert_eq!(Ok(pool.clone()), pool.to_string().parse());
+    assert!(pool.to_string().contains("<config file='/etc/ceph/ceph.conf'/>"));
+}
+
+#[test]
+fn test_parse_vol() {
+    let vol: StorageVolDef = VOL.parse().unwrap();
+    assert_eq!(Some(String::from("file")), vol.kind);
+    assert_eq!(Some(20 * 1024 * 1024 * 1024), vol.capacity);
+    assert_eq!(Some(200704), vol.allocation);
+    let target = vol.target.as_ref().unwrap();
+    assert_eq!(Some(String::from("qcow2")), target.format);
+    assert_eq!(Some(107), target.permissions.as_ref().unwrap().owner);
+    assert_eq!(Some(String::from("1.1")), target.compat);
+    assert_eq!(vec!["lazy_refcounts", "extended_l2"], target.features);
+    let encryption = target.encryption.as_ref().unwrap();
+    assert_eq!("luks", encryption.format);
+    assert_eq!(Some(String::from("f52a81b2-424e-490c-823d-6bd4235bc572")),
+               encryption.secret_uuid);
+    let backing = vol.backing_store.as_ref().unwrap();
+    assert_eq!("/var/lib/libvirt/images/base.qcow2", backing.path);
+    assert_eq!(Some(String::from("qcow2")), backing.format);
+}
+
+#[test]
+fn test_roundtrip() {
+    let pool: StoragePoolDef = POOL.parse().unwrap();
+    let xml = pool.to_string();
+    assert!(xml.contains("<cow state='no'/>"));
+    assert_eq!(Ok(pool), xml.parse());
+
+    let vol: StorageVolDef = VOL.parse().unwrap();
+    let xml = vol.to_string();
+    assert!(xml.contains("<capacity unit='bytes'>21474836480</capacity>"));
+    assert!(xml.contains("<atime>1665742123.417292372</atime>"));
+    assert!(xml.contains("<cipher name='aes' size='256' mode='xts'/>"));
+    assert_eq!(Ok(vol), xml.parse());
+}
+
+#[test]
+fn test_roundtrip_logical_devices() {
+    let xml = "<pool type='logical'>
+                 <name>vg0</name>
+                 <source>
+                   <device path='/dev/sda2' part_separator='no'>
+                     <freeExtent start='0' end='4194304'/>
+                   </device>
+                   <device path='/dev/sdb1'/>
+                   <name>vg0</name>
+                   <format type='lvm2'/>
+                 </source>
+               </pool>";
+    let pool: StoragePoolDef = xml.parse().unwrap();
+    let source = pool.source.as_ref().unwrap();
+    assert_eq!(vec![String::from("/dev/sda2"), String::from("/dev/sdb1")], source.devices);
+    let out = pool.to_string();
+    assert!(out.contains("<device path='/dev/sda2' part_separator='no'>"), "{}", out);
+    assert!(out.contains("<freeExtent start='0' end='4194304'/>"), "{}", out);
+    assert!(out.contains("<device path='/dev/sdb1'/>"), "{}", out);
+    assert_eq!(Ok(pool), out.parse());
+}
+
+#[test]
+fn test_build_pools() {
+    let pool = StoragePoolDef::netfs("nfs", "nfs.example.com", "/export", "/mnt/nfs");
+    let xml = pool.to_string();
+    assert!(xml.contains("<host name='nfs.example.com'/>"));
+    assert!(xml.contains("<dir path='/export'/>"));
+    assert!(xml.contains("<format type='auto'/>"));
+    assert_eq!(Ok(pool), xml.parse());
+
+    let pool = StoragePoolDef::logical("vg", "vg0", &["/dev/sdb1", "/dev/sdc1"]);
+    assert_eq!(2, pool.source.as_ref().unwrap().devices.len());
+    assert_eq!(Some(String::from("/dev/vg0")), pool.target.as_ref().unwrap().path);
+
+    let source = PoolSource::default()
+        .host("iscsi.example.com", Some(3260))
+        .device("iqn.2013-06.com.example:iscsi-pool")
+        .initiator("iqn.2013-06.com.example:client")
+        .auth(SourceAuth::new("chap", "admin", "libvirtiscsi"));
+    let pool = StoragePoolDef::new(StoragePoolType::Iscsi, "iscsi")
+        .source(source)
+        .target_path("/dev/disk/by-path");
+    let xml = pool.to_string();
+    assert!(xml.contains("<iqn name='iqn.2013-06.com.example:client'/>"));
+    assert!(xml.contains("<secret usage='libvirtiscsi'/>"));
+    assert_eq!(Ok(pool), xml.parse());
+}
+
+#[test]
+fn test_build_vol() {
+    let vol = StorageVolDef::new("top.qcow2", 10 * 1024 * 1024 * 1024)
+        .format("qcow2")
+        .compat("1.1")
+        .permissions(Permissions::new("0600", 107, 107))
+        .encryption(Encryption::luks("f52a81b2-424e-490c-823d-6bd4235bc572"))
+        .backing_store("/var/lib/libvirt/images/base.qcow2", "qcow2");
+    let xml = vol.to_string();
+    assert!(xml.contains("<capacity unit='bytes'>10737418240</capacity>"));
+    assert!(xml.contains("<format type='qcow2'/>"));
+    assert!(xml.contains("<secret type='passphrase' \
+                          uuid='f52a81b2-424e-490c-823d-6bd4235bc572'/>"));
+    assert!(xml.contains("<backingStore>"));
+    assert_eq!(Ok(vol), xml.parse());
+}